@@ -0,0 +1,117 @@
+//! # Address book
+//!
+//! Named addresses of users, contracts and module accounts, shared between
+//! tests using [App](crate::App) and deployment scripts targeting other chains.
+//!
+//! The address book is exported with [App::export_address_book](crate::App::export_address_book)
+//! and serialized to JSON. Importing it with [AppBuilder::with_address_book](crate::AppBuilder::with_address_book)
+//! registers the names of users and pins the addresses of contracts, so instantiating a contract
+//! with a label listed in the address book fails when the generated address differs
+//! from the expected one, catching the drift between deployments early.
+
+use crate::error::{AnyResult, Error};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use cosmwasm_std::{Addr, Order, Storage};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Namespace of the address book in the storage.
+const NAMESPACE_ADDRESS_BOOK: &[u8] = b"address_book";
+
+/// Addresses of users, indexed by name.
+const USERS: Map<&str, Addr> = Map::new("users");
+
+/// Expected addresses of contracts not instantiated yet, indexed by label.
+const PINNED_CONTRACTS: Map<&str, Vec<Addr>> = Map::new("pinned_contracts");
+
+/// Named addresses of users, contracts and module accounts.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AddressBook {
+    /// Addresses of users, indexed by name.
+    #[serde(default)]
+    pub users: BTreeMap<String, Addr>,
+    /// Instantiated contracts.
+    #[serde(default)]
+    pub contracts: Vec<AddressBookContract>,
+    /// Addresses of module accounts, indexed by module name.
+    #[serde(default)]
+    pub module_accounts: BTreeMap<String, Addr>,
+}
+
+/// Contract listed in the [AddressBook].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AddressBookContract {
+    /// Label of the contract.
+    pub label: String,
+    /// Identifier of the code the contract was instantiated from.
+    pub code_id: u64,
+    /// Address of the contract.
+    pub address: Addr,
+}
+
+/// Saves the address of the user under specified name.
+pub(crate) fn register_user(storage: &mut dyn Storage, name: &str, addr: &Addr) -> AnyResult<()> {
+    USERS.save(&mut prefixed(storage, NAMESPACE_ADDRESS_BOOK), name, addr)?;
+    Ok(())
+}
+
+/// Returns the addresses of all registered users, indexed by name.
+pub(crate) fn load_users(storage: &dyn Storage) -> AnyResult<BTreeMap<String, Addr>> {
+    Ok(USERS
+        .range(
+            &prefixed_read(storage, NAMESPACE_ADDRESS_BOOK),
+            None,
+            None,
+            Order::Ascending,
+        )
+        .collect::<Result<_, _>>()?)
+}
+
+/// Registers the names of users and pins the addresses of contracts from the address book.
+///
+/// Module accounts are not imported, their addresses are set when building the application.
+pub(crate) fn import_address_book(storage: &mut dyn Storage, book: &AddressBook) -> AnyResult<()> {
+    for (name, addr) in &book.users {
+        register_user(storage, name, addr)?;
+    }
+    let mut pinned = BTreeMap::<&str, Vec<Addr>>::new();
+    for contract in &book.contracts {
+        pinned
+            .entry(&contract.label)
+            .or_default()
+            .push(contract.address.clone());
+    }
+    let mut storage = prefixed(storage, NAMESPACE_ADDRESS_BOOK);
+    for (label, addresses) in pinned {
+        PINNED_CONTRACTS.save(&mut storage, label, &addresses)?;
+    }
+    Ok(())
+}
+
+/// Fails when the address generated for the contract with specified label
+/// is not one of the addresses pinned for this label in the address book.
+///
+/// Every pinned address is expected only once, so contracts sharing the label
+/// can be instantiated in any order. Labels not listed in the address book are not checked.
+pub(crate) fn check_pinned_address(
+    storage: &mut dyn Storage,
+    label: &str,
+    addr: &Addr,
+) -> AnyResult<()> {
+    let mut storage = prefixed(storage, NAMESPACE_ADDRESS_BOOK);
+    let Some(mut expected) = PINNED_CONTRACTS.may_load(&storage, label)? else {
+        return Ok(());
+    };
+    let Some(position) = expected.iter().position(|expected| expected == addr) else {
+        return Err(Error::address_book_mismatch(label, &expected, addr).into());
+    };
+    expected.remove(position);
+    if expected.is_empty() {
+        PINNED_CONTRACTS.remove(&mut storage, label);
+    } else {
+        PINNED_CONTRACTS.save(&mut storage, label, &expected)?;
+    }
+    Ok(())
+}
@@ -1,9 +1,10 @@
 //! # Implementation of address conversions and generators
 
-use crate::error::AnyResult;
+use crate::error::{bail, AnyResult};
+use crate::prefixed_storage::prefixed;
 use crate::{MockApiBech32, MockApiBech32m};
 use cosmwasm_std::testing::MockApi;
-use cosmwasm_std::{instantiate2_address, Addr, Api, CanonicalAddr, Storage};
+use cosmwasm_std::{instantiate2_address, Addr, Api, CanonicalAddr, Checksum, Storage};
 use sha2::digest::Update;
 use sha2::{Digest, Sha256};
 
@@ -74,6 +75,23 @@ impl IntoBech32m for &str {
     }
 }
 
+/// Details of the contract instantiation, available while generating the contract address.
+#[derive(Debug, Clone, Copy)]
+pub struct ContractInstantiationInfo<'a> {
+    /// Identifier of the contract's code.
+    pub code_id: u64,
+    /// Identifier of the contract instance.
+    pub instance_id: u64,
+    /// Label of the instantiated contract.
+    pub label: &'a str,
+    /// Address of the account instantiating the contract.
+    pub creator: &'a Addr,
+    /// Optional address of the contract's admin.
+    pub admin: Option<&'a Addr>,
+    /// Checksum of the contract's code.
+    pub checksum: &'a Checksum,
+}
+
 /// Common address generator interface.
 ///
 /// The default implementation of this trait generates fully predictable
@@ -166,22 +184,156 @@ pub trait AddressGenerator {
         creator: &CanonicalAddr,
         salt: &[u8],
     ) -> AnyResult<Addr> {
-        let canonical_addr = instantiate2_address(checksum, creator, salt)?;
-        Ok(api.addr_humanize(&canonical_addr)?)
+        humanized_instantiate2_address(api, checksum, creator, salt)
+    }
+
+    /// Generates a _non-predictable_ contract address using all available instantiation details.
+    ///
+    /// This function is called by [WasmKeeper](crate::WasmKeeper) while processing
+    /// `WasmMsg::Instantiate` message. The default implementation delegates
+    /// to [contract_address](AddressGenerator::contract_address), so generators
+    /// implementing only the old function keep working.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use cosmwasm_std::{Addr, Api, CanonicalAddr, Checksum, Storage};
+    /// # use cosmwasm_std::testing::{MockApi, MockStorage};
+    /// # use cw_multi_test::error::AnyResult;
+    /// # use cw_multi_test::{AddressGenerator, ContractInstantiationInfo};
+    /// # let api = MockApi::default();
+    /// # let mut storage = MockStorage::default();
+    /// struct LabelAddressGenerator;
+    ///
+    /// impl AddressGenerator for LabelAddressGenerator {
+    ///     fn contract_address_with_info(
+    ///         &self,
+    ///         api: &dyn Api,
+    ///         _storage: &mut dyn Storage,
+    ///         info: &ContractInstantiationInfo,
+    ///     ) -> AnyResult<Addr> {
+    ///         Ok(api.addr_humanize(&CanonicalAddr::from(info.label.as_bytes()))?)
+    ///     }
+    /// }
+    ///
+    /// let creator = api.addr_make("creator");
+    /// let checksum = Checksum::generate(&[1]);
+    /// let info = ContractInstantiationInfo {
+    ///     code_id: 1,
+    ///     instance_id: 0,
+    ///     label: "vault",
+    ///     creator: &creator,
+    ///     admin: None,
+    ///     checksum: &checksum,
+    /// };
+    ///
+    /// let addr = LabelAddressGenerator.contract_address_with_info(&api, &mut storage, &info).unwrap();
+    /// assert_eq!(addr, api.addr_humanize(&CanonicalAddr::from(b"vault")).unwrap());
+    /// ```
+    fn contract_address_with_info(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        info: &ContractInstantiationInfo,
+    ) -> AnyResult<Addr> {
+        self.contract_address(api, storage, info.code_id, info.instance_id)
     }
+
+    /// Generates a _predictable_ contract address using all available instantiation details.
+    ///
+    /// This function is called by [WasmKeeper](crate::WasmKeeper) while processing
+    /// `WasmMsg::Instantiate2` message. The default implementation delegates
+    /// to [predictable_contract_address](AddressGenerator::predictable_contract_address),
+    /// so generators implementing only the old function keep working.
+    fn predictable_contract_address_with_info(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        info: &ContractInstantiationInfo,
+        salt: &[u8],
+    ) -> AnyResult<Addr> {
+        let creator = api.addr_canonicalize(info.creator.as_str())?;
+        self.predictable_contract_address(
+            api,
+            storage,
+            info.code_id,
+            info.instance_id,
+            info.checksum.as_slice(),
+            &creator,
+            salt,
+        )
+    }
+}
+
+/// Returns the address of the module account with specified name,
+/// like the address of the staking module.
+///
+/// Address is generated using the same algorithm as [`NewModuleAddress`] in Cosmos SDK
+/// and is converted to human-readable format using provided [Api],
+/// so it has the prefix of the simulated chain.
+///
+/// [`NewModuleAddress`]: https://github.com/cosmos/cosmos-sdk/blob/v0.50.10/x/auth/types/account.go
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::testing::MockApi;
+/// use cosmwasm_std::Api;
+/// use cw_multi_test::module_address;
+///
+/// let api = MockApi::default();
+/// let addr = module_address(&api, "bonded_tokens_pool").unwrap();
+///
+/// assert!(addr.as_str().starts_with("cosmwasm1"));
+/// assert!(api.addr_validate(addr.as_str()).is_ok());
+/// ```
+pub fn module_address(api: &dyn Api, name: &str) -> AnyResult<Addr> {
+    let canonical = CanonicalAddr::from(&Sha256::digest(name.as_bytes())[..20]);
+    Ok(api.addr_humanize(&canonical)?)
 }
 
 /// Returns non-predictable contract address.
 ///
 /// Address is generated using the same algorithm as [`BuildContractAddressClassic`]
-/// implementation in `wasmd`.
+/// implementation in `wasmd`. This is the address generated by [SimpleAddressGenerator]
+/// for the contract instantiated from the code with specified identifier,
+/// where `instance_id` is the number of contracts instantiated before.
 ///
 /// [`BuildContractAddressClassic`]:https://github.com/CosmWasm/wasmd/blob/3b6512c9f154995188ead84ab3bd9e034b49a0f3/x/wasm/keeper/addresses.go#L35-L41
-fn instantiate_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::Api;
+/// use cw_multi_test::{instantiate_address, App, ContractWrapper, Executor};
+/// # use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+/// # fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> { Ok(Response::default()) }
+/// # fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> { Ok(Response::default()) }
+/// # fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> { Ok(Binary::default()) }
+///
+/// let mut app = App::default();
+/// let creator = app.api().addr_make("creator");
+/// let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+///
+/// // the address of the first contract can be predicted before instantiation
+/// let expected_addr = app.api().addr_humanize(&instantiate_address(code_id, 0)).unwrap();
+///
+/// let contract_addr = app
+///     .instantiate_contract(code_id, creator, &Empty {}, &[], "label", None)
+///     .unwrap();
+/// assert_eq!(expected_addr, contract_addr);
+/// ```
+pub fn instantiate_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
     let mut key = Vec::<u8>::new();
     key.extend_from_slice(b"wasm\0");
     key.extend_from_slice(&code_id.to_be_bytes());
     key.extend_from_slice(&instance_id.to_be_bytes());
+    module_hash(&key)
+}
+
+/// Returns the hash of the key, prefixed with the hash of the `module` type,
+/// like the address of a module-owned account in Cosmos SDK.
+fn module_hash(key: &[u8]) -> CanonicalAddr {
     let module = Sha256::digest("module".as_bytes());
     Sha256::new()
         .chain(module)
@@ -195,3 +347,188 @@ fn instantiate_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
 pub struct SimpleAddressGenerator;
 
 impl AddressGenerator for SimpleAddressGenerator {}
+
+/// Storage namespace of the addresses already generated by [DeterministicAddressGenerator].
+const NAMESPACE_DETERMINISTIC_ADDRESSES: &[u8] = b"deterministic_addresses";
+
+/// Contract address generator independent of the order of instantiations.
+///
+/// Addresses generated by [SimpleAddressGenerator] depend on the number of contracts
+/// instantiated before, so adding or removing an instantiation changes the addresses
+/// of all contracts instantiated later. [DeterministicAddressGenerator] derives the address
+/// of the contract instantiated with `WasmMsg::Instantiate` solely from the code identifier,
+/// label and creator of the contract. Instantiating two contracts with the same code identifier,
+/// label and creator fails with an error.
+///
+/// Addresses of contracts instantiated with `WasmMsg::Instantiate2` are derived from
+/// the checksum, creator and salt (just like in `wasmd`), so they are also independent
+/// of the order of instantiations. To instantiate multiple contracts with the same
+/// code identifier, label and creator, use `WasmMsg::Instantiate2` with different salts.
+///
+/// # Example
+///
+/// ```
+/// use cw_multi_test::{no_init, AppBuilder, DeterministicAddressGenerator, WasmKeeper};
+///
+/// let wasm_keeper = WasmKeeper::new().with_address_generator(DeterministicAddressGenerator);
+///
+/// let app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+/// ```
+pub struct DeterministicAddressGenerator;
+
+impl AddressGenerator for DeterministicAddressGenerator {
+    fn contract_address_with_info(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        info: &ContractInstantiationInfo,
+    ) -> AnyResult<Addr> {
+        let creator = api.addr_canonicalize(info.creator.as_str())?;
+        let mut key = Vec::<u8>::new();
+        key.extend_from_slice(b"wasm\0");
+        key.extend_from_slice(&info.code_id.to_be_bytes());
+        key.extend_from_slice(&(creator.len() as u64).to_be_bytes());
+        key.extend_from_slice(creator.as_slice());
+        key.extend_from_slice(info.label.as_bytes());
+        let addr = api.addr_humanize(&module_hash(&key))?;
+        let mut generated = prefixed(storage, NAMESPACE_DETERMINISTIC_ADDRESSES);
+        if generated.get(addr.as_bytes()).is_some() {
+            bail!(
+                "contract with code id {}, label '{}' and creator {} already instantiated at {}",
+                info.code_id,
+                info.label,
+                info.creator,
+                addr
+            );
+        }
+        generated.set(addr.as_bytes(), &[1]);
+        Ok(addr)
+    }
+}
+
+/// Handling of the canonical address of the creator while deriving `Instantiate2` addresses.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CreatorCanonicalization {
+    /// The canonical address returned by [Api::addr_canonicalize] is used as is,
+    /// like in `wasmd` and chains built on it (e.g. Juno, Osmosis, Neutron),
+    /// where accounts have 20-byte and contracts have 32-byte canonical addresses.
+    #[default]
+    Full,
+    /// Only the first 20 bytes of the canonical address are used, like on chains
+    /// with Ethereum-style 20-byte accounts (e.g. Injective), when the creators
+    /// used in tests have longer canonical addresses, like those made by [MockApi].
+    Truncated20,
+}
+
+/// Contract address generator with configurable derivation of `Instantiate2` addresses.
+///
+/// Addresses of contracts instantiated with `WasmMsg::Instantiate` are generated
+/// like by [SimpleAddressGenerator]. Addresses of contracts instantiated with `WasmMsg::Instantiate2`
+/// are derived with [predict](Self::predict), so tests can predict them using
+/// the same code path.
+///
+/// # Example
+///
+/// ```
+/// use cw_multi_test::{
+///     no_init, AppBuilder, CreatorCanonicalization, Instantiate2AddressGenerator, WasmKeeper,
+/// };
+///
+/// let address_generator = Instantiate2AddressGenerator::new()
+///     .with_creator_canonicalization(CreatorCanonicalization::Truncated20);
+/// let wasm_keeper = WasmKeeper::new().with_address_generator(address_generator);
+///
+/// let app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Instantiate2AddressGenerator {
+    creator_canonicalization: CreatorCanonicalization,
+}
+
+impl Instantiate2AddressGenerator {
+    /// Creates a generator deriving `Instantiate2` addresses like `wasmd`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the handling of the canonical address of the creator.
+    pub fn with_creator_canonicalization(
+        mut self,
+        creator_canonicalization: CreatorCanonicalization,
+    ) -> Self {
+        self.creator_canonicalization = creator_canonicalization;
+        self
+    }
+
+    /// Returns the address of the contract instantiated with `WasmMsg::Instantiate2`
+    /// from the code with specified checksum, by specified creator, with specified salt.
+    pub fn predict(
+        &self,
+        api: &dyn Api,
+        checksum: &[u8],
+        creator: &Addr,
+        salt: &[u8],
+    ) -> AnyResult<Addr> {
+        let creator = api.addr_canonicalize(creator.as_str())?;
+        let creator = match self.creator_canonicalization {
+            CreatorCanonicalization::Full => creator,
+            CreatorCanonicalization::Truncated20 => {
+                CanonicalAddr::from(&creator.as_slice()[..creator.len().min(20)])
+            }
+        };
+        humanized_instantiate2_address(api, checksum, &creator, salt)
+    }
+}
+
+impl AddressGenerator for Instantiate2AddressGenerator {
+    fn predictable_contract_address_with_info(
+        &self,
+        api: &dyn Api,
+        _storage: &mut dyn Storage,
+        info: &ContractInstantiationInfo,
+        salt: &[u8],
+    ) -> AnyResult<Addr> {
+        self.predict(api, info.checksum.as_slice(), info.creator, salt)
+    }
+}
+
+/// Returns the address of the contract instantiated with `WasmMsg::Instantiate2`
+/// from the code with specified checksum, by specified creator, with specified salt.
+///
+/// This is the address generated by [SimpleAddressGenerator] and by the default
+/// [Instantiate2AddressGenerator], derived with `instantiate2_address` from `cosmwasm-std`
+/// and converted to human-readable format using provided [Api].
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::testing::MockApi;
+/// use cosmwasm_std::Checksum;
+/// use cw_multi_test::predict_instantiate2_address;
+///
+/// let api = MockApi::default();
+/// let checksum = Checksum::generate(&[1]);
+/// let creator = api.addr_make("creator");
+///
+/// let addr = predict_instantiate2_address(&api, checksum.as_slice(), &creator, b"salt").unwrap();
+/// assert!(addr.as_str().starts_with("cosmwasm1"));
+/// ```
+pub fn predict_instantiate2_address(
+    api: &dyn Api,
+    checksum: &[u8],
+    creator: &Addr,
+    salt: &[u8],
+) -> AnyResult<Addr> {
+    Instantiate2AddressGenerator::new().predict(api, checksum, creator, salt)
+}
+
+/// Derives the `Instantiate2` address and converts it to human-readable format.
+fn humanized_instantiate2_address(
+    api: &dyn Api,
+    checksum: &[u8],
+    creator: &CanonicalAddr,
+    salt: &[u8],
+) -> AnyResult<Addr> {
+    let canonical_addr = instantiate2_address(checksum, creator, salt)?;
+    Ok(api.addr_humanize(&canonical_addr)?)
+}
@@ -1,28 +1,56 @@
+use crate::address_book::{load_users, register_user, AddressBook, AddressBookContract};
+use crate::addresses::module_address;
 use crate::bank::{Bank, BankKeeper, BankSudo};
+use crate::call_stats::CallStats;
+use crate::code_id::CodeId;
+#[cfg(any(feature = "stargate", feature = "cosmwasm_2_0"))]
+use crate::contract_state::{query_all_contract_state, QUERY_ALL_CONTRACT_STATE};
 use crate::contracts::Contract;
-use crate::error::{bail, AnyResult};
+use crate::error::{bail, AnyResult, Error};
 use crate::executor::{AppResponse, Executor};
+use crate::faucet::{Faucet, FaucetMinted};
 use crate::featured::staking::{
     Distribution, DistributionKeeper, StakeKeeper, Staking, StakingSudo,
 };
 use crate::gov::Gov;
 use crate::ibc::Ibc;
+use crate::layout::check_layout_versions;
 use crate::module::{FailingModule, Module};
+use crate::operation_log::{Operation, OperationLog, Outcome};
 use crate::prefixed_storage::{
     prefixed, prefixed_multilevel, prefixed_multilevel_read, prefixed_read,
 };
+use crate::provenance::{tag_events, ModuleKind};
+use crate::query_kinds::QueryKind;
+use crate::reply_traces::ReplyTrace;
+use crate::schedules::{
+    Schedule, ScheduledExecution, ScheduledExecutionResult, ScheduledResult, ScheduledSudo,
+};
+use crate::storage_access::StorageAccessStats;
+use crate::storage_stats::{StorageBudget, StorageStats};
 use crate::transactions::transactional;
-use crate::wasm::{ContractData, Wasm, WasmKeeper, WasmSudo};
+use crate::wasm::{ContractData, ContractHistoryEntry, Wasm, WasmKeeper, WasmSudo};
+use crate::wasm_permissions::{
+    check_upload, load_wasm_permissions, WasmPermissions, WasmPermissionsSudo,
+};
 use crate::{AppBuilder, GovFailingModule, IbcFailingModule, Stargate, StargateFailing};
 use cosmwasm_std::testing::{MockApi, MockStorage};
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Api, Binary, BlockInfo, ContractResult, CosmosMsg, CustomMsg,
-    CustomQuery, Empty, Querier, QuerierResult, QuerierWrapper, QueryRequest, Record, Storage,
-    SystemError, SystemResult,
+    from_json, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, Coin, ContractResult,
+    CosmosMsg, CustomMsg, CustomQuery, Empty, Event, Order, Querier, QuerierResult, QuerierWrapper,
+    QueryRequest, Record, StdResult, Storage, SystemError, SystemResult, Timestamp, Uint128,
+    WasmQuery,
 };
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::any::{type_name, TypeId};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Name of the governance module account, the same as in Cosmos SDK.
+const GOV_MODULE_NAME: &str = "gov";
 
 /// Advances the blockchain environment to the next block in tests, enabling developers to simulate
 /// time-dependent contract behaviors and block-related triggers efficiently.
@@ -66,6 +94,48 @@ pub struct App<
     pub(crate) api: Api,
     pub(crate) storage: Storage,
     pub(crate) block: BlockInfo,
+    #[allow(clippy::type_complexity)]
+    pub(crate) scheduled_changes:
+        Vec<ScheduledChange<Bank, Api, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate>>,
+    pub(crate) upgrade_events: Vec<Event>,
+    pub(crate) gov_authority: Option<Addr>,
+    pub(crate) scheduled_sudos: Vec<ScheduledSudo>,
+    pub(crate) next_schedule_id: u64,
+    pub(crate) last_scheduled_results: Vec<ScheduledResult>,
+    pub(crate) scheduled_executions: Vec<ScheduledExecution>,
+    pub(crate) auto_execute_due: bool,
+    pub(crate) last_due_executions: Vec<ScheduledExecutionResult>,
+    pub(crate) block_jitter: Option<BlockJitterFn>,
+    pub(crate) message_index: u64,
+    pub(crate) operation_log: Option<OperationLog>,
+    pub(crate) faucet_minted: Option<FaucetMinted>,
+    pub(crate) reversed_setup: bool,
+    /// Flag indicating if the storage layout versions were already checked successfully,
+    /// reset whenever the storage is handed out for modification.
+    pub(crate) layout_checked: Cell<bool>,
+}
+
+/// Function deriving the block observed by a top-level message, see [AppBuilder::with_block_jitter].
+pub(crate) type BlockJitterFn = Rc<dyn Fn(&BlockInfo, u64) -> BlockInfo>;
+
+/// Function changing the behavior of modules, see [App::schedule_behavior_change].
+type BehaviorChangeFn<Bank, Api, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate> = Rc<
+    dyn Fn(
+        &mut Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate>,
+        &Api,
+        &mut dyn Storage,
+    ),
+>;
+
+/// Change of modules' behavior scheduled at specified block height.
+#[derive(Clone)]
+pub(crate) struct ScheduledChange<Bank, Api, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate> {
+    /// Block height at which the change is applied.
+    height: u64,
+    /// Name of the change, reported in the `upgrade` event.
+    name: String,
+    /// Function applying the change.
+    change: BehaviorChangeFn<Bank, Api, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate>,
 }
 
 /// No-op application initialization function.
@@ -147,6 +217,12 @@ where
     StargateT: Stargate,
 {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        if let Err(err) = self.check_layout_versions() {
+            return SystemResult::Err(SystemError::InvalidRequest {
+                error: err.to_string(),
+                request: bin_request.into(),
+            });
+        }
         self.router
             .querier(&self.api, &self.storage, &self.block)
             .raw_query(bin_request)
@@ -210,10 +286,64 @@ where
 
     /// Returns a mutable reference to application's storage.
     pub fn storage_mut(&mut self) -> &mut StorageT {
+        // the storage may be replaced with state written in other layout versions
+        self.layout_checked.set(false);
         &mut self.storage
     }
 
+    /// Checks if the state of every built-in keeper is written in the current layout version.
+    ///
+    /// The storage is checked only until the check succeeds, the state written
+    /// by the keepers of this application always has the current layout version.
+    fn check_layout_versions(&self) -> AnyResult<()> {
+        if !self.layout_checked.get() {
+            check_layout_versions(&self.storage)?;
+            self.layout_checked.set(true);
+        }
+        Ok(())
+    }
+
+    /// Returns the log of operations applied to the application,
+    /// `None` when not enabled with [AppBuilder::with_operation_log].
+    pub fn operation_log(&self) -> Option<&OperationLog> {
+        self.operation_log.as_ref()
+    }
+
+    /// Appends the operation with the outcome of specified result to the operation log, if enabled.
+    fn record_operation<T>(
+        &mut self,
+        operation: impl FnOnce() -> Operation,
+        result: &AnyResult<T>,
+    ) {
+        if let Some(operation_log) = &mut self.operation_log {
+            operation_log.record(operation(), Outcome::of(result));
+        }
+    }
+
     /// Initializes modules.
+    ///
+    /// Provides the same access to the router, API and storage as the initialization
+    /// function passed to [AppBuilder::build](crate::AppBuilder::build), but at any point
+    /// after the application was built, e.g. to set balances or add validators in the middle
+    /// of a test. As it requires exclusive access to the application,
+    /// it can not be called while any message or query is processed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::coins;
+    /// use cw_multi_test::App;
+    ///
+    /// let mut app = App::default();
+    /// let owner = app.api().addr_make("owner");
+    ///
+    /// app.init_modules(|router, _, storage| {
+    ///     router
+    ///         .bank
+    ///         .init_balance(storage, &owner, coins(100, "uatom"))
+    ///         .unwrap()
+    /// });
+    /// ```
     pub fn init_modules<F, T>(&mut self, init_fn: F) -> T
     where
         F: FnOnce(
@@ -225,7 +355,83 @@ where
         init_fn(&mut self.router, &self.api, &mut self.storage)
     }
 
+    /// Schedules a change of modules' behavior, like a chain upgrade, at the specified block height.
+    ///
+    /// The change is applied when the block height reaches the specified value
+    /// in [update_block](Self::update_block) or [set_block](Self::set_block),
+    /// before processing the staking queue. Changes scheduled for the same height are applied
+    /// in the order they were scheduled. Each applied change emits an `upgrade` event
+    /// with the name and height of the change, see [upgrade_events](Self::upgrade_events).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{next_block, App};
+    ///
+    /// let mut app = App::default();
+    /// let upgrade_height = app.block_info().height + 1;
+    ///
+    /// app.schedule_behavior_change(upgrade_height, "v2", |router, _, _| {
+    ///     router.wasm_mut().set_max_query_depth(5);
+    /// });
+    ///
+    /// app.update_block(next_block);
+    /// assert_eq!(1, app.upgrade_events().len());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when the operation log is enabled with [AppBuilder::with_operation_log],
+    /// changes applied by closures can not be recorded.
+    pub fn schedule_behavior_change<F>(&mut self, height: u64, name: impl Into<String>, change: F)
+    where
+        F: Fn(
+                &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+                &ApiT,
+                &mut dyn Storage,
+            ) + 'static,
+    {
+        if self.operation_log.is_some() {
+            panic!("behavior changes can not be scheduled while the operation log is enabled");
+        }
+        // keep changes ordered by height, preserving the scheduling order for the same height
+        let index = self
+            .scheduled_changes
+            .partition_point(|scheduled| scheduled.height <= height);
+        self.scheduled_changes.insert(
+            index,
+            ScheduledChange {
+                height,
+                name: name.into(),
+                change: Rc::new(change),
+            },
+        );
+    }
+
+    /// Returns `upgrade` events emitted by all applied behavior changes,
+    /// see [schedule_behavior_change](Self::schedule_behavior_change).
+    pub fn upgrade_events(&self) -> &[Event] {
+        &self.upgrade_events
+    }
+
+    /// Applies all scheduled behavior changes up to the current block height.
+    fn apply_scheduled_changes(&mut self) {
+        let count = self
+            .scheduled_changes
+            .partition_point(|scheduled| scheduled.height <= self.block.height);
+        for scheduled in self.scheduled_changes.drain(..count) {
+            (scheduled.change)(&mut self.router, &self.api, &mut self.storage);
+            self.upgrade_events.push(
+                Event::new("upgrade")
+                    .add_attribute("name", scheduled.name)
+                    .add_attribute("height", scheduled.height.to_string()),
+            );
+        }
+    }
+
     /// Queries a module.
+    ///
+    /// Read-only counterpart of [init_modules](Self::init_modules).
     pub fn read_module<F, T>(&self, query_fn: F) -> T
     where
         F: FnOnce(
@@ -258,20 +464,71 @@ where
 {
     /// Registers contract code (like uploading wasm bytecode on a chain),
     /// so it can later be used to instantiate a contract.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the creator is not allowed to store code,
+    /// see [AppBuilder::with_wasm_permissions].
     pub fn store_code(&mut self, code: Box<dyn Contract<CustomT::ExecT, CustomT::QueryT>>) -> u64 {
-        self.router
-            .wasm
-            .store_code(MockApi::default().addr_make("creator"), code)
+        self.store_code_with_creator(MockApi::default().addr_make("creator"), code)
     }
 
     /// Registers contract code (like [store_code](Self::store_code)),
     /// but takes the address of the code creator as an additional argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the creator is not allowed to store code,
+    /// use [try_store_code_with_creator](Self::try_store_code_with_creator) to handle the error.
     pub fn store_code_with_creator(
         &mut self,
         creator: Addr,
         code: Box<dyn Contract<CustomT::ExecT, CustomT::QueryT>>,
     ) -> u64 {
-        self.router.wasm.store_code(creator, code)
+        self.try_store_code_with_creator(creator, code)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Registers contract code (like [store_code_with_creator](Self::store_code_with_creator)),
+    /// but returns an error when the creator is not allowed to store code,
+    /// see [AppBuilder::with_wasm_permissions].
+    pub fn try_store_code_with_creator(
+        &mut self,
+        creator: Addr,
+        code: Box<dyn Contract<CustomT::ExecT, CustomT::QueryT>>,
+    ) -> AnyResult<u64> {
+        self.store_code_internal(None, creator, code)
+    }
+
+    /// Registers contract code (like [store_code](Self::store_code)),
+    /// under the name used to find the code when replaying the [OperationLog],
+    /// see [replay](crate::replay).
+    ///
+    /// # Panics
+    ///
+    /// Panics when the default creator is not allowed to store code.
+    pub fn store_code_with_name(
+        &mut self,
+        name: impl Into<String>,
+        code: Box<dyn Contract<CustomT::ExecT, CustomT::QueryT>>,
+    ) -> u64 {
+        let creator = MockApi::default().addr_make("creator");
+        self.store_code_internal(Some(name.into()), creator, code)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Checks the upload permission of the creator, stores the code
+    /// and appends it to the operation log.
+    fn store_code_internal(
+        &mut self,
+        name: Option<String>,
+        creator: Addr,
+        code: Box<dyn Contract<CustomT::ExecT, CustomT::QueryT>>,
+    ) -> AnyResult<u64> {
+        check_upload(&self.storage, &creator)?;
+        let code_id = self.router.wasm.store_code(creator.clone(), code);
+        self.record_stored_code(name, creator, code_id);
+        Ok(code_id)
     }
 
     /// Registers contract code (like [store_code_with_creator](Self::store_code_with_creator)),
@@ -282,12 +539,36 @@ where
         code_id: u64,
         code: Box<dyn Contract<CustomT::ExecT, CustomT::QueryT>>,
     ) -> AnyResult<u64> {
-        self.router.wasm.store_code_with_id(creator, code_id, code)
+        check_upload(&self.storage, &creator)?;
+        let result = self
+            .router
+            .wasm
+            .store_code_with_id(creator.clone(), code_id, code);
+        if let Ok(code_id) = result {
+            self.record_stored_code(None, creator, code_id);
+        }
+        result
+    }
+
+    /// Appends the stored code to the operation log, if enabled.
+    fn record_stored_code(&mut self, name: Option<String>, creator: Addr, code_id: u64) {
+        let operation = || Operation::StoreCode {
+            name,
+            creator,
+            code_id,
+        };
+        self.record_operation(operation, &Ok(()));
     }
 
     /// Duplicates the contract code identified by `code_id` and returns
     /// the identifier of the newly created copy of the contract code.
     ///
+    /// The copy has the same checksum as the original code, so instantiating both codes
+    /// with `Instantiate2` by the same creator and with the same salt fails,
+    /// because both instances get the same address.
+    /// Use [duplicate_code_with_new_checksum](Self::duplicate_code_with_new_checksum)
+    /// when the copy should behave like a separately uploaded code.
+    ///
     /// # Examples
     ///
     /// ```
@@ -334,19 +615,217 @@ where
     /// assert_eq!("code id 100: no such code", app.duplicate_code(100).unwrap_err().to_string());
     /// ```
     pub fn duplicate_code(&mut self, code_id: u64) -> AnyResult<u64> {
+        self.check_duplicate_upload(code_id)?;
         self.router.wasm.duplicate_code(code_id)
     }
 
+    /// Duplicates the contract code identified by `code_id` and returns
+    /// the identifier of the newly created copy of the contract code.
+    ///
+    /// Unlike [duplicate_code](Self::duplicate_code), the copy gets a new checksum
+    /// from the checksum generator, so it is reported with a different checksum
+    /// in `CodeInfo` queries and gets different `Instantiate2` addresses.
+    pub fn duplicate_code_with_new_checksum(&mut self, code_id: u64) -> AnyResult<u64> {
+        self.check_duplicate_upload(code_id)?;
+        self.router.wasm.duplicate_code_with_new_checksum(code_id)
+    }
+
+    /// Fails when the creator of the code is not allowed to store code,
+    /// duplicating the code counts as storing it again by the same creator.
+    fn check_duplicate_upload(&self, code_id: u64) -> AnyResult<()> {
+        if load_wasm_permissions(&self.storage)? != WasmPermissions::Permissionless {
+            check_upload(&self.storage, &self.router.wasm.code_creator(code_id)?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the address of the governance module account, set with
+    /// [AppBuilder::with_gov_authority] or derived from the `gov` module name by default.
+    pub fn gov_authority(&self) -> AnyResult<Addr> {
+        match &self.gov_authority {
+            Some(gov_authority) => Ok(gov_authority.clone()),
+            None => module_address(&self.api, GOV_MODULE_NAME),
+        }
+    }
+
+    /// Registers the address of the user under specified name, listed in the exported address book.
+    pub fn register_address(&mut self, name: &str, addr: Addr) -> AnyResult<()> {
+        register_user(&mut self.storage, name, &addr)
+    }
+
+    /// Exports the address book with registered users, all instantiated contracts
+    /// and module accounts, to be shared with deployment scripts
+    /// or imported with [AppBuilder::with_address_book].
+    pub fn export_address_book(&self) -> AnyResult<AddressBook> {
+        let contracts = self
+            .router
+            .wasm
+            .contracts(&self.storage)?
+            .into_iter()
+            .map(|(address, data)| AddressBookContract {
+                label: data.label,
+                code_id: data.code_id,
+                address,
+            })
+            .collect();
+        let mut module_accounts = BTreeMap::new();
+        if let Ok(addr) = self.gov_authority() {
+            module_accounts.insert("gov".to_string(), addr);
+        }
+        if let Some(addr) = self.router.staking.module_account(&self.api) {
+            module_accounts.insert("staking".to_string(), addr);
+        }
+        Ok(AddressBook {
+            users: load_users(&self.storage)?,
+            contracts,
+            module_accounts,
+        })
+    }
+
     /// Returns `ContractData` for the contract with specified address.
     pub fn contract_data(&self, address: &Addr) -> AnyResult<ContractData> {
         self.router.wasm.contract_data(&self.storage, address)
     }
 
+    /// Returns the identifier of the code the contract with specified address is running.
+    pub fn contract_code_id(&self, address: &Addr) -> AnyResult<CodeId> {
+        Ok(self.contract_data(address)?.code_id.into())
+    }
+
+    /// Returns the addresses of all contracts instantiated from the code with specified identifier.
+    pub fn contracts_by_code(&self, code_id: impl Into<CodeId>) -> AnyResult<Vec<Addr>> {
+        let code_id = code_id.into();
+        Ok(self
+            .router
+            .wasm
+            .contracts(&self.storage)?
+            .into_iter()
+            .filter(|(_, data)| code_id == data.code_id)
+            .map(|(address, _)| address)
+            .collect())
+    }
+
     /// Returns a raw state dump of all key-values held by a contract with specified address.
     pub fn dump_wasm_raw(&self, address: &Addr) -> Vec<Record> {
         self.router.wasm.dump_wasm_raw(&self.storage, address)
     }
 
+    /// Returns the number and size of key-value pairs held by a contract with specified address,
+    /// grouped by `cw-storage-plus` namespaces.
+    pub fn contract_storage_stats(&self, address: &Addr) -> StorageStats {
+        StorageStats::from_records(&self.dump_wasm_raw(address))
+    }
+
+    /// Asserts that the contract with specified address keeps the storage budget.
+    ///
+    /// On failure, the number and size of key-value pairs are reported per namespace.
+    #[track_caller]
+    pub fn assert_storage_budget(&self, address: &Addr, budget: StorageBudget) {
+        let stats = self.contract_storage_stats(address);
+        if let Some(report) = stats.budget_violation(&budget) {
+            panic!(
+                "Storage budget exceeded by contract {}: {}",
+                address, report
+            );
+        }
+    }
+
+    /// Returns the history of the contract with specified address, recorded when enabled
+    /// with [WasmKeeper::with_contract_history].
+    pub fn contract_history(&self, address: &Addr) -> AnyResult<Vec<ContractHistoryEntry>> {
+        self.router.wasm.contract_history(&self.storage, address)
+    }
+
+    /// Returns statistics of contract calls, collected when enabled
+    /// with [AppBuilder::with_call_stats](crate::AppBuilder::with_call_stats).
+    pub fn call_stats(&self) -> CallStats {
+        self.router.wasm.call_stats()
+    }
+
+    /// Clears the statistics of contract calls.
+    pub fn reset_call_stats(&self) {
+        self.router.wasm.reset_call_stats()
+    }
+
+    /// Returns the setup steps in the order they should be performed,
+    /// reversed when enabled with [AppBuilder::with_reversed_setup].
+    pub fn in_setup_order<T>(&self, steps: impl IntoIterator<Item = T>) -> Vec<T> {
+        let mut steps: Vec<T> = steps.into_iter().collect();
+        if self.reversed_setup {
+            steps.reverse();
+        }
+        steps
+    }
+
+    /// Returns the faucet funding accounts with tokens of any denomination,
+    /// enabled with [AppBuilder::with_faucet].
+    pub fn faucet(&mut self) -> Faucet<'_, Self> {
+        Faucet::new(self)
+    }
+
+    /// Returns the numbers of storage operations performed by contracts during the last
+    /// top-level execution, counted when enabled with [AppBuilder::with_storage_access_stats].
+    ///
+    /// Statistics are cleared when a message or a sudo message is executed by the [App].
+    /// Queries sent directly to contracts, like with [App::wrap], are counted towards
+    /// the last execution.
+    pub fn last_execution_stats(&self) -> StorageAccessStats {
+        self.router.wasm.storage_access_stats()
+    }
+
+    /// Returns traces of reply invocations in order of invocation, recorded when enabled
+    /// with [AppBuilder::with_reply_traces](crate::AppBuilder::with_reply_traces).
+    pub fn reply_traces(&self) -> Vec<ReplyTrace> {
+        self.router.wasm.reply_traces()
+    }
+
+    /// Asserts that the payload of the last reply with specified identifier,
+    /// invoked on the specified contract, decoded from JSON equals the `expected` value.
+    ///
+    /// Requires reply traces to be enabled
+    /// with [AppBuilder::with_reply_traces](crate::AppBuilder::with_reply_traces).
+    #[track_caller]
+    pub fn assert_reply_payload<T>(&self, contract: &Addr, id: u64, expected: &T)
+    where
+        T: DeserializeOwned + PartialEq + Debug,
+    {
+        let traces = self.reply_traces();
+        let Some(trace) = traces
+            .iter()
+            .rev()
+            .find(|trace| trace.contract == *contract && trace.id == id)
+        else {
+            panic!(
+                "Expected a reply with id {} invoked on {}, but the reply traces are: {:?}",
+                id, contract, traces
+            );
+        };
+        match from_json::<T>(&trace.payload) {
+            Ok(actual) => assert!(
+                &actual == expected,
+                "Expected reply payload {:?}, but the payload is {:?} (id {} invoked on {})",
+                expected,
+                actual,
+                id,
+                contract
+            ),
+            Err(err) => panic!(
+                "Failed to decode reply payload {:?} as {}: {} (id {} invoked on {})",
+                trace.payload,
+                std::any::type_name::<T>(),
+                err,
+                id,
+                contract
+            ),
+        }
+    }
+
+    /// Returns warnings about re-entrant calls into contracts, recorded when enabled
+    /// with [WasmKeeper::with_reentrancy_guard] in [GuardMode::Warn](crate::GuardMode::Warn).
+    pub fn reentrancy_warnings(&self) -> Vec<String> {
+        self.router.wasm.reentrancy_warnings()
+    }
+
     /// Returns **read-only** storage for a contract with specified address.
     pub fn contract_storage<'a>(&'a self, contract_addr: &Addr) -> Box<dyn Storage + 'a> {
         self.router
@@ -406,20 +885,339 @@ where
 {
     /// Sets the initial block properties.
     pub fn set_block(&mut self, block: BlockInfo) {
+        let previous_height = self.block.height;
         self.block = block;
-        self.router
-            .staking
-            .process_queue(&self.api, &mut self.storage, &self.router, &self.block)
-            .unwrap();
+        self.record_block();
+        self.end_block(previous_height, false);
     }
 
     /// Updates the current block applying the specified closure, usually [next_block].
     pub fn update_block<F: Fn(&mut BlockInfo)>(&mut self, action: F) {
+        let previous_height = self.block.height;
         action(&mut self.block);
+        self.record_block();
+        self.end_block(previous_height, false);
+    }
+
+    /// Simulates a chain halt lasting the specified number of seconds.
+    ///
+    /// The block time is advanced by the duration of the halt, while the block height
+    /// is incremented only by one, like for the block produced when the chain restarts.
+    /// The block is processed once, like after [update_block](Self::update_block):
+    /// unbondings that matured during the halt are paid out and the contract sudo calls
+    /// scheduled for the restart block are executed once, flagged as
+    /// [late](crate::ScheduledResult::late).
+    pub fn simulate_halt(&mut self, duration_seconds: u64) {
+        let previous_height = self.block.height;
+        self.block.time = self.block.time.plus_seconds(duration_seconds);
+        self.block.height += 1;
+        self.record_block();
+        self.end_block(previous_height, true);
+    }
+
+    /// Appends the current block to the operation log, if enabled.
+    fn record_block(&mut self) {
+        let block = self.block.clone();
+        self.record_operation(|| Operation::SetBlock { block }, &Ok(()));
+    }
+
+    /// Processes the block change: applies scheduled behavior changes,
+    /// processes the staking queue and executes scheduled contract sudo calls,
+    /// flagged as late when the block is the restart block after a chain halt.
+    /// Due scheduled message executions are executed when enabled
+    /// with [AppBuilder::with_auto_execute_due].
+    fn end_block(&mut self, previous_height: u64, after_halt: bool) {
+        self.apply_scheduled_changes();
         self.router
             .staking
             .process_queue(&self.api, &mut self.storage, &self.router, &self.block)
             .unwrap();
+        self.execute_scheduled_sudos(previous_height, after_halt);
+        self.record_scheduled_results();
+        if self.auto_execute_due {
+            self.last_due_executions = self.execute_all_due();
+        }
+    }
+
+    /// Appends the scheduled contract sudo calls executed during the last block change
+    /// to the operation log, if enabled.
+    fn record_scheduled_results(&mut self) {
+        if let Some(operation_log) = &mut self.operation_log {
+            for scheduled in &self.last_scheduled_results {
+                let operation = Operation::ScheduledSudo {
+                    schedule_id: scheduled.schedule_id,
+                    height: scheduled.height,
+                };
+                operation_log.record(operation, Outcome::of_scheduled(&scheduled.result));
+            }
+        }
+    }
+
+    /// Schedules calls to the `sudo` entry-point of the contract, like a cron module does.
+    ///
+    /// Scheduled calls are executed in [update_block](Self::update_block)
+    /// and [set_block](Self::set_block), once for every block height crossed since the previous
+    /// block that matches the [Schedule], with the current block info. Calls scheduled for
+    /// the same height are executed in the order they were scheduled. Each call is executed
+    /// in its own transaction, so a failing call does not affect the other calls.
+    /// Results of the calls are available via [last_scheduled_results](Self::last_scheduled_results).
+    ///
+    /// Returns the identifier of the schedule, used to [remove](Self::remove_scheduled_sudo) it.
+    /// Fails when the contract is called every zero blocks.
+    pub fn schedule_sudo<U: Into<Addr>>(
+        &mut self,
+        contract_addr: U,
+        msg: Binary,
+        schedule: Schedule,
+    ) -> AnyResult<u64> {
+        if schedule == Schedule::EveryNBlocks(0) {
+            bail!("number of blocks between scheduled calls must be greater than zero");
+        }
+        let contract_addr = contract_addr.into();
+        let operation = || Operation::ScheduleSudo {
+            contract_addr: contract_addr.clone(),
+            msg: msg.clone(),
+            schedule,
+        };
+        self.record_operation(operation, &Ok(()));
+        self.next_schedule_id += 1;
+        self.scheduled_sudos.push(ScheduledSudo {
+            id: self.next_schedule_id,
+            contract_addr,
+            msg,
+            schedule,
+        });
+        Ok(self.next_schedule_id)
+    }
+
+    /// Removes the scheduled contract sudo calls with the specified identifier,
+    /// see [schedule_sudo](Self::schedule_sudo). Returns `false` when there is no such schedule.
+    pub fn remove_scheduled_sudo(&mut self, schedule_id: u64) -> bool {
+        let count = self.scheduled_sudos.len();
+        self.scheduled_sudos
+            .retain(|scheduled| scheduled.id != schedule_id);
+        self.record_operation(|| Operation::RemoveScheduledSudo { schedule_id }, &Ok(()));
+        self.scheduled_sudos.len() != count
+    }
+
+    /// Returns results of scheduled contract sudo calls executed during the last
+    /// [update_block](Self::update_block) or [set_block](Self::set_block),
+    /// ordered by block height and the scheduling order.
+    pub fn last_scheduled_results(&self) -> &[ScheduledResult] {
+        &self.last_scheduled_results
+    }
+
+    /// Schedules the execution of the message no earlier than at the specified block time,
+    /// like a timelock does.
+    ///
+    /// This is a test convenience, not a chain module: the message is executed
+    /// with [try_execute_scheduled](Self::try_execute_scheduled), with
+    /// [execute_all_due](Self::execute_all_due), or automatically when the block changes,
+    /// when enabled with [AppBuilder::with_auto_execute_due]. The message is executed
+    /// like with [execute](Executor::execute), with the block info current at that time.
+    ///
+    /// Returns the identifier of the scheduled execution, shared with the identifiers
+    /// returned from [schedule_sudo](Self::schedule_sudo). Fails when the operation log
+    /// is enabled with [AppBuilder::with_operation_log], scheduled executions are not recorded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::{coins, BankMsg};
+    /// use cw_multi_test::{App, IntoAddr};
+    ///
+    /// let timelock = "timelock".into_addr();
+    /// let mut app = App::new(|router, _, storage| {
+    ///     router.bank.init_balance(storage, &timelock, coins(100, "uatom")).unwrap();
+    /// });
+    /// let msg = BankMsg::Send {
+    ///     to_address: "recipient".into_addr().to_string(),
+    ///     amount: coins(100, "uatom"),
+    /// };
+    /// let not_before = app.block_info().time.plus_days(2);
+    /// let id = app.schedule_execute(timelock, msg.into(), not_before).unwrap();
+    ///
+    /// // too early
+    /// app.try_execute_scheduled(id).unwrap_err();
+    ///
+    /// app.update_block(|block| block.time = not_before);
+    /// app.try_execute_scheduled(id).unwrap();
+    /// ```
+    pub fn schedule_execute(
+        &mut self,
+        sender: Addr,
+        msg: CosmosMsg<CustomT::ExecT>,
+        not_before: Timestamp,
+    ) -> AnyResult<u64> {
+        if self.operation_log.is_some() {
+            bail!("messages can not be scheduled for execution while the operation log is enabled");
+        }
+        let msg = to_json_binary(&msg)?;
+        self.next_schedule_id += 1;
+        self.scheduled_executions.push(ScheduledExecution {
+            id: self.next_schedule_id,
+            sender,
+            msg,
+            not_before,
+        });
+        Ok(self.next_schedule_id)
+    }
+
+    /// Executes the scheduled message with the specified identifier,
+    /// see [schedule_execute](Self::schedule_execute).
+    ///
+    /// Fails when there is no such scheduled execution, or when the current block time
+    /// is earlier than the time the message was scheduled for. The scheduled execution
+    /// is removed only when the message is executed successfully, so a failed execution
+    /// can be retried.
+    pub fn try_execute_scheduled(&mut self, schedule_id: u64) -> AnyResult<AppResponse> {
+        let scheduled = match self
+            .scheduled_executions
+            .iter()
+            .find(|scheduled| scheduled.id == schedule_id)
+        {
+            Some(scheduled) => scheduled.clone(),
+            None => bail!("no scheduled execution with id {}", schedule_id),
+        };
+        if self.block.time < scheduled.not_before {
+            bail!(
+                "scheduled execution {} is not due before {}, current block time is {}",
+                schedule_id,
+                scheduled.not_before,
+                self.block.time
+            );
+        }
+        let msg: CosmosMsg<CustomT::ExecT> = from_json(&scheduled.msg)?;
+        let response = self.execute(scheduled.sender, msg)?;
+        self.scheduled_executions
+            .retain(|scheduled| scheduled.id != schedule_id);
+        Ok(response)
+    }
+
+    /// Executes all scheduled messages that are due at the current block time,
+    /// ordered by the time they were scheduled for and the scheduling order.
+    ///
+    /// Each message is executed in its own transaction, like with
+    /// [try_execute_scheduled](Self::try_execute_scheduled), so a failing execution
+    /// does not affect the other executions and is retried in the next call.
+    pub fn execute_all_due(&mut self) -> Vec<ScheduledExecutionResult> {
+        let mut due: Vec<(Timestamp, u64)> = self
+            .scheduled_executions
+            .iter()
+            .filter(|scheduled| scheduled.not_before <= self.block.time)
+            .map(|scheduled| (scheduled.not_before, scheduled.id))
+            .collect();
+        due.sort();
+        due.into_iter()
+            .map(|(_, schedule_id)| ScheduledExecutionResult {
+                schedule_id,
+                result: self
+                    .try_execute_scheduled(schedule_id)
+                    .map_err(|err| err.root_cause().to_string()),
+            })
+            .collect()
+    }
+
+    /// Cancels the scheduled message execution with the specified identifier,
+    /// see [schedule_execute](Self::schedule_execute).
+    /// Returns `false` when there is no such scheduled execution.
+    pub fn cancel_scheduled_execution(&mut self, schedule_id: u64) -> bool {
+        let count = self.scheduled_executions.len();
+        self.scheduled_executions
+            .retain(|scheduled| scheduled.id != schedule_id);
+        self.scheduled_executions.len() != count
+    }
+
+    /// Returns results of due scheduled message executions executed during the last
+    /// [update_block](Self::update_block) or [set_block](Self::set_block),
+    /// when enabled with [AppBuilder::with_auto_execute_due].
+    pub fn last_due_executions(&self) -> &[ScheduledExecutionResult] {
+        &self.last_due_executions
+    }
+
+    /// Disables answering queries of the specified kind, until enabled again
+    /// with [enable_query](Self::enable_query).
+    ///
+    /// Disabled queries fail with [SystemError::UnsupportedRequest], the same way
+    /// a chain without the queried module or query variant answers them.
+    /// This makes testing the feature detection of contracts possible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::coin;
+    /// use cw_multi_test::{App, BankQueryKind, IntoAddr, QueryKind};
+    ///
+    /// let owner = "owner".into_addr();
+    /// let mut app = App::new(|router, _, storage| {
+    ///     router.bank.init_balance(storage, &owner, vec![coin(100, "uatom")]).unwrap();
+    /// });
+    ///
+    /// app.disable_query(QueryKind::Bank(BankQueryKind::Balance));
+    /// let err = app.wrap().query_balance(&owner, "uatom").unwrap_err();
+    /// assert!(err.to_string().contains("Unsupported query type"));
+    ///
+    /// app.enable_query(QueryKind::Bank(BankQueryKind::Balance));
+    /// assert_eq!(100, app.wrap().query_balance(&owner, "uatom").unwrap().amount.u128());
+    /// ```
+    pub fn disable_query(&mut self, kind: QueryKind) {
+        self.router.disabled_queries.insert(kind);
+    }
+
+    /// Enables answering queries of the specified kind,
+    /// disabled with [disable_query](Self::disable_query).
+    pub fn enable_query(&mut self, kind: QueryKind) {
+        self.router.disabled_queries.remove(&kind);
+    }
+
+    /// Executes all scheduled contract sudo calls for block heights
+    /// crossed since the previous block height.
+    fn execute_scheduled_sudos(&mut self, previous_height: u64, late: bool) {
+        let mut due = vec![];
+        for scheduled in &self.scheduled_sudos {
+            for height in scheduled
+                .schedule
+                .heights(previous_height, self.block.height)
+            {
+                due.push((height, scheduled.clone()));
+            }
+        }
+        // schedules are kept in the scheduling order, the sort is stable
+        due.sort_by_key(|(height, _)| *height);
+        // calls scheduled at a single height are executed only once
+        self.scheduled_sudos
+            .retain(|scheduled| match scheduled.schedule {
+                Schedule::AtHeight(height) => {
+                    height <= previous_height || height > self.block.height
+                }
+                Schedule::EveryNBlocks(_) => true,
+            });
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+            last_scheduled_results,
+            ..
+        } = self;
+        last_scheduled_results.clear();
+        for (height, scheduled) in due {
+            let msg = WasmSudo {
+                contract_addr: scheduled.contract_addr.clone(),
+                message: scheduled.msg,
+            };
+            let result = transactional(&mut *storage, |write_cache, _| {
+                router.wasm.sudo(&*api, write_cache, router, block, msg)
+            })
+            .map_err(|err| err.root_cause().to_string());
+            last_scheduled_results.push(ScheduledResult {
+                schedule_id: scheduled.id,
+                contract: scheduled.contract_addr,
+                height,
+                result,
+                late,
+            });
+        }
     }
 
     /// Returns a copy of the current block info.
@@ -433,9 +1231,203 @@ where
         QuerierWrapper::new(self)
     }
 
+    /// Queries the contract with specified address, like [query_wasm_smart](QuerierWrapper::query_wasm_smart),
+    /// but returns the raw response and the error as returned by the contract.
+    ///
+    /// Queries made with [wrap](Self::wrap) report errors as strings, this function preserves
+    /// the error chain, so the contract's error can be retrieved by downcasting:
+    ///
+    /// ```
+    /// use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult};
+    /// use cw_multi_test::{App, ContractWrapper, Executor, IntoAddr};
+    ///
+    /// fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    ///     Ok(Response::new())
+    /// }
+    ///
+    /// fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    ///     Ok(Response::new())
+    /// }
+    ///
+    /// fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    ///     Err(StdError::generic_err("not supported"))
+    /// }
+    ///
+    /// let mut app = App::default();
+    /// let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    /// let contract_addr = app
+    ///     .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "label", None)
+    ///     .unwrap();
+    ///
+    /// let err = app.query_wasm_smart_raw(&contract_addr, &Empty {}).unwrap_err();
+    /// assert_eq!(
+    ///     &StdError::generic_err("not supported"),
+    ///     err.downcast_ref::<StdError>().unwrap()
+    /// );
+    /// ```
+    pub fn query_wasm_smart_raw<T: Serialize>(
+        &self,
+        contract_addr: impl Into<String>,
+        msg: &T,
+    ) -> AnyResult<Binary> {
+        self.query_wasm_smart_binary(contract_addr, to_json_binary(msg)?)
+    }
+
+    /// Queries the contract with specified address with the pre-serialized message,
+    /// like [query_wasm_smart_raw](Self::query_wasm_smart_raw), but the message is sent as is,
+    /// so contracts can be tested with malformed or unexpected queries.
+    pub fn query_wasm_smart_binary(
+        &self,
+        contract_addr: impl Into<String>,
+        msg: Binary,
+    ) -> AnyResult<Binary> {
+        self.check_layout_versions()?;
+        let request = WasmQuery::Smart {
+            contract_addr: contract_addr.into(),
+            msg,
+        };
+        self.router
+            .query(&self.api, &self.storage, &self.block, request.into())
+    }
+
+    /// Returns all balances of the specified address, ordered by denomination.
+    ///
+    /// Like on chain, an address without any tokens has no balances (empty list).
+    ///
+    /// # Panics
+    ///
+    /// Panics when the balances can not be queried, e.g. when the address is invalid.
+    #[track_caller]
+    pub fn balances(&self, address: impl Into<String>) -> Vec<Coin> {
+        let address = address.into();
+        self.wrap()
+            .query_all_balances(&address)
+            .unwrap_or_else(|err| panic!("Failed to query balances of {}: {}", address, err))
+    }
+
+    /// Asserts that the specified address holds exactly the `expected` amount
+    /// of tokens in the denomination of the `expected` coin.
+    ///
+    /// Zero amount is expected when the address has no tokens in this denomination.
+    #[track_caller]
+    pub fn assert_balance(&self, address: impl Into<String>, expected: &Coin) {
+        let address = address.into();
+        let balances = self.balances(&address);
+        let actual = balance_of(&balances, &expected.denom);
+        assert!(
+            actual == expected.amount,
+            "Expected balance of {} to be {}, but the balances are: {:?}",
+            address,
+            expected,
+            balances
+        );
+    }
+
+    /// Asserts that the specified address holds at least the `expected` amount
+    /// of tokens in the denomination of the `expected` coin.
+    #[track_caller]
+    pub fn assert_balance_at_least(&self, address: impl Into<String>, expected: &Coin) {
+        let address = address.into();
+        let balances = self.balances(&address);
+        let actual = balance_of(&balances, &expected.denom);
+        assert!(
+            actual >= expected.amount,
+            "Expected balance of {} to be at least {}, but the balances are: {:?}",
+            address,
+            expected,
+            balances
+        );
+    }
+
+    /// Asserts that the specified address holds no tokens in the specified denomination,
+    /// e.g. after sending its entire balance with [App::execute_contract_with_all_funds].
+    #[track_caller]
+    pub fn assert_emptied(&self, address: impl Into<String>, denom: &str) {
+        let address = address.into();
+        let balances = self.balances(&address);
+        let residual = balance_of(&balances, denom);
+        assert!(
+            residual.is_zero(),
+            "Expected balance of {} to be emptied of {}, but {}{} is left",
+            address,
+            denom,
+            residual,
+            denom
+        );
+    }
+
+    /// Executes the contract sending the entire balance of the sender in the specified denomination.
+    ///
+    /// The balance is queried right before the message is constructed, so the amount
+    /// does not have to be hard-coded in tests when it depends on earlier steps.
+    /// This is a test convenience, there is no such feature on chain, where
+    /// the exact amount must be specified in the message.
+    ///
+    /// Fails when the sender has no tokens in the specified denomination,
+    /// instead of executing the contract without any funds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::{coins, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+    /// use cw_multi_test::{App, ContractWrapper, Executor, IntoAddr};
+    ///
+    /// fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    ///     Ok(Response::new())
+    /// }
+    ///
+    /// fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    ///     Ok(Response::new())
+    /// }
+    ///
+    /// fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    ///     Ok(Binary::default())
+    /// }
+    ///
+    /// let owner = "owner".into_addr();
+    /// let mut app = App::new(|router, _, storage| {
+    ///     router.bank.init_balance(storage, &owner, coins(123, "uatom")).unwrap();
+    /// });
+    /// let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    /// let contract_addr = app
+    ///     .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "vault", None)
+    ///     .unwrap();
+    ///
+    /// app.execute_contract_with_all_funds(owner.clone(), contract_addr.clone(), &Empty {}, "uatom")
+    ///     .unwrap();
+    ///
+    /// app.assert_emptied(&owner, "uatom");
+    /// assert_eq!(coins(123, "uatom"), app.balances(&contract_addr));
+    /// ```
+    pub fn execute_contract_with_all_funds<T: Serialize + Debug>(
+        &mut self,
+        sender: Addr,
+        contract_addr: Addr,
+        msg: &T,
+        denom: &str,
+    ) -> AnyResult<AppResponse> {
+        let amount = self.wrap().query_balance(&sender, denom)?;
+        if amount.amount.is_zero() {
+            bail!("{} has no {} to send", sender, denom);
+        }
+        self.execute_contract(sender, contract_addr, msg, &[amount])
+    }
+
+    /// Returns the denominator of the staking token, as reported by the staking module.
+    ///
+    /// The denominator can be set with [AppBuilder::with_staking_denom]
+    /// or with [StakeKeeper::setup](crate::StakeKeeper::setup), by default it is `TOKEN`.
+    #[cfg(feature = "staking")]
+    pub fn staking_denom(&self) -> cosmwasm_std::StdResult<String> {
+        self.wrap().query_bonded_denom()
+    }
+
     /// Runs multiple CosmosMsg in one atomic operation.
     /// This will create a cache before the execution, so no state changes are persisted if any of them
     /// return an error. But all writes are persisted on success.
+    ///
+    /// Each message observes the block returned by the function set with
+    /// [AppBuilder::with_block_jitter], by default the current block.
     pub fn execute_multi(
         &mut self,
         sender: Addr,
@@ -444,19 +1436,42 @@ where
         // we need to do some caching of storage here, once in the entry point:
         // meaning, wrap current state, all writes go to a cache, only when execute
         // returns a success do we flush it (otherwise drop it)
+        self.check_layout_versions()?;
+        self.router.wasm.reset_storage_access_stats();
+        let recorded_msgs: Vec<Binary> = match self.operation_log {
+            Some(_) => msgs.iter().map(to_json_binary).collect::<StdResult<_>>()?,
+            None => vec![],
+        };
 
         let Self {
             block,
             router,
             api,
             storage,
+            block_jitter,
+            message_index,
+            ..
         } = self;
 
-        transactional(&mut *storage, |write_cache, _| {
+        let result = transactional(&mut *storage, |write_cache, _| {
             msgs.into_iter()
-                .map(|msg| router.execute(&*api, write_cache, block, sender.clone(), msg))
+                .map(|msg| {
+                    // nested messages observe the same block as the top-level message
+                    let block = match block_jitter {
+                        Some(block_jitter) => block_jitter(block, *message_index),
+                        None => block.clone(),
+                    };
+                    *message_index += 1;
+                    router.execute(&*api, write_cache, &block, sender.clone(), msg)
+                })
                 .collect()
-        })
+        });
+        let operation = || Operation::Execute {
+            sender,
+            msgs: recorded_msgs,
+        };
+        self.record_operation(operation, &result);
+        result
     }
 
     /// Call a smart contract in "sudo" mode.
@@ -467,21 +1482,30 @@ where
         contract_addr: U,
         msg: &T,
     ) -> AnyResult<AppResponse> {
+        self.check_layout_versions()?;
+        self.router.wasm.reset_storage_access_stats();
         let msg = WasmSudo {
             contract_addr: contract_addr.into(),
             message: to_json_binary(msg)?,
         };
+        let recorded_msg = msg.clone();
 
         let Self {
             block,
             router,
             api,
             storage,
+            ..
         } = self;
 
-        transactional(&mut *storage, |write_cache, _| {
+        let result = transactional(&mut *storage, |write_cache, _| {
             router.wasm.sudo(&*api, write_cache, router, block, msg)
-        })
+        });
+        let operation = || Operation::Sudo {
+            msg: recorded_msg.into(),
+        };
+        self.record_operation(operation, &result);
+        result
     }
 
     /// Runs arbitrary SudoMsg.
@@ -491,18 +1515,425 @@ where
         // we need to do some caching of storage here, once in the entry point:
         // meaning, wrap current state, all writes go to a cache, only when execute
         // returns a success do we flush it (otherwise drop it)
+        self.check_layout_versions()?;
+        self.router.wasm.reset_storage_access_stats();
+        let recorded_msg = msg.clone();
         let Self {
             block,
             router,
             api,
             storage,
+            ..
         } = self;
 
-        transactional(&mut *storage, |write_cache, _| {
+        let result = transactional(&mut *storage, |write_cache, _| {
             router.sudo(&*api, write_cache, block, msg)
-        })
+        });
+        self.record_operation(|| Operation::Sudo { msg: recorded_msg }, &result);
+        result
+    }
+
+    /// Runs the sudo message handler registered with the specified name,
+    /// see [Router::register_sudo_handler]. The message is serialized to JSON.
+    ///
+    /// This will create a cache before the execution, so no state changes are persisted if this
+    /// returns an error, but all are persisted on success.
+    /// Fails when there is no handler registered with the specified name.
+    pub fn sudo_named<T: Serialize>(&mut self, name: &str, msg: &T) -> AnyResult<AppResponse> {
+        let msg = to_json_binary(msg)?;
+        self.sudo_named_binary(name, msg)
     }
+
+    /// Runs the sudo message handler registered with the specified name,
+    /// passing the message already serialized to JSON.
+    pub(crate) fn sudo_named_binary(&mut self, name: &str, msg: Binary) -> AnyResult<AppResponse> {
+        self.check_layout_versions()?;
+        self.router.wasm.reset_storage_access_stats();
+        let recorded_msg = msg.clone();
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+            ..
+        } = self;
+        let Some(handler) = router.sudo_handlers.get(name).cloned() else {
+            let registered = router
+                .sudo_handlers
+                .keys()
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", ");
+            bail!(Error::unknown_sudo_handler(name, registered));
+        };
+        let result = transactional(&mut *storage, |write_cache, _| {
+            handler(&*api, write_cache, router, block, msg)
+        });
+        let operation = || Operation::SudoNamed {
+            name: name.to_string(),
+            msg: recorded_msg,
+        };
+        self.record_operation(operation, &result);
+        result
+    }
+
+    /// Executes a contract with the governance module account as the sender,
+    /// like a message executed by an accepted governance proposal.
+    ///
+    /// The address of the governance module account is returned by [App::gov_authority].
+    pub fn execute_as_gov<T: Serialize + Debug>(
+        &mut self,
+        contract_addr: Addr,
+        msg: &T,
+        send_funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        let sender = self.gov_authority()?;
+        self.execute_contract(sender, contract_addr, msg, send_funds)
+    }
+
+    /// Sends tokens from one account to multiple recipients at once,
+    /// using [BankSudo::MultiSend] message.
+    ///
+    /// No tokens are transferred if any of the transfers fails.
+    pub fn multi_send_tokens(
+        &mut self,
+        from: Addr,
+        outputs: &[(Addr, Vec<Coin>)],
+    ) -> AnyResult<AppResponse> {
+        self.sudo(
+            BankSudo::MultiSend {
+                from_address: from.to_string(),
+                outputs: outputs
+                    .iter()
+                    .map(|(to, amount)| (to.to_string(), amount.clone()))
+                    .collect(),
+            }
+            .into(),
+        )
+    }
+
+    /// Moves tokens from any account without the owner's involvement,
+    /// using [BankSudo::ForceTransfer] message.
+    ///
+    /// Tokens held by a contract are moved without executing the contract.
+    pub fn force_send_tokens(
+        &mut self,
+        from: Addr,
+        to: Addr,
+        amount: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.sudo(
+            BankSudo::ForceTransfer {
+                from: from.to_string(),
+                to: to.to_string(),
+                amount: amount.to_vec(),
+            }
+            .into(),
+        )
+    }
+
+    /// Sends tokens from one account to multiple recipients, executing one `BankMsg::Send`
+    /// message per recipient in a single atomic [execute_multi](Self::execute_multi).
+    ///
+    /// Coins with zero amount are skipped, as well as recipients left with no coins to send.
+    /// No tokens are transferred if any of the transfers fails.
+    pub fn send_tokens_multi(
+        &mut self,
+        sender: Addr,
+        outputs: &[(Addr, Vec<Coin>)],
+    ) -> AnyResult<Vec<AppResponse>> {
+        let msgs = outputs
+            .iter()
+            .filter_map(|(recipient, amount)| {
+                let amount = non_zero_coins(amount);
+                (!amount.is_empty()).then(|| {
+                    BankMsg::Send {
+                        to_address: recipient.to_string(),
+                        amount,
+                    }
+                    .into()
+                })
+            })
+            .collect();
+        self.execute_multi(sender, msgs)
+    }
+
+    /// Transfers all tokens held by one account to another account,
+    /// returns the transferred coins.
+    ///
+    /// When the account holds no tokens, nothing is transferred and an empty list is returned.
+    pub fn drain_tokens(&mut self, from: Addr, to: Addr) -> AnyResult<Vec<Coin>> {
+        let amount = non_zero_coins(&self.wrap().query_all_balances(&from)?);
+        self.send_tokens_multi(from, &[(to, amount.clone())])?;
+        Ok(amount)
+    }
+}
+
+/// Storage that can be copied into an independent instance, used by [App::fork].
+pub trait SnapshotStorage: Storage {
+    /// Returns a new storage containing all records of this storage.
+    fn snapshot(&self) -> Self;
 }
+
+impl SnapshotStorage for MockStorage {
+    /// Copies all records into a new storage.
+    fn snapshot(&self) -> Self {
+        let mut storage = MockStorage::new();
+        for (key, value) in self.range(None, None, Order::Ascending) {
+            storage.set(&key, &value);
+        }
+        storage
+    }
+}
+
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+    App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+where
+    BankT: Clone,
+    ApiT: Clone,
+    StorageT: SnapshotStorage,
+    CustomT: Clone,
+    WasmT: Clone,
+    StakingT: Clone,
+    DistrT: Clone,
+    IbcT: Clone,
+    GovT: Clone,
+    StargateT: Clone,
+{
+    /// Creates an independent copy of this application, with identical storage and block info.
+    ///
+    /// Contract codes stored in [WasmKeeper] are shared with the fork, all other state is copied,
+    /// so executing messages in one application does not affect the other. This way one prepared
+    /// chain state can be used to explore several scenarios.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::coins;
+    /// use cw_multi_test::{App, IntoAddr};
+    ///
+    /// let owner = "owner".into_addr();
+    /// let app = App::new(|router, _, storage| {
+    ///     router.bank.init_balance(storage, &owner, coins(100, "uatom")).unwrap();
+    /// });
+    ///
+    /// let mut fork = app.fork();
+    /// fork.init_modules(|router, _, storage| {
+    ///     router.bank.init_balance(storage, &owner, coins(50, "uatom")).unwrap();
+    /// });
+    ///
+    /// assert_eq!(coins(100, "uatom"), app.balances(&owner));
+    /// assert_eq!(coins(50, "uatom"), fork.balances(&owner));
+    /// ```
+    pub fn fork(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+            api: self.api.clone(),
+            storage: self.storage.snapshot(),
+            block: self.block.clone(),
+            scheduled_changes: self.scheduled_changes.clone(),
+            upgrade_events: self.upgrade_events.clone(),
+            gov_authority: self.gov_authority.clone(),
+            scheduled_sudos: self.scheduled_sudos.clone(),
+            next_schedule_id: self.next_schedule_id,
+            last_scheduled_results: self.last_scheduled_results.clone(),
+            scheduled_executions: self.scheduled_executions.clone(),
+            auto_execute_due: self.auto_execute_due,
+            last_due_executions: self.last_due_executions.clone(),
+            block_jitter: self.block_jitter.clone(),
+            message_index: self.message_index,
+            operation_log: self.operation_log.clone(),
+            faucet_minted: self.faucet_minted.clone(),
+            reversed_setup: self.reversed_setup,
+            layout_checked: self.layout_checked.clone(),
+        }
+    }
+}
+
+// Helper functions modifying the state kept by the default WasmKeeper.
+// They are meant for test fixtures only, there are no such operations on a real chain.
+impl<BankT, ApiT, StorageT, CustomT, StakingT, DistrT, IbcT, GovT, StargateT>
+    App<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmKeeper<CustomT::ExecT, CustomT::QueryT>,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+    >
+where
+    ApiT: Api,
+    StorageT: Storage,
+    CustomT: Module,
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+{
+    /// Updates the data of an existing contract with specified address, **test fixtures only**.
+    ///
+    /// This privileged operation allows setting up the state that can not be achieved
+    /// by processing messages, like reproducing the state of a contract on a real chain.
+    /// Fails when the contract does not exist or when the creator or admin address
+    /// of the updated data is not valid for the configured [Api].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{App, ContractWrapper, Executor};
+    /// # use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+    /// # fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> { Ok(Response::default()) }
+    /// # fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> { Ok(Response::default()) }
+    /// # fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> { Ok(Binary::default()) }
+    ///
+    /// let mut app = App::default();
+    /// let creator = app.api().addr_make("creator");
+    /// let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    /// let contract_addr = app
+    ///     .instantiate_contract(code_id, creator, &Empty {}, &[], "label", None)
+    ///     .unwrap();
+    ///
+    /// app.update_contract_data(&contract_addr, |data| data.created = 1).unwrap();
+    ///
+    /// assert_eq!(1, app.contract_data(&contract_addr).unwrap().created);
+    /// ```
+    pub fn update_contract_data(
+        &mut self,
+        contract_addr: &Addr,
+        update_fn: impl FnOnce(&mut ContractData),
+    ) -> AnyResult<()> {
+        let Ok(mut data) = self.router.wasm.contract_data(&self.storage, contract_addr) else {
+            bail!("contract {} does not exist", contract_addr);
+        };
+        update_fn(&mut data);
+        self.api.addr_validate(data.creator.as_str())?;
+        if let Some(admin) = &data.admin {
+            self.api.addr_validate(admin.as_str())?;
+        }
+        self.router
+            .wasm
+            .save_contract(&mut self.storage, contract_addr, &data)
+    }
+
+    /// Sets the admin of an existing contract with specified address, **test fixtures only**.
+    ///
+    /// See [update_contract_data](Self::update_contract_data) for details.
+    pub fn set_contract_admin(
+        &mut self,
+        contract_addr: &Addr,
+        admin: Option<Addr>,
+    ) -> AnyResult<()> {
+        self.update_contract_data(contract_addr, |data| data.admin = admin)
+    }
+
+    /// Sets the creator of an existing contract with specified address, **test fixtures only**.
+    ///
+    /// See [update_contract_data](Self::update_contract_data) for details.
+    pub fn set_contract_creator(&mut self, contract_addr: &Addr, creator: Addr) -> AnyResult<()> {
+        self.update_contract_data(contract_addr, |data| data.creator = creator)
+    }
+}
+
+impl<BankT, ApiT, StorageT, CustomT, StakingT, DistrT, IbcT, GovT, StargateT>
+    App<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmKeeper<CustomT::ExecT, CustomT::QueryT>,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+    >
+where
+    BankT: Bank,
+    ApiT: Api,
+    StorageT: Storage,
+    CustomT: Module,
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+    StakingT: Staking,
+    DistrT: Distribution,
+    IbcT: Ibc,
+    GovT: Gov,
+    StargateT: Stargate,
+{
+    /// Creates a contract like [instantiate_contract](Executor::instantiate_contract)
+    /// and attaches the custom metadata to it, **test fixtures only**.
+    ///
+    /// The metadata is returned by [contract_extensions](Self::contract_extensions),
+    /// is preserved across migrations and admin changes, and can be changed
+    /// with [update_contract_extensions](Self::update_contract_extensions).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{App, ContractWrapper, IntoAddr};
+    /// # use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+    /// # fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> { Ok(Response::default()) }
+    /// # fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> { Ok(Response::default()) }
+    /// # fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> { Ok(Binary::default()) }
+    ///
+    /// let mut app = App::default();
+    /// let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    /// let metadata = [("role".to_string(), "vault#3".to_string())].into();
+    /// let contract_addr = app
+    ///     .instantiate_contract_with_metadata(code_id, "owner".into_addr(), &Empty {}, &[], "vault", None, metadata)
+    ///     .unwrap();
+    ///
+    /// let extensions = app.contract_extensions(&contract_addr).unwrap();
+    /// assert_eq!("vault#3", extensions["role"]);
+    /// ```
+    pub fn instantiate_contract_with_metadata<T: Serialize, U: Into<String>>(
+        &mut self,
+        code_id: impl Into<CodeId>,
+        sender: Addr,
+        init_msg: &T,
+        send_funds: &[Coin],
+        label: U,
+        admin: Option<String>,
+        metadata: BTreeMap<String, String>,
+    ) -> AnyResult<Addr> {
+        let contract_addr =
+            self.instantiate_contract(code_id, sender, init_msg, send_funds, label, admin)?;
+        self.update_contract_extensions(&contract_addr, |extensions| *extensions = metadata)?;
+        Ok(contract_addr)
+    }
+
+    /// Returns the custom metadata attached to the contract with specified address,
+    /// empty when no metadata was attached.
+    ///
+    /// Fails when the contract does not exist.
+    pub fn contract_extensions(&self, contract_addr: &Addr) -> AnyResult<BTreeMap<String, String>> {
+        self.router
+            .wasm
+            .contract_data(&self.storage, contract_addr)?;
+        self.router
+            .wasm
+            .contract_extensions(&self.storage, contract_addr)
+    }
+
+    /// Updates the custom metadata attached to the contract with specified address,
+    /// **test fixtures only**.
+    ///
+    /// Fails when the contract does not exist.
+    pub fn update_contract_extensions(
+        &mut self,
+        contract_addr: &Addr,
+        update_fn: impl FnOnce(&mut BTreeMap<String, String>),
+    ) -> AnyResult<()> {
+        let mut extensions = self.contract_extensions(contract_addr)?;
+        update_fn(&mut extensions);
+        self.router
+            .wasm
+            .save_contract_extensions(&mut self.storage, contract_addr, &extensions)
+    }
+}
+
 /// The Router plays a critical role in managing and directing
 /// transactions within the Cosmos blockchain.
 #[derive(Clone)]
@@ -523,8 +1954,30 @@ pub struct Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate> {
     pub gov: Gov,
     /// Stargate handler instance to be used in this [Router].
     pub stargate: Stargate,
+    /// Handlers of named sudo messages, see [Router::register_sudo_handler].
+    #[allow(clippy::type_complexity)]
+    pub(crate) sudo_handlers: BTreeMap<
+        &'static str,
+        SudoHandlerFn<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate>,
+    >,
+    /// Flag indicating if events are tagged with the module that emitted them,
+    /// see [AppBuilder::with_event_provenance].
+    pub(crate) event_provenance: bool,
+    /// Kinds of queries the router refuses to answer, see [App::disable_query].
+    pub(crate) disabled_queries: BTreeSet<QueryKind>,
 }
 
+/// Function handling named sudo messages, see [Router::register_sudo_handler].
+type SudoHandlerFn<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate> = Rc<
+    dyn Fn(
+        &dyn Api,
+        &mut dyn Storage,
+        &Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate>,
+        &BlockInfo,
+        Binary,
+    ) -> AnyResult<AppResponse>,
+>;
+
 impl<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
     Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
 where
@@ -553,10 +2006,77 @@ where
             block_info,
         }
     }
+
+    /// Returns a shared reference to the wasm module used in this [Router].
+    pub fn wasm(&self) -> &WasmT {
+        &self.wasm
+    }
+
+    /// Returns a mutable reference to the wasm module used in this [Router],
+    /// e.g. for changing its behavior in
+    /// [App::schedule_behavior_change](crate::App::schedule_behavior_change).
+    pub fn wasm_mut(&mut self) -> &mut WasmT {
+        &mut self.wasm
+    }
+
+    /// Registers a handler of sudo messages with the specified name,
+    /// called with [App::sudo_named].
+    ///
+    /// This gives custom modules a privileged entry-point without extending [SudoMsg].
+    /// Handlers are usually registered in the initialization function passed to
+    /// [AppBuilder::build]. A handler registered with the same name replaces the previous one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::{from_json, Event};
+    /// use cw_multi_test::{AppBuilder, AppResponse};
+    ///
+    /// let mut app = AppBuilder::default().build(|router, _, _| {
+    ///     router.register_sudo_handler("oracle", |_, _, _, _, msg| {
+    ///         let price: u64 = from_json(msg)?;
+    ///         Ok(AppResponse {
+    ///             events: vec![Event::new("price").add_attribute("value", price.to_string())],
+    ///             data: None,
+    ///         })
+    ///     });
+    /// });
+    ///
+    /// let response = app.sudo_named("oracle", &42).unwrap();
+    /// assert_eq!("42", response.events[0].attributes[0].value);
+    /// ```
+    pub fn register_sudo_handler<F>(&mut self, name: &'static str, handler: F)
+    where
+        F: Fn(
+                &dyn Api,
+                &mut dyn Storage,
+                &Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+                &BlockInfo,
+                Binary,
+            ) -> AnyResult<AppResponse>
+            + 'static,
+    {
+        self.sudo_handlers.insert(name, Rc::new(handler));
+    }
+
+    /// Tags the events of the response produced by the specified module, when enabled.
+    fn tag_provenance(
+        &self,
+        module: ModuleKind,
+        result: AnyResult<AppResponse>,
+    ) -> AnyResult<AppResponse> {
+        result.map(|mut response| {
+            if self.event_provenance {
+                tag_events(&mut response.events, module);
+            }
+            response
+        })
+    }
 }
 
 /// We use it to allow calling into modules from another module in sudo mode.
 /// Things like gov proposals belong here.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SudoMsg {
     /// Bank privileged actions.
     Bank(BankSudo),
@@ -566,6 +2086,8 @@ pub enum SudoMsg {
     Staking(StakingSudo),
     /// Wasm privileged actions.
     Wasm(WasmSudo),
+    /// Privileged actions changing the permissions of the wasm module.
+    WasmPermissions(WasmPermissionsSudo),
 }
 
 impl From<WasmSudo> for SudoMsg {
@@ -574,6 +2096,12 @@ impl From<WasmSudo> for SudoMsg {
     }
 }
 
+impl From<WasmPermissionsSudo> for SudoMsg {
+    fn from(permissions: WasmPermissionsSudo) -> Self {
+        SudoMsg::WasmPermissions(permissions)
+    }
+}
+
 impl From<BankSudo> for SudoMsg {
     fn from(bank: BankSudo) -> Self {
         SudoMsg::Bank(bank)
@@ -585,6 +2113,7 @@ impl From<StakingSudo> for SudoMsg {
         SudoMsg::Staking(staking)
     }
 }
+
 /// A trait representing the Cosmos based chain's router.
 ///
 /// This trait is designed for routing messages within the Cosmos ecosystem.
@@ -623,6 +2152,12 @@ pub trait CosmosRouter {
         block: &BlockInfo,
         msg: SudoMsg,
     ) -> AnyResult<AppResponse>;
+
+    /// Returns `true` when queries of the specified kind are answered by this router.
+    fn is_query_enabled(&self, kind: QueryKind) -> bool {
+        let _ = kind;
+        true
+    }
 }
 
 impl<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT> CosmosRouter
@@ -650,31 +2185,67 @@ where
         sender: Addr,
         msg: CosmosMsg<Self::ExecC>,
     ) -> AnyResult<AppResponse> {
-        match msg {
-            CosmosMsg::Wasm(msg) => self.wasm.execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Bank(msg) => self.bank.execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Custom(msg) => self.custom.execute(api, storage, self, block, sender, msg),
+        let (module, result) = match msg {
+            CosmosMsg::Wasm(msg) => (
+                ModuleKind::Wasm,
+                self.wasm.execute(api, storage, self, block, sender, msg),
+            ),
+            CosmosMsg::Bank(msg) => (
+                ModuleKind::Bank,
+                self.bank.execute(api, storage, self, block, sender, msg),
+            ),
+            CosmosMsg::Custom(msg) => {
+                // with `Empty` as custom message type, the App was most likely built
+                // without a custom module, so a hint is added to the reported error
+                let hint = is_empty::<Self::ExecC>().then(|| format!("{:?}", msg));
+                let result = self
+                    .custom
+                    .execute(api, storage, self, block, sender, msg)
+                    .map_err(|err| match hint {
+                        Some(msg) => {
+                            err.context(Error::custom_msg_mismatch(type_name::<Self::ExecC>(), msg))
+                        }
+                        None => err,
+                    });
+                (ModuleKind::Custom, result)
+            }
             #[cfg(feature = "staking")]
-            CosmosMsg::Staking(msg) => self.staking.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Staking(msg) => (
+                ModuleKind::Staking,
+                self.staking.execute(api, storage, self, block, sender, msg),
+            ),
             #[cfg(feature = "staking")]
-            CosmosMsg::Distribution(msg) => self
-                .distribution
-                .execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Distribution(msg) => (
+                ModuleKind::Distribution,
+                self.distribution
+                    .execute(api, storage, self, block, sender, msg),
+            ),
             #[cfg(feature = "stargate")]
-            CosmosMsg::Ibc(msg) => self.ibc.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Ibc(msg) => (
+                ModuleKind::Ibc,
+                self.ibc.execute(api, storage, self, block, sender, msg),
+            ),
             #[cfg(feature = "stargate")]
-            CosmosMsg::Gov(msg) => self.gov.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Gov(msg) => (
+                ModuleKind::Gov,
+                self.gov.execute(api, storage, self, block, sender, msg),
+            ),
             #[allow(deprecated)]
             #[cfg(feature = "stargate")]
-            CosmosMsg::Stargate { type_url, value } => self
-                .stargate
-                .execute_stargate(api, storage, self, block, sender, type_url, value),
+            CosmosMsg::Stargate { type_url, value } => (
+                ModuleKind::Stargate,
+                self.stargate
+                    .execute_stargate(api, storage, self, block, sender, type_url, value),
+            ),
             #[cfg(feature = "cosmwasm_2_0")]
-            CosmosMsg::Any(msg) => self
-                .stargate
-                .execute_any(api, storage, self, block, sender, msg),
+            CosmosMsg::Any(msg) => (
+                ModuleKind::Stargate,
+                self.stargate
+                    .execute_any(api, storage, self, block, sender, msg),
+            ),
             _ => bail!("Cannot execute {:?}", msg),
-        }
+        };
+        self.tag_provenance(module, result)
     }
 
     /// This is used by `RouterQuerier` to actual implement the `Querier` interface.
@@ -698,10 +2269,19 @@ where
             QueryRequest::Ibc(req) => self.ibc.query(api, storage, &querier, block, req),
             #[allow(deprecated)]
             #[cfg(feature = "stargate")]
+            QueryRequest::Stargate { path, data } if path == QUERY_ALL_CONTRACT_STATE => {
+                query_all_contract_state(&self.wasm, api, storage, &data)
+            }
+            #[allow(deprecated)]
+            #[cfg(feature = "stargate")]
             QueryRequest::Stargate { path, data } => self
                 .stargate
                 .query_stargate(api, storage, &querier, block, path, data),
             #[cfg(feature = "cosmwasm_2_0")]
+            QueryRequest::Grpc(req) if req.path == QUERY_ALL_CONTRACT_STATE => {
+                query_all_contract_state(&self.wasm, api, storage, &req.data)
+            }
+            #[cfg(feature = "cosmwasm_2_0")]
             QueryRequest::Grpc(req) => self.stargate.query_grpc(api, storage, &querier, block, req),
             _ => unimplemented!(),
         }
@@ -714,13 +2294,31 @@ where
         block: &BlockInfo,
         msg: SudoMsg,
     ) -> AnyResult<AppResponse> {
-        match msg {
-            SudoMsg::Wasm(msg) => self.wasm.sudo(api, storage, self, block, msg),
-            SudoMsg::Bank(msg) => self.bank.sudo(api, storage, self, block, msg),
+        let (module, result) = match msg {
+            SudoMsg::Wasm(msg) => (
+                ModuleKind::Wasm,
+                self.wasm.sudo(api, storage, self, block, msg),
+            ),
+            SudoMsg::WasmPermissions(msg) => (
+                ModuleKind::Wasm,
+                crate::wasm_permissions::sudo(storage, msg),
+            ),
+            SudoMsg::Bank(msg) => (
+                ModuleKind::Bank,
+                self.bank.sudo(api, storage, self, block, msg),
+            ),
             #[cfg(feature = "staking")]
-            SudoMsg::Staking(msg) => self.staking.sudo(api, storage, self, block, msg),
+            SudoMsg::Staking(msg) => (
+                ModuleKind::Staking,
+                self.staking.sudo(api, storage, self, block, msg),
+            ),
             _ => unimplemented!(),
-        }
+        };
+        self.tag_provenance(module, result)
+    }
+
+    fn is_query_enabled(&self, kind: QueryKind) -> bool {
+        !self.disabled_queries.contains(&kind)
     }
 }
 
@@ -811,14 +2409,35 @@ where
 {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         let request: QueryRequest<QueryC> = match from_json(bin_request) {
+            // `Empty` accepts any object, so the custom query must be checked explicitly
+            Ok(request @ QueryRequest::Custom(_)) if is_empty::<QueryC>() => {
+                if let Some(err) = custom_query_mismatch::<QueryC>(bin_request) {
+                    return SystemResult::Err(SystemError::InvalidRequest {
+                        error: err.to_string(),
+                        request: bin_request.into(),
+                    });
+                }
+                request
+            }
             Ok(v) => v,
             Err(e) => {
+                let error = match custom_query_mismatch::<QueryC>(bin_request) {
+                    Some(err) => err.to_string(),
+                    None => format!("Parsing query request: {}", e),
+                };
                 return SystemResult::Err(SystemError::InvalidRequest {
-                    error: format!("Parsing query request: {}", e),
+                    error,
                     request: bin_request.into(),
-                })
+                });
             }
         };
+        if let Some(kind) = QueryKind::of(&request) {
+            if !self.router.is_query_enabled(kind) {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: kind.unsupported_request_kind().to_string(),
+                });
+            }
+        }
         let contract_result: ContractResult<Binary> = self
             .router
             .query(self.api, self.storage, self.block_info, request)
@@ -826,3 +2445,51 @@ where
         SystemResult::Ok(contract_result)
     }
 }
+
+/// Returns `true` when the type is [Empty], the default custom message and query type of the App.
+fn is_empty<T: 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<Empty>()
+}
+
+/// Checks if the custom part of the raw query request can be deserialized into the custom
+/// query type of the App, returns the error describing the mismatch when it cannot.
+///
+/// [Empty] accepts any JSON object, so with [Empty] as the custom query type
+/// any custom query other than an empty object is reported as a mismatch.
+fn custom_query_mismatch<QueryC>(bin_request: &[u8]) -> Option<Error>
+where
+    QueryC: DeserializeOwned + 'static,
+{
+    let request: serde_json::Value = serde_json::from_slice(bin_request).ok()?;
+    let custom = request.as_object()?.get("custom")?;
+    let reason = match serde_json::from_value::<QueryC>(custom.clone()) {
+        Ok(_) if is_empty::<QueryC>() && custom.as_object().is_some_and(|o| !o.is_empty()) => {
+            format!("unexpected fields for {}", type_name::<QueryC>())
+        }
+        Ok(_) => return None,
+        Err(e) => e.to_string(),
+    };
+    Some(Error::custom_query_mismatch(
+        type_name::<QueryC>(),
+        custom.to_string(),
+        reason,
+    ))
+}
+
+/// Returns the coins with non-zero amount.
+fn non_zero_coins(coins: &[Coin]) -> Vec<Coin> {
+    coins
+        .iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .cloned()
+        .collect()
+}
+
+/// Returns the amount of tokens in the specified denomination, zero when not found.
+fn balance_of(balances: &[Coin], denom: &str) -> Uint128 {
+    balances
+        .iter()
+        .find(|coin| coin.denom == denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default()
+}
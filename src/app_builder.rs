@@ -1,13 +1,25 @@
 //! AppBuilder helps you set up your test blockchain environment step by step [App].
 
+use crate::address_book::{import_address_book, AddressBook};
+use crate::app::BlockJitterFn;
 use crate::featured::staking::{Distribution, DistributionKeeper, StakeKeeper, Staking};
+use crate::layout::{check_layout_versions, init_layout_versions};
+use crate::operation_log::OperationLog;
+use crate::wasm_permissions::{
+    save_default_instantiate_permission, save_wasm_permissions, InstantiatePermission,
+    WasmPermissions,
+};
 use crate::{
-    App, Bank, BankKeeper, FailingModule, Gov, GovFailingModule, Ibc, IbcFailingModule, Module,
-    Router, Stargate, StargateFailing, Wasm, WasmKeeper,
+    App, Bank, BankKeeper, FailingModule, Gov, GovFailingModule, Ibc, IbcFailingModule,
+    LabelValidation, Module, Router, Stargate, StargateFailing, Wasm, WasmKeeper,
 };
 use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
-use cosmwasm_std::{Api, BlockInfo, CustomMsg, CustomQuery, Empty, Storage};
+use cosmwasm_std::{
+    Addr, Api, BlockInfo, Coin, CustomMsg, CustomQuery, DenomMetadata, Empty, Storage,
+};
 use serde::de::DeserializeOwned;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Debug;
 
 /// This is essential to create a custom app with custom module.
@@ -55,6 +67,19 @@ pub struct AppBuilder<Bank, Api, Storage, Custom, Wasm, Staking, Distr, Ibc, Gov
     ibc: Ibc,
     gov: Gov,
     stargate: Stargate,
+    genesis_balances: Vec<(Addr, Vec<Coin>)>,
+    gov_authority: Option<Addr>,
+    staking_denom: Option<String>,
+    staking_denom_metadata: Option<DenomMetadata>,
+    wasm_permissions: Option<WasmPermissions>,
+    default_instantiate_permission: Option<InstantiatePermission>,
+    address_book: Option<AddressBook>,
+    block_jitter: Option<BlockJitterFn>,
+    operation_log: bool,
+    event_provenance: bool,
+    faucet: bool,
+    reversed_setup: bool,
+    auto_execute_due: bool,
 }
 
 impl Default
@@ -93,6 +118,19 @@ impl
     /// Creates builder with default components working with empty exec and query messages.
     pub fn new() -> Self {
         AppBuilder {
+            genesis_balances: vec![],
+            gov_authority: None,
+            staking_denom: None,
+            staking_denom_metadata: None,
+            wasm_permissions: None,
+            default_instantiate_permission: None,
+            address_book: None,
+            block_jitter: None,
+            operation_log: false,
+            event_provenance: false,
+            faucet: false,
+            reversed_setup: false,
+            auto_execute_due: false,
             api: MockApi::default(),
             block: mock_env().block,
             storage: MockStorage::new(),
@@ -129,6 +167,19 @@ where
     /// messages.
     pub fn new_custom() -> Self {
         AppBuilder {
+            genesis_balances: vec![],
+            gov_authority: None,
+            staking_denom: None,
+            staking_denom_metadata: None,
+            wasm_permissions: None,
+            default_instantiate_permission: None,
+            address_book: None,
+            block_jitter: None,
+            operation_log: false,
+            event_provenance: false,
+            faucet: false,
+            reversed_setup: false,
+            auto_execute_due: false,
             api: MockApi::default(),
             block: mock_env().block,
             storage: MockStorage::new(),
@@ -171,10 +222,36 @@ where
             ibc,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -206,10 +283,36 @@ where
             ibc,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -241,10 +344,36 @@ where
             ibc,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -276,10 +405,36 @@ where
             ibc,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -315,10 +470,36 @@ where
             ibc,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -350,10 +531,36 @@ where
             ibc,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -395,10 +602,36 @@ where
             ibc,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -436,10 +669,36 @@ where
             distribution,
             gov,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -471,10 +730,36 @@ where
             distribution,
             ibc,
             stargate,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -506,10 +791,36 @@ where
             distribution,
             ibc,
             gov,
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             ..
         } = self;
 
         AppBuilder {
+            genesis_balances,
+            gov_authority,
+            staking_denom,
+            staking_denom_metadata,
+            wasm_permissions,
+            default_instantiate_permission,
+            address_book,
+            block_jitter,
+            operation_log,
+            event_provenance,
+            faucet,
+            reversed_setup,
+            auto_execute_due,
             api,
             block,
             storage,
@@ -530,6 +841,353 @@ where
         self
     }
 
+    /// Sets the function deriving the block observed by each top-level message
+    /// from the canonical block of the [App] and the index of the message.
+    ///
+    /// Messages are indexed in order of execution, starting from zero when the [App] is built,
+    /// including all messages executed with [App::execute_multi]. Nested messages, like
+    /// submessages of contracts, observe the same block as the top-level message.
+    /// The canonical block returned by [App::block_info] and used by queries is not changed.
+    /// By default, all messages observe the canonical block.
+    ///
+    /// This is useful for simulating the bounded clock drift of validators.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::BlockInfo;
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_block_jitter(Box::new(|block: &BlockInfo, index: u64| {
+    ///         let mut block = block.clone();
+    ///         // every other message observes the time one second ahead
+    ///         block.time = block.time.plus_seconds(index % 2);
+    ///         block
+    ///     }))
+    ///     .build(no_init);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn with_block_jitter(
+        mut self,
+        block_jitter: Box<dyn Fn(&BlockInfo, u64) -> BlockInfo>,
+    ) -> Self {
+        self.block_jitter = Some(block_jitter.into());
+        self
+    }
+
+    /// Enables recording of top-level operations applied to the [App] into an [OperationLog],
+    /// available via [App::operation_log] and replayable with [replay](crate::replay).
+    ///
+    /// The state of the [App] after initialization is recorded as the starting point of the log.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default().with_operation_log().build(no_init);
+    ///
+    /// assert!(app.operation_log().unwrap().operations.is_empty());
+    /// ```
+    pub fn with_operation_log(mut self) -> Self {
+        self.operation_log = true;
+        self
+    }
+
+    /// Enables or disables tagging events with the module that emitted them.
+    ///
+    /// When enabled, the router appends the `_module` attribute to each event produced
+    /// by the module processing a message, with values like `bank`, `wasm` or `custom`,
+    /// so identically named events from different modules can be told apart
+    /// with [AppResponse::events_from](crate::AppResponse::events_from).
+    /// Events emitted by contracts are tagged as emitted by the wasm module,
+    /// also in the events passed to contract's `reply` entry-point.
+    /// Disabled by default, so the events match the events emitted by the chain.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::{coins, BankMsg};
+    /// use cw_multi_test::{AppBuilder, Executor, IntoAddr, ModuleKind};
+    ///
+    /// let owner = "owner".into_addr();
+    /// let mut app = AppBuilder::default()
+    ///     .with_event_provenance(true)
+    ///     .build(|router, _, storage| {
+    ///         router.bank.init_balance(storage, &owner, coins(100, "uatom")).unwrap();
+    ///     });
+    ///
+    /// let msg = BankMsg::Send {
+    ///     to_address: "recipient".into_addr().to_string(),
+    ///     amount: coins(10, "uatom"),
+    /// };
+    /// let response = app.execute(owner, msg.into()).unwrap();
+    /// assert_eq!("transfer", response.events_from(ModuleKind::Bank)[0].ty);
+    /// ```
+    pub fn with_event_provenance(mut self, enabled: bool) -> Self {
+        self.event_provenance = enabled;
+        self
+    }
+
+    /// Enables the faucet funding accounts with tokens of any denomination,
+    /// available with [App::faucet].
+    ///
+    /// Unlike [Bank::init_balance], the faucet can be used after the [App] is built,
+    /// registers the minimal metadata for the funded denominations
+    /// and keeps track of all minted tokens.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::coin;
+    /// use cw_multi_test::{no_init, AppBuilder, IntoAddr};
+    ///
+    /// let mut app = AppBuilder::default().with_faucet().build(no_init);
+    /// let user = "user".into_addr();
+    ///
+    /// let voucher = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+    ///
+    /// app.faucet().fund(&user, &[coin(100, voucher)]).unwrap();
+    /// assert_eq!(vec![coin(100, voucher)], app.balances(&user));
+    /// ```
+    pub fn with_faucet(mut self) -> Self {
+        self.faucet = true;
+        self
+    }
+
+    /// Reverses the order of the setup steps iterated with [App::in_setup_order],
+    /// used to detect contracts whose state depends on the order of the test setup
+    /// with [assert_setup_order_independent](crate::assert_setup_order_independent).
+    ///
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default().with_reversed_setup(true).build(no_init);
+    ///
+    /// assert_eq!(vec![3, 2, 1], app.in_setup_order([1, 2, 3]));
+    /// ```
+    pub fn with_reversed_setup(mut self, enabled: bool) -> Self {
+        self.reversed_setup = enabled;
+        self
+    }
+
+    /// Enables or disables executing due scheduled messages every time the block changes,
+    /// see [App::schedule_execute]. Results of the executions are available via
+    /// [App::last_due_executions]. Disabled by default, so scheduled messages are executed
+    /// only when requested with [App::try_execute_scheduled] or [App::execute_all_due].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::{coins, BankMsg};
+    /// use cw_multi_test::{AppBuilder, IntoAddr};
+    ///
+    /// let timelock = "timelock".into_addr();
+    /// let recipient = "recipient".into_addr();
+    /// let mut app = AppBuilder::default()
+    ///     .with_auto_execute_due(true)
+    ///     .build(|router, _, storage| {
+    ///         router.bank.init_balance(storage, &timelock, coins(100, "uatom")).unwrap();
+    ///     });
+    /// let msg = BankMsg::Send {
+    ///     to_address: recipient.to_string(),
+    ///     amount: coins(100, "uatom"),
+    /// };
+    /// let not_before = app.block_info().time.plus_seconds(60);
+    /// app.schedule_execute(timelock, msg.into(), not_before).unwrap();
+    ///
+    /// app.update_block(|block| block.time = not_before);
+    /// assert_eq!(coins(100, "uatom"), app.balances(&recipient));
+    /// ```
+    pub fn with_auto_execute_due(mut self, enabled: bool) -> Self {
+        self.auto_execute_due = enabled;
+        self
+    }
+
+    /// Sets initial balances of multiple accounts,
+    /// initialized by [Bank::init_balances] when the [App] is built.
+    ///
+    /// Building the application panics when the same account is provided more than once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::coins;
+    /// use cw_multi_test::{no_init, AppBuilder, IntoAddr};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_genesis_balances(vec![
+    ///         ("alice".into_addr(), coins(100, "uatom")),
+    ///         ("bob".into_addr(), coins(200, "uatom")),
+    ///     ])
+    ///     .build(no_init);
+    ///
+    /// let balance = app.wrap().query_balance("bob".into_addr(), "uatom").unwrap();
+    /// assert_eq!(200, balance.amount.u128());
+    /// ```
+    pub fn with_genesis_balances(mut self, balances: Vec<(Addr, Vec<Coin>)>) -> Self {
+        self.genesis_balances = balances;
+        self
+    }
+
+    /// Sets the address of the governance module account, used as the sender
+    /// of messages executed with [App::execute_as_gov].
+    ///
+    /// By default, the address is derived from the `gov` module name
+    /// using [module_address](crate::module_address).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, IntoAddr};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_gov_authority("authority".into_addr())
+    ///     .build(no_init);
+    ///
+    /// assert_eq!("authority".into_addr(), app.gov_authority().unwrap());
+    /// ```
+    pub fn with_gov_authority(mut self, gov_authority: Addr) -> Self {
+        self.gov_authority = Some(gov_authority);
+        self
+    }
+
+    /// Sets the chain-level permissions to store contract code,
+    /// written to the wasm module's storage when the [App] is built.
+    ///
+    /// By default, any address can store contract code.
+    /// Permissions can be changed later with [WasmPermissionsSudo](crate::WasmPermissionsSudo).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, IntoAddr, WasmPermissions};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_wasm_permissions(WasmPermissions::PermissionedUpload {
+    ///         allowed_uploaders: vec!["uploader".into_addr()],
+    ///     })
+    ///     .build(no_init);
+    /// ```
+    pub fn with_wasm_permissions(mut self, permissions: WasmPermissions) -> Self {
+        self.wasm_permissions = Some(permissions);
+        self
+    }
+
+    /// Sets the instantiate permission applied to contract codes without their own
+    /// instantiate permission, written to the wasm module's storage when the [App] is built.
+    ///
+    /// By default, any address can instantiate contracts.
+    /// Instantiate permissions of specific codes are set with [WasmPermissionsSudo](crate::WasmPermissionsSudo).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, InstantiatePermission, IntoAddr};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_default_instantiate_permission(InstantiatePermission::AnyOfAddresses(vec![
+    ///         "deployer".into_addr(),
+    ///     ]))
+    ///     .build(no_init);
+    /// ```
+    pub fn with_default_instantiate_permission(
+        mut self,
+        permission: InstantiatePermission,
+    ) -> Self {
+        self.default_instantiate_permission = Some(permission);
+        self
+    }
+
+    /// Imports the address book, exported with [App::export_address_book], when the [App] is built.
+    ///
+    /// Names of users are registered, and addresses of contracts are pinned to their labels,
+    /// so instantiating a contract with a label listed in the address book fails
+    /// when the generated address differs from the expected one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AddressBook, AppBuilder, IntoAddr};
+    ///
+    /// let mut book = AddressBook::default();
+    /// book.users.insert("alice".to_string(), "alice".into_addr());
+    ///
+    /// let app = AppBuilder::default().with_address_book(book).build(no_init);
+    ///
+    /// let book = app.export_address_book().unwrap();
+    /// assert_eq!("alice".into_addr(), book.users["alice"]);
+    /// ```
+    pub fn with_address_book(mut self, address_book: AddressBook) -> Self {
+        self.address_book = Some(address_book);
+        self
+    }
+
+    /// Sets the denominator of the staking token, written as [StakingInfo](crate::StakingInfo)
+    /// (with default unbonding time and APR) to the staking module's storage when the [App] is built.
+    ///
+    /// By default, the staking token denominator is `TOKEN`.
+    /// The initialization function passed to [build](Self::build) is executed afterwards,
+    /// so calling [StakeKeeper::setup] there overwrites the value set here.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_staking_denom("uatom")
+    ///     .build(no_init);
+    ///
+    /// assert_eq!("uatom", app.staking_denom().unwrap());
+    /// ```
+    #[cfg(feature = "staking")]
+    pub fn with_staking_denom(mut self, denom: impl Into<String>) -> Self {
+        self.staking_denom = Some(denom.into());
+        self
+    }
+
+    /// Sets the metadata of the staking token, registered in the bank module when the [App] is built,
+    /// so contracts can query the display unit and its exponent of the bonded denominator.
+    ///
+    /// The staking token denominator is set to the `base` denomination of the metadata,
+    /// like with [with_staking_denom](Self::with_staking_denom), so the metadata
+    /// and the staking configuration always refer to the same token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::{DenomMetadata, DenomUnit};
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_staking_denom_metadata(DenomMetadata {
+    ///         base: "uatom".to_string(),
+    ///         display: "atom".to_string(),
+    ///         symbol: "ATOM".to_string(),
+    ///         denom_units: vec![DenomUnit {
+    ///             denom: "atom".to_string(),
+    ///             exponent: 6,
+    ///             aliases: vec![],
+    ///         }],
+    ///         ..Default::default()
+    ///     })
+    ///     .build(no_init);
+    ///
+    /// assert_eq!("uatom", app.staking_denom().unwrap());
+    /// ```
+    #[cfg(feature = "staking")]
+    pub fn with_staking_denom_metadata(mut self, metadata: DenomMetadata) -> Self {
+        self.staking_denom = Some(metadata.base.clone());
+        self.staking_denom_metadata = Some(metadata);
+        self
+    }
+
     /// Builds the final [App] with initialization.
     ///
     /// At this point all component types have to be properly related to each other.
@@ -565,14 +1223,203 @@ where
                 ibc: self.ibc,
                 gov: self.gov,
                 stargate: self.stargate,
+                sudo_handlers: BTreeMap::new(),
+                event_provenance: self.event_provenance,
+                disabled_queries: BTreeSet::new(),
             },
             api: self.api,
             block: self.block,
             storage: self.storage,
+            scheduled_changes: vec![],
+            upgrade_events: vec![],
+            gov_authority: self.gov_authority,
+            scheduled_sudos: vec![],
+            next_schedule_id: 0,
+            last_scheduled_results: vec![],
+            scheduled_executions: vec![],
+            auto_execute_due: self.auto_execute_due,
+            last_due_executions: vec![],
+            block_jitter: self.block_jitter,
+            message_index: 0,
+            operation_log: None,
+            faucet_minted: self.faucet.then(Default::default),
+            reversed_setup: self.reversed_setup,
+            layout_checked: Cell::new(false),
         };
+        // write the storage layout versions of built-in keepers and check them once
+        init_layout_versions(&mut app.storage).unwrap();
+        app.layout_checked
+            .set(check_layout_versions(&app.storage).is_ok());
+        // initialize genesis balances
+        if !self.genesis_balances.is_empty() {
+            app.init_modules(|router, _, storage| {
+                router
+                    .bank
+                    .init_balances(storage, &self.genesis_balances)
+                    .unwrap()
+            });
+        }
+        // initialize the staking token denominator
+        #[cfg(feature = "staking")]
+        if let Some(bonded_denom) = self.staking_denom {
+            app.init_modules(|_, _, storage| {
+                if let Some(metadata) = &self.staking_denom_metadata {
+                    crate::staking::save_staking_denom_metadata(storage, &bonded_denom, metadata)
+                        .unwrap();
+                }
+                crate::staking::save_staking_info(
+                    storage,
+                    &crate::StakingInfo {
+                        bonded_denom,
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+            });
+        }
+        // initialize the permissions of the wasm module
+        if let Some(permissions) = self.wasm_permissions {
+            app.init_modules(|_, _, storage| save_wasm_permissions(storage, &permissions).unwrap());
+        }
+        if let Some(permission) = self.default_instantiate_permission {
+            app.init_modules(|_, _, storage| {
+                save_default_instantiate_permission(storage, &permission).unwrap()
+            });
+        }
+        // import the address book
+        if let Some(address_book) = self.address_book {
+            app.init_modules(|_, _, storage| import_address_book(storage, &address_book).unwrap());
+        }
         // execute initialization provided by the caller
         app.init_modules(init_fn);
+        // start recording operations applied to the initialized application
+        if self.operation_log {
+            app.operation_log = Some(OperationLog::new(app.block.clone(), &app.storage));
+        }
         // return already initialized application
         app
     }
 }
+
+impl<BankT, ApiT, StorageT, CustomT, StakingT, DistrT, IbcT, GovT, StargateT>
+    AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmKeeper<CustomT::ExecT, CustomT::QueryT>,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+    >
+where
+    CustomT: Module,
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+{
+    /// Enables or disables collecting statistics of contract calls in the default wasm keeper.
+    ///
+    /// Collected statistics are returned by [App::call_stats].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default().with_call_stats(true).build(no_init);
+    ///
+    /// assert_eq!(0, app.call_stats().total_executes());
+    /// ```
+    pub fn with_call_stats(mut self, enabled: bool) -> Self {
+        self.wasm = self.wasm.with_call_stats(enabled);
+        self
+    }
+
+    /// Enables or disables counting storage operations performed by contracts
+    /// in the default wasm keeper.
+    ///
+    /// Statistics of the last top-level execution are returned by [App::last_execution_stats].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_storage_access_stats(true)
+    ///     .build(no_init);
+    ///
+    /// assert_eq!(0, app.last_execution_stats().total().writes);
+    /// ```
+    pub fn with_storage_access_stats(mut self, enabled: bool) -> Self {
+        self.wasm = self.wasm.with_storage_access_stats(enabled);
+        self
+    }
+
+    /// Enables or disables recording traces of reply invocations in the default wasm keeper.
+    ///
+    /// Recorded traces are returned by [App::reply_traces].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    ///
+    /// assert!(app.reply_traces().is_empty());
+    /// ```
+    pub fn with_reply_traces(mut self, enabled: bool) -> Self {
+        self.wasm = self.wasm.with_reply_traces(enabled);
+        self
+    }
+
+    /// Sets the validation of contract labels in the default wasm keeper,
+    /// see [WasmKeeper::with_label_validation].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, LabelValidation};
+    ///
+    /// let app = AppBuilder::default()
+    ///     .with_label_validation(LabelValidation::Permissive)
+    ///     .build(no_init);
+    /// ```
+    pub fn with_label_validation(mut self, label_validation: LabelValidation) -> Self {
+        self.wasm = self.wasm.with_label_validation(label_validation);
+        self
+    }
+
+    /// Sets the maximum depth of nested contract calls in the default wasm keeper,
+    /// see [WasmKeeper::with_max_call_depth].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default().with_max_call_depth(1000).build(no_init);
+    /// ```
+    pub fn with_max_call_depth(mut self, max_call_depth: usize) -> Self {
+        self.wasm = self.wasm.with_max_call_depth(max_call_depth);
+        self
+    }
+
+    /// Enables or disables verifying the sender of messages sent by contracts
+    /// in the default wasm keeper, see [WasmKeeper::with_sender_checks].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder};
+    ///
+    /// let app = AppBuilder::default().with_sender_checks(true).build(no_init);
+    /// ```
+    pub fn with_sender_checks(mut self, enabled: bool) -> Self {
+        self.wasm = self.wasm.with_sender_checks(enabled);
+        self
+    }
+}
@@ -0,0 +1,224 @@
+//! # Async-friendly application wrapper
+
+use crate::error::AnyResult;
+use crate::featured::staking::{Distribution, Staking};
+use crate::{App, AppResponse, Bank, CodeId, Executor, Gov, Ibc, Module, Stargate, Wasm};
+use cosmwasm_std::{Addr, Api, BlockInfo, Coin, CustomMsg, CustomQuery, Storage};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::Any;
+use std::fmt::Debug;
+use std::future::Future;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+
+/// Command processed by the thread owning the application.
+type Command<A> = Box<dyn FnOnce(&mut A) + Send>;
+
+/// Result of a command, or the payload of a panic raised while processing it.
+type Outcome<R> = Result<R, Box<dyn Any + Send>>;
+
+/// Wrapper owning an [App] inside a dedicated thread, for use in async test harnesses.
+///
+/// [App] is not `Send`, so it can not be moved between tasks of a multithreaded async runtime.
+/// [AsyncApp] creates the application in its own thread and processes the submitted
+/// operations one by one, in the order they were submitted. All operations return futures
+/// that do not depend on any particular async runtime. [AsyncApp] is `Send` and `Sync`,
+/// so it can be shared between tasks (e.g. in an `Arc`) without any additional locking.
+///
+/// A panic raised while processing an operation is propagated to the task awaiting its result.
+///
+/// # Example
+///
+/// ```
+/// use cw_multi_test::{App, AsyncApp};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let app = AsyncApp::new(App::default);
+///
+/// app.update_block(|block| block.height += 1).await;
+///
+/// let height = app.with_app(|app| app.block_info().height).await;
+/// assert_eq!(12346, height);
+/// # }
+/// ```
+pub struct AsyncApp<A: 'static = App> {
+    /// Sender of commands to the thread owning the application.
+    sender: Option<Sender<Command<A>>>,
+    /// Handle of the thread owning the application.
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<A: 'static> AsyncApp<A> {
+    /// Creates a new wrapper, the application is created by `factory`
+    /// in the thread owning it.
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: FnOnce() -> A + Send + 'static,
+    {
+        let (sender, receiver) = channel::<Command<A>>();
+        let worker = std::thread::spawn(move || {
+            let mut app = factory();
+            for command in receiver {
+                command(&mut app);
+            }
+        });
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// Runs the provided function with exclusive access to the application,
+    /// returns a future resolving to the function result.
+    ///
+    /// This is the escape hatch for all operations not covered by dedicated methods.
+    pub fn with_app<F, R>(&self, f: F) -> impl Future<Output = R>
+    where
+        F: FnOnce(&mut A) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let completion = Arc::clone(&shared);
+        let command: Command<A> = Box::new(move |app| {
+            let outcome = catch_unwind(AssertUnwindSafe(|| f(app)));
+            let mut shared = completion.lock().unwrap();
+            shared.outcome = Some(outcome);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        });
+        self.sender
+            .as_ref()
+            .expect("sender is available until dropped")
+            .send(command)
+            .expect("application thread stopped");
+        Completion { shared }
+    }
+}
+
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+    AsyncApp<App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>>
+where
+    BankT: Bank + 'static,
+    ApiT: Api + 'static,
+    StorageT: Storage + 'static,
+    CustomT: Module + 'static,
+    WasmT: Wasm<CustomT::ExecT, CustomT::QueryT> + 'static,
+    StakingT: Staking + 'static,
+    DistrT: Distribution + 'static,
+    IbcT: Ibc + 'static,
+    GovT: Gov + 'static,
+    StargateT: Stargate + 'static,
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+{
+    /// Executes a contract, like [Executor::execute_contract].
+    pub fn execute_contract<M>(
+        &self,
+        sender: Addr,
+        contract_addr: Addr,
+        msg: M,
+        send_funds: Vec<Coin>,
+    ) -> impl Future<Output = AnyResult<AppResponse>>
+    where
+        M: Serialize + Debug + Send + 'static,
+    {
+        self.with_app(move |app| app.execute_contract(sender, contract_addr, &msg, &send_funds))
+    }
+
+    /// Instantiates a contract, like [Executor::instantiate_contract].
+    pub fn instantiate_contract<M>(
+        &self,
+        code_id: impl Into<CodeId>,
+        sender: Addr,
+        init_msg: M,
+        send_funds: Vec<Coin>,
+        label: String,
+        admin: Option<String>,
+    ) -> impl Future<Output = AnyResult<Addr>>
+    where
+        M: Serialize + Send + 'static,
+    {
+        let code_id = code_id.into();
+        self.with_app(move |app| {
+            app.instantiate_contract(code_id, sender, &init_msg, &send_funds, label, admin)
+        })
+    }
+
+    /// Sends a smart query to a contract and returns the deserialized response.
+    pub fn query_wasm_smart<T, M>(
+        &self,
+        contract_addr: Addr,
+        msg: M,
+    ) -> impl Future<Output = AnyResult<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+        M: Serialize + Send + 'static,
+    {
+        self.with_app(move |app| Ok(app.wrap().query_wasm_smart(contract_addr, &msg)?))
+    }
+
+    /// Updates the current block, like [App::update_block].
+    pub fn update_block<F>(&self, action: F) -> impl Future<Output = ()>
+    where
+        F: Fn(&mut BlockInfo) + Send + 'static,
+    {
+        self.with_app(move |app| app.update_block(action))
+    }
+}
+
+impl<A: 'static> Drop for AsyncApp<A> {
+    /// Stops the thread owning the application after all submitted operations are processed.
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(worker) = self.worker.take() {
+            // panics are already propagated to the awaiting tasks
+            let _ = worker.join();
+        }
+    }
+}
+
+/// State shared between the application thread and the awaiting task.
+struct Shared<R> {
+    outcome: Option<Outcome<R>>,
+    waker: Option<Waker>,
+}
+
+impl<R> Default for Shared<R> {
+    fn default() -> Self {
+        Self {
+            outcome: None,
+            waker: None,
+        }
+    }
+}
+
+/// Future resolving to the result of an operation processed by the application thread.
+struct Completion<R> {
+    shared: Arc<Mutex<Shared<R>>>,
+}
+
+impl<R> Future for Completion<R> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<R> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.outcome.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(payload)) => {
+                drop(shared);
+                resume_unwind(payload)
+            }
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
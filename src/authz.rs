@@ -0,0 +1,699 @@
+//! # Authz module
+//!
+//! Simplified simulation of the `x/authz` module of the Cosmos SDK.
+//!
+//! [AuthzKeeper] handles `MsgGrant`, `MsgRevoke` and `MsgExec` messages sent from contracts
+//! as `CosmosMsg::Stargate` or `CosmosMsg::Any` messages, stores granted authorizations,
+//! and answers `/cosmos.authz.v1beta1.Query/Grants`, `/cosmos.authz.v1beta1.Query/GranterGrants`
+//! and `/cosmos.authz.v1beta1.Query/GranteeGrants` queries sent as `QueryRequest::Stargate`
+//! or `QueryRequest::Grpc`. All other messages and queries are rejected,
+//! like in [StargateFailing](crate::StargateFailing).
+//!
+//! Messages wrapped in `MsgExec` are validated against the granted authorizations
+//! and dispatched through the router with the granter as the sender.
+//! Supported wrapped messages are `/cosmos.bank.v1beta1.MsgSend`
+//! (dispatched as `BankMsg::Send`) and `/cosmwasm.wasm.v1.MsgExecuteContract`
+//! (dispatched as `WasmMsg::Execute`).
+
+use crate::error::{bail, AnyResult};
+use crate::feegrant::{coins_from_proto, coins_to_proto, ProtoAny, ProtoCoin, ProtoTimestamp};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::{AppResponse, CosmosRouter, Stargate};
+use cosmwasm_std::{
+    Addr, AnyMsg, Api, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, CustomMsg, CustomQuery, Event,
+    GrpcQuery, Order, Querier, Storage, Timestamp, WasmMsg,
+};
+use cw_storage_plus::Map;
+use prost::Message;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Type URL of the message granting an authorization.
+pub(crate) const MSG_GRANT: &str = "/cosmos.authz.v1beta1.MsgGrant";
+
+/// Type URL of the message revoking an authorization.
+pub(crate) const MSG_REVOKE: &str = "/cosmos.authz.v1beta1.MsgRevoke";
+
+/// Type URL of the message executing messages on behalf of the granter.
+pub(crate) const MSG_EXEC: &str = "/cosmos.authz.v1beta1.MsgExec";
+
+/// Type URL of the generic authorization.
+pub(crate) const GENERIC_AUTHORIZATION: &str = "/cosmos.authz.v1beta1.GenericAuthorization";
+
+/// Type URL of the send authorization.
+pub(crate) const SEND_AUTHORIZATION: &str = "/cosmos.bank.v1beta1.SendAuthorization";
+
+/// Type URL of the bank send message.
+pub(crate) const MSG_SEND: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+/// Type URL of the message executing a contract.
+pub(crate) const MSG_EXECUTE_CONTRACT: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+/// Path of the query returning grants between granter and grantee.
+pub(crate) const QUERY_GRANTS: &str = "/cosmos.authz.v1beta1.Query/Grants";
+
+/// Path of the query returning grants given by a granter.
+pub(crate) const QUERY_GRANTER_GRANTS: &str = "/cosmos.authz.v1beta1.Query/GranterGrants";
+
+/// Path of the query returning grants received by a grantee.
+pub(crate) const QUERY_GRANTEE_GRANTS: &str = "/cosmos.authz.v1beta1.Query/GranteeGrants";
+
+/// Default storage namespace for authz module.
+const NAMESPACE_AUTHZ: &[u8] = b"authz";
+
+/// Collection of grants, indexed by granter and grantee addresses and message type URL.
+const GRANTS: Map<(&Addr, &Addr, &str), AuthzGrant> = Map::new("grants");
+
+/// Authorization allowing the grantee to execute any message of the specified type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GenericAuthorization {
+    /// Type URL of the authorized message.
+    pub msg: String,
+}
+
+/// Authorization allowing the grantee to send tokens from the granter's account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SendAuthorization {
+    /// Maximum amount of tokens that can be sent, decremented with every send.
+    pub spend_limit: Vec<Coin>,
+    /// Addresses tokens can be sent to, empty means any address.
+    pub allow_list: Vec<String>,
+}
+
+/// Authorization granted by granter to grantee.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Authorization {
+    /// Generic authorization.
+    Generic(GenericAuthorization),
+    /// Send authorization.
+    Send(SendAuthorization),
+}
+
+impl Authorization {
+    /// Returns the type URL of the message authorized by this authorization.
+    pub fn msg_type_url(&self) -> &str {
+        match self {
+            Authorization::Generic(generic) => &generic.msg,
+            Authorization::Send(_) => MSG_SEND,
+        }
+    }
+}
+
+/// Authorization with optional expiration time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AuthzGrant {
+    /// Granted authorization.
+    pub authorization: Authorization,
+    /// Time when this grant expires, `None` means no expiration.
+    pub expiration: Option<Timestamp>,
+}
+
+impl AuthzGrant {
+    /// Returns `true` when this grant has expired at the specified block.
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expiration
+            .is_some_and(|expiration| expiration < block.time)
+    }
+}
+
+/// Keeper simulating the authz module.
+#[derive(Clone, Default)]
+pub struct AuthzKeeper;
+
+impl AuthzKeeper {
+    /// Creates a new authz keeper.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns the grant given by `granter` to `grantee` for messages of type `msg_type_url`,
+    /// expired grants are reported as not existing.
+    pub fn grant(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        granter: &Addr,
+        grantee: &Addr,
+        msg_type_url: &str,
+    ) -> AnyResult<Option<AuthzGrant>> {
+        let storage = prefixed_read(storage, NAMESPACE_AUTHZ);
+        Ok(GRANTS
+            .may_load(&storage, (granter, grantee, msg_type_url))?
+            .filter(|grant| !grant.is_expired(block)))
+    }
+
+    /// Returns all not expired grants, optionally filtered by granter and grantee,
+    /// as tuples of granter address, grantee address and grant.
+    fn grants(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        granter: Option<&Addr>,
+        grantee: Option<&Addr>,
+    ) -> AnyResult<Vec<(Addr, Addr, AuthzGrant)>> {
+        let storage = prefixed_read(storage, NAMESPACE_AUTHZ);
+        let records = match granter {
+            Some(granter) => GRANTS
+                .sub_prefix(granter)
+                .range(&storage, None, None, Order::Ascending)
+                .map(|item| item.map(|((grantee, _), grant)| (granter.clone(), grantee, grant)))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => GRANTS
+                .range(&storage, None, None, Order::Ascending)
+                .map(|item| item.map(|((granter, grantee, _), grant)| (granter, grantee, grant)))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        Ok(records
+            .into_iter()
+            .filter(|(_, record_grantee, grant)| {
+                !grant.is_expired(block) && grantee.iter().all(|grantee| *grantee == record_grantee)
+            })
+            .collect())
+    }
+
+    fn execute_msg<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        type_url: &str,
+        value: &[u8],
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match type_url {
+            MSG_GRANT => {
+                let msg = MsgGrant::decode(value)?;
+                let (granter, grantee) =
+                    validate_parties(api, &sender, &msg.granter, &msg.grantee)?;
+                let Some(grant) = msg.grant else {
+                    bail!("missing grant");
+                };
+                let Some(authorization) = grant.authorization else {
+                    bail!("missing authorization");
+                };
+                let grant = AuthzGrant {
+                    authorization: decode_authorization(api, authorization)?,
+                    expiration: grant.expiration.map(Timestamp::from),
+                };
+                if grant.is_expired(block) {
+                    bail!("expiration must be after the current block time");
+                }
+                let msg_type_url = grant.authorization.msg_type_url().to_string();
+                let mut storage = prefixed(storage, NAMESPACE_AUTHZ);
+                GRANTS.save(&mut storage, (&granter, &grantee, &msg_type_url), &grant)?;
+                Ok(AppResponse {
+                    events: vec![grant_event(
+                        "cosmos.authz.v1beta1.EventGrant",
+                        &granter,
+                        &grantee,
+                        &msg_type_url,
+                    )],
+                    data: None,
+                })
+            }
+            MSG_REVOKE => {
+                let msg = MsgRevoke::decode(value)?;
+                let (granter, grantee) =
+                    validate_parties(api, &sender, &msg.granter, &msg.grantee)?;
+                let mut storage = prefixed(storage, NAMESPACE_AUTHZ);
+                let key = (&granter, &grantee, msg.msg_type_url.as_str());
+                if !GRANTS.has(&storage, key) {
+                    bail!("authorization not found");
+                }
+                GRANTS.remove(&mut storage, key);
+                Ok(AppResponse {
+                    events: vec![grant_event(
+                        "cosmos.authz.v1beta1.EventRevoke",
+                        &granter,
+                        &grantee,
+                        &msg.msg_type_url,
+                    )],
+                    data: None,
+                })
+            }
+            MSG_EXEC => {
+                let msg = MsgExec::decode(value)?;
+                let grantee = api.addr_validate(&msg.grantee)?;
+                if grantee != sender {
+                    bail!(
+                        "unauthorized: sender {} is not the grantee {}",
+                        sender,
+                        grantee
+                    );
+                }
+                if msg.msgs.is_empty() {
+                    bail!("messages cannot be empty");
+                }
+                let mut events = vec![];
+                let mut results = vec![];
+                for (index, any) in msg.msgs.into_iter().enumerate() {
+                    let (granter, cosmos_msg) = decode_exec_msg(api, &any)?;
+                    if granter != grantee {
+                        events.extend(self.accept(
+                            storage,
+                            block,
+                            &granter,
+                            &grantee,
+                            &any.type_url,
+                            &cosmos_msg,
+                        )?);
+                    }
+                    let res = router.execute(api, storage, block, granter, cosmos_msg)?;
+                    events.extend(
+                        res.events
+                            .into_iter()
+                            .map(|event| event.add_attribute("authz_msg_index", index.to_string())),
+                    );
+                    results.push(res.data.map(Vec::from).unwrap_or_default());
+                }
+                Ok(AppResponse {
+                    events,
+                    data: Some(MsgExecResponse { results }.encode_to_vec().into()),
+                })
+            }
+            _ => bail!("Unexpected authz message: type_url={}", type_url),
+        }
+    }
+
+    /// Checks if the message can be executed by the grantee on behalf of the granter,
+    /// and updates the grant. Fully spent grants are removed, returns the emitted events.
+    fn accept<ExecC>(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        granter: &Addr,
+        grantee: &Addr,
+        msg_type_url: &str,
+        msg: &CosmosMsg<ExecC>,
+    ) -> AnyResult<Vec<Event>> {
+        let Some(mut grant) = self.grant(storage, block, granter, grantee, msg_type_url)? else {
+            bail!(
+                "failed to get grant with given granter: {}, grantee: {} & msgType: {} : authorization not found",
+                granter,
+                grantee,
+                msg_type_url
+            );
+        };
+        let mut storage = prefixed(storage, NAMESPACE_AUTHZ);
+        if let (Authorization::Send(send), CosmosMsg::Bank(BankMsg::Send { to_address, amount })) =
+            (&mut grant.authorization, msg)
+        {
+            if !send.allow_list.is_empty() && !send.allow_list.contains(to_address) {
+                bail!("cannot send to {} address", to_address);
+            }
+            for coin in amount {
+                match send.spend_limit.iter_mut().find(|c| c.denom == coin.denom) {
+                    Some(limit) if limit.amount >= coin.amount => limit.amount -= coin.amount,
+                    _ => bail!("requested amount is more than spend limit"),
+                }
+            }
+            send.spend_limit.retain(|c| !c.amount.is_zero());
+            if send.spend_limit.is_empty() {
+                GRANTS.remove(&mut storage, (granter, grantee, msg_type_url));
+                return Ok(vec![grant_event(
+                    "cosmos.authz.v1beta1.EventRevoke",
+                    granter,
+                    grantee,
+                    msg_type_url,
+                )]);
+            }
+            GRANTS.save(&mut storage, (granter, grantee, msg_type_url), &grant)?;
+        }
+        Ok(vec![])
+    }
+
+    fn query_msg(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        path: &str,
+        data: &[u8],
+    ) -> AnyResult<Binary> {
+        match path {
+            QUERY_GRANTS => {
+                let request = QueryGrantsRequest::decode(data)?;
+                let granter = api.addr_validate(&request.granter)?;
+                let grantee = api.addr_validate(&request.grantee)?;
+                let grants = if request.msg_type_url.is_empty() {
+                    self.grants(storage, block, Some(&granter), Some(&grantee))?
+                        .into_iter()
+                        .map(|(_, _, grant)| grant)
+                        .collect()
+                } else {
+                    match self.grant(storage, block, &granter, &grantee, &request.msg_type_url)? {
+                        Some(grant) => vec![grant],
+                        None => bail!("authorization not found for {} type", request.msg_type_url),
+                    }
+                };
+                let response = QueryGrantsResponse {
+                    grants: grants.iter().map(ProtoGrant::from).collect(),
+                };
+                Ok(response.encode_to_vec().into())
+            }
+            QUERY_GRANTER_GRANTS => {
+                let request = QueryGranterGrantsRequest::decode(data)?;
+                let granter = api.addr_validate(&request.granter)?;
+                let grants = self.grants(storage, block, Some(&granter), None)?;
+                Ok(grant_authorizations(grants).encode_to_vec().into())
+            }
+            QUERY_GRANTEE_GRANTS => {
+                let request = QueryGranteeGrantsRequest::decode(data)?;
+                let grantee = api.addr_validate(&request.grantee)?;
+                let grants = self.grants(storage, block, None, Some(&grantee))?;
+                Ok(grant_authorizations(grants).encode_to_vec().into())
+            }
+            _ => bail!("Unexpected authz query: path={}", path),
+        }
+    }
+}
+
+impl Stargate for AuthzKeeper {
+    fn execute_stargate<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        type_url: String,
+        value: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        self.execute_msg(api, storage, router, block, sender, &type_url, &value)
+    }
+
+    fn query_stargate(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        block: &BlockInfo,
+        path: String,
+        data: Binary,
+    ) -> AnyResult<Binary> {
+        self.query_msg(api, storage, block, &path, &data)
+    }
+
+    fn execute_any<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: AnyMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        self.execute_msg(
+            api,
+            storage,
+            router,
+            block,
+            sender,
+            &msg.type_url,
+            &msg.value,
+        )
+    }
+
+    fn query_grpc(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> AnyResult<Binary> {
+        self.query_msg(api, storage, block, &request.path, &request.data)
+    }
+}
+
+/// Validates the granter and the grantee, only the granter is allowed to send the message.
+fn validate_parties(
+    api: &dyn Api,
+    sender: &Addr,
+    granter: &str,
+    grantee: &str,
+) -> AnyResult<(Addr, Addr)> {
+    let granter = api.addr_validate(granter)?;
+    let grantee = api.addr_validate(grantee)?;
+    if granter != *sender {
+        bail!(
+            "unauthorized: sender {} is not the granter {}",
+            sender,
+            granter
+        );
+    }
+    if granter == grantee {
+        bail!("grantee and granter should be different");
+    }
+    Ok((granter, grantee))
+}
+
+/// Creates an event reporting a granted or revoked authorization.
+fn grant_event(ty: &str, granter: &Addr, grantee: &Addr, msg_type_url: &str) -> Event {
+    Event::new(ty)
+        .add_attribute("msg_type_url", msg_type_url)
+        .add_attribute("granter", granter)
+        .add_attribute("grantee", grantee)
+}
+
+fn decode_authorization(api: &dyn Api, any: ProtoAny) -> AnyResult<Authorization> {
+    match any.type_url.as_str() {
+        GENERIC_AUTHORIZATION => {
+            let proto = ProtoGenericAuthorization::decode(any.value.as_slice())?;
+            if proto.msg.is_empty() {
+                bail!("message type URL cannot be empty");
+            }
+            Ok(Authorization::Generic(GenericAuthorization {
+                msg: proto.msg,
+            }))
+        }
+        SEND_AUTHORIZATION => {
+            let proto = ProtoSendAuthorization::decode(any.value.as_slice())?;
+            let spend_limit = coins_from_proto(proto.spend_limit)?;
+            if spend_limit.is_empty() || spend_limit.iter().any(|c| c.amount.is_zero()) {
+                bail!("spend limit must be positive");
+            }
+            for address in &proto.allow_list {
+                api.addr_validate(address)?;
+            }
+            Ok(Authorization::Send(SendAuthorization {
+                spend_limit,
+                allow_list: proto.allow_list,
+            }))
+        }
+        other => bail!("unsupported authorization type: {}", other),
+    }
+}
+
+fn encode_authorization(authorization: &Authorization) -> ProtoAny {
+    match authorization {
+        Authorization::Generic(generic) => ProtoAny {
+            type_url: GENERIC_AUTHORIZATION.to_string(),
+            value: ProtoGenericAuthorization {
+                msg: generic.msg.clone(),
+            }
+            .encode_to_vec(),
+        },
+        Authorization::Send(send) => ProtoAny {
+            type_url: SEND_AUTHORIZATION.to_string(),
+            value: ProtoSendAuthorization {
+                spend_limit: coins_to_proto(&send.spend_limit),
+                allow_list: send.allow_list.clone(),
+            }
+            .encode_to_vec(),
+        },
+    }
+}
+
+/// Decodes the message wrapped in `MsgExec`, returns its signer and the message to be dispatched.
+fn decode_exec_msg<ExecC>(api: &dyn Api, any: &ProtoAny) -> AnyResult<(Addr, CosmosMsg<ExecC>)> {
+    match any.type_url.as_str() {
+        MSG_SEND => {
+            let msg = ProtoMsgSend::decode(any.value.as_slice())?;
+            Ok((
+                api.addr_validate(&msg.from_address)?,
+                BankMsg::Send {
+                    to_address: msg.to_address,
+                    amount: coins_from_proto(msg.amount)?,
+                }
+                .into(),
+            ))
+        }
+        MSG_EXECUTE_CONTRACT => {
+            let msg = ProtoMsgExecuteContract::decode(any.value.as_slice())?;
+            Ok((
+                api.addr_validate(&msg.sender)?,
+                WasmMsg::Execute {
+                    contract_addr: msg.contract,
+                    msg: msg.msg.into(),
+                    funds: coins_from_proto(msg.funds)?,
+                }
+                .into(),
+            ))
+        }
+        other => bail!("unsupported message type in MsgExec: {}", other),
+    }
+}
+
+fn grant_authorizations(grants: Vec<(Addr, Addr, AuthzGrant)>) -> QueryGrantAuthorizationsResponse {
+    QueryGrantAuthorizationsResponse {
+        grants: grants
+            .into_iter()
+            .map(|(granter, grantee, grant)| ProtoGrantAuthorization {
+                granter: granter.into(),
+                grantee: grantee.into(),
+                authorization: Some(encode_authorization(&grant.authorization)),
+                expiration: grant.expiration.map(ProtoTimestamp::from),
+            })
+            .collect(),
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoGenericAuthorization {
+    #[prost(string, tag = "1")]
+    pub msg: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoSendAuthorization {
+    #[prost(message, repeated, tag = "1")]
+    pub spend_limit: Vec<ProtoCoin>,
+    #[prost(string, repeated, tag = "2")]
+    pub allow_list: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoGrant {
+    #[prost(message, optional, tag = "1")]
+    pub authorization: Option<ProtoAny>,
+    #[prost(message, optional, tag = "2")]
+    pub expiration: Option<ProtoTimestamp>,
+}
+
+impl From<&AuthzGrant> for ProtoGrant {
+    fn from(value: &AuthzGrant) -> Self {
+        Self {
+            authorization: Some(encode_authorization(&value.authorization)),
+            expiration: value.expiration.map(ProtoTimestamp::from),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoGrantAuthorization {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub authorization: Option<ProtoAny>,
+    #[prost(message, optional, tag = "4")]
+    pub expiration: Option<ProtoTimestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgGrant {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub grant: Option<ProtoGrant>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgRevoke {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(string, tag = "3")]
+    pub msg_type_url: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgExec {
+    #[prost(string, tag = "1")]
+    pub grantee: String,
+    #[prost(message, repeated, tag = "2")]
+    pub msgs: Vec<ProtoAny>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgExecResponse {
+    #[prost(bytes, repeated, tag = "1")]
+    pub results: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoMsgSend {
+    #[prost(string, tag = "1")]
+    pub from_address: String,
+    #[prost(string, tag = "2")]
+    pub to_address: String,
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoMsgExecuteContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(bytes, tag = "3")]
+    pub msg: Vec<u8>,
+    #[prost(message, repeated, tag = "5")]
+    pub funds: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryGrantsRequest {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(string, tag = "3")]
+    pub msg_type_url: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryGrantsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub grants: Vec<ProtoGrant>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryGranterGrantsRequest {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryGranteeGrantsRequest {
+    #[prost(string, tag = "1")]
+    pub grantee: String,
+}
+
+/// Response shared by `GranterGrants` and `GranteeGrants` queries, both have the same layout.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryGrantAuthorizationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub grants: Vec<ProtoGrantAuthorization>,
+}
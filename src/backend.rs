@@ -0,0 +1,136 @@
+//! # Chain backend
+
+use crate::error::AnyResult;
+use crate::featured::staking::{Distribution, Staking};
+use crate::{
+    next_block, App, AppResponse, Bank, Contract, Executor, Gov, Ibc, Module, Stargate, Wasm,
+};
+use cosmwasm_std::{Addr, Api, Binary, Coin, Empty, Storage, WasmMsg, WasmQuery};
+use cw_utils::parse_instantiate_response_data;
+
+/// Object-safe facade over an environment that can upload, instantiate, execute and query
+/// contracts, like a chain simulated by [App] or a live node.
+///
+/// Deployment frameworks and scripts can depend on this trait (usually used as a trait object)
+/// instead of the full generic [App] type. Messages are passed as raw JSON bytes.
+///
+/// # Example
+///
+/// ```
+/// use cw_multi_test::{App, ChainBackend};
+///
+/// fn advance(backend: &mut dyn ChainBackend, blocks: u64) {
+///     for _ in 0..blocks {
+///         backend.advance_block();
+///     }
+/// }
+///
+/// let mut app = App::default();
+/// let height = app.block_info().height;
+///
+/// advance(&mut app, 5);
+///
+/// assert_eq!(height + 5, app.block_info().height);
+/// ```
+pub trait ChainBackend {
+    /// Uploads the contract's code and returns the code identifier.
+    fn upload(&mut self, code: Box<dyn Contract<Empty>>) -> u64;
+
+    /// Instantiates a new contract from the code with specified identifier
+    /// and returns the address of the contract.
+    fn instantiate(
+        &mut self,
+        code_id: u64,
+        sender: &Addr,
+        msg: &[u8],
+        funds: &[Coin],
+        label: &str,
+        admin: Option<&Addr>,
+    ) -> AnyResult<Addr>;
+
+    /// Executes the contract with specified address.
+    fn execute(
+        &mut self,
+        sender: &Addr,
+        contract: &Addr,
+        msg: &[u8],
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse>;
+
+    /// Sends a smart query to the contract with specified address and returns the raw response.
+    fn query(&self, contract: &Addr, msg: &[u8]) -> AnyResult<Binary>;
+
+    /// Advances the chain to the next block.
+    fn advance_block(&mut self);
+}
+
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT> ChainBackend
+    for App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+where
+    BankT: Bank,
+    ApiT: Api,
+    StorageT: Storage,
+    CustomT: Module<ExecT = Empty, QueryT = Empty>,
+    WasmT: Wasm<Empty, Empty>,
+    StakingT: Staking,
+    DistrT: Distribution,
+    IbcT: Ibc,
+    GovT: Gov,
+    StargateT: Stargate,
+{
+    fn upload(&mut self, code: Box<dyn Contract<Empty>>) -> u64 {
+        self.store_code(code)
+    }
+
+    fn instantiate(
+        &mut self,
+        code_id: u64,
+        sender: &Addr,
+        msg: &[u8],
+        funds: &[Coin],
+        label: &str,
+        admin: Option<&Addr>,
+    ) -> AnyResult<Addr> {
+        let msg = WasmMsg::Instantiate {
+            admin: admin.map(Addr::to_string),
+            code_id,
+            msg: Binary::from(msg),
+            funds: funds.to_vec(),
+            label: label.to_string(),
+        };
+        let res = Executor::execute(self, sender.clone(), msg.into())?;
+        let data = parse_instantiate_response_data(res.data.unwrap_or_default().as_slice())?;
+        Ok(Addr::unchecked(data.contract_address))
+    }
+
+    fn execute(
+        &mut self,
+        sender: &Addr,
+        contract: &Addr,
+        msg: &[u8],
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        let msg = WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: Binary::from(msg),
+            funds: funds.to_vec(),
+        };
+        Executor::execute(self, sender.clone(), msg.into())
+    }
+
+    fn query(&self, contract: &Addr, msg: &[u8]) -> AnyResult<Binary> {
+        let request = WasmQuery::Smart {
+            contract_addr: contract.to_string(),
+            msg: Binary::from(msg),
+        };
+        self.read_module(|router, api, storage| {
+            let block = self.block_info();
+            let querier = router.querier(api, storage, &block);
+            router.wasm.query(api, storage, &querier, &block, request)
+        })
+    }
+
+    fn advance_block(&mut self) {
+        self.update_block(next_block);
+    }
+}
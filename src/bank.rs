@@ -2,10 +2,11 @@ use crate::app::CosmosRouter;
 use crate::error::{bail, AnyResult};
 use crate::executor::AppResponse;
 use crate::module::Module;
+use crate::module_address;
 use crate::prefixed_storage::{prefixed, prefixed_read};
 use cosmwasm_std::{
     coin, to_json_binary, Addr, AllBalanceResponse, Api, BalanceResponse, BankMsg, BankQuery,
-    Binary, BlockInfo, Coin, DenomMetadata, Event, Querier, Storage,
+    Binary, BlockInfo, Coin, DenomMetadata, Event, PageRequest, Querier, Storage,
 };
 #[cfg(feature = "cosmwasm_1_3")]
 use cosmwasm_std::{AllDenomMetadataResponse, DenomMetadataResponse};
@@ -15,6 +16,8 @@ use cw_storage_plus::Map;
 use cw_utils::NativeBalance;
 use itertools::Itertools;
 use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// Collection of bank balances.
 const BALANCES: Map<&Addr, NativeBalance> = Map::new("balances");
@@ -22,19 +25,71 @@ const BALANCES: Map<&Addr, NativeBalance> = Map::new("balances");
 /// Collection of metadata for denomination.
 const DENOM_METADATA: Map<String, DenomMetadata> = Map::new("metadata");
 
+/// Saves the metadata of the denomination specified as `base`
+/// in the same storage the denomination metadata queries are served from.
+pub(crate) fn save_denom_metadata(
+    storage: &mut dyn Storage,
+    metadata: &DenomMetadata,
+) -> AnyResult<()> {
+    DENOM_METADATA
+        .save(storage, metadata.base.clone(), metadata)
+        .map_err(Into::into)
+}
+
+/// Returns `true` when the metadata of the denomination is saved
+/// in the storage the denomination metadata queries are served from.
+pub(crate) fn has_denom_metadata(storage: &dyn Storage, denom: &str) -> bool {
+    DENOM_METADATA.has(storage, denom.to_string())
+}
+
 /// Default storage namespace for bank module.
-const NAMESPACE_BANK: &[u8] = b"bank";
+pub(crate) const NAMESPACE_BANK: &[u8] = b"bank";
+
+/// Default number of items in a page, when the limit in page request is zero.
+const DEFAULT_PAGE_LIMIT: u32 = 100;
+
+/// Name of the mint module account, the same as in Cosmos SDK.
+const MINT_MODULE_NAME: &str = "mint";
 
 /// A message representing privileged actions in bank module.
-#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum BankSudo {
     /// Minting privileged action.
+    ///
+    /// Emits `coin_received` and `coinbase` events, with the mint module account
+    /// reported as the minter.
     Mint {
         /// Destination address the tokens will be minted for.
         to_address: String,
         /// Amount of the minted tokens.
         amount: Vec<Coin>,
     },
+    /// Privileged transfer of tokens from one account to multiple recipients,
+    /// like `MsgMultiSend` in Cosmos SDK.
+    ///
+    /// The total amount is deducted from the sender's balance at once.
+    /// When any recipient address is invalid or the sender has insufficient funds,
+    /// no tokens are transferred.
+    MultiSend {
+        /// Source address the tokens will be sent from.
+        from_address: String,
+        /// Destination addresses with the amounts of tokens sent to each of them.
+        outputs: Vec<(String, Vec<Coin>)>,
+    },
+    /// Privileged transfer of tokens from any account, without the owner's involvement,
+    /// e.g. to move tokens held by a contract without executing it.
+    ///
+    /// Emits a `force_transfer` event instead of the `transfer` event,
+    /// so forced transfers are never confused with transfers made by the owner.
+    /// The total supply is not changed.
+    ForceTransfer {
+        /// Source address the tokens will be taken from.
+        from: String,
+        /// Destination address the tokens will be given to.
+        to: String,
+        /// Amount of the transferred tokens.
+        amount: Vec<Coin>,
+    },
 }
 
 /// This trait defines the interface for simulating banking operations.
@@ -42,15 +97,32 @@ pub enum BankSudo {
 /// In the test environment, it is essential for testing financial transactions,
 /// like transfers and balance checks, within your smart contracts.
 /// This trait implements all of these functionalities.
-pub trait Bank: Module<ExecT = BankMsg, QueryT = BankQuery, SudoT = BankSudo> {}
+pub trait Bank: Module<ExecT = BankMsg, QueryT = BankQuery, SudoT = BankSudo> {
+    /// Initializes balances of multiple accounts in genesis,
+    /// used by [AppBuilder::with_genesis_balances](crate::AppBuilder::with_genesis_balances).
+    ///
+    /// The default implementation always returns an error.
+    fn init_balances(
+        &self,
+        storage: &mut dyn Storage,
+        balances: &[(Addr, Vec<Coin>)],
+    ) -> AnyResult<()> {
+        let _ = (storage, balances);
+        bail!("genesis balances are not supported by this bank module")
+    }
+}
 
 /// A structure representing a default bank keeper.
 ///
 /// Manages financial interactions in CosmWasm tests, such as simulating token transactions
 /// and account balances. This is particularly important for contracts that deal with financial
 /// operations in the Cosmos ecosystem.
-#[derive(Default)]
-pub struct BankKeeper {}
+#[derive(Clone, Default)]
+pub struct BankKeeper {
+    /// Address of the mint module account reported in `coinbase` events,
+    /// derived from the module name when not set explicitly.
+    minter: Option<Addr>,
+}
 
 impl BankKeeper {
     /// Creates a new instance of a bank keeper with default settings.
@@ -58,6 +130,26 @@ impl BankKeeper {
         Self::default()
     }
 
+    /// Sets the address of the mint module account, reported as `minter` in `coinbase` events.
+    ///
+    /// By default, the address is derived from the module name
+    /// using [module_address](crate::module_address).
+    pub fn with_minter(mut self, minter: Addr) -> Self {
+        self.minter = Some(minter);
+        self
+    }
+
+    /// Returns the address of the mint module account.
+    ///
+    /// Unless set with [with_minter](Self::with_minter), the address is derived
+    /// from the module name, so it is a valid address with the chain prefix.
+    pub fn minter(&self, api: &dyn Api) -> AnyResult<Addr> {
+        match &self.minter {
+            Some(minter) => Ok(minter.clone()),
+            None => module_address(api, MINT_MODULE_NAME),
+        }
+    }
+
     /// Administration function for adjusting bank accounts in genesis.
     pub fn init_balance(
         &self,
@@ -69,6 +161,28 @@ impl BankKeeper {
         self.set_balance(&mut bank_storage, account, amount)
     }
 
+    /// Administration function for adjusting multiple bank accounts in genesis.
+    ///
+    /// Fails when the same account is provided more than once
+    /// (no balance is written in such case).
+    pub fn init_balances(
+        &self,
+        storage: &mut dyn Storage,
+        balances: &[(Addr, Vec<Coin>)],
+    ) -> AnyResult<()> {
+        let mut accounts = BTreeSet::new();
+        for (account, _) in balances {
+            if !accounts.insert(account) {
+                bail!("duplicated account in genesis balances: {}", account);
+            }
+        }
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        for (account, amount) in balances {
+            self.set_balance(&mut bank_storage, account, amount.clone())?;
+        }
+        Ok(())
+    }
+
     /// Administration function for adjusting bank accounts.
     fn set_balance(
         &self,
@@ -101,6 +215,26 @@ impl BankKeeper {
         Ok(val.unwrap_or_default().into_vec())
     }
 
+    /// Returns one page of balances for specified address, ordered by denomination.
+    ///
+    /// The key in page request is the denomination the page starts with,
+    /// the returned key is the denomination starting the next page,
+    /// or `None` when there are no more balances.
+    pub fn get_balances_page(
+        &self,
+        storage: &dyn Storage,
+        address: &Addr,
+        pagination: Option<PageRequest>,
+    ) -> AnyResult<(Vec<Coin>, Option<Binary>)> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        let balances = self
+            .get_balance(&bank_storage, address)?
+            .into_iter()
+            .map(|c| (c.denom.clone(), c))
+            .collect();
+        paginate(balances, pagination)
+    }
+
     #[cfg(feature = "cosmwasm_1_1")]
     fn get_supply(&self, bank_storage: &dyn Storage, denom: String) -> AnyResult<Coin> {
         let supply: Uint128 = BALANCES
@@ -131,6 +265,24 @@ impl BankKeeper {
         self.mint(bank_storage, to_address, amount)
     }
 
+    fn multi_send(
+        &self,
+        bank_storage: &mut dyn Storage,
+        from_address: Addr,
+        outputs: Vec<(Addr, Vec<Coin>)>,
+    ) -> AnyResult<()> {
+        let total = outputs
+            .iter()
+            .fold(NativeBalance::default(), |total, (_, amount)| {
+                total + NativeBalance(amount.clone())
+            });
+        self.burn(bank_storage, from_address, total.into_vec())?;
+        for (to_address, amount) in outputs {
+            self.mint(bank_storage, to_address, amount)?;
+        }
+        Ok(())
+    }
+
     fn mint(
         &self,
         bank_storage: &mut dyn Storage,
@@ -166,6 +318,44 @@ impl BankKeeper {
     }
 }
 
+/// Returns the page of values selected by page request from items ordered by keys.
+fn paginate<T>(
+    mut items: Vec<(String, T)>,
+    pagination: Option<PageRequest>,
+) -> AnyResult<(Vec<T>, Option<Binary>)> {
+    let Some(page) = pagination else {
+        return Ok((items.into_iter().map(|(_, value)| value).collect(), None));
+    };
+    if page.reverse {
+        items.reverse();
+    }
+    let start = match page.key {
+        Some(key) => {
+            let key = String::from_utf8(key.to_vec())?;
+            items
+                .iter()
+                .position(|(k, _)| if page.reverse { *k <= key } else { *k >= key })
+                .unwrap_or(items.len())
+        }
+        None => 0,
+    };
+    let limit = (if page.limit == 0 {
+        DEFAULT_PAGE_LIMIT
+    } else {
+        page.limit
+    }) as usize;
+    let mut remaining = items.into_iter().skip(start);
+    let values = remaining
+        .by_ref()
+        .take(limit)
+        .map(|(_, value)| value)
+        .collect();
+    let next_key = remaining
+        .next()
+        .map(|(key, _)| Binary::from(key.into_bytes()));
+    Ok((values, next_key))
+}
+
 fn coins_to_string(coins: &[Coin]) -> String {
     coins
         .iter()
@@ -173,7 +363,15 @@ fn coins_to_string(coins: &[Coin]) -> String {
         .join(",")
 }
 
-impl Bank for BankKeeper {}
+impl Bank for BankKeeper {
+    fn init_balances(
+        &self,
+        storage: &mut dyn Storage,
+        balances: &[(Addr, Vec<Coin>)],
+    ) -> AnyResult<()> {
+        BankKeeper::init_balances(self, storage, balances)
+    }
+}
 
 impl Module for BankKeeper {
     type ExecT = BankMsg;
@@ -206,9 +404,12 @@ impl Module for BankKeeper {
                 Ok(AppResponse { events, data: None })
             }
             BankMsg::Burn { amount } => {
-                // burn doesn't seem to emit any events
+                // the same event as emitted by Cosmos SDK when burning coins
+                let events = vec![Event::new("burn")
+                    .add_attribute("burner", &sender)
+                    .add_attribute("amount", coins_to_string(&amount))];
                 self.burn(&mut bank_storage, sender, amount)?;
-                Ok(AppResponse::default())
+                Ok(AppResponse { events, data: None })
             }
             other => unimplemented!("bank message: {other:?}"),
         }
@@ -253,12 +454,12 @@ impl Module for BankKeeper {
                 to_json_binary(&res).map_err(Into::into)
             }
             #[cfg(feature = "cosmwasm_1_3")]
-            BankQuery::AllDenomMetadata { pagination: _ } => {
-                let mut metadata = vec![];
-                for key in DENOM_METADATA.keys(storage, None, None, Order::Ascending) {
-                    metadata.push(DENOM_METADATA.may_load(storage, key?)?.unwrap_or_default());
-                }
-                let res = AllDenomMetadataResponse::new(metadata, None);
+            BankQuery::AllDenomMetadata { pagination } => {
+                let metadata = DENOM_METADATA
+                    .range(storage, None, None, Order::Ascending)
+                    .collect::<StdResult<Vec<_>>>()?;
+                let (metadata, next_key) = paginate(metadata, pagination)?;
+                let res = AllDenomMetadataResponse::new(metadata, next_key);
                 to_json_binary(&res).map_err(Into::into)
             }
             other => unimplemented!("bank query: {other:?}"),
@@ -277,8 +478,49 @@ impl Module for BankKeeper {
         match msg {
             BankSudo::Mint { to_address, amount } => {
                 let to_address = api.addr_validate(&to_address)?;
+                let amount = self.normalize_amount(amount)?;
+                // the same events as emitted by Cosmos SDK when minting coins
+                let events = vec![
+                    Event::new("coin_received")
+                        .add_attribute("receiver", &to_address)
+                        .add_attribute("amount", coins_to_string(&amount)),
+                    Event::new("coinbase")
+                        .add_attribute("minter", self.minter(api)?)
+                        .add_attribute("amount", coins_to_string(&amount)),
+                ];
                 self.mint(&mut bank_storage, to_address, amount)?;
-                Ok(AppResponse::default())
+                Ok(AppResponse { events, data: None })
+            }
+            BankSudo::MultiSend {
+                from_address,
+                outputs,
+            } => {
+                let from_address = api.addr_validate(&from_address)?;
+                let outputs = outputs
+                    .into_iter()
+                    .map(|(to_address, amount)| Ok((api.addr_validate(&to_address)?, amount)))
+                    .collect::<AnyResult<Vec<_>>>()?;
+                let events = outputs
+                    .iter()
+                    .map(|(to_address, amount)| {
+                        Event::new("transfer")
+                            .add_attribute("recipient", to_address)
+                            .add_attribute("sender", &from_address)
+                            .add_attribute("amount", coins_to_string(amount))
+                    })
+                    .collect();
+                self.multi_send(&mut bank_storage, from_address, outputs)?;
+                Ok(AppResponse { events, data: None })
+            }
+            BankSudo::ForceTransfer { from, to, amount } => {
+                let from = api.addr_validate(&from)?;
+                let to = api.addr_validate(&to)?;
+                let events = vec![Event::new("force_transfer")
+                    .add_attribute("recipient", &to)
+                    .add_attribute("sender", &from)
+                    .add_attribute("amount", coins_to_string(&amount))];
+                self.send(&mut bank_storage, from, to, amount)?;
+                Ok(AppResponse { events, data: None })
             }
         }
     }
@@ -569,6 +811,160 @@ mod test {
         assert_eq!(res.metadata[1].name, denom_eth_name);
     }
 
+    #[test]
+    #[cfg(feature = "cosmwasm_1_3")]
+    fn paginate_all_denom_metadata_should_work() {
+        let api = MockApi::default();
+        let mut store = MockStorage::new();
+        let block = mock_env().block;
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        let bank = BankKeeper::new();
+        // set metadata for 25 denominations
+        for i in 0..25 {
+            let denom = format!("denom{:02}", i);
+            let metadata = DenomMetadata {
+                name: denom.clone(),
+                ..Default::default()
+            };
+            bank.set_denom_metadata(&mut store, denom, metadata)
+                .unwrap();
+        }
+        // query metadata in pages of 10
+        let mut names = vec![];
+        let mut key = None;
+        for expected_len in [10, 10, 5] {
+            let req = BankQuery::AllDenomMetadata {
+                pagination: Some(PageRequest {
+                    key,
+                    limit: 10,
+                    reverse: false,
+                }),
+            };
+            let raw = bank.query(&api, &store, &querier, &block, req).unwrap();
+            let res: AllDenomMetadataResponse = from_json(raw).unwrap();
+            assert_eq!(expected_len, res.metadata.len());
+            names.extend(res.metadata.into_iter().map(|m| m.name));
+            key = res.next_key;
+        }
+        // there are no more pages, no denomination is duplicated or skipped
+        assert_eq!(None, key);
+        let expected = (0..25)
+            .map(|i| format!("denom{:02}", i))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, names);
+    }
+
+    #[test]
+    fn paginate_balances_should_work() {
+        let api = MockApi::default();
+        let mut store = MockStorage::new();
+        let bank = BankKeeper::new();
+        let owner = api.addr_make("owner");
+        // set balances in 25 denominations
+        let balances = (0..25)
+            .map(|i| coin(100 + i, format!("denom{:02}", i)))
+            .collect::<Vec<_>>();
+        bank.init_balance(&mut store, &owner, balances.clone())
+            .unwrap();
+        // read balances in pages of 10
+        let mut coins = vec![];
+        let mut key = None;
+        for expected_len in [10, 10, 5] {
+            let page = PageRequest {
+                key,
+                limit: 10,
+                reverse: false,
+            };
+            let (page_coins, next_key) =
+                bank.get_balances_page(&store, &owner, Some(page)).unwrap();
+            assert_eq!(expected_len, page_coins.len());
+            coins.extend(page_coins);
+            key = next_key;
+        }
+        // there are no more pages, no balance is duplicated or skipped
+        assert_eq!(None, key);
+        assert_eq!(balances, coins);
+        // reading in reverse order returns the last balances first
+        let page = PageRequest {
+            key: None,
+            limit: 2,
+            reverse: true,
+        };
+        let (page_coins, next_key) = bank.get_balances_page(&store, &owner, Some(page)).unwrap();
+        assert_eq!(vec![balances[24].clone(), balances[23].clone()], page_coins);
+        assert_eq!(Some(Binary::from(b"denom22")), next_key);
+        // without page request all balances are returned
+        let (all_coins, next_key) = bank.get_balances_page(&store, &owner, None).unwrap();
+        assert_eq!(balances, all_coins);
+        assert_eq!(None, next_key);
+    }
+
+    #[test]
+    fn init_balances_should_work() {
+        let api = MockApi::default();
+        let mut store = MockStorage::new();
+        let bank = BankKeeper::new();
+
+        // initialize balances of a few accounts
+        let balances = (0..3u128)
+            .map(|i| {
+                (
+                    api.addr_make(&format!("account{}", i)),
+                    vec![coin(i + 1, "eth"), coin(2, "btc")],
+                )
+            })
+            .collect::<Vec<_>>();
+        bank.init_balances(&mut store, &balances).unwrap();
+
+        let bank_storage = prefixed_read(&store, NAMESPACE_BANK);
+        // check all balances
+        for (i, (account, _)) in balances.iter().enumerate() {
+            let i = i as u128;
+            assert_eq!(
+                vec![coin(2, "btc"), coin(i + 1, "eth")],
+                bank.get_balance(&bank_storage, account).unwrap()
+            );
+        }
+
+        // check the aggregated supply
+        #[cfg(feature = "cosmwasm_1_1")]
+        {
+            assert_eq!(
+                coin(6, "btc"),
+                bank.get_supply(&bank_storage, "btc".to_string()).unwrap()
+            );
+            assert_eq!(
+                coin(6, "eth"),
+                bank.get_supply(&bank_storage, "eth".to_string()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn init_balances_should_fail_for_duplicated_accounts() {
+        let api = MockApi::default();
+        let mut store = MockStorage::new();
+        let bank = BankKeeper::new();
+
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let balances = vec![
+            (alice.clone(), coins(100, "eth")),
+            (bob.clone(), coins(200, "eth")),
+            (alice.clone(), coins(300, "eth")),
+        ];
+        assert_eq!(
+            format!("duplicated account in genesis balances: {}", alice),
+            bank.init_balances(&mut store, &balances)
+                .unwrap_err()
+                .to_string()
+        );
+
+        // no balance was written
+        let bank_storage = prefixed_read(&store, NAMESPACE_BANK);
+        assert!(bank.get_balance(&bank_storage, &bob).unwrap().is_empty());
+    }
+
     #[test]
     fn fail_on_zero_values() {
         let api = MockApi::default();
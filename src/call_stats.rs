@@ -0,0 +1,130 @@
+//! # Contract call statistics
+
+use cosmwasm_std::Addr;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Contract entry-point counted in [CallStats].
+#[derive(
+    Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryPoint {
+    /// `instantiate` entry-point.
+    Instantiate,
+    /// `execute` entry-point.
+    Execute,
+    /// `query` entry-point.
+    Query,
+    /// `sudo` entry-point.
+    Sudo,
+    /// `reply` entry-point.
+    Reply,
+    /// `migrate` entry-point.
+    Migrate,
+}
+
+/// Number of invocations of contract entry-points, counted per contract address.
+///
+/// Statistics are collected by [WasmKeeper](crate::WasmKeeper) only when enabled
+/// with [AppBuilder::with_call_stats](crate::AppBuilder::with_call_stats).
+/// All invocations are counted, including calls triggered by submessages
+/// and queries sent by contracts to other contracts. Calls that failed
+/// are counted too, because the statistics are not reverted with the state.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallStats {
+    counters: BTreeMap<(Addr, EntryPoint), u64>,
+}
+
+impl CallStats {
+    /// Increments the counter of invocations of the entry-point of specified contract.
+    pub(crate) fn record(&mut self, address: &Addr, entry_point: EntryPoint) {
+        *self
+            .counters
+            .entry((address.clone(), entry_point))
+            .or_default() += 1;
+    }
+
+    /// Returns the number of invocations of the entry-point of specified contract.
+    pub fn calls(&self, address: &Addr, entry_point: EntryPoint) -> u64 {
+        self.counters
+            .get(&(address.clone(), entry_point))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of invocations of the entry-point of all contracts.
+    pub fn total_calls(&self, entry_point: EntryPoint) -> u64 {
+        self.counters
+            .iter()
+            .filter(|((_, ep), _)| *ep == entry_point)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Returns the number of `instantiate` calls of specified contract.
+    pub fn instantiations(&self, address: &Addr) -> u64 {
+        self.calls(address, EntryPoint::Instantiate)
+    }
+
+    /// Returns the number of `execute` calls of specified contract.
+    pub fn executes(&self, address: &Addr) -> u64 {
+        self.calls(address, EntryPoint::Execute)
+    }
+
+    /// Returns the number of smart queries of specified contract.
+    pub fn queries(&self, address: &Addr) -> u64 {
+        self.calls(address, EntryPoint::Query)
+    }
+
+    /// Returns the number of `sudo` calls of specified contract.
+    pub fn sudos(&self, address: &Addr) -> u64 {
+        self.calls(address, EntryPoint::Sudo)
+    }
+
+    /// Returns the number of `reply` calls of specified contract.
+    pub fn replies(&self, address: &Addr) -> u64 {
+        self.calls(address, EntryPoint::Reply)
+    }
+
+    /// Returns the number of `migrate` calls of specified contract.
+    pub fn migrations(&self, address: &Addr) -> u64 {
+        self.calls(address, EntryPoint::Migrate)
+    }
+
+    /// Returns the number of `instantiate` calls of all contracts.
+    pub fn total_instantiations(&self) -> u64 {
+        self.total_calls(EntryPoint::Instantiate)
+    }
+
+    /// Returns the number of `execute` calls of all contracts.
+    pub fn total_executes(&self) -> u64 {
+        self.total_calls(EntryPoint::Execute)
+    }
+
+    /// Returns the number of smart queries of all contracts.
+    pub fn total_queries(&self) -> u64 {
+        self.total_calls(EntryPoint::Query)
+    }
+
+    /// Returns the number of `sudo` calls of all contracts.
+    pub fn total_sudos(&self) -> u64 {
+        self.total_calls(EntryPoint::Sudo)
+    }
+
+    /// Returns the number of `reply` calls of all contracts.
+    pub fn total_replies(&self) -> u64 {
+        self.total_calls(EntryPoint::Reply)
+    }
+
+    /// Returns the number of `migrate` calls of all contracts.
+    pub fn total_migrations(&self) -> u64 {
+        self.total_calls(EntryPoint::Migrate)
+    }
+
+    /// Returns `true` when no calls were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+}
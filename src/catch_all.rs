@@ -0,0 +1,405 @@
+//! # Catch-all contract
+//!
+//! A contract standing in for dependencies whose real implementation is out of the scope
+//! of a test. It accepts any JSON message, records every call in its own storage,
+//! responds with canned responses and answers queries with canned answers.
+//!
+//! # Example
+//!
+//! ```
+//! use cosmwasm_std::{Empty, Event, Response};
+//! use cw_multi_test::catch_all::{
+//!     mock_dependency_contract, CannedResponse, CatchAllInstantiateMsg, MsgMatcher, RecordedCalls,
+//! };
+//! use cw_multi_test::{App, EntryPoint, Executor};
+//!
+//! let mut app = App::default();
+//! let owner = app.api().addr_make("owner");
+//! let code_id = app.store_code(mock_dependency_contract());
+//!
+//! // respond to any executed message with an event
+//! let msg = CatchAllInstantiateMsg::new().with_response(
+//!     CannedResponse::new(
+//!         MsgMatcher::any(),
+//!         Response::new().add_event(Event::new("handled")),
+//!     )
+//!     .on(EntryPoint::Execute),
+//! );
+//! let contract_addr = app
+//!     .instantiate_contract(code_id, owner.clone(), &msg, &[], "dependency", None)
+//!     .unwrap();
+//!
+//! let res = app
+//!     .execute_contract(owner, contract_addr.clone(), &Empty {}, &[])
+//!     .unwrap();
+//! assert!(res.has_event(&Event::new("wasm-handled")));
+//!
+//! // both instantiation and execution are recorded
+//! let calls = RecordedCalls::load(&app, &contract_addr).unwrap();
+//! assert_eq!(EntryPoint::Instantiate, calls.calls[0].entry_point);
+//! assert_eq!(EntryPoint::Execute, calls.calls[1].entry_point);
+//! ```
+
+use crate::error::{anyhow, AnyResult};
+use crate::{ChainBackend, Contract, EntryPoint};
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_string, Addr, Binary, Coin, Deps, DepsMut, Empty, Env,
+    MessageInfo, Reply, Response, StdResult,
+};
+use cw_storage_plus::{Deque, Item};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Configuration of the catch-all contract, set at instantiation.
+const CONFIG: Item<CatchAllInstantiateMsg> = Item::new("catch_all_config");
+
+/// Calls received by the catch-all contract, oldest call first.
+const CALLS: Deque<RecordedCall> = Deque::new("catch_all_calls");
+
+/// Key of the built-in query returning the recorded calls.
+const CALLS_QUERY_KEY: &str = "__calls__";
+
+/// Instantiation message of the catch-all contract, configuring canned responses and answers.
+///
+/// Instantiation messages not matching this shape are recorded like any other message,
+/// and the contract starts without canned responses and answers.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CatchAllInstantiateMsg {
+    /// Responses returned from entry-points, the first matching response is used.
+    #[serde(default)]
+    pub responses: Vec<CannedResponse>,
+    /// Answers returned from queries, the first matching answer is used.
+    #[serde(default)]
+    pub queries: Vec<CannedQuery>,
+}
+
+impl CatchAllInstantiateMsg {
+    /// Creates an instantiation message without canned responses and answers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a canned response returned from entry-points.
+    pub fn with_response(mut self, response: CannedResponse) -> Self {
+        self.responses.push(response);
+        self
+    }
+
+    /// Adds a canned answer returned from queries.
+    pub fn with_query(mut self, query: CannedQuery) -> Self {
+        self.queries.push(query);
+        self
+    }
+}
+
+/// Response returned from `execute`, `sudo` or `migrate` entry-point
+/// when the incoming message is matched.
+///
+/// When no canned response matches, an empty response is returned.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CannedResponse {
+    /// Entry-point the response is returned from, any entry-point when not set.
+    pub entry_point: Option<EntryPoint>,
+    /// Matcher of the incoming message.
+    pub matcher: MsgMatcher,
+    /// Returned response, including data, events and submessages.
+    pub response: Response,
+}
+
+impl CannedResponse {
+    /// Creates a response returned from any entry-point when the message is matched.
+    pub fn new(matcher: MsgMatcher, response: Response) -> Self {
+        Self {
+            entry_point: None,
+            matcher,
+            response,
+        }
+    }
+
+    /// Restricts the response to the specified entry-point.
+    pub fn on(mut self, entry_point: EntryPoint) -> Self {
+        self.entry_point = Some(entry_point);
+        self
+    }
+}
+
+/// Answer returned from `query` entry-point when the incoming query is matched.
+///
+/// When no canned answer matches, the query fails.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CannedQuery {
+    /// Matcher of the incoming query.
+    pub matcher: MsgMatcher,
+    /// Returned answer.
+    pub answer: Binary,
+}
+
+impl CannedQuery {
+    /// Creates an answer returned when the query is matched.
+    pub fn new(matcher: MsgMatcher, answer: &impl Serialize) -> StdResult<Self> {
+        Ok(Self {
+            matcher,
+            answer: to_json_binary(answer)?,
+        })
+    }
+}
+
+/// Matcher of incoming JSON messages.
+///
+/// The path is a dot-separated list of object keys and array indexes,
+/// like `transfer.amount` or `batch.0.recipient`. An empty path matches any message.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct MsgMatcher {
+    /// Path of the value in the message, that must be present.
+    pub path: String,
+    /// JSON representation of the value expected at the path, any value when not set.
+    pub value: Option<String>,
+}
+
+impl MsgMatcher {
+    /// Creates a matcher accepting any message.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Creates a matcher accepting messages with any value at the specified path.
+    pub fn path(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            value: None,
+        }
+    }
+
+    /// Creates a matcher accepting messages with the specified value at the specified path.
+    pub fn path_eq(path: impl Into<String>, value: &impl Serialize) -> StdResult<Self> {
+        Ok(Self {
+            path: path.into(),
+            value: Some(to_json_string(value)?),
+        })
+    }
+
+    /// Returns `true` when the raw JSON message is matched.
+    pub fn matches(&self, msg: &[u8]) -> bool {
+        let Ok(msg) = serde_json::from_slice::<Value>(msg) else {
+            return false;
+        };
+        let Some(actual) = value_at_path(&msg, &self.path) else {
+            return false;
+        };
+        match &self.value {
+            None => true,
+            Some(expected) => serde_json::from_str::<Value>(expected).is_ok_and(|v| &v == actual),
+        }
+    }
+}
+
+/// Call received by the catch-all contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RecordedCall {
+    /// Called entry-point.
+    pub entry_point: EntryPoint,
+    /// Sender of the message, not set for `sudo` and `migrate` entry-points.
+    pub sender: Option<Addr>,
+    /// Funds sent with the message.
+    pub funds: Vec<Coin>,
+    /// Raw message.
+    pub msg: Binary,
+    /// Height of the block the call was made in.
+    pub height: u64,
+}
+
+/// Calls recorded by the catch-all contract, returned from the built-in `{"__calls__":{}}` query.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct RecordedCalls {
+    /// Recorded calls, oldest call first.
+    pub calls: Vec<RecordedCall>,
+}
+
+impl RecordedCalls {
+    /// Loads the calls recorded by the catch-all contract with specified address.
+    pub fn load(backend: &dyn ChainBackend, contract: &Addr) -> AnyResult<Self> {
+        let msg = format!(r#"{{"{}":{{}}}}"#, CALLS_QUERY_KEY);
+        let calls = backend.query(contract, msg.as_bytes())?;
+        Ok(from_json(calls)?)
+    }
+}
+
+/// Returns the catch-all contract, standing in for dependencies of the tested contract.
+pub fn mock_dependency_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(CatchAllContract)
+}
+
+/// Contract accepting any message, see [mock_dependency_contract].
+struct CatchAllContract;
+
+impl CatchAllContract {
+    /// Records the call in the contract storage.
+    fn record(
+        deps: &mut DepsMut,
+        env: &Env,
+        entry_point: EntryPoint,
+        info: Option<MessageInfo>,
+        msg: &[u8],
+    ) -> AnyResult<()> {
+        let (sender, funds) = info.map_or((None, vec![]), |info| (Some(info.sender), info.funds));
+        let call = RecordedCall {
+            entry_point,
+            sender,
+            funds,
+            msg: msg.to_vec().into(),
+            height: env.block.height,
+        };
+        Ok(CALLS.push_back(deps.storage, &call)?)
+    }
+
+    /// Records the call and returns the first matching canned response.
+    fn respond(
+        mut deps: DepsMut,
+        env: &Env,
+        entry_point: EntryPoint,
+        info: Option<MessageInfo>,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response> {
+        Self::record(&mut deps, env, entry_point, info, &msg)?;
+        let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+        Ok(config
+            .responses
+            .into_iter()
+            .find(|canned| {
+                (canned.entry_point.is_none() || canned.entry_point == Some(entry_point))
+                    && canned.matcher.matches(&msg)
+            })
+            .map(|canned| canned.response)
+            .unwrap_or_default())
+    }
+}
+
+impl Contract<Empty> for CatchAllContract {
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response> {
+        Self::respond(deps, &env, EntryPoint::Execute, Some(info), msg)
+    }
+
+    fn instantiate(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response> {
+        let config = from_json::<CatchAllInstantiateMsg>(&msg).unwrap_or_default();
+        CONFIG.save(deps.storage, &config)?;
+        Self::record(&mut deps, &env, EntryPoint::Instantiate, Some(info), &msg)?;
+        Ok(Response::default())
+    }
+
+    fn query(&self, deps: Deps, _env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        if MsgMatcher::path(CALLS_QUERY_KEY).matches(&msg) {
+            let calls = CALLS.iter(deps.storage)?.collect::<StdResult<Vec<_>>>()?;
+            return Ok(to_json_binary(&RecordedCalls { calls })?);
+        }
+        let config = CONFIG.may_load(deps.storage)?.unwrap_or_default();
+        config
+            .queries
+            .into_iter()
+            .find(|canned| canned.matcher.matches(&msg))
+            .map(|canned| canned.answer)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no canned answer for query: {}",
+                    String::from_utf8_lossy(&msg)
+                )
+            })
+    }
+
+    fn sudo(&self, deps: DepsMut, env: Env, msg: Vec<u8>) -> AnyResult<Response> {
+        Self::respond(deps, &env, EntryPoint::Sudo, None, msg)
+    }
+
+    fn reply(&self, _deps: DepsMut, _env: Env, _msg: Reply) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn migrate(&self, deps: DepsMut, env: Env, msg: Vec<u8>) -> AnyResult<Response> {
+        Self::respond(deps, &env, EntryPoint::Migrate, None, msg)
+    }
+}
+
+/// Returns the value at the dot-separated path, the value itself for an empty path.
+fn value_at_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.')
+        .try_fold(value, |value, segment| match value {
+            Value::Object(fields) => fields.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matching_paths_should_work() {
+        let msg =
+            br#"{"batch":[{"recipient":"alice","amount":10},{"recipient":"bob"}],"memo":null}"#;
+        assert!(MsgMatcher::any().matches(msg));
+        assert!(MsgMatcher::path("batch").matches(msg));
+        assert!(MsgMatcher::path("batch.1.recipient").matches(msg));
+        assert!(MsgMatcher::path("memo").matches(msg));
+        assert!(!MsgMatcher::path("batch.2").matches(msg));
+        assert!(!MsgMatcher::path("batch.one").matches(msg));
+        assert!(!MsgMatcher::path("transfer").matches(msg));
+        assert!(MsgMatcher::path_eq("batch.0.amount", &10)
+            .unwrap()
+            .matches(msg));
+        assert!(MsgMatcher::path_eq("batch.1.recipient", &"bob")
+            .unwrap()
+            .matches(msg));
+        assert!(!MsgMatcher::path_eq("batch.1.recipient", &"alice")
+            .unwrap()
+            .matches(msg));
+        assert!(!MsgMatcher::path_eq("batch.0.amount", &"10")
+            .unwrap()
+            .matches(msg));
+    }
+
+    #[test]
+    fn matching_objects_should_ignore_key_order() {
+        let msg = br#"{"transfer":{"recipient":"alice","amount":10}}"#;
+        let matcher = MsgMatcher {
+            path: "transfer".to_string(),
+            value: Some(r#"{"amount":10,"recipient":"alice"}"#.to_string()),
+        };
+        assert!(matcher.matches(msg));
+    }
+
+    #[test]
+    fn messages_with_floats_should_be_matched() {
+        let msg = br#"{"swap":{"max_slippage":0.5,"amount":10}}"#;
+        assert!(MsgMatcher::any().matches(msg));
+        assert!(MsgMatcher::path("swap.max_slippage").matches(msg));
+        assert!(MsgMatcher::path_eq("swap.amount", &10)
+            .unwrap()
+            .matches(msg));
+        let matcher = MsgMatcher {
+            path: "swap.max_slippage".to_string(),
+            value: Some("0.5".to_string()),
+        };
+        assert!(matcher.matches(msg));
+    }
+
+    #[test]
+    fn invalid_json_should_not_be_matched() {
+        assert!(!MsgMatcher::any().matches(b"not a json"));
+    }
+}
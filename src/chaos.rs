@@ -0,0 +1,458 @@
+//! # Chaos testing
+//!
+//! Decorators injecting reproducible failures into module operations,
+//! used to test how multi-contract protocols recover from failing messages and queries.
+
+use crate::app::CosmosRouter;
+use crate::error::{bail, AnyResult};
+#[cfg(feature = "staking")]
+use crate::featured::staking::{StakeKeeper, Staking};
+use crate::{
+    AppResponse, Bank, BankKeeper, CallStats, Contract, ContractData, ContractHistoryEntry, Module,
+    ReplyTrace, StorageAccessStats, Wasm, WasmKeeper, WasmSudo,
+};
+use cosmwasm_std::{
+    Addr, Api, Binary, BlockInfo, Coin, CustomMsg, CustomQuery, Decimal, Empty, Querier, Record,
+    Storage, WasmMsg, WasmQuery,
+};
+use serde::de::DeserializeOwned;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::ops::Deref;
+
+/// Default error message reported by injected failures.
+const DEFAULT_ERROR_MESSAGE: &str = "out of gas";
+
+/// Resolution of random draws compared with the failure rate.
+const DRAW_RESOLUTION: u64 = 1_000_000;
+
+/// Configuration of failures injected by a [Chaotic] module.
+///
+/// Every message (and every query, when enabled) processed by the decorated module
+/// is a numbered call, starting from zero. A call fails when its index is listed
+/// in `failing_calls`, or when a random draw, made from a generator seeded with `seed`,
+/// falls below `failure_rate`. The same configuration fails the same calls in every run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChaosConfig {
+    /// Seed of the random number generator.
+    pub seed: u64,
+    /// Probability of failing a call, from zero (never) to one (always).
+    pub failure_rate: Decimal,
+    /// Error message reported by failing calls.
+    pub error_message: String,
+    /// Indexes of calls that always fail.
+    pub failing_calls: BTreeSet<u64>,
+    /// Flag indicating if queries may fail too.
+    pub fail_queries: bool,
+}
+
+impl Default for ChaosConfig {
+    /// Creates a configuration that never injects failures.
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            failure_rate: Decimal::zero(),
+            error_message: DEFAULT_ERROR_MESSAGE.to_string(),
+            failing_calls: BTreeSet::new(),
+            fail_queries: false,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Creates a configuration failing calls randomly with specified probability.
+    pub fn new(seed: u64, failure_rate: Decimal) -> Self {
+        Self {
+            seed,
+            failure_rate,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a configuration failing only the calls with specified indexes.
+    pub fn deterministic_failures(failing_calls: Vec<u64>) -> Self {
+        Self {
+            failing_calls: failing_calls.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the error message reported by failing calls.
+    pub fn with_error_message(mut self, error_message: impl Into<String>) -> Self {
+        self.error_message = error_message.into();
+        self
+    }
+
+    /// Enables failing queries, by default only messages fail.
+    pub fn with_failing_queries(mut self) -> Self {
+        self.fail_queries = true;
+        self
+    }
+}
+
+/// Decorator of a module, failing some of its calls as configured with [ChaosConfig].
+///
+/// Failures are injected before the call is delegated to the decorated module,
+/// so a failing call never changes the state. Privileged (sudo) actions never fail.
+/// The decorated module is accessible through [Deref], so it can be initialized
+/// the same way as when used directly.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{coins, BankMsg};
+/// use cw_multi_test::{AppBuilder, BankKeeper, ChaosConfig, ChaoticBank, Executor};
+///
+/// let bank = ChaoticBank::new(BankKeeper::new(), ChaosConfig::deterministic_failures(vec![0]));
+/// let mut app = AppBuilder::default().with_bank(bank).build(|router, api, storage| {
+///     let owner = api.addr_make("owner");
+///     router.bank.init_balance(storage, &owner, coins(10, "denom")).unwrap();
+/// });
+/// let owner = app.api().addr_make("owner");
+/// let msg = BankMsg::Send {
+///     to_address: app.api().addr_make("recipient").to_string(),
+///     amount: coins(1, "denom"),
+/// };
+///
+/// // the first send fails, the second one succeeds
+/// let err = app.execute(owner.clone(), msg.clone().into()).unwrap_err();
+/// assert_eq!("out of gas", err.root_cause().to_string());
+/// app.execute(owner, msg.into()).unwrap();
+/// ```
+pub struct Chaotic<M> {
+    /// Decorated module.
+    inner: M,
+    /// Configuration of injected failures.
+    config: ChaosConfig,
+    /// Number of calls processed so far.
+    calls: Cell<u64>,
+    /// State of the random number generator.
+    rng: Cell<u64>,
+}
+
+/// Bank module failing some of bank messages.
+pub type ChaoticBank<B = BankKeeper> = Chaotic<B>;
+
+/// Staking module failing some of staking messages and queries.
+#[cfg(feature = "staking")]
+pub type ChaoticStaking<S = StakeKeeper> = Chaotic<S>;
+
+/// Wasm module failing some of wasm messages, including messages sent by contracts.
+pub type ChaoticWasm<ExecC = Empty, QueryC = Empty> = Chaotic<WasmKeeper<ExecC, QueryC>>;
+
+impl<M> Chaotic<M> {
+    /// Creates a decorator of the module, failing calls as configured.
+    pub fn new(inner: M, config: ChaosConfig) -> Self {
+        let rng = Cell::new(config.seed);
+        Self {
+            inner,
+            config,
+            calls: Cell::new(0),
+            rng,
+        }
+    }
+
+    /// Returns the number of calls processed so far, including the failed ones.
+    pub fn calls(&self) -> u64 {
+        self.calls.get()
+    }
+
+    /// Returns the decorated module.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Counts the call and returns an error when the call should fail.
+    fn check(&self) -> AnyResult<()> {
+        let index = self.calls.get();
+        self.calls.set(index + 1);
+        // the random draw is made for every call, so scripted failures do not shift it
+        let draw = Decimal::from_ratio(self.next_random() % DRAW_RESOLUTION, DRAW_RESOLUTION);
+        if self.config.failing_calls.contains(&index) || draw < self.config.failure_rate {
+            bail!("{}", self.config.error_message);
+        }
+        Ok(())
+    }
+
+    /// Counts the query and returns an error when the query should fail.
+    fn check_query(&self) -> AnyResult<()> {
+        if self.config.fail_queries {
+            self.check()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the next number from SplitMix64 generator.
+    fn next_random(&self) -> u64 {
+        let state = self.rng.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+        self.rng.set(state);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl<M> Deref for Chaotic<M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.inner
+    }
+}
+
+impl<M: Module> Module for Chaotic<M> {
+    type ExecT = M::ExecT;
+    type QueryT = M::QueryT;
+    type SudoT = M::SudoT;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: Self::ExecT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        self.check()?;
+        self.inner.execute(api, storage, router, block, sender, msg)
+    }
+
+    fn query(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: Self::QueryT,
+    ) -> AnyResult<Binary> {
+        self.check_query()?;
+        self.inner.query(api, storage, querier, block, request)
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: Self::SudoT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        self.inner.sudo(api, storage, router, block, msg)
+    }
+}
+
+impl<B: Bank> Bank for Chaotic<B> {
+    fn init_balances(
+        &self,
+        storage: &mut dyn Storage,
+        balances: &[(Addr, Vec<Coin>)],
+    ) -> AnyResult<()> {
+        self.inner.init_balances(storage, balances)
+    }
+}
+
+#[cfg(feature = "staking")]
+impl<S: Staking> Staking for Chaotic<S> {
+    fn process_queue<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+    ) -> AnyResult<AppResponse> {
+        self.inner.process_queue(api, storage, router, block)
+    }
+}
+
+impl<ExecC, QueryC, W: Wasm<ExecC, QueryC>> Wasm<ExecC, QueryC> for Chaotic<W> {
+    fn execute(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: WasmMsg,
+    ) -> AnyResult<AppResponse> {
+        self.check()?;
+        self.inner.execute(api, storage, router, block, sender, msg)
+    }
+
+    fn query(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: WasmQuery,
+    ) -> AnyResult<Binary> {
+        self.check_query()?;
+        self.inner.query(api, storage, querier, block, request)
+    }
+
+    fn sudo(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: WasmSudo,
+    ) -> AnyResult<AppResponse> {
+        self.inner.sudo(api, storage, router, block, msg)
+    }
+
+    fn store_code(&mut self, creator: Addr, code: Box<dyn Contract<ExecC, QueryC>>) -> u64 {
+        self.inner.store_code(creator, code)
+    }
+
+    fn store_code_with_id(
+        &mut self,
+        creator: Addr,
+        code_id: u64,
+        code: Box<dyn Contract<ExecC, QueryC>>,
+    ) -> AnyResult<u64> {
+        self.inner.store_code_with_id(creator, code_id, code)
+    }
+
+    fn duplicate_code(&mut self, code_id: u64) -> AnyResult<u64> {
+        self.inner.duplicate_code(code_id)
+    }
+
+    fn duplicate_code_with_new_checksum(&mut self, code_id: u64) -> AnyResult<u64> {
+        self.inner.duplicate_code_with_new_checksum(code_id)
+    }
+
+    fn code_creator(&self, code_id: u64) -> AnyResult<Addr> {
+        self.inner.code_creator(code_id)
+    }
+
+    fn contract_data(&self, storage: &dyn Storage, address: &Addr) -> AnyResult<ContractData> {
+        self.inner.contract_data(storage, address)
+    }
+
+    fn contracts(&self, storage: &dyn Storage) -> AnyResult<Vec<(Addr, ContractData)>> {
+        self.inner.contracts(storage)
+    }
+
+    fn dump_wasm_raw(&self, storage: &dyn Storage, address: &Addr) -> Vec<Record> {
+        self.inner.dump_wasm_raw(storage, address)
+    }
+
+    fn contract_history(
+        &self,
+        storage: &dyn Storage,
+        address: &Addr,
+    ) -> AnyResult<Vec<ContractHistoryEntry>> {
+        self.inner.contract_history(storage, address)
+    }
+
+    fn contract_namespace(&self, contract: &Addr) -> Vec<u8> {
+        self.inner.contract_namespace(contract)
+    }
+
+    fn contract_storage<'a>(
+        &self,
+        storage: &'a dyn Storage,
+        address: &Addr,
+    ) -> Box<dyn Storage + 'a> {
+        self.inner.contract_storage(storage, address)
+    }
+
+    fn contract_storage_mut<'a>(
+        &self,
+        storage: &'a mut dyn Storage,
+        address: &Addr,
+    ) -> Box<dyn Storage + 'a> {
+        self.inner.contract_storage_mut(storage, address)
+    }
+
+    fn call_stats(&self) -> CallStats {
+        self.inner.call_stats()
+    }
+
+    fn reset_call_stats(&self) {
+        self.inner.reset_call_stats()
+    }
+
+    fn storage_access_stats(&self) -> StorageAccessStats {
+        self.inner.storage_access_stats()
+    }
+
+    fn reset_storage_access_stats(&self) {
+        self.inner.reset_storage_access_stats()
+    }
+
+    fn reply_traces(&self) -> Vec<ReplyTrace> {
+        self.inner.reply_traces()
+    }
+
+    fn reentrancy_warnings(&self) -> Vec<String> {
+        self.inner.reentrancy_warnings()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns the indexes of failed calls among the first `count` calls.
+    fn failed_calls(config: ChaosConfig, count: u64) -> Vec<u64> {
+        let chaotic = Chaotic::new((), config);
+        (0..count).filter(|_| chaotic.check().is_err()).collect()
+    }
+
+    #[test]
+    fn same_seed_should_fail_same_calls() {
+        let config = ChaosConfig::new(42, Decimal::percent(30));
+        let failed = failed_calls(config.clone(), 100);
+        assert!(!failed.is_empty());
+        assert!(failed.len() < 100);
+        assert_eq!(failed, failed_calls(config, 100));
+    }
+
+    #[test]
+    fn different_seeds_should_fail_different_calls() {
+        assert_ne!(
+            failed_calls(ChaosConfig::new(1, Decimal::percent(50)), 100),
+            failed_calls(ChaosConfig::new(2, Decimal::percent(50)), 100)
+        );
+    }
+
+    #[test]
+    fn extreme_failure_rates_should_work() {
+        assert!(failed_calls(ChaosConfig::new(7, Decimal::zero()), 100).is_empty());
+        assert_eq!(
+            100,
+            failed_calls(ChaosConfig::new(7, Decimal::one()), 100).len()
+        );
+    }
+
+    #[test]
+    fn deterministic_failures_should_work() {
+        assert_eq!(
+            vec![0, 3, 4],
+            failed_calls(ChaosConfig::deterministic_failures(vec![4, 0, 3]), 10)
+        );
+    }
+
+    #[test]
+    fn error_message_should_be_reported() {
+        let chaotic = Chaotic::new(
+            (),
+            ChaosConfig::new(0, Decimal::one()).with_error_message("injected failure"),
+        );
+        assert_eq!("injected failure", chaotic.check().unwrap_err().to_string());
+        assert_eq!(1, chaotic.calls());
+    }
+}
@@ -0,0 +1,86 @@
+//! # Strongly-typed code identifiers
+//!
+//! Used to tell code identifiers apart from other numbers, so they can not be mixed up
+//! with e.g. counters, heights or amounts without the compiler noticing.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+
+/// Identifier of the stored contract code.
+///
+/// Code identifiers returned by [App::store_code](crate::App::store_code) are plain `u64` values,
+/// all helpers taking a code identifier, like [Executor::instantiate_contract](crate::Executor::instantiate_contract),
+/// accept both `u64` and [CodeId]. Converting the stored code identifier into [CodeId]
+/// and taking [CodeId] in own helper functions (strict usage) lets the compiler catch
+/// arguments passed in a wrong order.
+///
+/// # Example
+///
+/// ```compile_fail
+/// use cw_multi_test::CodeId;
+///
+/// /// Instantiates a vault holding `cw20_count` tokens.
+/// fn instantiate_vault(vault_code_id: CodeId, cw20_count: u64) { /* ... */ }
+///
+/// let vault_code_id = CodeId::new(1);
+/// let cw20_count = 3;
+///
+/// // arguments swapped, does not compile
+/// instantiate_vault(cw20_count, vault_code_id);
+/// ```
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(transparent)]
+pub struct CodeId(u64);
+
+impl CodeId {
+    /// Creates a code identifier from the raw value.
+    pub const fn new(code_id: u64) -> Self {
+        Self(code_id)
+    }
+
+    /// Returns the raw value of the code identifier.
+    pub const fn u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for CodeId {
+    fn from(code_id: u64) -> Self {
+        Self(code_id)
+    }
+}
+
+impl From<CodeId> for u64 {
+    fn from(code_id: CodeId) -> Self {
+        code_id.0
+    }
+}
+
+impl Deref for CodeId {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PartialEq<u64> for CodeId {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<CodeId> for u64 {
+    fn eq(&self, other: &CodeId) -> bool {
+        *self == other.0
+    }
+}
+
+impl fmt::Display for CodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,112 @@
+//! # Raw contract state queries
+//!
+//! Answers `/cosmwasm.wasm.v1.Query/AllContractState` queries sent as `QueryRequest::Stargate`
+//! or `QueryRequest::Grpc`, like the wasm module of `wasmd` does, so off-chain components
+//! syncing the full state of a contract (e.g. indexers) can be tested with the multi-test.
+//!
+//! Records of the contract are returned in the ascending order of keys (or descending,
+//! when `reverse` is requested), paginated with `next_key` like in Cosmos SDK.
+
+use crate::error::{bail, AnyResult};
+use crate::Wasm;
+use cosmwasm_std::{Api, Binary, Order, Storage};
+use prost::Message;
+
+/// Path of the query returning all raw records of the contract's storage.
+pub(crate) const QUERY_ALL_CONTRACT_STATE: &str = "/cosmwasm.wasm.v1.Query/AllContractState";
+
+/// Number of records returned when no limit is specified, like in Cosmos SDK.
+const DEFAULT_PAGE_LIMIT: u64 = 100;
+
+/// Processes the `AllContractState` query, encoded as protobuf.
+pub(crate) fn query_all_contract_state<ExecC, QueryC>(
+    wasm: &impl Wasm<ExecC, QueryC>,
+    api: &dyn Api,
+    storage: &dyn Storage,
+    data: &[u8],
+) -> AnyResult<Binary> {
+    let request = QueryAllContractStateRequest::decode(data)?;
+    let address = api.addr_validate(&request.address)?;
+    // fails when the contract does not exist
+    wasm.contract_data(storage, &address)?;
+    let page = request.pagination.unwrap_or_default();
+    if !page.key.is_empty() && page.offset > 0 {
+        bail!("invalid request, either offset or key is expected, got both");
+    }
+    let limit = match page.limit {
+        0 => DEFAULT_PAGE_LIMIT,
+        limit => limit,
+    } as usize;
+    let contract_storage = wasm.contract_storage(storage, &address);
+    let start = (!page.key.is_empty()).then_some(page.key.as_slice());
+    let records = if page.reverse {
+        // the key of the next page is inclusive, so it is the end of the range
+        let end = start.map(|key| [key, &[0]].concat());
+        contract_storage.range(None, end.as_deref(), Order::Descending)
+    } else {
+        contract_storage.range(start, None, Order::Ascending)
+    };
+    let mut records = records.skip(page.offset as usize);
+    let models: Vec<ContractStateModel> = records
+        .by_ref()
+        .take(limit)
+        .map(|(key, value)| ContractStateModel { key, value })
+        .collect();
+    let next_key = records.next().map(|(key, _)| key).unwrap_or_default();
+    let total = if page.count_total {
+        contract_storage.range(None, None, Order::Ascending).count() as u64
+    } else {
+        0
+    };
+    let response = QueryAllContractStateResponse {
+        models,
+        pagination: Some(PageResponse { next_key, total }),
+    };
+    Ok(response.encode_to_vec().into())
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct PageRequest {
+    #[prost(bytes, tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+    #[prost(uint64, tag = "3")]
+    pub limit: u64,
+    #[prost(bool, tag = "4")]
+    pub count_total: bool,
+    #[prost(bool, tag = "5")]
+    pub reverse: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct PageResponse {
+    #[prost(bytes, tag = "1")]
+    pub next_key: Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ContractStateModel {
+    #[prost(bytes, tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(bytes, tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryAllContractStateRequest {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageRequest>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryAllContractStateResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub models: Vec<ContractStateModel>,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageResponse>,
+}
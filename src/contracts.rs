@@ -209,6 +209,27 @@ where
             checksum: None,
         }
     }
+
+    /// Creates a new contract wrapper from closures.
+    ///
+    /// Unlike [new](Self::new), which accepts only function pointers, this function
+    /// accepts closures capturing their environment, which is convenient for small
+    /// inline mock contracts whose behavior is parameterized by the test.
+    pub fn new_with_closures(
+        execute_fn: impl Fn(DepsMut<Q>, Env, MessageInfo, T1) -> Result<Response<C>, E1> + 'static,
+        instantiate_fn: impl Fn(DepsMut<Q>, Env, MessageInfo, T2) -> Result<Response<C>, E2> + 'static,
+        query_fn: impl Fn(Deps<Q>, Env, T3) -> Result<Binary, E3> + 'static,
+    ) -> Self {
+        Self {
+            execute_fn: Box::new(execute_fn),
+            instantiate_fn: Box::new(instantiate_fn),
+            query_fn: Box::new(query_fn),
+            sudo_fn: None,
+            reply_fn: None,
+            migrate_fn: None,
+            checksum: None,
+        }
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -347,6 +368,65 @@ where
         }
     }
 
+    /// Populates [ContractWrapper] with contract's `sudo` entry-point provided as a closure.
+    pub fn with_sudo_closure<T4A, E4A>(
+        self,
+        sudo_fn: impl Fn(DepsMut<Q>, Env, T4A) -> Result<Response<C>, E4A> + 'static,
+    ) -> ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4A, E4A, E5, T6, E6>
+    where
+        T4A: DeserializeOwned + 'static,
+        E4A: Display + Debug + Send + Sync + 'static,
+    {
+        ContractWrapper {
+            execute_fn: self.execute_fn,
+            instantiate_fn: self.instantiate_fn,
+            query_fn: self.query_fn,
+            sudo_fn: Some(Box::new(sudo_fn)),
+            reply_fn: self.reply_fn,
+            migrate_fn: self.migrate_fn,
+            checksum: None,
+        }
+    }
+
+    /// Populates [ContractWrapper] with contract's `reply` entry-point provided as a closure.
+    pub fn with_reply_closure<E5A>(
+        self,
+        reply_fn: impl Fn(DepsMut<Q>, Env, Reply) -> Result<Response<C>, E5A> + 'static,
+    ) -> ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4, E4, E5A, T6, E6>
+    where
+        E5A: Display + Debug + Send + Sync + 'static,
+    {
+        ContractWrapper {
+            execute_fn: self.execute_fn,
+            instantiate_fn: self.instantiate_fn,
+            query_fn: self.query_fn,
+            sudo_fn: self.sudo_fn,
+            reply_fn: Some(Box::new(reply_fn)),
+            migrate_fn: self.migrate_fn,
+            checksum: None,
+        }
+    }
+
+    /// Populates [ContractWrapper] with contract's `migrate` entry-point provided as a closure.
+    pub fn with_migrate_closure<T6A, E6A>(
+        self,
+        migrate_fn: impl Fn(DepsMut<Q>, Env, T6A) -> Result<Response<C>, E6A> + 'static,
+    ) -> ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4, E4, E5, T6A, E6A>
+    where
+        T6A: DeserializeOwned + 'static,
+        E6A: Display + Debug + Send + Sync + 'static,
+    {
+        ContractWrapper {
+            execute_fn: self.execute_fn,
+            instantiate_fn: self.instantiate_fn,
+            query_fn: self.query_fn,
+            sudo_fn: self.sudo_fn,
+            reply_fn: self.reply_fn,
+            migrate_fn: Some(Box::new(migrate_fn)),
+            checksum: None,
+        }
+    }
+
     /// Populates [ContractWrapper] with the provided checksum of the contract's Wasm blob.
     pub fn with_checksum(mut self, checksum: Checksum) -> Self {
         self.checksum = Some(checksum);
@@ -354,6 +434,141 @@ where
     }
 }
 
+/// Creates a boxed [ContractWrapper] from the entry-points defined in the contract's module.
+///
+/// The `execute`, `instantiate` and `query` entry-points are always wired. The optional
+/// `reply`, `migrate` and `sudo` entry-points are wired when listed after the module path,
+/// in any order. Functions are resolved by name in the given module, so a listed entry-point
+/// that is not defined there is reported by the compiler, instead of panicking with
+/// an "entry-point not implemented" error in the middle of a test.
+///
+/// `macro_rules` can not detect which functions a module defines, so the optional
+/// entry-points have to be listed explicitly. IBC entry-points are not supported,
+/// because [ContractWrapper] does not support them.
+///
+/// # Example
+///
+/// ```
+/// use cw_multi_test::{contract_wrapper, App, Executor, IntoAddr};
+/// use cosmwasm_std::Empty;
+///
+/// mod counter {
+///     use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Empty, Env};
+///     use cosmwasm_std::{MessageInfo, Reply, Response, StdResult};
+///
+///     pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///         Ok(Response::new())
+///     }
+///
+///     pub fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///         Ok(Response::new())
+///     }
+///
+///     pub fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+///         to_json_binary(&0u64)
+///     }
+///
+///     pub fn reply(_: DepsMut, _: Env, _: Reply) -> StdResult<Response> {
+///         Ok(Response::new())
+///     }
+///
+///     pub fn migrate(_: DepsMut, _: Env, _: Empty) -> StdResult<Response> {
+///         Ok(Response::new())
+///     }
+/// }
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(contract_wrapper!(counter, reply, migrate));
+/// let contract_addr = app
+///     .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "counter", None)
+///     .unwrap();
+/// ```
+///
+/// Listing an entry-point not defined in the module does not compile:
+///
+/// ```compile_fail
+/// use cw_multi_test::{contract_wrapper, App};
+///
+/// mod counter {
+///     use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+///
+///     pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///         Ok(Response::new())
+///     }
+///
+///     pub fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///         Ok(Response::new())
+///     }
+///
+///     pub fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+///         Ok(Binary::default())
+///     }
+/// }
+///
+/// // error: cannot find function `reply` in module `counter`
+/// let code_id = App::default().store_code(contract_wrapper!(counter, reply));
+/// ```
+///
+/// Neither does listing an unknown entry-point:
+///
+/// ```compile_fail
+/// # use cw_multi_test::{contract_wrapper, App};
+/// # mod counter {
+/// #     use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+/// #     pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+/// #         Ok(Response::new())
+/// #     }
+/// #     pub fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+/// #         Ok(Response::new())
+/// #     }
+/// #     pub fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+/// #         Ok(Binary::default())
+/// #     }
+/// # }
+/// // error: unsupported entry-point `ibc_channel_open`, expected one of: reply, migrate, sudo
+/// let code_id = App::default().store_code(contract_wrapper!(counter, ibc_channel_open));
+/// ```
+#[macro_export]
+macro_rules! contract_wrapper {
+    ($($module:ident)::+ $(, $entry_point:ident)* $(,)?) => {
+        ::std::boxed::Box::new($crate::contract_wrapper!(
+            @chain
+            $crate::ContractWrapper::new(
+                $($module)::+::execute,
+                $($module)::+::instantiate,
+                $($module)::+::query,
+            ),
+            [$($module)::+]
+            $($entry_point)*
+        ))
+    };
+    (@chain $wrapper:expr, [$($module:ident)::+]) => {
+        $wrapper
+    };
+    (@chain $wrapper:expr, [$($module:ident)::+] reply $($rest:ident)*) => {
+        $crate::contract_wrapper!(
+            @chain $wrapper.with_reply($($module)::+::reply), [$($module)::+] $($rest)*
+        )
+    };
+    (@chain $wrapper:expr, [$($module:ident)::+] migrate $($rest:ident)*) => {
+        $crate::contract_wrapper!(
+            @chain $wrapper.with_migrate($($module)::+::migrate), [$($module)::+] $($rest)*
+        )
+    };
+    (@chain $wrapper:expr, [$($module:ident)::+] sudo $($rest:ident)*) => {
+        $crate::contract_wrapper!(
+            @chain $wrapper.with_sudo($($module)::+::sudo), [$($module)::+] $($rest)*
+        )
+    };
+    (@chain $wrapper:expr, [$($module:ident)::+] $unknown:ident $($rest:ident)*) => {
+        ::std::compile_error!(::std::concat!(
+            "unsupported entry-point `",
+            ::std::stringify!($unknown),
+            "`, expected one of: reply, migrate, sudo"
+        ))
+    };
+}
+
 fn customize_contract_fn<T, C, E, Q>(
     raw_fn: ContractFn<T, Empty, E, Empty>,
 ) -> ContractClosure<T, C, E, Q>
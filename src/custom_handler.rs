@@ -2,9 +2,12 @@
 
 use crate::app::CosmosRouter;
 use crate::error::{bail, AnyResult};
-use crate::{AppResponse, Module};
-use cosmwasm_std::{Addr, Api, Binary, BlockInfo, Empty, Querier, Storage};
+use crate::{AppResponse, FailingModule, Module};
+use cosmwasm_std::{Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Empty, Querier, Storage};
+use serde::de::DeserializeOwned;
 use std::cell::{Ref, RefCell};
+use std::fmt::Debug;
+use std::marker::PhantomData;
 use std::ops::Deref;
 use std::rc::Rc;
 
@@ -122,3 +125,334 @@ where
         bail!("Unexpected custom sudo message {:?}", msg)
     }
 }
+
+/// Chain of routes registered in [CustomRouter].
+pub trait CustomRoutes<ExecT, QueryT> {
+    /// Returns `true` when any route in the chain accepts the message.
+    fn accepts_exec(&self, msg: &ExecT) -> bool;
+
+    /// Returns `true` when any route in the chain accepts the query.
+    fn accepts_query(&self, request: &QueryT) -> bool;
+
+    /// Executes the message in the first route accepting it.
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: ExecT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static;
+
+    /// Evaluates the query in the first route accepting it.
+    fn query(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: QueryT,
+    ) -> AnyResult<Binary>;
+}
+
+/// Empty chain of routes, accepts nothing.
+pub struct NoRoutes;
+
+impl<ExecT, QueryT> CustomRoutes<ExecT, QueryT> for NoRoutes
+where
+    ExecT: Debug,
+    QueryT: Debug,
+{
+    fn accepts_exec(&self, _msg: &ExecT) -> bool {
+        false
+    }
+
+    fn accepts_query(&self, _request: &QueryT) -> bool {
+        false
+    }
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        msg: ExecT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("No route for custom message {:?}", msg)
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: QueryT,
+    ) -> AnyResult<Binary> {
+        bail!("No route for custom query {:?}", request)
+    }
+}
+
+/// Single route in the chain of routes registered in [CustomRouter].
+pub struct CustomRoute<ExecT, QueryT, Handler, Prev> {
+    /// Selects messages processed by the handler.
+    exec_matcher: fn(&ExecT) -> bool,
+    /// Selects queries processed by the handler.
+    query_matcher: fn(&QueryT) -> bool,
+    /// Handler processing selected messages and queries.
+    handler: Handler,
+    /// Routes registered earlier.
+    prev: Prev,
+}
+
+impl<ExecT, QueryT, Handler, Prev> CustomRoutes<ExecT, QueryT>
+    for CustomRoute<ExecT, QueryT, Handler, Prev>
+where
+    Handler: Module<ExecT = ExecT, QueryT = QueryT>,
+    Prev: CustomRoutes<ExecT, QueryT>,
+{
+    fn accepts_exec(&self, msg: &ExecT) -> bool {
+        self.prev.accepts_exec(msg) || (self.exec_matcher)(msg)
+    }
+
+    fn accepts_query(&self, request: &QueryT) -> bool {
+        self.prev.accepts_query(request) || (self.query_matcher)(request)
+    }
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: ExecT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        // routes registered earlier take precedence
+        if self.prev.accepts_exec(&msg) {
+            self.prev.execute(api, storage, router, block, sender, msg)
+        } else {
+            self.handler
+                .execute(api, storage, router, block, sender, msg)
+        }
+    }
+
+    fn query(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: QueryT,
+    ) -> AnyResult<Binary> {
+        // routes registered earlier take precedence
+        if self.prev.accepts_query(&request) {
+            self.prev.query(api, storage, querier, block, request)
+        } else {
+            self.handler.query(api, storage, querier, block, request)
+        }
+    }
+}
+
+/// Never matches any message or query.
+fn never<T>(_: &T) -> bool {
+    false
+}
+
+/// Custom module dispatching custom messages and queries to multiple handlers.
+///
+/// Each handler is registered together with a matcher function selecting the messages
+/// (or queries) it processes. Routes are checked in the order they were registered,
+/// messages and queries not selected by any route are passed to the fallback handler,
+/// which is by default a [FailingModule].
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::Empty;
+/// use cw_multi_test::custom_handler::{CachingCustomHandler, CustomRouter};
+/// use cw_multi_test::FailingModule;
+///
+/// #[derive(Debug, Default, Clone)]
+/// enum ChainMsg {
+///     #[default]
+///     Oracle,
+///     Exchange,
+/// }
+///
+/// let oracle = CachingCustomHandler::<ChainMsg, Empty>::new();
+/// let exchange = CachingCustomHandler::<ChainMsg, Empty>::new();
+///
+/// let custom = CustomRouter::<ChainMsg, Empty>::new()
+///     .route(|msg| matches!(msg, ChainMsg::Oracle), oracle)
+///     .route(|msg| matches!(msg, ChainMsg::Exchange), exchange)
+///     .with_fallback(FailingModule::new());
+/// ```
+pub struct CustomRouter<
+    ExecT,
+    QueryT,
+    Fallback = FailingModule<ExecT, QueryT, Empty>,
+    Routes = NoRoutes,
+> {
+    /// Registered routes.
+    routes: Routes,
+    /// Handler processing messages and queries not selected by any route.
+    fallback: Fallback,
+    _p: PhantomData<(ExecT, QueryT)>,
+}
+
+impl<ExecT, QueryT> CustomRouter<ExecT, QueryT> {
+    /// Creates a new [CustomRouter] without routes and with always failing fallback handler.
+    pub fn new() -> Self {
+        Self {
+            routes: NoRoutes,
+            fallback: FailingModule::new(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<ExecT, QueryT> Default for CustomRouter<ExecT, QueryT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<ExecT, QueryT, Fallback, Routes> CustomRouter<ExecT, QueryT, Fallback, Routes> {
+    /// Registers a handler processing custom messages selected by the matcher.
+    pub fn route<Handler>(
+        self,
+        matcher: fn(&ExecT) -> bool,
+        handler: Handler,
+    ) -> CustomRouter<ExecT, QueryT, Fallback, CustomRoute<ExecT, QueryT, Handler, Routes>>
+    where
+        Handler: Module<ExecT = ExecT, QueryT = QueryT>,
+    {
+        self.add_route(matcher, never, handler)
+    }
+
+    /// Registers a handler processing custom queries selected by the matcher.
+    pub fn route_query<Handler>(
+        self,
+        matcher: fn(&QueryT) -> bool,
+        handler: Handler,
+    ) -> CustomRouter<ExecT, QueryT, Fallback, CustomRoute<ExecT, QueryT, Handler, Routes>>
+    where
+        Handler: Module<ExecT = ExecT, QueryT = QueryT>,
+    {
+        self.add_route(never, matcher, handler)
+    }
+
+    /// Registers a handler processing custom messages and queries selected by matchers.
+    pub fn add_route<Handler>(
+        self,
+        exec_matcher: fn(&ExecT) -> bool,
+        query_matcher: fn(&QueryT) -> bool,
+        handler: Handler,
+    ) -> CustomRouter<ExecT, QueryT, Fallback, CustomRoute<ExecT, QueryT, Handler, Routes>>
+    where
+        Handler: Module<ExecT = ExecT, QueryT = QueryT>,
+    {
+        CustomRouter {
+            routes: CustomRoute {
+                exec_matcher,
+                query_matcher,
+                handler,
+                prev: self.routes,
+            },
+            fallback: self.fallback,
+            _p: PhantomData,
+        }
+    }
+
+    /// Sets the handler processing messages and queries not selected by any route.
+    pub fn with_fallback<NewFallback>(
+        self,
+        fallback: NewFallback,
+    ) -> CustomRouter<ExecT, QueryT, NewFallback, Routes>
+    where
+        NewFallback: Module<ExecT = ExecT, QueryT = QueryT, SudoT = Empty>,
+    {
+        CustomRouter {
+            routes: self.routes,
+            fallback,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<ExecT, QueryT, Fallback, Routes> Module for CustomRouter<ExecT, QueryT, Fallback, Routes>
+where
+    Fallback: Module<ExecT = ExecT, QueryT = QueryT, SudoT = Empty>,
+    Routes: CustomRoutes<ExecT, QueryT>,
+{
+    type ExecT = ExecT;
+    type QueryT = QueryT;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: Self::ExecT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        if self.routes.accepts_exec(&msg) {
+            self.routes
+                .execute(api, storage, router, block, sender, msg)
+        } else {
+            self.fallback
+                .execute(api, storage, router, block, sender, msg)
+        }
+    }
+
+    fn query(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: Self::QueryT,
+    ) -> AnyResult<Binary> {
+        if self.routes.accepts_query(&request) {
+            self.routes.query(api, storage, querier, block, request)
+        } else {
+            self.fallback.query(api, storage, querier, block, request)
+        }
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: Self::SudoT,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        self.fallback.sudo(api, storage, router, block, msg)
+    }
+}
@@ -1,7 +1,7 @@
 //! # Error definitions
 
 pub use anyhow::{anyhow, bail, Context as AnyContext, Error as AnyError, Result as AnyResult};
-use cosmwasm_std::{WasmMsg, WasmQuery};
+use cosmwasm_std::{Addr, WasmMsg, WasmQuery};
 use thiserror::Error;
 
 /// An enumeration of errors reported across the **CosmWasm MultiTest** library.
@@ -51,6 +51,113 @@ pub enum Error {
     /// Error variant for reporting duplicated contract addresses.
     #[error("Contract with this address already exists: {0}")]
     DuplicatedContractAddress(String),
+
+    /// Error variant for reporting duplicated contract addresses, caused by instantiating
+    /// a contract from a duplicated code with the same checksum, creator and salt.
+    #[error("Contract with this address already exists: {0}, instantiated from code id {1} with the same checksum, creator and salt; use `duplicate_code_with_new_checksum` to get a different address")]
+    DuplicatedContractAddressSameChecksum(String, u64),
+
+    /// Error variant for reporting too deeply nested smart queries.
+    #[error("max query depth {0} exceeded, query chain: {1}")]
+    MaxQueryDepthExceeded(usize, String),
+
+    /// Error variant for reporting too deeply nested contract calls.
+    #[error("max call depth exceeded: depth {0} reached with the limit of {1}, call chain: {2}")]
+    MaxCallDepthExceeded(usize, usize, String),
+
+    /// Error variant for reporting too many submessages processed in a single contract call.
+    #[error("too many submessages, the limit is {0}")]
+    TooManySubmessages(usize),
+
+    /// Error variant for reporting a re-entrant call into a contract.
+    #[error("re-entrant call into {0} (call stack: {1})")]
+    ReentrantCall(String, String),
+
+    /// Error variant for reporting a sudo message sent to an unknown handler.
+    #[error("unknown sudo handler {0:?}, registered handlers: [{1}]")]
+    UnknownSudoHandler(String, String),
+
+    /// Error variant for reporting a contract label rejected by label validation.
+    #[error("invalid label {0:?}: {1}")]
+    InvalidLabel(String, String),
+
+    /// Error variant for reporting a smart query response exceeding the size limit.
+    #[error("query response of {0} bytes exceeds the limit of {1} bytes")]
+    QueryResponseTooLarge(usize, usize),
+
+    /// Error variant for reporting response data exceeding the size limit.
+    #[error("response data of {0} bytes exceeds the limit of {1} bytes")]
+    ResponseDataTooLarge(usize, usize),
+
+    /// Error variant for reporting a response with too many events.
+    #[error("response with {0} events exceeds the limit of {1} events")]
+    TooManyEvents(usize, usize),
+
+    /// Error variant for reporting an event with too many attributes.
+    #[error("event {0} with {1} attributes exceeds the limit of {2} attributes per event")]
+    TooManyAttributes(String, usize, usize),
+
+    /// Error variant for reporting a custom query that does not match the custom query type of the App.
+    #[error("custom query {1} cannot be deserialized into {0}: {2}; the custom query type of the App may not match the one expected by the contract, use `custom_app` or `AppBuilder::with_custom` with a matching custom module")]
+    CustomQueryMismatch(String, String, String),
+
+    /// Error variant for reporting a custom message sent to an App without a custom module.
+    #[error("custom message {1} cannot be handled by the App with {0} as custom message type; the custom message type of the App may not match the one expected by the contract, use `custom_app` or `AppBuilder::with_custom` with a matching custom module")]
+    CustomMsgMismatch(String, String),
+
+    /// Error variant for reporting the state of a module written in an unexpected storage layout.
+    #[error("{0} storage layout v{1} found, this version expects v{2}; see MIGRATIONS.md")]
+    StorageLayoutMismatch(String, u32, u32),
+
+    /// Error variant for reporting an error returned from the contract's `query` entry-point.
+    ///
+    /// The error is reported in the format `contract <address>: <error>: query wasm contract failed`,
+    /// ending like the errors reported by `wasmd` for failed smart queries.
+    #[error("contract {0}: {1}: query wasm contract failed")]
+    QueryContractFailed(String, String),
+
+    /// Error variant for reporting a replayed operation with an outcome different from the recorded one.
+    #[error("replay diverged at operation {0}: recorded {1}, replayed {2}")]
+    ReplayDivergence(usize, String, String),
+
+    /// Error variant for reporting an address not allowed to store contract code.
+    #[error("address {0} is not allowed to store code: unauthorized")]
+    UploadNotAllowed(String),
+
+    /// Error variant for reporting an address not allowed to instantiate contracts from the code.
+    #[error("address {1} is not allowed to instantiate code {0}: unauthorized")]
+    InstantiateNotAllowed(u64, String),
+
+    /// Error variant for reporting a write to the contract storage attempted during a query.
+    #[error("contract {0} attempted to write key {1} during query")]
+    QueryStorageWrite(String, String),
+
+    /// Error variant for reporting a contract instantiated at an address not expected by the address book.
+    #[error("contract with label {0} was instantiated at {2}, but the address book expects {1}")]
+    AddressBookMismatch(String, String, String),
+
+    /// Error variant for reporting an attribute key exceeding the size limit.
+    #[error("attribute key {1:?} in event {0} has {2} bytes and exceeds the limit of {3} bytes")]
+    AttributeKeyTooLong(String, String, usize, usize),
+
+    /// Error variant for reporting an attribute value exceeding the size limit.
+    #[error(
+        "value of attribute {1:?} in event {0} has {2} bytes and exceeds the limit of {3} bytes"
+    )]
+    AttributeValueTooLong(String, String, usize, usize),
+
+    /// Error variant for reporting an invalid denomination.
+    #[error("invalid denom {0:?}: must be 3 to 128 characters long, start with a letter and contain only letters, digits and '/', ':', '.', '_', '-'")]
+    InvalidDenom(String),
+
+    /// Error variant for reporting a submessage sent by a contract that is not being executed.
+    #[error("submessage sent by contract {0} while executing {1}")]
+    SubmessageSenderMismatch(String, String),
+
+    /// Error variant for reporting a wasm message dispatched with a sender
+    /// different from the contract that sent it.
+    #[error("wasm message sent by contract {0} was dispatched with sender {1}")]
+    WasmMessageSenderMismatch(String, String),
 }
 
 impl Error {
@@ -110,4 +217,180 @@ impl Error {
     pub fn duplicated_contract_address(address: impl Into<String>) -> Self {
         Self::DuplicatedContractAddress(address.into())
     }
+
+    /// Creates an instance of the [Error](Self) for duplicated contract addresses,
+    /// caused by the same checksum, creator and salt used with another code identifier.
+    pub fn duplicated_contract_address_same_checksum(
+        address: impl Into<String>,
+        code_id: u64,
+    ) -> Self {
+        Self::DuplicatedContractAddressSameChecksum(address.into(), code_id)
+    }
+
+    /// Creates an instance of the [Error](Self) for too deeply nested smart queries.
+    pub fn max_query_depth_exceeded(max_depth: usize, query_chain: impl Into<String>) -> Self {
+        Self::MaxQueryDepthExceeded(max_depth, query_chain.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for too deeply nested contract calls.
+    pub fn max_call_depth_exceeded(
+        depth: usize,
+        max_depth: usize,
+        call_chain: impl Into<String>,
+    ) -> Self {
+        Self::MaxCallDepthExceeded(depth, max_depth, call_chain.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for too many submessages.
+    pub fn too_many_submessages(max_submessages: usize) -> Self {
+        Self::TooManySubmessages(max_submessages)
+    }
+
+    /// Creates an instance of the [Error](Self) for re-entrant call into a contract.
+    pub fn reentrant_call(address: impl Into<String>, call_stack: impl Into<String>) -> Self {
+        Self::ReentrantCall(address.into(), call_stack.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a sudo message sent to an unknown handler.
+    pub fn unknown_sudo_handler(name: impl Into<String>, registered: impl Into<String>) -> Self {
+        Self::UnknownSudoHandler(name.into(), registered.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a contract label rejected by label validation.
+    pub fn invalid_label(label: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidLabel(label.into(), reason.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a smart query response exceeding the size limit.
+    pub fn query_response_too_large(size: usize, limit: usize) -> Self {
+        Self::QueryResponseTooLarge(size, limit)
+    }
+
+    /// Creates an instance of the [Error](Self) for response data exceeding the size limit.
+    pub fn response_data_too_large(size: usize, limit: usize) -> Self {
+        Self::ResponseDataTooLarge(size, limit)
+    }
+
+    /// Creates an instance of the [Error](Self) for a response with too many events.
+    pub fn too_many_events(count: usize, limit: usize) -> Self {
+        Self::TooManyEvents(count, limit)
+    }
+
+    /// Creates an instance of the [Error](Self) for an event with too many attributes.
+    pub fn too_many_attributes(ty: impl Into<String>, count: usize, limit: usize) -> Self {
+        Self::TooManyAttributes(ty.into(), count, limit)
+    }
+
+    /// Creates an instance of the [Error](Self) for a custom query not matching the App's custom query type.
+    pub fn custom_query_mismatch(
+        type_name: impl Into<String>,
+        query: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::CustomQueryMismatch(type_name.into(), query.into(), reason.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a custom message not matching the App's custom message type.
+    pub fn custom_msg_mismatch(type_name: impl Into<String>, msg: impl Into<String>) -> Self {
+        Self::CustomMsgMismatch(type_name.into(), msg.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for the state of a module
+    /// written in an unexpected storage layout.
+    pub fn storage_layout_mismatch(module: impl Into<String>, found: u32, expected: u32) -> Self {
+        Self::StorageLayoutMismatch(module.into(), found, expected)
+    }
+
+    /// Creates an instance of the [Error](Self) for an error returned from the contract's `query` entry-point.
+    pub fn query_contract_failed(
+        contract_addr: impl Into<String>,
+        error: impl Into<String>,
+    ) -> Self {
+        Self::QueryContractFailed(contract_addr.into(), error.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a replayed operation
+    /// with an outcome different from the recorded one.
+    pub fn replay_divergence(
+        step: usize,
+        recorded: impl Into<String>,
+        replayed: impl Into<String>,
+    ) -> Self {
+        Self::ReplayDivergence(step, recorded.into(), replayed.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for an address not allowed to store contract code.
+    pub fn upload_not_allowed(uploader: impl Into<String>) -> Self {
+        Self::UploadNotAllowed(uploader.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for an address not allowed
+    /// to instantiate contracts from the code.
+    pub fn instantiate_not_allowed(code_id: u64, sender: impl Into<String>) -> Self {
+        Self::InstantiateNotAllowed(code_id, sender.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a write to the contract storage
+    /// attempted during a query, the key is reported in hexadecimal format.
+    pub fn query_storage_write(contract_addr: impl Into<String>, key: impl Into<String>) -> Self {
+        Self::QueryStorageWrite(contract_addr.into(), key.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a contract instantiated at an address
+    /// not pinned for its label in the address book.
+    pub fn address_book_mismatch(
+        label: impl Into<String>,
+        expected: &[Addr],
+        actual: impl Into<String>,
+    ) -> Self {
+        let expected = expected
+            .iter()
+            .map(Addr::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        Self::AddressBookMismatch(label.into(), expected, actual.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for an attribute key exceeding the size limit.
+    pub fn attribute_key_too_long(
+        ty: impl Into<String>,
+        key: impl Into<String>,
+        size: usize,
+        limit: usize,
+    ) -> Self {
+        Self::AttributeKeyTooLong(ty.into(), key.into(), size, limit)
+    }
+
+    /// Creates an instance of the [Error](Self) for an attribute value exceeding the size limit.
+    pub fn attribute_value_too_long(
+        ty: impl Into<String>,
+        key: impl Into<String>,
+        size: usize,
+        limit: usize,
+    ) -> Self {
+        Self::AttributeValueTooLong(ty.into(), key.into(), size, limit)
+    }
+
+    /// Creates an instance of the [Error](Self) for an invalid denomination.
+    pub fn invalid_denom(denom: impl Into<String>) -> Self {
+        Self::InvalidDenom(denom.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a submessage sent by a contract
+    /// that is not being executed.
+    pub fn submessage_sender_mismatch(
+        contract: impl Into<String>,
+        executing: impl Into<String>,
+    ) -> Self {
+        Self::SubmessageSenderMismatch(contract.into(), executing.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for a wasm message dispatched
+    /// with a sender different from the contract that sent it.
+    pub fn wasm_message_sender_mismatch(
+        contract: impl Into<String>,
+        sender: impl Into<String>,
+    ) -> Self {
+        Self::WasmMessageSenderMismatch(contract.into(), sender.into())
+    }
 }
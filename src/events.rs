@@ -0,0 +1,180 @@
+//! # Event normalization
+
+use crate::AppResponse;
+use cosmwasm_std::{Attribute, Event};
+
+/// Contract address attribute key.
+const CONTRACT_ATTR: &str = "_contract_address";
+
+/// Message index attribute key, added by Cosmos SDK v0.50+.
+const MSG_INDEX_ATTR: &str = "msg_index";
+
+/// Shape of the events returned by [normalize_events].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EventNormalization {
+    /// Events in the shape emitted by `wasmd`.
+    ///
+    /// Adds `coin_spent`, `coin_received` and `message` events around each `transfer` event,
+    /// removes `coinbase` events together with `coin_received` events of minted coins
+    /// and renames `withdraw_delegator_reward` to `withdraw_rewards`. The bank events
+    /// emitted by `wasmd` when transferring rewards from the distribution module account
+    /// are not added, because the module address is not known.
+    Wasmd,
+    /// Events in the shape emitted by **MultiTest**.
+    ///
+    /// Removes `coin_spent`, `coin_received`, `coinbase` and `message` events, removes `msg_index`
+    /// attributes, renames `withdraw_rewards` to `withdraw_delegator_reward` and removes
+    /// the `transfer` event of rewards directly preceding it.
+    MultiTest,
+}
+
+/// Returns the events of the response, transformed to the specified shape.
+///
+/// This function mechanically applies known transformations, so the same expected
+/// events can be used in assertions against both **MultiTest** and `wasmd`.
+///
+/// Events emitted by **MultiTest** have the same types and attributes as events emitted
+/// by `wasmd` for contract related operations (`execute`, `instantiate`, `wasm`, `wasm-*` etc.),
+/// but differ for events emitted by other Cosmos SDK modules. The known differences are:
+///
+/// 1. `wasmd` emits a `message` event with `sender` attribute after each `transfer` event.
+///    **MultiTest** does not emit `message` events at all. The `message` event with `action`
+///    attribute, emitted by the transaction handler, is not included in message responses.
+/// 2. `wasmd` emits `coin_spent` (`spender`, `amount`) and `coin_received` (`receiver`, `amount`)
+///    events before each `transfer` event. **MultiTest** emits only `transfer` events.
+/// 3. Starting with Cosmos SDK v0.50, `wasmd` adds `msg_index` attribute to all events.
+///    **MultiTest** never adds this attribute.
+/// 4. When withdrawing delegator rewards, `wasmd` transfers the rewards from the distribution
+///    module account (emitting bank events described above) and emits `withdraw_rewards` event
+///    with `amount`, `validator` and `delegator` attributes. **MultiTest** mints the rewards
+///    and emits `withdraw_delegator_reward` event with `validator`, `sender`
+///    and `amount` attributes.
+/// 5. `wasmd` always places `_contract_address` attribute first in contract related events.
+///    **MultiTest** does the same for events it creates, but events captured from other
+///    sources may have this attribute in different position.
+/// 6. When minting coins (also when paying out delegator rewards), **MultiTest** emits
+///    `coin_received` (`receiver`, `amount`) and `coinbase` (`minter`, `amount`) events.
+///    Messages processed by `wasmd` never mint coins, so these events are not emitted there.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::Event;
+/// use cw_multi_test::{normalize_events, AppResponse, EventNormalization};
+///
+/// let response = AppResponse {
+///     events: vec![Event::new("transfer")
+///         .add_attribute("recipient", "bob")
+///         .add_attribute("sender", "alice")
+///         .add_attribute("amount", "10uatom")],
+///     data: None,
+/// };
+///
+/// let events = normalize_events(&response, EventNormalization::Wasmd);
+/// assert_eq!(
+///     vec!["coin_spent", "coin_received", "transfer", "message"],
+///     events.iter().map(|e| e.ty.as_str()).collect::<Vec<_>>()
+/// );
+/// ```
+pub fn normalize_events(response: &AppResponse, normalization: EventNormalization) -> Vec<Event> {
+    match normalization {
+        EventNormalization::Wasmd => to_wasmd(&response.events),
+        EventNormalization::MultiTest => to_multi_test(&response.events),
+    }
+}
+
+/// Transforms events emitted by **MultiTest** into events emitted by `wasmd`.
+fn to_wasmd(events: &[Event]) -> Vec<Event> {
+    let mut normalized = Vec::with_capacity(events.len());
+    for event in events {
+        let event = contract_address_first(event.clone());
+        match event.ty.clone().as_str() {
+            "transfer" => {
+                let sender = attribute_value(&event, "sender");
+                let recipient = attribute_value(&event, "recipient");
+                let amount = attribute_value(&event, "amount");
+                normalized.push(
+                    Event::new("coin_spent")
+                        .add_attribute("spender", sender)
+                        .add_attribute("amount", amount),
+                );
+                normalized.push(
+                    Event::new("coin_received")
+                        .add_attribute("receiver", recipient)
+                        .add_attribute("amount", amount),
+                );
+                normalized.push(event.clone());
+                normalized.push(Event::new("message").add_attribute("sender", sender));
+            }
+            "coinbase" => {
+                // minted coins are not reported in wasmd, drop the preceding receive event too
+                if normalized.last().is_some_and(|previous| {
+                    previous.ty == "coin_received"
+                        && attribute_value(previous, "amount") == attribute_value(&event, "amount")
+                }) {
+                    normalized.pop();
+                }
+            }
+            "withdraw_delegator_reward" => normalized.push(
+                Event::new("withdraw_rewards")
+                    .add_attribute("amount", attribute_value(&event, "amount"))
+                    .add_attribute("validator", attribute_value(&event, "validator"))
+                    .add_attribute("delegator", attribute_value(&event, "sender")),
+            ),
+            _ => normalized.push(event),
+        }
+    }
+    normalized
+}
+
+/// Transforms events emitted by `wasmd` into events emitted by **MultiTest**.
+fn to_multi_test(events: &[Event]) -> Vec<Event> {
+    let mut normalized: Vec<Event> = Vec::with_capacity(events.len());
+    for event in events {
+        let mut event = contract_address_first(event.clone());
+        event.attributes.retain(|attr| attr.key != MSG_INDEX_ATTR);
+        match event.ty.clone().as_str() {
+            "message" | "coin_spent" | "coin_received" | "coinbase" => {}
+            "withdraw_rewards" => {
+                let delegator = attribute_value(&event, "delegator");
+                // rewards are minted in MultiTest, so the transfer from the module account is dropped
+                if normalized.last().is_some_and(|previous| {
+                    previous.ty == "transfer" && attribute_value(previous, "recipient") == delegator
+                }) {
+                    normalized.pop();
+                }
+                normalized.push(
+                    Event::new("withdraw_delegator_reward")
+                        .add_attribute("validator", attribute_value(&event, "validator"))
+                        .add_attribute("sender", delegator)
+                        .add_attribute("amount", attribute_value(&event, "amount")),
+                );
+            }
+            _ => normalized.push(event),
+        }
+    }
+    normalized
+}
+
+/// Moves the contract address attribute (if present) to the first position.
+fn contract_address_first(mut event: Event) -> Event {
+    if let Some(index) = event
+        .attributes
+        .iter()
+        .position(|attr| attr.key == CONTRACT_ATTR)
+    {
+        let attr: Attribute = event.attributes.remove(index);
+        event.attributes.insert(0, attr);
+    }
+    event
+}
+
+/// Returns the value of the first attribute with specified key, or empty string when not found.
+fn attribute_value<'a>(event: &'a Event, key: &str) -> &'a str {
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+        .unwrap_or_default()
+}
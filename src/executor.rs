@@ -1,15 +1,23 @@
+use crate::code_id::CodeId;
 use crate::error::AnyResult;
+use crate::provenance::{is_emitted_by_module, ModuleKind};
+use crate::redaction::{Redaction, Redactor};
 use cosmwasm_std::{
-    to_json_binary, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, CustomMsg, Event,
+    from_json, to_json_binary, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, CustomMsg, Event,
     SubMsgResponse, WasmMsg,
 };
 use cw_utils::{parse_execute_response_data, parse_instantiate_response_data};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::any::type_name;
 use std::fmt::Debug;
 
 /// A subset of data returned as a response of a contract entry point,
 /// such as `instantiate`, `execute` or `migrate`.
-#[derive(Default, Clone, Debug)]
+///
+/// Serializes to JSON as `{"events":[...],"data":"<base64>"}`, suitable for snapshot testing,
+/// see also [redacted](Self::redacted).
+#[derive(Default, Clone, Debug, Serialize)]
 pub struct AppResponse {
     /// Response events.
     pub events: Vec<Event>,
@@ -51,6 +59,136 @@ impl AppResponse {
             self.events
         );
     }
+
+    /// Returns custom events emitted by the contract with the given address,
+    /// in the contract's own vocabulary.
+    ///
+    /// The `wasm-` prefix is stripped from event types and the injected
+    /// `_contract_address` attribute is removed, so the returned events look
+    /// like the events created by the contract. Events emitted by other contracts,
+    /// even with the same type, are skipped.
+    pub fn contract_events(&self, contract: &Addr) -> Vec<Event> {
+        self.events
+            .iter()
+            .filter(|ev| is_emitted_by(ev, contract))
+            .filter_map(|ev| {
+                let ty = ev.ty.strip_prefix("wasm-")?;
+                let attributes = ev
+                    .attributes
+                    .iter()
+                    .filter(|at| at.key != CONTRACT_ADDRESS_ATTR)
+                    .cloned();
+                Some(Event::new(ty).add_attributes(attributes))
+            })
+            .collect()
+    }
+
+    /// Returns events emitted by the specified module.
+    ///
+    /// Events are tagged with the module that emitted them only when enabled
+    /// with [AppBuilder::with_event_provenance](crate::AppBuilder::with_event_provenance),
+    /// otherwise no events are returned.
+    pub fn events_from(&self, module: ModuleKind) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|ev| is_emitted_by_module(ev, module))
+            .collect()
+    }
+
+    /// Returns the value of the attribute with the given key, from the first custom event
+    /// of the given type (without `wasm-` prefix) emitted by the contract with the given address.
+    pub fn contract_attr(&self, contract: &Addr, event_type: &str, key: &str) -> Option<String> {
+        self.contract_events(contract)
+            .into_iter()
+            .filter(|ev| ev.ty == event_type)
+            .find_map(|ev| {
+                ev.attributes
+                    .into_iter()
+                    .find(|at| at.key == key)
+                    .map(|at| at.value)
+            })
+    }
+
+    /// Returns the response data decoded from JSON.
+    ///
+    /// Data wrapped in `MsgExecuteContractResponse`, like the data returned
+    /// for `WasmMsg::Execute` processed with [Executor::execute], is unwrapped first.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the response has no data or the data can not be decoded.
+    #[track_caller]
+    pub fn unwrap_data<T: DeserializeOwned>(&self) -> T {
+        let Some(data) = &self.data else {
+            panic!(
+                "Expected response data of type {}, but the response has no data, events: {:?}",
+                type_name::<T>(),
+                self.events
+            );
+        };
+        let err = match from_json(data) {
+            Ok(value) => return value,
+            Err(err) => err,
+        };
+        if let Ok(Some(inner)) =
+            parse_execute_response_data(data.as_slice()).map(|response| response.data)
+        {
+            if let Ok(value) = from_json(&inner) {
+                return value;
+            }
+        }
+        panic!(
+            "Failed to decode response data {:?} as {}: {}",
+            data,
+            type_name::<T>(),
+            err
+        );
+    }
+
+    /// Returns the JSON representation of this response, with volatile values
+    /// in event attributes replaced with stable placeholders, see [Redaction].
+    ///
+    /// Response data is left as is (base64 encoded).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::Event;
+    /// use cw_multi_test::{AppResponse, IntoBech32, Redaction};
+    ///
+    /// let response = AppResponse {
+    ///     events: vec![Event::new("transfer")
+    ///         .add_attribute("sender", "alice".into_bech32())
+    ///         .add_attribute("recipient", "bob".into_bech32())
+    ///         .add_attribute("amount", "100uatom")],
+    ///     data: None,
+    /// };
+    ///
+    /// let redacted = response.redacted(&[Redaction::Bech32Addresses("cosmwasm".to_string())]);
+    ///
+    /// let attributes = &redacted["events"][0]["attributes"];
+    /// assert_eq!("addr#1", attributes[0]["value"]);
+    /// assert_eq!("addr#2", attributes[1]["value"]);
+    /// assert_eq!("100uatom", attributes[2]["value"]);
+    /// ```
+    pub fn redacted(&self, redactions: &[Redaction]) -> serde_json::Value {
+        let response = AppResponse {
+            events: Redactor::new(redactions).redact_events(&self.events),
+            data: self.data.clone(),
+        };
+        serde_json::to_value(response).expect("response should be serializable to JSON")
+    }
+}
+
+/// Name of the attribute injected into events emitted by contracts.
+const CONTRACT_ADDRESS_ATTR: &str = "_contract_address";
+
+/// Returns `true` when the event was emitted by the contract with the given address.
+fn is_emitted_by(event: &Event, contract: &Addr) -> bool {
+    event
+        .attributes
+        .iter()
+        .any(|at| at.key == CONTRACT_ADDRESS_ATTR && at.value == contract.as_str())
 }
 
 /// They have the same shape, SubMsgResponse is what is returned in reply.
@@ -83,18 +221,33 @@ where
     /// This is just a helper around execute()
     fn instantiate_contract<T: Serialize, U: Into<String>>(
         &mut self,
-        code_id: u64,
+        code_id: impl Into<CodeId>,
         sender: Addr,
         init_msg: &T,
         send_funds: &[Coin],
         label: U,
         admin: Option<String>,
     ) -> AnyResult<Addr> {
-        // instantiate contract
-        let init_msg = to_json_binary(init_msg)?;
+        self.instantiate_contract_with_response(code_id, sender, init_msg, send_funds, label, admin)
+            .map(|(contract_addr, _)| contract_addr)
+    }
+
+    /// Create a contract from the pre-serialized instantiate message and get the new address.
+    ///
+    /// This is just like [instantiate_contract](Self::instantiate_contract), but the message
+    /// is sent as is, so contracts can be tested with malformed or unexpected messages.
+    fn instantiate_contract_raw<U: Into<String>>(
+        &mut self,
+        code_id: impl Into<CodeId>,
+        sender: Addr,
+        init_msg: Binary,
+        send_funds: &[Coin],
+        label: U,
+        admin: Option<String>,
+    ) -> AnyResult<Addr> {
         let msg = WasmMsg::Instantiate {
             admin,
-            code_id,
+            code_id: code_id.into().u64(),
             msg: init_msg,
             funds: send_funds.to_vec(),
             label: label.into(),
@@ -104,13 +257,40 @@ where
         Ok(Addr::unchecked(data.contract_address))
     }
 
+    /// Create a contract and get the new address together with the full response.
+    /// This is just a helper around execute(), the returned response contains
+    /// all events emitted during instantiation and the raw (protobuf encoded) data.
+    fn instantiate_contract_with_response<T: Serialize, U: Into<String>>(
+        &mut self,
+        code_id: impl Into<CodeId>,
+        sender: Addr,
+        init_msg: &T,
+        send_funds: &[Coin],
+        label: U,
+        admin: Option<String>,
+    ) -> AnyResult<(Addr, AppResponse)> {
+        // instantiate contract
+        let init_msg = to_json_binary(init_msg)?;
+        let msg = WasmMsg::Instantiate {
+            admin,
+            code_id: code_id.into().u64(),
+            msg: init_msg,
+            funds: send_funds.to_vec(),
+            label: label.into(),
+        };
+        let res = self.execute(sender, msg.into())?;
+        let data =
+            parse_instantiate_response_data(res.data.clone().unwrap_or_default().as_slice())?;
+        Ok((Addr::unchecked(data.contract_address), res))
+    }
+
     /// Instantiates a new contract and returns its predictable address.
     /// This is a helper function around [execute][Self::execute] function
     /// with `WasmMsg::Instantiate2` message.
     #[cfg(feature = "cosmwasm_1_2")]
     fn instantiate2_contract<M, L, A, S>(
         &mut self,
-        code_id: u64,
+        code_id: impl Into<CodeId>,
         sender: Addr,
         init_msg: &M,
         funds: &[Coin],
@@ -118,6 +298,32 @@ where
         admin: A,
         salt: S,
     ) -> AnyResult<Addr>
+    where
+        M: Serialize,
+        L: Into<String>,
+        A: Into<Option<String>>,
+        S: Into<Binary>,
+    {
+        self.instantiate2_contract_with_response(
+            code_id, sender, init_msg, funds, label, admin, salt,
+        )
+        .map(|(contract_addr, _)| contract_addr)
+    }
+
+    /// Instantiates a new contract and returns its predictable address together with the full response.
+    /// This is a helper function around [execute][Self::execute] function
+    /// with `WasmMsg::Instantiate2` message.
+    #[cfg(feature = "cosmwasm_1_2")]
+    fn instantiate2_contract_with_response<M, L, A, S>(
+        &mut self,
+        code_id: impl Into<CodeId>,
+        sender: Addr,
+        init_msg: &M,
+        funds: &[Coin],
+        label: L,
+        admin: A,
+        salt: S,
+    ) -> AnyResult<(Addr, AppResponse)>
     where
         M: Serialize,
         L: Into<String>,
@@ -126,16 +332,20 @@ where
     {
         let msg = WasmMsg::Instantiate2 {
             admin: admin.into(),
-            code_id,
+            code_id: code_id.into().u64(),
             msg: to_json_binary(init_msg)?,
             funds: funds.to_vec(),
             label: label.into(),
             salt: salt.into(),
         };
         let execute_response = self.execute(sender, msg.into())?;
-        let instantiate_response =
-            parse_instantiate_response_data(execute_response.data.unwrap_or_default().as_slice())?;
-        Ok(Addr::unchecked(instantiate_response.contract_address))
+        let instantiate_response = parse_instantiate_response_data(
+            execute_response.data.clone().unwrap_or_default().as_slice(),
+        )?;
+        Ok((
+            Addr::unchecked(instantiate_response.contract_address),
+            execute_response,
+        ))
     }
 
     /// Execute a contract and process all returned messages.
@@ -150,9 +360,23 @@ where
         send_funds: &[Coin],
     ) -> AnyResult<AppResponse> {
         let binary_msg = to_json_binary(msg)?;
+        self.execute_contract_raw(sender, contract_addr, binary_msg, send_funds)
+    }
+
+    /// Execute a contract with the pre-serialized message and process all returned messages.
+    ///
+    /// This is just like [execute_contract](Self::execute_contract), but the message
+    /// is sent as is, so contracts can be tested with malformed or unexpected messages.
+    fn execute_contract_raw(
+        &mut self,
+        sender: Addr,
+        contract_addr: Addr,
+        msg: Binary,
+        send_funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
         let wrapped_msg = WasmMsg::Execute {
             contract_addr: contract_addr.into_string(),
-            msg: binary_msg,
+            msg,
             funds: send_funds.to_vec(),
         };
         let mut res = self.execute(sender, wrapped_msg.into())?;
@@ -166,18 +390,72 @@ where
     /// Sender must be registered admin.
     /// This is just a helper function around [execute()](Self::execute)
     /// with `WasmMsg::Migrate` message.
+    ///
+    /// The returned response contains the `migrate` event with the new `code_id`,
+    /// followed by the events emitted by the contract and its submessages.
+    ///
+    /// Like `MsgMigrateContract` in `wasmd`, migration can not send any funds,
+    /// and the contract's `migrate` entry-point gets no `MessageInfo`. A contract relying
+    /// on funds sent with the migration has to take them from its own balance:
+    ///
+    /// ```
+    /// use cosmwasm_std::{coins, to_json_binary, BankMsg, Binary, Deps, DepsMut, Empty, Env};
+    /// use cosmwasm_std::{MessageInfo, Response, StdError, StdResult};
+    /// use cw_multi_test::{App, ContractWrapper, Executor, IntoAddr};
+    ///
+    /// fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    ///     Ok(Response::new())
+    /// }
+    ///
+    /// fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    ///     Ok(Response::new())
+    /// }
+    ///
+    /// fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    ///     to_json_binary(&Empty {})
+    /// }
+    ///
+    /// /// Pays a migration fee to the treasury, expecting the fee to be sent with the migration.
+    /// fn migrate(deps: DepsMut, env: Env, _: Empty) -> StdResult<Response> {
+    ///     let balance = deps.querier.query_balance(env.contract.address, "uatom")?;
+    ///     if balance.amount.is_zero() {
+    ///         return Err(StdError::generic_err("migration fee not paid"));
+    ///     }
+    ///     Ok(Response::new().add_message(BankMsg::Send {
+    ///         to_address: "treasury".to_string(),
+    ///         amount: vec![balance],
+    ///     }))
+    /// }
+    ///
+    /// let owner = "owner".into_addr();
+    /// let mut app = App::new(|router, _, storage| {
+    ///     router.bank.init_balance(storage, &owner, coins(100, "uatom")).unwrap();
+    /// });
+    /// let contract = ContractWrapper::new(execute, instantiate, query).with_migrate(migrate);
+    /// let code_id = app.store_code(Box::new(contract));
+    /// let contract_addr = app
+    ///     .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "fees", Some(owner.to_string()))
+    ///     .unwrap();
+    ///
+    /// // the owner's funds are not sent along with the migration
+    /// let err = app
+    ///     .migrate_contract(owner.clone(), contract_addr, &Empty {}, code_id)
+    ///     .unwrap_err();
+    /// assert_eq!("Generic error: migration fee not paid", err.root_cause().to_string());
+    /// app.assert_balance(&owner, &cosmwasm_std::coin(100, "uatom"));
+    /// ```
     fn migrate_contract<T: Serialize>(
         &mut self,
         sender: Addr,
         contract_addr: Addr,
         msg: &T,
-        new_code_id: u64,
+        new_code_id: impl Into<CodeId>,
     ) -> AnyResult<AppResponse> {
         let msg = to_json_binary(msg)?;
         let msg = WasmMsg::Migrate {
             contract_addr: contract_addr.into(),
             msg,
-            new_code_id,
+            new_code_id: new_code_id.into().u64(),
         };
         self.execute(sender, msg.into())
     }
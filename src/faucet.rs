@@ -0,0 +1,143 @@
+//! # Multi-denom faucet
+//!
+//! Used to fund accounts with tokens of any denomination in tests (LP shares, IBC vouchers,
+//! token factory denominations), keeping track of all minted tokens for supply invariants,
+//! see [AppBuilder::with_faucet](crate::AppBuilder::with_faucet).
+
+use crate::bank::{has_denom_metadata, save_denom_metadata};
+use crate::error::{bail, AnyResult, Error};
+use crate::featured::staking::{Distribution, Staking};
+use crate::{App, AppResponse, Bank, BankSudo, Gov, Ibc, Module, Stargate, Wasm};
+use cosmwasm_std::{
+    Addr, Api, Coin, CustomMsg, CustomQuery, DenomMetadata, DenomUnit, Storage, Uint128,
+};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+
+/// Tokens minted by the faucet, per denomination.
+pub(crate) type FaucetMinted = BTreeMap<String, Uint128>;
+
+/// Validates the denomination like Cosmos SDK does by default: the denomination must
+/// be 3 to 128 characters long, must start with a letter, followed by letters, digits
+/// or one of the `/`, `:`, `.`, `_`, `-` characters.
+pub fn validate_denom(denom: &str) -> AnyResult<()> {
+    let mut chars = denom.chars();
+    let starts_with_letter = chars.next().is_some_and(|c| c.is_ascii_alphabetic());
+    let valid_chars = chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+    if !starts_with_letter || !valid_chars || !(3..=128).contains(&denom.len()) {
+        bail!(Error::invalid_denom(denom));
+    }
+    Ok(())
+}
+
+/// Faucet funding accounts with tokens of any denomination, returned by [App::faucet].
+///
+/// Tokens are minted with [BankSudo::Mint]. The minimal metadata (a single denomination unit
+/// with exponent zero) is registered for denominations without metadata, so they are reported
+/// by denomination metadata queries. Denominations rejected by [validate_denom] are refused
+/// when funding, before anything is minted.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::coin;
+/// use cw_multi_test::{no_init, AppBuilder, IntoAddr};
+///
+/// let mut app = AppBuilder::default().with_faucet().build(no_init);
+/// let user = "user".into_addr();
+///
+/// app.faucet()
+///     .fund(&user, &[coin(1_000_000, "gamm/pool/1"), coin(500, "uatom")])
+///     .unwrap();
+/// app.faucet().fund(&user, &[coin(500, "uatom")]).unwrap();
+///
+/// assert_eq!(
+///     vec![coin(1_000_000, "gamm/pool/1"), coin(1_000, "uatom")],
+///     app.faucet().total_minted()
+/// );
+/// ```
+pub struct Faucet<'a, A> {
+    app: &'a mut A,
+}
+
+impl<'a, A> Faucet<'a, A> {
+    pub(crate) fn new(app: &'a mut A) -> Self {
+        Self { app }
+    }
+}
+
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+    Faucet<'_, App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>>
+where
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+    WasmT: Wasm<CustomT::ExecT, CustomT::QueryT>,
+    BankT: Bank,
+    ApiT: Api,
+    StorageT: Storage,
+    CustomT: Module,
+    StakingT: Staking,
+    DistrT: Distribution,
+    IbcT: Ibc,
+    GovT: Gov,
+    StargateT: Stargate,
+{
+    /// Mints the tokens for the specified account.
+    ///
+    /// Fails when the faucet is not enabled, when any denomination is invalid
+    /// or any amount is zero, in which case no tokens are minted.
+    pub fn fund(&mut self, address: &Addr, amount: &[Coin]) -> AnyResult<AppResponse> {
+        if self.app.faucet_minted.is_none() {
+            bail!("faucet is not enabled, see AppBuilder::with_faucet");
+        }
+        for coin in amount {
+            validate_denom(&coin.denom)?;
+            if coin.amount.is_zero() {
+                bail!("cannot fund zero amount of {}", coin.denom);
+            }
+        }
+        let response = self.app.sudo(
+            BankSudo::Mint {
+                to_address: address.to_string(),
+                amount: amount.to_vec(),
+            }
+            .into(),
+        )?;
+        for coin in amount {
+            if !has_denom_metadata(&self.app.storage, &coin.denom) {
+                save_denom_metadata(&mut self.app.storage, &minimal_metadata(&coin.denom))?;
+            }
+        }
+        let minted = self.app.faucet_minted.get_or_insert_with(Default::default);
+        for coin in amount {
+            *minted.entry(coin.denom.clone()).or_default() += coin.amount;
+        }
+        Ok(response)
+    }
+
+    /// Returns all tokens minted by the faucet, ordered by denomination.
+    pub fn total_minted(&self) -> Vec<Coin> {
+        self.app
+            .faucet_minted
+            .iter()
+            .flatten()
+            .map(|(denom, amount)| Coin::new(*amount, denom))
+            .collect()
+    }
+}
+
+/// Returns the minimal metadata of the denomination, with a single unit with exponent zero.
+fn minimal_metadata(denom: &str) -> DenomMetadata {
+    DenomMetadata {
+        base: denom.to_string(),
+        display: denom.to_string(),
+        name: denom.to_string(),
+        symbol: denom.to_string(),
+        denom_units: vec![DenomUnit {
+            denom: denom.to_string(),
+            exponent: 0,
+            aliases: vec![],
+        }],
+        ..Default::default()
+    }
+}
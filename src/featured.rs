@@ -15,8 +15,10 @@ pub mod staking {
 pub mod staking {
     use crate::error::AnyResult;
     use crate::{AppResponse, CosmosRouter, FailingModule, Module};
-    use cosmwasm_std::{Api, BlockInfo, CustomMsg, CustomQuery, Empty, Storage};
+    use cosmwasm_std::{Addr, Api, BlockInfo, CustomMsg, CustomQuery, Empty, Storage};
+    use serde::{Deserialize, Serialize};
 
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub enum StakingSudo {}
 
     pub trait Staking: Module<ExecT = Empty, QueryT = Empty, SudoT = Empty> {
@@ -29,6 +31,10 @@ pub mod staking {
         ) -> AnyResult<AppResponse> {
             Ok(AppResponse::default())
         }
+
+        fn module_account(&self, _api: &dyn Api) -> Option<Addr> {
+            None
+        }
     }
 
     pub type StakeKeeper = FailingModule<Empty, Empty, Empty>;
@@ -0,0 +1,543 @@
+//! # Fee grant module
+//!
+//! Simplified simulation of the `x/feegrant` module of the Cosmos SDK.
+//!
+//! [FeeGrantKeeper] handles `MsgGrantAllowance` and `MsgRevokeAllowance` messages
+//! sent from contracts as `CosmosMsg::Stargate` or `CosmosMsg::Any` messages,
+//! stores granted allowances, and answers `/cosmos.feegrant.v1beta1.Query/Allowance`
+//! queries sent as `QueryRequest::Stargate` or `QueryRequest::Grpc`.
+//! All other messages and queries are rejected, like in [StargateFailing](crate::StargateFailing).
+
+use crate::error::{bail, AnyResult};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::{AppResponse, CosmosRouter, Stargate};
+use cosmwasm_std::{
+    Addr, AnyMsg, Api, Binary, BlockInfo, Coin, CustomMsg, CustomQuery, Event, GrpcQuery, Querier,
+    Storage, Timestamp, Uint128,
+};
+use cw_storage_plus::Map;
+use prost::Message;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Type URL of the message granting an allowance.
+pub(crate) const MSG_GRANT_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.MsgGrantAllowance";
+
+/// Type URL of the message revoking an allowance.
+pub(crate) const MSG_REVOKE_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.MsgRevokeAllowance";
+
+/// Type URL of the basic allowance.
+pub(crate) const BASIC_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.BasicAllowance";
+
+/// Type URL of the periodic allowance.
+pub(crate) const PERIODIC_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.PeriodicAllowance";
+
+/// Path of the query returning an allowance granted by granter to grantee.
+pub(crate) const QUERY_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.Query/Allowance";
+
+/// Default storage namespace for fee grant module.
+const NAMESPACE_FEEGRANT: &[u8] = b"feegrant";
+
+/// Collection of allowances, indexed by granter and grantee addresses.
+const ALLOWANCES: Map<(&Addr, &Addr), FeeAllowance> = Map::new("allowances");
+
+/// Allowance with optional spend limit and expiration time.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BasicAllowance {
+    /// Maximum amount of tokens that can be spent, empty means no limit.
+    pub spend_limit: Vec<Coin>,
+    /// Time when this allowance expires, `None` means no expiration.
+    pub expiration: Option<Timestamp>,
+}
+
+/// Allowance with spend limit that is reset periodically.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PeriodicAllowance {
+    /// Overall limits of this allowance.
+    pub basic: BasicAllowance,
+    /// Duration of the period in seconds.
+    pub period: u64,
+    /// Maximum amount of tokens that can be spent within one period.
+    pub period_spend_limit: Vec<Coin>,
+    /// Amount of tokens left to be spent in the current period.
+    pub period_can_spend: Vec<Coin>,
+    /// Time when the current period ends.
+    pub period_reset: Timestamp,
+}
+
+/// Allowance granted by granter to grantee.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeAllowance {
+    /// Basic allowance.
+    Basic(BasicAllowance),
+    /// Periodic allowance.
+    Periodic(PeriodicAllowance),
+}
+
+impl FeeAllowance {
+    /// Returns the expiration time of this allowance.
+    pub fn expiration(&self) -> Option<Timestamp> {
+        match self {
+            FeeAllowance::Basic(basic) => basic.expiration,
+            FeeAllowance::Periodic(periodic) => periodic.basic.expiration,
+        }
+    }
+
+    /// Returns `true` when this allowance has expired at the specified block.
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expiration()
+            .is_some_and(|expiration| expiration < block.time)
+    }
+}
+
+/// Keeper simulating the fee grant module.
+#[derive(Clone, Default)]
+pub struct FeeGrantKeeper;
+
+impl FeeGrantKeeper {
+    /// Creates a new fee grant keeper.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Returns the allowance granted by `granter` to `grantee`,
+    /// expired allowances are reported as not existing.
+    pub fn allowance(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        granter: &Addr,
+        grantee: &Addr,
+    ) -> AnyResult<Option<FeeAllowance>> {
+        let storage = prefixed_read(storage, NAMESPACE_FEEGRANT);
+        Ok(ALLOWANCES
+            .may_load(&storage, (granter, grantee))?
+            .filter(|allowance| !allowance.is_expired(block)))
+    }
+
+    /// Deducts the `fee` from the allowance granted by `granter` to `grantee`.
+    ///
+    /// Fully spent allowances are removed.
+    pub fn use_allowance(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        granter: &Addr,
+        grantee: &Addr,
+        fee: &[Coin],
+    ) -> AnyResult<()> {
+        let Some(allowance) = self.allowance(storage, block, granter, grantee)? else {
+            bail!("fee-grant not found");
+        };
+        let allowance = match allowance {
+            FeeAllowance::Basic(mut basic) => {
+                basic.spend_limit = spend(basic.spend_limit, fee)?;
+                FeeAllowance::Basic(basic)
+            }
+            FeeAllowance::Periodic(mut periodic) => {
+                if block.time >= periodic.period_reset {
+                    periodic.period_can_spend = periodic.period_spend_limit.clone();
+                    periodic.period_reset = periodic.period_reset.plus_seconds(periodic.period);
+                    if block.time >= periodic.period_reset {
+                        periodic.period_reset = block.time.plus_seconds(periodic.period);
+                    }
+                }
+                periodic.period_can_spend = spend(periodic.period_can_spend, fee)?;
+                periodic.basic.spend_limit = spend(periodic.basic.spend_limit, fee)?;
+                FeeAllowance::Periodic(periodic)
+            }
+        };
+        let mut storage = prefixed(storage, NAMESPACE_FEEGRANT);
+        let spent = match &allowance {
+            FeeAllowance::Basic(basic) => is_spent(&basic.spend_limit),
+            FeeAllowance::Periodic(periodic) => is_spent(&periodic.basic.spend_limit),
+        };
+        if spent {
+            ALLOWANCES.remove(&mut storage, (granter, grantee));
+        } else {
+            ALLOWANCES.save(&mut storage, (granter, grantee), &allowance)?;
+        }
+        Ok(())
+    }
+
+    fn execute_msg(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        sender: Addr,
+        type_url: &str,
+        value: &[u8],
+    ) -> AnyResult<AppResponse> {
+        match type_url {
+            MSG_GRANT_ALLOWANCE => {
+                let msg = MsgGrantAllowance::decode(value)?;
+                let (granter, grantee) =
+                    validate_parties(api, &sender, &msg.granter, &msg.grantee)?;
+                let Some(allowance) = msg.allowance else {
+                    bail!("missing allowance");
+                };
+                let allowance = decode_allowance(block, allowance)?;
+                if allowance.is_expired(block) {
+                    bail!("expiration is before current block time");
+                }
+                let mut storage = prefixed(storage, NAMESPACE_FEEGRANT);
+                if let Some(existing) = ALLOWANCES.may_load(&storage, (&granter, &grantee))? {
+                    if !existing.is_expired(block) {
+                        bail!("fee allowance already exists");
+                    }
+                }
+                ALLOWANCES.save(&mut storage, (&granter, &grantee), &allowance)?;
+                Ok(AppResponse {
+                    events: vec![Event::new("set_feegrant")
+                        .add_attribute("granter", granter)
+                        .add_attribute("grantee", grantee)],
+                    data: None,
+                })
+            }
+            MSG_REVOKE_ALLOWANCE => {
+                let msg = MsgRevokeAllowance::decode(value)?;
+                let (granter, grantee) =
+                    validate_parties(api, &sender, &msg.granter, &msg.grantee)?;
+                let mut storage = prefixed(storage, NAMESPACE_FEEGRANT);
+                if !ALLOWANCES.has(&storage, (&granter, &grantee)) {
+                    bail!("fee-grant not found");
+                }
+                ALLOWANCES.remove(&mut storage, (&granter, &grantee));
+                Ok(AppResponse {
+                    events: vec![Event::new("revoke_feegrant")
+                        .add_attribute("granter", granter)
+                        .add_attribute("grantee", grantee)],
+                    data: None,
+                })
+            }
+            _ => bail!("Unexpected fee grant message: type_url={}", type_url),
+        }
+    }
+
+    fn query_msg(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        path: &str,
+        data: &[u8],
+    ) -> AnyResult<Binary> {
+        match path {
+            QUERY_ALLOWANCE => {
+                let request = QueryAllowanceRequest::decode(data)?;
+                let granter = api.addr_validate(&request.granter)?;
+                let grantee = api.addr_validate(&request.grantee)?;
+                let Some(allowance) = self.allowance(storage, block, &granter, &grantee)? else {
+                    bail!("fee-grant not found");
+                };
+                let response = QueryAllowanceResponse {
+                    allowance: Some(ProtoGrant {
+                        granter: granter.into(),
+                        grantee: grantee.into(),
+                        allowance: Some(encode_allowance(&allowance)),
+                    }),
+                };
+                Ok(response.encode_to_vec().into())
+            }
+            _ => bail!("Unexpected fee grant query: path={}", path),
+        }
+    }
+}
+
+impl Stargate for FeeGrantKeeper {
+    fn execute_stargate<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        type_url: String,
+        value: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        self.execute_msg(api, storage, block, sender, &type_url, &value)
+    }
+
+    fn query_stargate(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        block: &BlockInfo,
+        path: String,
+        data: Binary,
+    ) -> AnyResult<Binary> {
+        self.query_msg(api, storage, block, &path, &data)
+    }
+
+    fn execute_any<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: AnyMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        self.execute_msg(api, storage, block, sender, &msg.type_url, &msg.value)
+    }
+
+    fn query_grpc(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> AnyResult<Binary> {
+        self.query_msg(api, storage, block, &request.path, &request.data)
+    }
+}
+
+/// Validates the granter and the grantee, only the granter is allowed to send the message.
+fn validate_parties(
+    api: &dyn Api,
+    sender: &Addr,
+    granter: &str,
+    grantee: &str,
+) -> AnyResult<(Addr, Addr)> {
+    let granter = api.addr_validate(granter)?;
+    let grantee = api.addr_validate(grantee)?;
+    if granter != *sender {
+        bail!(
+            "unauthorized: sender {} is not the granter {}",
+            sender,
+            granter
+        );
+    }
+    if granter == grantee {
+        bail!("cannot self-grant fee authorization");
+    }
+    Ok((granter, grantee))
+}
+
+/// Subtracts the fee from the limit, empty limit means no limit at all.
+fn spend(mut limit: Vec<Coin>, fee: &[Coin]) -> AnyResult<Vec<Coin>> {
+    if limit.is_empty() {
+        return Ok(limit);
+    }
+    for fee_coin in fee {
+        match limit.iter_mut().find(|c| c.denom == fee_coin.denom) {
+            Some(coin) if coin.amount >= fee_coin.amount => coin.amount -= fee_coin.amount,
+            _ => bail!("fee limit exceeded"),
+        }
+    }
+    Ok(limit)
+}
+
+/// Returns `true` when a (non-empty) limit was completely spent.
+fn is_spent(limit: &[Coin]) -> bool {
+    !limit.is_empty() && limit.iter().all(|c| c.amount.is_zero())
+}
+
+fn decode_allowance(block: &BlockInfo, any: ProtoAny) -> AnyResult<FeeAllowance> {
+    match any.type_url.as_str() {
+        BASIC_ALLOWANCE => Ok(FeeAllowance::Basic(
+            ProtoBasicAllowance::decode(any.value.as_slice())?.try_into()?,
+        )),
+        PERIODIC_ALLOWANCE => {
+            let proto = ProtoPeriodicAllowance::decode(any.value.as_slice())?;
+            let period = proto.period.map(|d| d.seconds as u64).unwrap_or_default();
+            if period == 0 {
+                bail!("invalid period: must be positive");
+            }
+            let period_spend_limit = coins_from_proto(proto.period_spend_limit)?;
+            let period_can_spend = coins_from_proto(proto.period_can_spend)?;
+            Ok(FeeAllowance::Periodic(PeriodicAllowance {
+                basic: proto.basic.unwrap_or_default().try_into()?,
+                period,
+                period_can_spend: if period_can_spend.is_empty() {
+                    period_spend_limit.clone()
+                } else {
+                    period_can_spend
+                },
+                period_spend_limit,
+                period_reset: proto
+                    .period_reset
+                    .map(Timestamp::from)
+                    .unwrap_or_else(|| block.time.plus_seconds(period)),
+            }))
+        }
+        other => bail!("unsupported allowance type: {}", other),
+    }
+}
+
+fn encode_allowance(allowance: &FeeAllowance) -> ProtoAny {
+    match allowance {
+        FeeAllowance::Basic(basic) => ProtoAny {
+            type_url: BASIC_ALLOWANCE.to_string(),
+            value: ProtoBasicAllowance::from(basic).encode_to_vec(),
+        },
+        FeeAllowance::Periodic(periodic) => ProtoAny {
+            type_url: PERIODIC_ALLOWANCE.to_string(),
+            value: ProtoPeriodicAllowance {
+                basic: Some(ProtoBasicAllowance::from(&periodic.basic)),
+                period: Some(ProtoTimestamp {
+                    seconds: periodic.period as i64,
+                    nanos: 0,
+                }),
+                period_spend_limit: coins_to_proto(&periodic.period_spend_limit),
+                period_can_spend: coins_to_proto(&periodic.period_can_spend),
+                period_reset: Some(periodic.period_reset.into()),
+            }
+            .encode_to_vec(),
+        },
+    }
+}
+
+pub(crate) fn coins_from_proto(coins: Vec<ProtoCoin>) -> AnyResult<Vec<Coin>> {
+    coins
+        .into_iter()
+        .map(|c| Ok(Coin::new(c.amount.parse::<Uint128>()?, c.denom)))
+        .collect()
+}
+
+pub(crate) fn coins_to_proto(coins: &[Coin]) -> Vec<ProtoCoin> {
+    coins
+        .iter()
+        .map(|c| ProtoCoin {
+            denom: c.denom.clone(),
+            amount: c.amount.to_string(),
+        })
+        .collect()
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoAny {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes, tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+/// Shared by `google.protobuf.Timestamp` and `google.protobuf.Duration`, both have the same layout.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoTimestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+impl From<ProtoTimestamp> for Timestamp {
+    fn from(value: ProtoTimestamp) -> Self {
+        Timestamp::from_seconds(value.seconds as u64).plus_nanos(value.nanos as u64)
+    }
+}
+
+impl From<Timestamp> for ProtoTimestamp {
+    fn from(value: Timestamp) -> Self {
+        Self {
+            seconds: value.seconds() as i64,
+            nanos: value.subsec_nanos() as i32,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoBasicAllowance {
+    #[prost(message, repeated, tag = "1")]
+    pub spend_limit: Vec<ProtoCoin>,
+    #[prost(message, optional, tag = "2")]
+    pub expiration: Option<ProtoTimestamp>,
+}
+
+impl TryFrom<ProtoBasicAllowance> for BasicAllowance {
+    type Error = anyhow::Error;
+
+    fn try_from(value: ProtoBasicAllowance) -> AnyResult<Self> {
+        Ok(Self {
+            spend_limit: coins_from_proto(value.spend_limit)?,
+            expiration: value.expiration.map(Timestamp::from),
+        })
+    }
+}
+
+impl From<&BasicAllowance> for ProtoBasicAllowance {
+    fn from(value: &BasicAllowance) -> Self {
+        Self {
+            spend_limit: coins_to_proto(&value.spend_limit),
+            expiration: value.expiration.map(ProtoTimestamp::from),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoPeriodicAllowance {
+    #[prost(message, optional, tag = "1")]
+    pub basic: Option<ProtoBasicAllowance>,
+    #[prost(message, optional, tag = "2")]
+    pub period: Option<ProtoTimestamp>,
+    #[prost(message, repeated, tag = "3")]
+    pub period_spend_limit: Vec<ProtoCoin>,
+    #[prost(message, repeated, tag = "4")]
+    pub period_can_spend: Vec<ProtoCoin>,
+    #[prost(message, optional, tag = "5")]
+    pub period_reset: Option<ProtoTimestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgGrantAllowance {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub allowance: Option<ProtoAny>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MsgRevokeAllowance {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryAllowanceRequest {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct QueryAllowanceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub allowance: Option<ProtoGrant>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ProtoGrant {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub allowance: Option<ProtoAny>,
+}
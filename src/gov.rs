@@ -14,3 +14,187 @@ impl Gov for GovAcceptingModule {}
 pub type GovFailingModule = FailingModule<GovMsg, Empty, Empty>;
 
 impl Gov for GovFailingModule {}
+
+#[cfg(feature = "stargate")]
+pub use recording::{GovRecordingModule, RecordedVote};
+
+#[cfg(feature = "stargate")]
+mod recording {
+    use super::Gov;
+    use crate::error::{bail, AnyResult};
+    use crate::prefixed_storage::{prefixed, prefixed_read};
+    use crate::{AppResponse, CosmosRouter, Module};
+    use cosmwasm_std::{
+        Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Decimal, Empty, Event, GovMsg, Order,
+        Querier, Storage, VoteOption,
+    };
+    use cw_storage_plus::Map;
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+
+    /// Default storage namespace for governance module.
+    const NAMESPACE_GOV: &[u8] = b"gov";
+
+    /// Collection of recorded votes, indexed by proposal identifier and voter address.
+    const VOTES: Map<(u64, &Addr), RecordedVote> = Map::new("votes");
+
+    /// Vote accepted by [GovRecordingModule].
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct RecordedVote {
+        /// Identifier of the proposal.
+        pub proposal_id: u64,
+        /// Address of the voter (sender of the vote message).
+        pub voter: Addr,
+        /// Option chosen in a simple vote, `None` for weighted votes.
+        pub option: Option<VoteOption>,
+        /// Weighted options of the vote, a simple vote has its single option weighted 1.
+        pub weighted_options: Vec<(VoteOption, Decimal)>,
+    }
+
+    /// Governance module accepting all votes and recording them in storage.
+    ///
+    /// Votes are indexed by proposal identifier and voter, so voting again
+    /// on the same proposal overwrites the previous vote, like in Cosmos SDK.
+    /// Every accepted vote emits the `proposal_vote` event.
+    #[derive(Clone, Default)]
+    pub struct GovRecordingModule;
+
+    impl GovRecordingModule {
+        /// Creates a new governance module recording votes.
+        pub fn new() -> Self {
+            Self
+        }
+
+        /// Returns all recorded votes, ordered by proposal identifier and voter address.
+        pub fn votes(&self, storage: &dyn Storage) -> AnyResult<Vec<RecordedVote>> {
+            let storage = prefixed_read(storage, NAMESPACE_GOV);
+            VOTES
+                .range(&storage, None, None, Order::Ascending)
+                .map(|item| Ok(item?.1))
+                .collect()
+        }
+
+        /// Returns the vote cast by `voter` on the proposal with specified identifier.
+        pub fn vote_of(
+            &self,
+            storage: &dyn Storage,
+            proposal_id: u64,
+            voter: &Addr,
+        ) -> AnyResult<Option<RecordedVote>> {
+            let storage = prefixed_read(storage, NAMESPACE_GOV);
+            Ok(VOTES.may_load(&storage, (proposal_id, voter))?)
+        }
+
+        fn record(&self, storage: &mut dyn Storage, vote: RecordedVote) -> AnyResult<AppResponse> {
+            let mut storage = prefixed(storage, NAMESPACE_GOV);
+            VOTES.save(&mut storage, (vote.proposal_id, &vote.voter), &vote)?;
+            let event = Event::new("proposal_vote")
+                .add_attribute("voter", vote.voter.as_str())
+                .add_attribute("option", format_options(&vote.weighted_options))
+                .add_attribute("proposal_id", vote.proposal_id.to_string());
+            Ok(AppResponse {
+                events: vec![event],
+                data: None,
+            })
+        }
+    }
+
+    impl Module for GovRecordingModule {
+        type ExecT = GovMsg;
+        type QueryT = Empty;
+        type SudoT = Empty;
+
+        fn execute<ExecC, QueryC>(
+            &self,
+            _api: &dyn Api,
+            storage: &mut dyn Storage,
+            _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            _block: &BlockInfo,
+            sender: Addr,
+            msg: GovMsg,
+        ) -> AnyResult<AppResponse>
+        where
+            ExecC: CustomMsg + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static,
+        {
+            let vote = match msg {
+                GovMsg::Vote {
+                    proposal_id,
+                    option,
+                } => RecordedVote {
+                    proposal_id,
+                    voter: sender,
+                    option: Some(option.clone()),
+                    weighted_options: vec![(option, Decimal::one())],
+                },
+                #[cfg(feature = "cosmwasm_1_2")]
+                GovMsg::VoteWeighted {
+                    proposal_id,
+                    options,
+                } => RecordedVote {
+                    proposal_id,
+                    voter: sender,
+                    option: None,
+                    weighted_options: options
+                        .into_iter()
+                        .map(|weighted| (weighted.option, weighted.weight))
+                        .collect(),
+                },
+            };
+            self.record(storage, vote)
+        }
+
+        fn query(
+            &self,
+            _api: &dyn Api,
+            _storage: &dyn Storage,
+            _querier: &dyn Querier,
+            _block: &BlockInfo,
+            request: Empty,
+        ) -> AnyResult<Binary> {
+            bail!("Unexpected governance query: {:?}", request)
+        }
+
+        fn sudo<ExecC, QueryC>(
+            &self,
+            _api: &dyn Api,
+            _storage: &mut dyn Storage,
+            _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            _block: &BlockInfo,
+            msg: Empty,
+        ) -> AnyResult<AppResponse>
+        where
+            ExecC: CustomMsg + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static,
+        {
+            bail!("Unexpected governance sudo: {:?}", msg)
+        }
+    }
+
+    impl Gov for GovRecordingModule {}
+
+    /// Formats weighted vote options the way Cosmos SDK does in the `option` attribute,
+    /// e.g. `option:VOTE_OPTION_YES weight:"1.000000000000000000"`, one option per line.
+    fn format_options(options: &[(VoteOption, Decimal)]) -> String {
+        options
+            .iter()
+            .map(|(option, weight)| {
+                let option = match option {
+                    VoteOption::Yes => "VOTE_OPTION_YES",
+                    VoteOption::No => "VOTE_OPTION_NO",
+                    VoteOption::Abstain => "VOTE_OPTION_ABSTAIN",
+                    VoteOption::NoWithVeto => "VOTE_OPTION_NO_WITH_VETO",
+                };
+                let atomics = weight.atomics().u128();
+                let unit = 10u128.pow(Decimal::DECIMAL_PLACES);
+                format!(
+                    "option:{} weight:\"{}.{:018}\"",
+                    option,
+                    atomics / unit,
+                    atomics % unit
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
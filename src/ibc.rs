@@ -1,3 +1,8 @@
+//! # IBC module
+//!
+//! Provides the [Ibc] trait, placeholder implementations of the IBC module
+//! and helpers for IBC related values, see [denoms].
+
 use crate::{AcceptingModule, FailingModule, Module};
 use cosmwasm_std::{Empty, IbcMsg, IbcQuery};
 
@@ -13,3 +18,35 @@ impl Ibc for IbcAcceptingModule {}
 pub type IbcFailingModule = FailingModule<IbcMsg, IbcQuery, Empty>;
 
 impl Ibc for IbcFailingModule {}
+
+/// # Denominations of tokens transferred over IBC
+pub mod denoms {
+    use cosmwasm_std::HexBinary;
+    use sha2::{Digest, Sha256};
+
+    /// Returns the denomination of an ICS20 voucher, received over the specified port
+    /// and channel for the specified base denomination, like `ibc/27394FB0...`.
+    ///
+    /// The hash is the uppercase hex encoded SHA-256 of the trace path `{port}/{channel}/{base_denom}`,
+    /// like in ICS20 implementation of Cosmos SDK. Voucher denominations can be used with
+    /// the bank module like any other denominations, e.g. to fund accounts in tests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::ibc::denoms::voucher_denom;
+    ///
+    /// assert_eq!(
+    ///     "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+    ///     voucher_denom("transfer", "channel-0", "uatom")
+    /// );
+    /// ```
+    pub fn voucher_denom(port: &str, channel: &str, base_denom: &str) -> String {
+        let trace = format!("{}/{}/{}", port, channel, base_denom);
+        let hash = Sha256::digest(trace.as_bytes());
+        format!(
+            "ibc/{}",
+            HexBinary::from(hash.as_slice()).to_hex().to_uppercase()
+        )
+    }
+}
@@ -0,0 +1,139 @@
+//! # Storage layout versions
+//!
+//! Built-in keepers keep their state in the [Storage] of the [App](crate::App),
+//! each one under its own namespace. Every namespace holds the version of its layout,
+//! so the state written by a version of **CosmWasm MultiTest** with a different layout
+//! is reported with a clear error instead of being silently misinterpreted.
+//!
+//! The history of layout changes is described in `MIGRATIONS.md`.
+
+use crate::bank::NAMESPACE_BANK;
+use crate::error::{bail, AnyResult, Error};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+#[cfg(feature = "staking")]
+use crate::staking::{NAMESPACE_DISTRIBUTION, NAMESPACE_STAKING};
+use crate::wasm::NAMESPACE_WASM;
+use cosmwasm_std::{Order, Storage};
+use cw_storage_plus::Item;
+
+/// Version of the storage layouts of built-in keepers expected by this version of the library.
+///
+/// Version `0` denotes the layouts written before the layout versions were introduced.
+pub const LAYOUT_VERSION: u32 = 1;
+
+/// Key of the layout version, stored under the namespace of each built-in keeper.
+pub const LAYOUT_VERSION_KEY: &str = "layout_version";
+
+/// Layout version of a built-in keeper.
+const VERSION: Item<u32> = Item::new(LAYOUT_VERSION_KEY);
+
+/// Names and storage namespaces of built-in keepers with versioned layouts.
+const MODULES: &[(&str, &[u8])] = &[
+    ("bank", NAMESPACE_BANK),
+    #[cfg(feature = "staking")]
+    ("staking", NAMESPACE_STAKING),
+    #[cfg(feature = "staking")]
+    ("distribution", NAMESPACE_DISTRIBUTION),
+    ("wasm", NAMESPACE_WASM),
+];
+
+/// Returns the layout version stored under specified namespace.
+///
+/// Records without the layout version are treated as written in layout version `0`,
+/// when there are no records at all, `None` is returned.
+fn stored_version(storage: &dyn Storage, namespace: &[u8]) -> AnyResult<Option<u32>> {
+    let storage = prefixed_read(storage, namespace);
+    match VERSION.may_load(&storage)? {
+        Some(version) => Ok(Some(version)),
+        None if storage.range(None, None, Order::Ascending).next().is_some() => Ok(Some(0)),
+        None => Ok(None),
+    }
+}
+
+/// Writes the current layout version of every built-in keeper that has no state yet.
+///
+/// The state already written in any layout version is left untouched,
+/// mismatched versions are reported by [check_layout_versions].
+pub(crate) fn init_layout_versions(storage: &mut dyn Storage) -> AnyResult<()> {
+    for (_, namespace) in MODULES {
+        if stored_version(storage, namespace)?.is_none() {
+            VERSION.save(&mut prefixed(storage, namespace), &LAYOUT_VERSION)?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks if the state of every built-in keeper is written in the current layout version.
+pub(crate) fn check_layout_versions(storage: &dyn Storage) -> AnyResult<()> {
+    for (module, namespace) in MODULES {
+        let version = stored_version(storage, namespace)?.unwrap_or(LAYOUT_VERSION);
+        if version != LAYOUT_VERSION {
+            bail!(Error::storage_layout_mismatch(
+                *module,
+                version,
+                LAYOUT_VERSION
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Migrates the state of built-in keepers from specified layout version
+/// to the layout version expected by this version of the library, see [LAYOUT_VERSION].
+///
+/// Fails when the state of any built-in keeper is written in a layout version
+/// other than `from_version`, no changes are made to the storage in such case.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::testing::MockStorage;
+/// use cosmwasm_std::Storage;
+/// use cw_multi_test::prefixed_storage::prefixed;
+/// use cw_multi_test::{migrate_state, no_init, AppBuilder, IntoAddr};
+///
+/// // state written before the layout versions were introduced (layout version 0)
+/// let mut storage = MockStorage::new();
+/// prefixed(&mut storage, b"bank").set(b"key", b"value");
+///
+/// migrate_state(&mut storage, 0).unwrap();
+///
+/// let app = AppBuilder::default().with_storage(storage).build(no_init);
+/// app.wrap().query_all_balances("owner".into_addr()).unwrap();
+/// ```
+pub fn migrate_state(storage: &mut dyn Storage, from_version: u32) -> AnyResult<()> {
+    if from_version > LAYOUT_VERSION {
+        bail!(
+            "storage layout v{} is unknown, the latest one is v{}",
+            from_version,
+            LAYOUT_VERSION
+        );
+    }
+    for (module, namespace) in MODULES {
+        let version = stored_version(storage, namespace)?.unwrap_or(from_version);
+        if version != from_version {
+            bail!(
+                "{} storage layout v{} found, cannot migrate from v{}",
+                module,
+                version,
+                from_version
+            );
+        }
+    }
+    for version in from_version..LAYOUT_VERSION {
+        match version {
+            0 => migrate_v0_to_v1(storage)?,
+            _ => unreachable!("no migration from layout version {}", version),
+        }
+    }
+    Ok(())
+}
+
+/// Layout version 1 introduced the layout versions, the layouts themselves are unchanged,
+/// so only the version of every built-in keeper is written.
+fn migrate_v0_to_v1(storage: &mut dyn Storage) -> AnyResult<()> {
+    for (_, namespace) in MODULES {
+        VERSION.save(&mut prefixed(storage, namespace), &1)?;
+    }
+    Ok(())
+}
@@ -102,6 +102,8 @@
 //! Currently, the message processing logic is divided into one _module_ for every [CosmosMsg](cosmwasm_std) variant.
 //! [Bank] handles [BankMsg](cosmwasm_std::BankMsg) and [BankQuery](cosmwasm_std::BankQuery),
 //! [Wasm] handles [WasmMsg](cosmwasm_std::WasmMsg) and [WasmQuery](cosmwasm_std::WasmQuery), etc.
+//! Custom modules that have to execute several operations atomically can start
+//! their own nested transaction using [transactional].
 //!
 //! ### Router
 //!
@@ -116,6 +118,14 @@
 //! that call into [Bank]. You can see an example of that in _send_ method of the [WasmKeeper],
 //! where it moves bank tokens from one account to another.
 //!
+//! ### Determinism
+//!
+//! Running the same sequence of operations on two freshly created [App]s always produces
+//! the same results: identical ordered lists of [Event](cosmwasm_std::Event)s, identical
+//! contract addresses and identical storage content. All modules keep their state in ordered
+//! collections and process submessages and replies strictly in the order they were returned,
+//! so there is no hidden source of randomness like hash map iteration order.
+//!
 //! ### Addons
 //!
 //! (tbd)
@@ -124,47 +134,118 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::missing_crate_level_docs)]
 
+mod address_book;
 mod addresses;
 mod api;
 mod app;
 mod app_builder;
+#[cfg(feature = "async-helpers")]
+mod async_app;
+mod authz;
+mod backend;
 mod bank;
+mod call_stats;
+pub mod catch_all;
+mod chaos;
 mod checksums;
+mod code_id;
+#[cfg(any(feature = "stargate", feature = "cosmwasm_2_0"))]
+mod contract_state;
 mod contracts;
 pub mod custom_handler;
 pub mod error;
+mod events;
 mod executor;
+mod faucet;
 mod featured;
+mod feegrant;
 mod gov;
-mod ibc;
+pub mod ibc;
+mod layout;
 mod module;
-mod prefixed_storage;
+mod operation_log;
+pub mod prefixed_storage;
+#[cfg(feature = "proptest-support")]
+pub mod proptest_support;
+mod provenance;
+mod query_kinds;
+mod redaction;
+mod reply_traces;
+mod schedules;
+mod setup_order;
 #[cfg(feature = "staking")]
 mod staking;
 mod stargate;
+mod storage_access;
+mod storage_stats;
 mod test_helpers;
 mod tests;
 mod transactions;
+mod transfers;
 mod wasm;
+mod wasm_permissions;
 
+pub use crate::address_book::{AddressBook, AddressBookContract};
 pub use crate::addresses::{
-    AddressGenerator, IntoAddr, IntoBech32, IntoBech32m, SimpleAddressGenerator,
+    instantiate_address, module_address, predict_instantiate2_address, AddressGenerator,
+    ContractInstantiationInfo, CreatorCanonicalization, DeterministicAddressGenerator,
+    Instantiate2AddressGenerator, IntoAddr, IntoBech32, IntoBech32m, SimpleAddressGenerator,
 };
 pub use crate::api::{MockApiBech32, MockApiBech32m};
 pub use crate::app::{
-    custom_app, next_block, no_init, App, BasicApp, CosmosRouter, Router, SudoMsg,
+    custom_app, next_block, no_init, App, BasicApp, CosmosRouter, Router, SnapshotStorage, SudoMsg,
 };
 pub use crate::app_builder::{AppBuilder, BasicAppBuilder};
+#[cfg(feature = "async-helpers")]
+pub use crate::async_app::AsyncApp;
+pub use crate::authz::{
+    Authorization, AuthzGrant, AuthzKeeper, GenericAuthorization, SendAuthorization,
+};
+pub use crate::backend::ChainBackend;
 pub use crate::bank::{Bank, BankKeeper, BankSudo};
+pub use crate::call_stats::{CallStats, EntryPoint};
+#[cfg(feature = "staking")]
+pub use crate::chaos::ChaoticStaking;
+pub use crate::chaos::{ChaosConfig, Chaotic, ChaoticBank, ChaoticWasm};
 pub use crate::checksums::ChecksumGenerator;
+pub use crate::code_id::CodeId;
 pub use crate::contracts::{Contract, ContractWrapper};
+pub use crate::events::{normalize_events, EventNormalization};
 pub use crate::executor::{AppResponse, Executor};
+pub use crate::faucet::{validate_denom, Faucet};
+pub use crate::feegrant::{BasicAllowance, FeeAllowance, FeeGrantKeeper, PeriodicAllowance};
 pub use crate::gov::{Gov, GovAcceptingModule, GovFailingModule};
+#[cfg(feature = "stargate")]
+pub use crate::gov::{GovRecordingModule, RecordedVote};
 pub use crate::ibc::{Ibc, IbcAcceptingModule, IbcFailingModule};
+pub use crate::layout::{migrate_state, LAYOUT_VERSION, LAYOUT_VERSION_KEY};
 pub use crate::module::{AcceptingModule, FailingModule, Module};
+pub use crate::operation_log::{
+    replay, ContractFactory, Operation, OperationLog, Outcome, RecordedOperation,
+};
+pub use crate::provenance::ModuleKind;
+pub use crate::query_kinds::{
+    BankQueryKind, DistributionQueryKind, IbcQueryKind, QueryKind, StakingQueryKind, WasmQueryKind,
+};
+pub use crate::redaction::Redaction;
+pub use crate::reply_traces::ReplyTrace;
+pub use crate::schedules::{Schedule, ScheduledExecutionResult, ScheduledResult};
+pub use crate::setup_order::{
+    assert_setup_order_independent, check_setup_order, first_storage_divergence,
+    SetupOrderDivergence,
+};
 #[cfg(feature = "staking")]
 pub use crate::staking::{
-    Distribution, DistributionKeeper, StakeKeeper, Staking, StakingInfo, StakingSudo,
+    Distribution, DistributionKeeper, RewardsClock, RewardsModel, StakeKeeper, Staking,
+    StakingInfo, StakingSudo, ValidatorMetadata,
 };
 pub use crate::stargate::{Stargate, StargateAccepting, StargateFailing};
-pub use crate::wasm::{ContractData, Wasm, WasmKeeper, WasmSudo};
+pub use crate::storage_access::{StorageAccessCounts, StorageAccessStats};
+pub use crate::storage_stats::{PrefixStats, StorageBudget, StorageStats};
+pub use crate::transactions::{transactional, RepLog, StorageTransaction};
+pub use crate::transfers::{BankTransfers, TransferKind, TransferRecord};
+pub use crate::wasm::{
+    ContractData, ContractHistoryEntry, ContractHistoryOperation, GuardMode, LabelValidation,
+    ResponseLimits, Wasm, WasmKeeper, WasmSudo,
+};
+pub use crate::wasm_permissions::{InstantiatePermission, WasmPermissions, WasmPermissionsSudo};
@@ -37,6 +37,11 @@ pub trait Module {
 
     /// Runs any [QueryT](Self::QueryT) message,
     /// which can be called by any external actor or smart contract.
+    ///
+    /// The provided storage always reflects the state visible at the point of the query,
+    /// no matter how deeply nested the query is: it includes all changes made by messages
+    /// (and their replies) already processed in the current transaction, and the changes
+    /// made so far by the contract issuing the query in its currently running entry-point.
     fn query(
         &self,
         api: &dyn Api,
@@ -83,6 +88,13 @@ impl<ExecT, QueryT, SudoT> Default for FailingModule<ExecT, QueryT, SudoT> {
     }
 }
 
+impl<ExecT, QueryT, SudoT> Clone for FailingModule<ExecT, QueryT, SudoT> {
+    /// Creates a copy of a failing module.
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
 impl<ExecT, QueryT, SudoT> Module for FailingModule<ExecT, QueryT, SudoT>
 where
     ExecT: Debug,
@@ -150,6 +162,13 @@ impl<ExecT, QueryT, SudoT> Default for AcceptingModule<ExecT, QueryT, SudoT> {
     }
 }
 
+impl<ExecT, QueryT, SudoT> Clone for AcceptingModule<ExecT, QueryT, SudoT> {
+    /// Creates a copy of an accepting module.
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
 impl<ExecT, QueryT, SudoT> Module for AcceptingModule<ExecT, QueryT, SudoT>
 where
     ExecT: Debug,
@@ -0,0 +1,382 @@
+//! # Operation log
+//!
+//! Records top-level operations applied to the [App] and replays them onto a fresh [App],
+//! see [AppBuilder::with_operation_log].
+
+use crate::error::{bail, AnyResult, Error};
+use crate::featured::staking::{Distribution, Staking};
+use crate::schedules::Schedule;
+use crate::{App, AppBuilder, Bank, Contract, Gov, Ibc, Module, Router, Stargate, SudoMsg, Wasm};
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::{
+    from_json, Addr, Api, Binary, BlockInfo, CosmosMsg, CustomMsg, CustomQuery, Empty, Order,
+    StdResult, Storage,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+/// Top-level operation applied to the [App].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Operation {
+    /// Messages executed with [App::execute_multi] (or any function using it,
+    /// like [execute](crate::Executor::execute)), serialized to JSON.
+    Execute {
+        /// Address of the sender.
+        sender: Addr,
+        /// Executed messages serialized to JSON.
+        msgs: Vec<Binary>,
+    },
+    /// Privileged message executed with [App::sudo] or [App::wasm_sudo].
+    Sudo {
+        /// Executed privileged message.
+        msg: SudoMsg,
+    },
+    /// Message executed by the handler registered with specified name, see [App::sudo_named].
+    SudoNamed {
+        /// Name of the sudo handler.
+        name: String,
+        /// Executed message serialized to JSON.
+        msg: Binary,
+    },
+    /// Block set with [App::set_block] or [App::update_block].
+    SetBlock {
+        /// New block info.
+        block: BlockInfo,
+    },
+    /// Contract sudo calls scheduled with [App::schedule_sudo].
+    ScheduleSudo {
+        /// Address of the called contract.
+        contract_addr: Addr,
+        /// Message passed to contract's `sudo` entry-point.
+        msg: Binary,
+        /// Schedule of the calls.
+        schedule: Schedule,
+    },
+    /// Scheduled contract sudo calls removed with [App::remove_scheduled_sudo].
+    RemoveScheduledSudo {
+        /// Identifier of the removed schedule.
+        schedule_id: u64,
+    },
+    /// Scheduled contract sudo call executed when the block was changed.
+    ///
+    /// The call is not executed again during replay, the call executed
+    /// by the replayed block change is compared with the recorded one instead.
+    ScheduledSudo {
+        /// Identifier of the schedule.
+        schedule_id: u64,
+        /// Block height at which the call was scheduled.
+        height: u64,
+    },
+    /// Contract code stored with [App::store_code] and similar functions.
+    StoreCode {
+        /// Name under which the code is registered for replay,
+        /// set only for codes stored with [App::store_code_with_name].
+        name: Option<String>,
+        /// Address of the code creator.
+        creator: Addr,
+        /// Identifier of the stored code.
+        code_id: u64,
+    },
+}
+
+/// Outcome of the top-level operation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    /// The operation succeeded.
+    Success,
+    /// The operation failed with the reported error.
+    Failure {
+        /// Error reported by the operation.
+        error: String,
+    },
+}
+
+impl Outcome {
+    /// Returns the outcome of the operation with specified result.
+    pub(crate) fn of<T>(result: &AnyResult<T>) -> Self {
+        match result {
+            Ok(_) => Self::Success,
+            Err(err) => Self::Failure {
+                error: err.to_string(),
+            },
+        }
+    }
+
+    /// Returns the outcome of the scheduled contract sudo call with specified result.
+    pub(crate) fn of_scheduled<T>(result: &Result<T, String>) -> Self {
+        match result {
+            Ok(_) => Self::Success,
+            Err(error) => Self::Failure {
+                error: error.clone(),
+            },
+        }
+    }
+
+    /// Returns `true` when the operation succeeded.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Success => write!(f, "success"),
+            Self::Failure { error } => write!(f, "failure ({})", error),
+        }
+    }
+}
+
+/// Top-level operation with its outcome.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedOperation {
+    /// Applied operation.
+    pub operation: Operation,
+    /// Outcome of the operation.
+    pub outcome: Outcome,
+}
+
+/// Serializable log of top-level operations applied to the [App],
+/// recorded when enabled with [AppBuilder::with_operation_log].
+///
+/// Recorded are the messages executed with [App::execute_multi], privileged messages
+/// executed with [App::sudo], [App::wasm_sudo] and [App::sudo_named], block changes,
+/// stored contract codes, scheduled contract sudo calls and their results.
+/// Direct modifications of the storage are not recorded. Behavior changes
+/// and message executions can not be scheduled while the log is enabled,
+/// see [App::schedule_behavior_change] and [App::schedule_execute].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OperationLog {
+    /// Block info at the time the recording was started.
+    pub block: BlockInfo,
+    /// Records held by the storage at the time the recording was started.
+    pub genesis: Vec<(Binary, Binary)>,
+    /// Recorded operations, in the order they were applied.
+    pub operations: Vec<RecordedOperation>,
+}
+
+impl OperationLog {
+    /// Creates an empty log starting with specified block and state of the storage.
+    pub(crate) fn new(block: BlockInfo, storage: &dyn Storage) -> Self {
+        Self {
+            block,
+            genesis: storage
+                .range(None, None, Order::Ascending)
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+            operations: vec![],
+        }
+    }
+
+    /// Appends the operation with specified outcome.
+    pub(crate) fn record(&mut self, operation: Operation, outcome: Outcome) {
+        self.operations
+            .push(RecordedOperation { operation, outcome });
+    }
+}
+
+/// Function creating an instance of the contract code, used by [replay]
+/// to store the code each time it was stored in the recorded application.
+pub type ContractFactory<ExecC = Empty, QueryC = Empty> =
+    Box<dyn Fn() -> Box<dyn Contract<ExecC, QueryC>>>;
+
+/// Replays the operation log onto a fresh [App], returns the [App] with all operations applied.
+///
+/// The [App] is built with the provided `builder` and initialization function `init_fn`,
+/// starting with the block and the state of the storage recorded in the log. The builder
+/// has to provide the same components as used by the recorded application (like the api,
+/// the address generator or custom keepers), otherwise the replayed operations may have
+/// different outcomes. The state of the storage is taken from the log, so the builder
+/// and the initialization function should only configure the components (e.g. register
+/// [sudo handlers](crate::Router::register_sudo_handler)), without initializing the state.
+///
+/// Codes stored with [App::store_code_with_name] are created by the factories
+/// from the `code_registry`, taken by their names, and stored with the recorded identifiers.
+/// The same code may be stored any number of times.
+///
+/// Fails when the outcome of any operation (success or failure with the reported error)
+/// differs from the recorded one, or when scheduled contract sudo calls executed during
+/// the replayed block changes differ from the recorded ones, reporting the index
+/// of the diverging operation. Fails also when the operation can not be replayed.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::coins;
+/// use cw_multi_test::{no_init, replay, AppBuilder, Executor, IntoAddr};
+/// use std::collections::{BTreeMap, VecDeque};
+///
+/// let owner = "owner".into_addr();
+/// let mut app = AppBuilder::default()
+///     .with_operation_log()
+///     .build(|router, _, storage| {
+///         router
+///             .bank
+///             .init_balance(storage, &owner, coins(100, "uatom"))
+///             .unwrap();
+///     });
+///
+/// app.send_tokens(owner.clone(), "alice".into_addr(), &coins(30, "uatom"))
+///     .unwrap();
+///
+/// let log = app.operation_log().unwrap();
+/// let replayed = replay(log, AppBuilder::default(), no_init, &BTreeMap::new()).unwrap();
+///
+/// assert_eq!(coins(70, "uatom"), replayed.balances(&owner));
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn replay<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, F>(
+    log: &OperationLog,
+    builder: AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+    >,
+    init_fn: F,
+    code_registry: &BTreeMap<String, ContractFactory<CustomT::ExecT, CustomT::QueryT>>,
+) -> AnyResult<App<BankT, ApiT, MockStorage, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>>
+where
+    BankT: Bank,
+    ApiT: Api,
+    CustomT: Module,
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+    WasmT: Wasm<CustomT::ExecT, CustomT::QueryT>,
+    StakingT: Staking,
+    DistrT: Distribution,
+    IbcT: Ibc,
+    GovT: Gov,
+    StargateT: Stargate,
+    F: FnOnce(
+        &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+        &ApiT,
+        &mut dyn Storage,
+    ),
+{
+    let mut storage = MockStorage::new();
+    for (key, value) in &log.genesis {
+        storage.set(key, value);
+    }
+    let mut app = builder
+        .with_block(log.block.clone())
+        .with_storage(storage)
+        .build(init_fn);
+    // scheduled contract sudo calls executed by the last replayed block change
+    let mut scheduled_calls = VecDeque::new();
+    for (step, recorded) in log.operations.iter().enumerate() {
+        if !matches!(recorded.operation, Operation::ScheduledSudo { .. }) {
+            check_scheduled_calls(step, &scheduled_calls)?;
+        }
+        let outcome = match &recorded.operation {
+            Operation::Execute { sender, msgs } => {
+                let msgs = msgs
+                    .iter()
+                    .map(from_json)
+                    .collect::<StdResult<Vec<CosmosMsg<CustomT::ExecT>>>>()?;
+                Outcome::of(&app.execute_multi(sender.clone(), msgs))
+            }
+            Operation::Sudo { msg } => Outcome::of(&app.sudo(msg.clone())),
+            Operation::SudoNamed { name, msg } => {
+                Outcome::of(&app.sudo_named_binary(name, msg.clone()))
+            }
+            Operation::SetBlock { block } => {
+                app.set_block(block.clone());
+                scheduled_calls = app
+                    .last_scheduled_results()
+                    .iter()
+                    .map(|scheduled| {
+                        (
+                            format!(
+                                "scheduled call {} at height {}",
+                                scheduled.schedule_id, scheduled.height
+                            ),
+                            Outcome::of_scheduled(&scheduled.result),
+                        )
+                    })
+                    .collect();
+                Outcome::Success
+            }
+            Operation::StoreCode {
+                name,
+                creator,
+                code_id,
+            } => {
+                let Some(name) = name else {
+                    bail!(
+                        "operation {}: code {} was stored without a name, use `store_code_with_name` to replay it",
+                        step,
+                        code_id
+                    );
+                };
+                let Some(factory) = code_registry.get(name) else {
+                    bail!(
+                        "operation {}: no contract code registered under name {}",
+                        step,
+                        name
+                    );
+                };
+                Outcome::of(&app.store_code_with_id(creator.clone(), *code_id, factory()))
+            }
+            Operation::ScheduleSudo {
+                contract_addr,
+                msg,
+                schedule,
+            } => Outcome::of(&app.schedule_sudo(contract_addr.clone(), msg.clone(), *schedule)),
+            Operation::RemoveScheduledSudo { schedule_id } => {
+                app.remove_scheduled_sudo(*schedule_id);
+                Outcome::Success
+            }
+            Operation::ScheduledSudo {
+                schedule_id,
+                height,
+            } => {
+                let recorded_call = format!("scheduled call {} at height {}", schedule_id, height);
+                let Some((replayed_call, outcome)) = scheduled_calls.pop_front() else {
+                    bail!(Error::replay_divergence(
+                        step,
+                        recorded_call,
+                        "no scheduled call"
+                    ));
+                };
+                if replayed_call != recorded_call {
+                    bail!(Error::replay_divergence(step, recorded_call, replayed_call));
+                }
+                outcome
+            }
+        };
+        if outcome != recorded.outcome {
+            bail!(Error::replay_divergence(
+                step,
+                recorded.outcome.to_string(),
+                outcome.to_string()
+            ));
+        }
+    }
+    check_scheduled_calls(log.operations.len(), &scheduled_calls)?;
+    Ok(app)
+}
+
+/// Checks if all scheduled contract sudo calls executed by the replayed block change
+/// were recorded, the next operation is not a recorded scheduled call.
+fn check_scheduled_calls(
+    step: usize,
+    scheduled_calls: &VecDeque<(String, Outcome)>,
+) -> AnyResult<()> {
+    if let Some((replayed_call, _)) = scheduled_calls.front() {
+        bail!(Error::replay_divergence(
+            step,
+            "no scheduled call",
+            replayed_call
+        ));
+    }
+    Ok(())
+}
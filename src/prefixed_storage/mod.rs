@@ -1,6 +1,26 @@
+//! # Prefixed storage
+//!
+//! Storage wrappers placing all keys in a (possibly nested) namespace,
+//! using the [length-prefixed keys] scheme, the same as used by `cw-storage-plus`.
+//! Module authors can use them to keep the state of custom modules separated.
+//!
+//! Keys passed to and returned from prefixed storages are logical keys, the namespace
+//! prefix is added when accessing the underlying storage and stripped in results
+//! returned from `range`. Prefixed storages have following guarantees:
+//!
+//! - `range` returns only the records stored under the namespace of the prefixed storage,
+//!   including records stored in nested namespaces (with their length-prefixed namespace
+//!   as a part of the returned key),
+//! - keys returned from `range` never contain the namespace prefix of the prefixed storage,
+//! - `start` bound is inclusive and `end` bound is exclusive, both are logical keys,
+//! - records are ordered by raw bytes of the logical keys, in ascending or descending order,
+//! - an empty key is a valid key, it is placed before all other keys of the namespace.
+//!
+//! [length-prefixed keys]: https://github.com/webmaster128/key-namespacing#length-prefixed-keys
+
 use cosmwasm_std::Storage;
 use cosmwasm_std::{Order, Record};
-use length_prefixed::{to_length_prefixed, to_length_prefixed_nested};
+pub use length_prefixed::{to_length_prefixed, to_length_prefixed_nested};
 use namespace_helpers::{get_with_prefix, range_with_prefix, remove_with_prefix, set_with_prefix};
 
 mod length_prefixed;
@@ -59,6 +79,31 @@ impl<'a> PrefixedStorage<'a> {
             prefix: to_length_prefixed_nested(namespaces),
         }
     }
+
+    /// Returns a mutable prefixed storage with specified namespace nested
+    /// in the namespace of this storage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cosmwasm_std::Storage;
+    /// use cosmwasm_std::testing::MockStorage;
+    /// use cw_multi_test::prefixed_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+    ///
+    /// let mut storage = MockStorage::new();
+    ///
+    /// let mut foo = PrefixedStorage::new(&mut storage, b"foo");
+    /// foo.sub_prefix(b"bar").set(b"baz", b"winner");
+    ///
+    /// let bar = ReadonlyPrefixedStorage::multilevel(&storage, &[b"foo", b"bar"]);
+    /// assert_eq!(Some(b"winner".to_vec()), bar.get(b"baz"));
+    /// ```
+    pub fn sub_prefix(&mut self, namespace: &[u8]) -> PrefixedStorage<'_> {
+        PrefixedStorage {
+            storage: &mut *self.storage,
+            prefix: nested_prefix(&self.prefix, namespace),
+        }
+    }
 }
 
 impl Storage for PrefixedStorage<'_> {
@@ -68,6 +113,7 @@ impl Storage for PrefixedStorage<'_> {
 
     /// Range allows iteration over a set of keys, either forwards or backwards.
     /// Uses standard rust range notation, and e.g. `db.range(b"foo"‥b"bar")` and also works reverse.
+    /// Returned keys do not contain the namespace prefix, see [module](crate::prefixed_storage) guarantees.
     fn range<'b>(
         &'b self,
         start: Option<&[u8]>,
@@ -110,6 +156,15 @@ impl<'a> ReadonlyPrefixedStorage<'a> {
             prefix: to_length_prefixed_nested(namespaces),
         }
     }
+
+    /// Returns a read-only prefixed storage with specified namespace nested
+    /// in the namespace of this storage.
+    pub fn sub_prefix(&self, namespace: &[u8]) -> ReadonlyPrefixedStorage<'_> {
+        ReadonlyPrefixedStorage {
+            storage: self.storage,
+            prefix: nested_prefix(&self.prefix, namespace),
+        }
+    }
 }
 
 impl Storage for ReadonlyPrefixedStorage<'_> {
@@ -118,6 +173,7 @@ impl Storage for ReadonlyPrefixedStorage<'_> {
     }
 
     /// Range allows iteration over a set of keys, either forwards or backwards.
+    /// Returned keys do not contain the namespace prefix, see [module](crate::prefixed_storage) guarantees.
     fn range<'b>(
         &'b self,
         start: Option<&[u8]>,
@@ -136,6 +192,13 @@ impl Storage for ReadonlyPrefixedStorage<'_> {
     }
 }
 
+/// Returns the prefix of the namespace nested in the namespace with specified prefix.
+fn nested_prefix(prefix: &[u8], namespace: &[u8]) -> Vec<u8> {
+    let mut nested = prefix.to_vec();
+    nested.extend_from_slice(&to_length_prefixed(namespace));
+    nested
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
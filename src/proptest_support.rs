@@ -0,0 +1,140 @@
+//! # Property-based testing strategies
+//!
+//! [proptest](https://docs.rs/proptest) strategies generating values accepted by [App],
+//! enabled with the `proptest-support` feature.
+//!
+//! Generated addresses are built with the [Api] of the tested application, so they use
+//! the same address format (e.g. the same Bech32 prefix) as the application.
+//! Denominations are checked with [validate_denom], labels satisfy
+//! [LabelValidation::Wasmd](crate::LabelValidation::Wasmd), and salts have a length
+//! accepted when instantiating contracts with predictable addresses.
+//!
+//! # Example
+//!
+//! ```
+//! use cosmwasm_std::Api;
+//! use cw_multi_test::proptest_support::{arb_addr, arb_funds, proptest_app};
+//! use cw_multi_test::App;
+//!
+//! let api = App::default().api().clone();
+//! proptest_app(
+//!     (arb_addr(&api), arb_funds(&["uatom", "uosmo"], 1_000)),
+//!     |app, (addr, funds)| {
+//!         app.init_modules(|router, _, storage| {
+//!             router.bank.init_balance(storage, &addr, funds.clone()).unwrap();
+//!         });
+//!         assert!(app.api().addr_validate(addr.as_str()).is_ok());
+//!         for coin in funds {
+//!             assert_eq!(coin, app.wrap().query_balance(&addr, &coin.denom).unwrap());
+//!         }
+//!     },
+//! );
+//! ```
+
+use crate::{validate_denom, App};
+use cosmwasm_std::{coin, Addr, Api, Binary, CanonicalAddr, Coin};
+use proptest::prelude::*;
+use proptest::sample::{select, subsequence};
+use proptest::string::string_regex;
+use proptest::test_runner::TestRunner;
+use std::fmt::Debug;
+
+/// Length of the canonical form of generated addresses, like addresses of contracts in `wasmd`.
+const CANONICAL_ADDR_LEN: usize = 32;
+
+/// Maximum length of a salt used when instantiating contracts with predictable addresses.
+const MAX_SALT_LEN: usize = 64;
+
+/// Returns a strategy generating addresses valid for the specified [Api].
+///
+/// Addresses are humanized from random canonical addresses,
+/// so they are accepted by `addr_validate` of the same [Api].
+pub fn arb_addr<A: Api>(api: &A) -> impl Strategy<Value = Addr> + '_ {
+    prop::collection::vec(any::<u8>(), CANONICAL_ADDR_LEN).prop_map(move |bytes| {
+        api.addr_humanize(&CanonicalAddr::from(bytes))
+            .expect("generating address failed")
+    })
+}
+
+/// Returns a strategy generating coins of one of the specified denominations,
+/// with the amount in range from 1 to `max`.
+///
+/// # Panics
+///
+/// This function panics when no denomination is specified, when any of the denominations
+/// is rejected by [validate_denom], or when `max` is zero.
+pub fn arb_coin(denoms: &[&str], max: u128) -> impl Strategy<Value = Coin> {
+    (select(checked_denoms(denoms)), 1..=checked_max(max))
+        .prop_map(|(denom, amount)| coin(amount, denom))
+}
+
+/// Returns a strategy generating funds: coins of distinct denominations chosen from
+/// the specified ones, sorted by denomination, each with the amount in range from 1 to `max`.
+/// The generated funds may be empty.
+///
+/// # Panics
+///
+/// This function panics in the same cases as [arb_coin].
+pub fn arb_funds(denoms: &[&str], max: u128) -> impl Strategy<Value = Vec<Coin>> {
+    let mut denoms = checked_denoms(denoms);
+    denoms.sort();
+    denoms.dedup();
+    let max = checked_max(max);
+    let count = denoms.len();
+    subsequence(denoms, 0..=count).prop_flat_map(move |denoms| {
+        denoms
+            .into_iter()
+            .map(|denom| (1..=max).prop_map(move |amount| coin(amount, &denom)))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Returns a strategy generating contract labels accepted by
+/// [LabelValidation::Wasmd](crate::LabelValidation::Wasmd).
+pub fn arb_label() -> impl Strategy<Value = String> {
+    string_regex("[a-zA-Z0-9]([a-zA-Z0-9 ._-]{0,62}[a-zA-Z0-9])?").expect("invalid label pattern")
+}
+
+/// Returns a strategy generating salts for instantiating contracts
+/// with predictable addresses, from 1 to 64 bytes long.
+pub fn arb_salt() -> impl Strategy<Value = Binary> {
+    prop::collection::vec(any::<u8>(), 1..=MAX_SALT_LEN).prop_map(Binary::from)
+}
+
+/// Runs the property test for inputs generated by the specified strategy,
+/// each case with a fresh default [App].
+///
+/// # Panics
+///
+/// This function panics with the minimal failing input when the test fails for any case.
+pub fn proptest_app<S>(strategy: S, test: impl Fn(&mut App, S::Value))
+where
+    S: Strategy,
+    S::Value: Debug,
+{
+    let mut runner = TestRunner::default();
+    if let Err(err) = runner.run(&strategy, |input| {
+        test(&mut App::default(), input);
+        Ok(())
+    }) {
+        panic!("{}", err);
+    }
+}
+
+/// Returns owned denominations, after checking they are accepted by [validate_denom].
+fn checked_denoms(denoms: &[&str]) -> Vec<String> {
+    assert!(!denoms.is_empty(), "at least one denomination is required");
+    denoms
+        .iter()
+        .map(|denom| match validate_denom(denom) {
+            Ok(()) => denom.to_string(),
+            Err(err) => panic!("{}", err),
+        })
+        .collect()
+}
+
+/// Returns the maximum amount, after checking it is not zero.
+fn checked_max(max: u128) -> u128 {
+    assert!(max > 0, "maximum amount must be greater than zero");
+    max
+}
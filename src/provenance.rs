@@ -0,0 +1,71 @@
+//! # Event provenance
+//!
+//! Used to tag events with the module that emitted them, so identically named events
+//! emitted by different modules (e.g. `transfer` emitted by the bank module and by a custom
+//! module) can be told apart, see [AppBuilder::with_event_provenance](crate::AppBuilder::with_event_provenance).
+
+use cosmwasm_std::{Attribute, Event};
+
+/// Key of the attribute tagging events with the module that emitted them.
+pub(crate) const MODULE_ATTR: &str = "_module";
+
+/// Kind of the module emitting events, see [AppResponse::events_from](crate::AppResponse::events_from).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ModuleKind {
+    /// Bank module.
+    Bank,
+    /// Wasm module, including events emitted by contracts.
+    Wasm,
+    /// Staking module.
+    Staking,
+    /// Distribution module.
+    Distribution,
+    /// Custom module.
+    Custom,
+    /// Governance module.
+    Gov,
+    /// IBC module.
+    Ibc,
+    /// Stargate (`Any`) message handler.
+    Stargate,
+}
+
+impl ModuleKind {
+    /// Returns the value of the `_module` attribute tagging events emitted by this module.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModuleKind::Bank => "bank",
+            ModuleKind::Wasm => "wasm",
+            ModuleKind::Staking => "staking",
+            ModuleKind::Distribution => "distribution",
+            ModuleKind::Custom => "custom",
+            ModuleKind::Gov => "gov",
+            ModuleKind::Ibc => "ibc",
+            ModuleKind::Stargate => "stargate",
+        }
+    }
+}
+
+/// Returns `true` when the event is tagged as emitted by the specified module.
+pub(crate) fn is_emitted_by_module(event: &Event, module: ModuleKind) -> bool {
+    event
+        .attributes
+        .iter()
+        .any(|at| at.key == MODULE_ATTR && at.value == module.as_str())
+}
+
+/// Tags the events not tagged yet as emitted by the specified module.
+///
+/// Events emitted while processing messages dispatched by the module to other modules
+/// were already tagged when those messages were processed by the router.
+pub(crate) fn tag_events(events: &mut [Event], module: ModuleKind) {
+    for event in events {
+        if !event.attributes.iter().any(|at| at.key == MODULE_ATTR) {
+            // `Attribute::new` rejects keys reserved for the chain, like this one
+            event.attributes.push(Attribute {
+                key: MODULE_ATTR.to_string(),
+                value: module.as_str().to_string(),
+            });
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! # Query kinds
+//!
+//! Used to disable answering specific queries at runtime, so the feature detection
+//! of contracts (trying a query and falling back when the chain does not support it)
+//! can be tested, see [App::disable_query](crate::App::disable_query).
+
+use cosmwasm_std::{BankQuery, QueryRequest, WasmQuery};
+
+/// Kind of a query answered by the router, see [App::disable_query](crate::App::disable_query).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QueryKind {
+    /// Query of the bank module.
+    Bank(BankQueryKind),
+    /// Query of the staking module.
+    Staking(StakingQueryKind),
+    /// Query of the distribution module.
+    Distribution(DistributionQueryKind),
+    /// Query of the IBC module.
+    Ibc(IbcQueryKind),
+    /// Query of the wasm module.
+    Wasm(WasmQueryKind),
+}
+
+/// Kind of the `BankQuery`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BankQueryKind {
+    /// `BankQuery::Supply`.
+    Supply,
+    /// `BankQuery::Balance`.
+    Balance,
+    /// `BankQuery::AllBalances`.
+    AllBalances,
+    /// `BankQuery::DenomMetadata`.
+    DenomMetadata,
+    /// `BankQuery::AllDenomMetadata`.
+    AllDenomMetadata,
+}
+
+/// Kind of the `StakingQuery`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StakingQueryKind {
+    /// `StakingQuery::BondedDenom`.
+    BondedDenom,
+    /// `StakingQuery::AllDelegations`.
+    AllDelegations,
+    /// `StakingQuery::Delegation`.
+    Delegation,
+    /// `StakingQuery::AllValidators`.
+    AllValidators,
+    /// `StakingQuery::Validator`.
+    Validator,
+}
+
+/// Kind of the `DistributionQuery`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DistributionQueryKind {
+    /// `DistributionQuery::DelegatorWithdrawAddress`.
+    DelegatorWithdrawAddress,
+    /// `DistributionQuery::DelegationRewards`.
+    DelegationRewards,
+    /// `DistributionQuery::DelegationTotalRewards`.
+    DelegationTotalRewards,
+    /// `DistributionQuery::DelegatorValidators`.
+    DelegatorValidators,
+}
+
+/// Kind of the `IbcQuery`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IbcQueryKind {
+    /// `IbcQuery::PortId`.
+    PortId,
+    /// `IbcQuery::ListChannels`.
+    ListChannels,
+    /// `IbcQuery::Channel`.
+    Channel,
+}
+
+/// Kind of the `WasmQuery`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WasmQueryKind {
+    /// `WasmQuery::Smart`.
+    Smart,
+    /// `WasmQuery::Raw`.
+    Raw,
+    /// `WasmQuery::ContractInfo`.
+    ContractInfo,
+    /// `WasmQuery::CodeInfo`.
+    CodeInfo,
+}
+
+impl QueryKind {
+    /// Returns the kind of the query request, `None` for requests that can not be disabled.
+    pub(crate) fn of<C>(request: &QueryRequest<C>) -> Option<QueryKind> {
+        match request {
+            QueryRequest::Bank(query) => bank_query_kind(query).map(QueryKind::Bank),
+            #[cfg(feature = "staking")]
+            QueryRequest::Staking(query) => staking_query_kind(query).map(QueryKind::Staking),
+            #[cfg(feature = "cosmwasm_1_4")]
+            QueryRequest::Distribution(query) => {
+                distribution_query_kind(query).map(QueryKind::Distribution)
+            }
+            #[cfg(feature = "stargate")]
+            QueryRequest::Ibc(query) => ibc_query_kind(query).map(QueryKind::Ibc),
+            QueryRequest::Wasm(query) => wasm_query_kind(query).map(QueryKind::Wasm),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of the unsupported request reported by `wasmd`
+    /// for queries not supported by the chain.
+    pub(crate) fn unsupported_request_kind(&self) -> &'static str {
+        match self {
+            QueryKind::Bank(_) => "unknown BankQuery variant",
+            QueryKind::Staking(_) => "unknown StakingQuery variant",
+            QueryKind::Distribution(_) => "unknown DistributionQuery variant",
+            QueryKind::Ibc(_) => "unknown IBCQuery variant",
+            QueryKind::Wasm(_) => "unknown WasmQuery variant",
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn bank_query_kind(query: &BankQuery) -> Option<BankQueryKind> {
+    match query {
+        #[cfg(feature = "cosmwasm_1_1")]
+        BankQuery::Supply { .. } => Some(BankQueryKind::Supply),
+        BankQuery::Balance { .. } => Some(BankQueryKind::Balance),
+        BankQuery::AllBalances { .. } => Some(BankQueryKind::AllBalances),
+        #[cfg(feature = "cosmwasm_1_3")]
+        BankQuery::DenomMetadata { .. } => Some(BankQueryKind::DenomMetadata),
+        #[cfg(feature = "cosmwasm_1_3")]
+        BankQuery::AllDenomMetadata { .. } => Some(BankQueryKind::AllDenomMetadata),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "staking")]
+fn staking_query_kind(query: &cosmwasm_std::StakingQuery) -> Option<StakingQueryKind> {
+    use cosmwasm_std::StakingQuery;
+    match query {
+        StakingQuery::BondedDenom {} => Some(StakingQueryKind::BondedDenom),
+        StakingQuery::AllDelegations { .. } => Some(StakingQueryKind::AllDelegations),
+        StakingQuery::Delegation { .. } => Some(StakingQueryKind::Delegation),
+        StakingQuery::AllValidators {} => Some(StakingQueryKind::AllValidators),
+        StakingQuery::Validator { .. } => Some(StakingQueryKind::Validator),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+fn distribution_query_kind(
+    query: &cosmwasm_std::DistributionQuery,
+) -> Option<DistributionQueryKind> {
+    use cosmwasm_std::DistributionQuery;
+    match query {
+        DistributionQuery::DelegatorWithdrawAddress { .. } => {
+            Some(DistributionQueryKind::DelegatorWithdrawAddress)
+        }
+        DistributionQuery::DelegationRewards { .. } => {
+            Some(DistributionQueryKind::DelegationRewards)
+        }
+        DistributionQuery::DelegationTotalRewards { .. } => {
+            Some(DistributionQueryKind::DelegationTotalRewards)
+        }
+        DistributionQuery::DelegatorValidators { .. } => {
+            Some(DistributionQueryKind::DelegatorValidators)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(feature = "stargate")]
+fn ibc_query_kind(query: &cosmwasm_std::IbcQuery) -> Option<IbcQueryKind> {
+    use cosmwasm_std::IbcQuery;
+    match query {
+        IbcQuery::PortId {} => Some(IbcQueryKind::PortId),
+        IbcQuery::ListChannels { .. } => Some(IbcQueryKind::ListChannels),
+        IbcQuery::Channel { .. } => Some(IbcQueryKind::Channel),
+        _ => None,
+    }
+}
+
+fn wasm_query_kind(query: &WasmQuery) -> Option<WasmQueryKind> {
+    match query {
+        WasmQuery::Smart { .. } => Some(WasmQueryKind::Smart),
+        WasmQuery::Raw { .. } => Some(WasmQueryKind::Raw),
+        WasmQuery::ContractInfo { .. } => Some(WasmQueryKind::ContractInfo),
+        #[cfg(feature = "cosmwasm_1_2")]
+        WasmQuery::CodeInfo { .. } => Some(WasmQueryKind::CodeInfo),
+        _ => None,
+    }
+}
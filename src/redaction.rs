@@ -0,0 +1,159 @@
+//! # Redaction of volatile values in responses
+//!
+//! Used by [AppResponse::redacted](crate::AppResponse::redacted) to produce a stable
+//! representation of responses for snapshot testing.
+
+use cosmwasm_std::{Addr, Event};
+use std::collections::BTreeMap;
+
+/// Replacement of volatile values in attributes of response events with stable placeholders.
+///
+/// Redactions are applied to attribute values in the following order:
+/// values of listed attributes ([Attribute](Redaction::Attribute)), exact values
+/// ([Value](Redaction::Value)) and finally addresses ([Addresses](Redaction::Addresses)
+/// and [Bech32Addresses](Redaction::Bech32Addresses)).
+///
+/// All address redactions share a single numbering: every unique address is replaced
+/// with `addr#1`, `addr#2` and so on, in the order of the first appearance of the address
+/// in events (and attributes within events). This way, the redacted output does not change
+/// when the addresses generated for accounts or contracts change, as long as
+/// they appear in the same order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Redaction {
+    /// Replaces the listed addresses with `addr#N` placeholders.
+    Addresses(Vec<Addr>),
+    /// Replaces all valid bech32 (or bech32m) addresses with the specified prefix
+    /// with `addr#N` placeholders.
+    Bech32Addresses(String),
+    /// Replaces values of all attributes with the specified key with `[key]` placeholder,
+    /// e.g. values of `code_id` attributes are replaced with `[code_id]`.
+    Attribute(String),
+    /// Replaces the exact value (like a block height or checksum) with the placeholder.
+    Value {
+        /// Value to be replaced.
+        value: String,
+        /// Placeholder replacing the value.
+        placeholder: String,
+    },
+}
+
+/// Applies redactions to events, keeping the numbering of redacted addresses.
+pub(crate) struct Redactor<'a> {
+    redactions: &'a [Redaction],
+    addresses: BTreeMap<String, usize>,
+}
+
+impl<'a> Redactor<'a> {
+    pub fn new(redactions: &'a [Redaction]) -> Self {
+        Self {
+            redactions,
+            addresses: BTreeMap::new(),
+        }
+    }
+
+    /// Returns events with redacted attribute values.
+    pub fn redact_events(&mut self, events: &[Event]) -> Vec<Event> {
+        events
+            .iter()
+            .map(|event| {
+                let mut event = event.clone();
+                for attribute in event.attributes.iter_mut() {
+                    attribute.value = self.redact_value(&attribute.key, &attribute.value);
+                }
+                event
+            })
+            .collect()
+    }
+
+    fn redact_value(&mut self, key: &str, value: &str) -> String {
+        for redaction in self.redactions {
+            if let Redaction::Attribute(redacted_key) = redaction {
+                if redacted_key == key {
+                    return format!("[{}]", key);
+                }
+            }
+        }
+        for redaction in self.redactions {
+            if let Redaction::Value {
+                value: redacted_value,
+                placeholder,
+            } = redaction
+            {
+                if redacted_value == value {
+                    return placeholder.clone();
+                }
+            }
+        }
+        if self.is_redacted_address(value) {
+            let next = self.addresses.len() + 1;
+            let number = *self.addresses.entry(value.to_string()).or_insert(next);
+            return format!("addr#{}", number);
+        }
+        value.to_string()
+    }
+
+    fn is_redacted_address(&self, value: &str) -> bool {
+        self.redactions.iter().any(|redaction| match redaction {
+            Redaction::Addresses(addresses) => addresses.iter().any(|addr| addr.as_str() == value),
+            Redaction::Bech32Addresses(prefix) => bech32::decode(value)
+                .map(|(hrp, _)| hrp.as_str() == prefix)
+                .unwrap_or(false),
+            _ => false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn addresses_should_be_numbered_in_order_of_appearance() {
+        let redactions = [Redaction::Addresses(vec![
+            Addr::unchecked("b"),
+            Addr::unchecked("a"),
+        ])];
+        let events = [
+            Event::new("first")
+                .add_attribute("x", "a")
+                .add_attribute("y", "c"),
+            Event::new("second")
+                .add_attribute("x", "b")
+                .add_attribute("y", "a"),
+        ];
+        let redacted = Redactor::new(&redactions).redact_events(&events);
+        assert_eq!(
+            vec![
+                Event::new("first")
+                    .add_attribute("x", "addr#1")
+                    .add_attribute("y", "c"),
+                Event::new("second")
+                    .add_attribute("x", "addr#2")
+                    .add_attribute("y", "addr#1"),
+            ],
+            redacted
+        );
+    }
+
+    #[test]
+    fn attribute_redaction_should_take_precedence() {
+        let redactions = [
+            Redaction::Addresses(vec![Addr::unchecked("a")]),
+            Redaction::Value {
+                value: "a".to_string(),
+                placeholder: "<a>".to_string(),
+            },
+            Redaction::Attribute("owner".to_string()),
+        ];
+        let events = [Event::new("event")
+            .add_attribute("owner", "a")
+            .add_attribute("other", "a")];
+        let redacted = Redactor::new(&redactions).redact_events(&events);
+        assert_eq!(
+            vec![Event::new("event")
+                .add_attribute("owner", "[owner]")
+                .add_attribute("other", "<a>")],
+            redacted
+        );
+    }
+}
@@ -0,0 +1,35 @@
+//! # Traces of reply invocations
+
+use cosmwasm_std::{Addr, Binary};
+
+/// Invocation of the `reply` entry-point of a contract, together with the payload
+/// of the submessage.
+///
+/// Traces are recorded by [WasmKeeper](crate::WasmKeeper) only when enabled
+/// with [AppBuilder::with_reply_traces](crate::AppBuilder::with_reply_traces).
+/// Like [CallStats](crate::CallStats), traces of failed replies are recorded too,
+/// because traces are not reverted with the state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplyTrace {
+    /// Address of the contract whose `reply` entry-point was called.
+    pub contract: Addr,
+    /// Identifier of the submessage.
+    pub id: u64,
+    /// Payload of the submessage.
+    pub payload: Binary,
+    /// Payload decoded as JSON, `None` when the payload is empty or is not valid JSON.
+    pub payload_json: Option<serde_json::Value>,
+}
+
+impl ReplyTrace {
+    /// Creates a trace of the reply, decoding the payload as JSON when possible.
+    pub(crate) fn new(contract: Addr, id: u64, payload: Binary) -> Self {
+        let payload_json = serde_json::from_slice(payload.as_slice()).ok();
+        Self {
+            contract,
+            id,
+            payload,
+            payload_json,
+        }
+    }
+}
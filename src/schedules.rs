@@ -0,0 +1,103 @@
+//! # Scheduled contract sudo calls and message executions
+//!
+//! Used by [App::schedule_sudo](crate::App::schedule_sudo) to simulate chain modules
+//! (like cron modules) calling contracts' `sudo` entry-point at specified block heights,
+//! and by [App::schedule_execute](crate::App::schedule_execute) to simulate timelocks
+//! executing messages no earlier than at the specified block time.
+
+use crate::AppResponse;
+use cosmwasm_std::{Addr, Binary, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Schedule of a contract sudo call, see [App::schedule_sudo](crate::App::schedule_sudo).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Schedule {
+    /// Calls the contract at every block height that is a multiple of the specified number.
+    EveryNBlocks(u64),
+    /// Calls the contract once, at the specified block height.
+    AtHeight(u64),
+}
+
+impl Schedule {
+    /// Returns block heights in range `(from, to]`, at which the scheduled call is executed.
+    pub(crate) fn heights(&self, from: u64, to: u64) -> Vec<u64> {
+        match *self {
+            Schedule::EveryNBlocks(n) => {
+                let first = (from / n + 1).saturating_mul(n);
+                (first..=to).step_by(n as usize).collect()
+            }
+            Schedule::AtHeight(height) if from < height && height <= to => vec![height],
+            Schedule::AtHeight(_) => vec![],
+        }
+    }
+}
+
+/// Contract sudo call registered with [App::schedule_sudo](crate::App::schedule_sudo).
+#[derive(Clone)]
+pub(crate) struct ScheduledSudo {
+    /// Identifier of the schedule.
+    pub id: u64,
+    /// Address of the called contract.
+    pub contract_addr: Addr,
+    /// Message passed to contract's `sudo` entry-point.
+    pub msg: Binary,
+    /// Schedule of the call.
+    pub schedule: Schedule,
+}
+
+/// Result of a scheduled contract sudo call,
+/// see [App::last_scheduled_results](crate::App::last_scheduled_results).
+#[derive(Clone, Debug)]
+pub struct ScheduledResult {
+    /// Identifier of the schedule, as returned from [App::schedule_sudo](crate::App::schedule_sudo).
+    pub schedule_id: u64,
+    /// Address of the called contract.
+    pub contract: Addr,
+    /// Block height at which the call was scheduled.
+    pub height: u64,
+    /// Response of the call, or the root cause of the error when the call failed.
+    pub result: Result<AppResponse, String>,
+    /// Flag indicating the call was executed late, in the restart block
+    /// after a chain halt, see [App::simulate_halt](crate::App::simulate_halt).
+    pub late: bool,
+}
+
+/// Message execution registered with [App::schedule_execute](crate::App::schedule_execute).
+#[derive(Clone)]
+pub(crate) struct ScheduledExecution {
+    /// Identifier of the scheduled execution.
+    pub id: u64,
+    /// Address of the sender.
+    pub sender: Addr,
+    /// Executed message serialized to JSON.
+    pub msg: Binary,
+    /// Block time before which the message can not be executed.
+    pub not_before: Timestamp,
+}
+
+/// Result of a due scheduled message execution,
+/// see [App::execute_all_due](crate::App::execute_all_due).
+#[derive(Clone, Debug)]
+pub struct ScheduledExecutionResult {
+    /// Identifier of the scheduled execution, as returned from
+    /// [App::schedule_execute](crate::App::schedule_execute).
+    pub schedule_id: u64,
+    /// Response of the execution, or the root cause of the error when the execution failed.
+    pub result: Result<AppResponse, String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn heights_should_be_in_range() {
+        assert_eq!(vec![3, 6, 9], Schedule::EveryNBlocks(3).heights(0, 10));
+        assert_eq!(vec![6, 9], Schedule::EveryNBlocks(3).heights(3, 9));
+        assert_eq!(Vec::<u64>::new(), Schedule::EveryNBlocks(3).heights(3, 5));
+        assert_eq!(vec![4], Schedule::EveryNBlocks(1).heights(3, 4));
+        assert_eq!(vec![5], Schedule::AtHeight(5).heights(4, 5));
+        assert_eq!(Vec::<u64>::new(), Schedule::AtHeight(5).heights(5, 10));
+        assert_eq!(Vec::<u64>::new(), Schedule::AtHeight(5).heights(10, 4));
+    }
+}
@@ -0,0 +1,180 @@
+//! # Setup order independence
+//!
+//! Used to detect contracts whose final state depends on the order in which the test
+//! setup was performed (e.g. the first registered user becoming the leader), by running
+//! the same scenario in the original and in the reversed setup order and comparing
+//! the final states, see [AppBuilder::with_reversed_setup](crate::AppBuilder::with_reversed_setup).
+
+use crate::featured::staking::{Distribution, Staking};
+use crate::{App, Bank, Gov, Ibc, Module, Stargate, Wasm};
+use cosmwasm_std::{Api, Order, Storage};
+use std::fmt;
+
+/// First storage record that differs between the final states of a scenario
+/// run in the original and in the reversed setup order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetupOrderDivergence {
+    /// Key of the differing record.
+    pub key: Vec<u8>,
+    /// Value of the record in the original setup order, `None` when not present.
+    pub original: Option<Vec<u8>>,
+    /// Value of the record in the reversed setup order, `None` when not present.
+    pub reversed: Option<Vec<u8>>,
+}
+
+impl fmt::Display for SetupOrderDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |value: &Option<Vec<u8>>| match value {
+            Some(value) => String::from_utf8_lossy(value).into_owned(),
+            None => "<none>".to_string(),
+        };
+        write!(
+            f,
+            "state depends on the setup order, record {:?} is {:?} in the original order and {:?} in the reversed order",
+            String::from_utf8_lossy(&self.key),
+            value(&self.original),
+            value(&self.reversed)
+        )
+    }
+}
+
+/// Returns the first record that differs between both storages, in the ascending order of keys.
+pub fn first_storage_divergence(
+    original: &dyn Storage,
+    reversed: &dyn Storage,
+) -> Option<SetupOrderDivergence> {
+    let mut original_records = original.range(None, None, Order::Ascending).peekable();
+    let mut reversed_records = reversed.range(None, None, Order::Ascending).peekable();
+    loop {
+        let divergence = match (original_records.peek(), reversed_records.peek()) {
+            (None, None) => return None,
+            (Some((key, value)), None) => (key.clone(), Some(value.clone()), None),
+            (None, Some((key, value))) => (key.clone(), None, Some(value.clone())),
+            (Some((original_key, original_value)), Some((reversed_key, reversed_value))) => {
+                if original_key < reversed_key {
+                    (original_key.clone(), Some(original_value.clone()), None)
+                } else if original_key > reversed_key {
+                    (reversed_key.clone(), None, Some(reversed_value.clone()))
+                } else if original_value != reversed_value {
+                    (
+                        original_key.clone(),
+                        Some(original_value.clone()),
+                        Some(reversed_value.clone()),
+                    )
+                } else {
+                    original_records.next();
+                    reversed_records.next();
+                    continue;
+                }
+            }
+        };
+        let (key, original, reversed) = divergence;
+        return Some(SetupOrderDivergence {
+            key,
+            original,
+            reversed,
+        });
+    }
+}
+
+/// Runs the scenario in the original and in the reversed setup order
+/// and returns the first difference between the final states, if any.
+///
+/// The scenario gets `true` when the setup order should be reversed, which is typically
+/// passed to [AppBuilder::with_reversed_setup](crate::AppBuilder::with_reversed_setup),
+/// so the setup steps iterated with [App::in_setup_order] are performed in the reversed order.
+/// Reversing the order of instantiating contracts changes their addresses,
+/// so only the steps that should not matter for the final state are expected to be reordered.
+pub fn check_setup_order<
+    BankT,
+    ApiT,
+    StorageT,
+    CustomT,
+    WasmT,
+    StakingT,
+    DistrT,
+    IbcT,
+    GovT,
+    StargateT,
+    F,
+>(
+    scenario: F,
+) -> Option<SetupOrderDivergence>
+where
+    F: Fn(
+        bool,
+    )
+        -> App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+    WasmT: Wasm<CustomT::ExecT, CustomT::QueryT>,
+    BankT: Bank,
+    ApiT: Api,
+    StorageT: Storage,
+    CustomT: Module,
+    StakingT: Staking,
+    DistrT: Distribution,
+    IbcT: Ibc,
+    GovT: Gov,
+    StargateT: Stargate,
+{
+    let original = scenario(false);
+    let reversed = scenario(true);
+    first_storage_divergence(original.storage(), reversed.storage())
+}
+
+/// Runs the scenario in the original and in the reversed setup order,
+/// like [check_setup_order], and panics when the final states differ.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::coins;
+/// use cw_multi_test::{assert_setup_order_independent, no_init, AppBuilder, Executor, IntoAddr};
+///
+/// assert_setup_order_independent(|reversed| {
+///     let treasury = "treasury".into_addr();
+///     let users = ["alice", "bob", "carol"].map(|name| name.into_addr());
+///     let mut app = AppBuilder::default()
+///         .with_reversed_setup(reversed)
+///         .with_genesis_balances(users.iter().map(|user| (user.clone(), coins(10, "uatom"))).collect())
+///         .build(no_init);
+///     for user in app.in_setup_order(users) {
+///         app.send_tokens(user, treasury.clone(), &coins(10, "uatom")).unwrap();
+///     }
+///     app
+/// });
+/// ```
+#[track_caller]
+pub fn assert_setup_order_independent<
+    BankT,
+    ApiT,
+    StorageT,
+    CustomT,
+    WasmT,
+    StakingT,
+    DistrT,
+    IbcT,
+    GovT,
+    StargateT,
+    F,
+>(
+    scenario: F,
+) where
+    F: Fn(
+        bool,
+    )
+        -> App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+    WasmT: Wasm<CustomT::ExecT, CustomT::QueryT>,
+    BankT: Bank,
+    ApiT: Api,
+    StorageT: Storage,
+    CustomT: Module,
+    StakingT: Staking,
+    DistrT: Distribution,
+    IbcT: Ibc,
+    GovT: Gov,
+    StargateT: Stargate,
+{
+    if let Some(divergence) = check_setup_order(scenario) {
+        panic!("{}", divergence);
+    }
+}
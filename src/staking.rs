@@ -1,13 +1,14 @@
 use crate::app::CosmosRouter;
+use crate::bank::save_denom_metadata;
 use crate::error::{anyhow, bail, AnyResult};
 use crate::executor::AppResponse;
 use crate::prefixed_storage::{prefixed, prefixed_read};
-use crate::{BankSudo, Module};
+use crate::{module_address, BankSudo, Module};
 use cosmwasm_std::{
     coin, ensure, ensure_eq, to_json_binary, Addr, AllDelegationsResponse, AllValidatorsResponse,
     Api, BankMsg, Binary, BlockInfo, BondedDenomResponse, Coin, CustomMsg, CustomQuery, Decimal,
-    Delegation, DelegationResponse, DistributionMsg, Empty, Event, FullDelegation, Querier,
-    StakingMsg, StakingQuery, Storage, Timestamp, Uint128, Validator, ValidatorResponse,
+    Delegation, DelegationResponse, DenomMetadata, DistributionMsg, Empty, Event, FullDelegation,
+    Querier, StakingMsg, StakingQuery, Storage, Timestamp, Uint128, Validator, ValidatorResponse,
 };
 use cw_storage_plus::{Deque, Item, Map};
 use schemars::JsonSchema;
@@ -17,6 +18,9 @@ use std::collections::{BTreeSet, VecDeque};
 /// Default denominator of the staking token.
 const BONDED_DENOM: &str = "TOKEN";
 
+/// Name of the staking module account holding all staked tokens, the same as in Cosmos SDK.
+const STAKING_MODULE_NAME: &str = "bonded_tokens_pool";
+
 /// One year expressed in seconds.
 const YEAR: u64 = 60 * 60 * 24 * 365;
 
@@ -42,11 +46,55 @@ impl Default for StakingInfo {
     }
 }
 
+/// Model of the staking rewards accrual, see [StakeKeeper::with_rewards_model].
+///
+/// In both models the validator's commission is deducted from the rewards,
+/// and the rest is split among the validator's delegators proportionally to their stake.
+/// Jailed validators do not accrue rewards.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardsModel {
+    /// Annual percentage rate (interest rate and any additional fees associated with bonding),
+    /// rewards accrue continuously with the elapsed time, see [RewardsClock].
+    Apr(Decimal),
+    /// Fixed emission per block, like in the mint module of many chains.
+    ///
+    /// Every validator with a non-zero stake accrues `amount_per_block` tokens
+    /// for each block elapsed since the last rewards calculation.
+    /// The elapsed blocks are counted from the block height, the [RewardsClock] is not used.
+    PerBlock {
+        /// Amount of tokens accrued by a validator for each block.
+        amount_per_block: Uint128,
+    },
+}
+
+/// Descriptive metadata of a validator, like its moniker.
+///
+/// [Validator] from `cosmwasm_std` carries only the address and commission rates,
+/// so the metadata is registered separately with
+/// [add_validator_with_metadata](StakeKeeper::add_validator_with_metadata)
+/// and read back with [validator_metadata](StakeKeeper::validator_metadata).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct ValidatorMetadata {
+    /// Human-readable name of the validator.
+    pub moniker: String,
+    /// Website of the validator.
+    pub website: String,
+    /// Additional details about the validator.
+    pub details: String,
+    /// Identity signature of the validator, like a Keybase key.
+    pub identity: String,
+}
+
 /// The number of stake and rewards of this validator the staker has. These can be fractional in case of slashing.
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 struct Shares {
     stake: Decimal,
     rewards: Decimal,
+    /// Cumulative reward ratio of the validator when the rewards of this staker were last settled,
+    /// used only when [StakeKeeper::with_sdk_rounding] is enabled.
+    #[serde(default)]
+    starting_ratio: Decimal,
 }
 
 impl Shares {
@@ -57,6 +105,31 @@ impl Shares {
         }
         rewards * self.stake / validator_info.stake
     }
+
+    /// Calculates the rewards accrued by this staker since its rewards were last settled,
+    /// for the given cumulative reward ratio of the validator
+    /// (see [StakeKeeper::with_sdk_rounding]).
+    pub fn rewards_since_settled(&self, cumulative_ratio: Decimal) -> Decimal {
+        // multiplication of decimals truncates the result to 18 decimal places
+        self.stake * (cumulative_ratio - self.starting_ratio)
+    }
+
+    /// Adds the rewards accrued since the last settlement to the staker's rewards
+    /// and starts accruing from the current cumulative reward ratio of the validator.
+    pub fn settle_rewards(&mut self, validator_info: &ValidatorInfo) {
+        self.rewards += self.rewards_since_settled(validator_info.cumulative_ratio);
+        self.starting_ratio = validator_info.cumulative_ratio;
+    }
+}
+
+/// Returns the growth of the cumulative reward ratio (rewards per staked token) of the validator
+/// for the given new rewards, truncated to 18 decimal places
+/// (see [StakeKeeper::with_sdk_rounding]).
+fn reward_ratio(validator_info: &ValidatorInfo, rewards: Decimal) -> Decimal {
+    if validator_info.stake.is_zero() {
+        return Decimal::zero();
+    }
+    rewards / Decimal::from_ratio(validator_info.stake, 1u128)
 }
 
 /// Holds some operational data about a validator.
@@ -69,14 +142,33 @@ struct ValidatorInfo {
     stake: Uint128,
     /// The block time when this validator's rewards were last update. This is needed for rewards calculation.
     last_rewards_calculation: Timestamp,
+    /// The block height when this validator's rewards were last update. This is needed for rewards calculation
+    /// with [RewardsModel::PerBlock].
+    #[serde(default)]
+    last_rewards_height: u64,
+    /// Flag indicating if this validator is jailed. Jailed validators do not accrue rewards.
+    #[serde(default)]
+    jailed: bool,
+    /// Externally funded rewards not yet distributed to the stakers,
+    /// see [`DistributionKeeper::fund_validator_rewards`].
+    #[serde(default)]
+    external_rewards: Decimal,
+    /// Sum of the reward ratios (rewards per staked token) of all rewards calculations,
+    /// used only when [StakeKeeper::with_sdk_rounding] is enabled.
+    #[serde(default)]
+    cumulative_ratio: Decimal,
 }
 
 impl ValidatorInfo {
-    pub fn new(block_time: Timestamp) -> Self {
+    pub fn new(block_time: Timestamp, block_height: u64) -> Self {
         Self {
             stakers: BTreeSet::new(),
             stake: Uint128::zero(),
             last_rewards_calculation: block_time,
+            last_rewards_height: block_height,
+            jailed: false,
+            external_rewards: Decimal::zero(),
+            cumulative_ratio: Decimal::zero(),
         }
     }
 }
@@ -93,6 +185,18 @@ struct Unbonding {
     pub payout_at: Timestamp,
 }
 
+/// Settings of the rewards accrual configured in the [StakeKeeper], kept in the storage,
+/// so they are also applied when the rewards are withdrawn through the [DistributionKeeper].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+struct RewardsSettings {
+    /// Model of the rewards accrual, [RewardsModel::Apr] with [StakingInfo::apr] when not set.
+    #[serde(default)]
+    model: Option<RewardsModel>,
+    /// See [StakeKeeper::with_sdk_rounding].
+    #[serde(default)]
+    sdk_rounding: bool,
+}
+
 const STAKING_INFO: Item<StakingInfo> = Item::new("staking_info");
 /// (staker_addr, validator_addr) -> shares
 const STAKES: Map<(&Addr, &str), Shares> = Map::new("stakes");
@@ -101,21 +205,60 @@ const VALIDATOR_MAP: Map<&str, Validator> = Map::new("validator_map");
 const VALIDATORS: Deque<Validator> = Deque::new("validators");
 /// Contains additional info for each validator
 const VALIDATOR_INFO: Map<&str, ValidatorInfo> = Map::new("validator_info");
+/// Descriptive metadata of validators, registered with [`StakeKeeper::add_validator_with_metadata`]
+const VALIDATOR_METADATA: Map<&str, ValidatorMetadata> = Map::new("validator_metadata");
 /// The queue of unbonding operations. This is needed because unbonding has a waiting time. See [`StakeKeeper`]
 const UNBONDING_QUEUE: Item<VecDeque<Unbonding>> = Item::new("unbonding_queue");
 /// (addr) -> addr. Maps addresses to the address they have delegated
 /// to receive their staking rewards. A missing key => no delegation
 /// has been set.
 const WITHDRAW_ADDRESS: Map<&Addr, Addr> = Map::new("withdraw_address");
+/// Current time of the manual rewards clock, see [`RewardsClock::Manual`].
+/// When not present, the rewards accrue with the block time.
+const REWARDS_CLOCK: Item<Timestamp> = Item::new("rewards_clock");
+/// Settings of the rewards accrual, see [`StakeKeeper::with_rewards_model`].
+/// When not present, the default settings are used.
+const REWARDS_SETTINGS: Item<RewardsSettings> = Item::new("rewards_settings");
 
 pub const NAMESPACE_STAKING: &[u8] = b"staking";
 // https://github.com/cosmos/cosmos-sdk/blob/4f6f6c00021f4b5ee486bbb71ae2071a8ceb47c9/x/distribution/types/keys.go#L16
 pub const NAMESPACE_DISTRIBUTION: &[u8] = b"distribution";
 
+/// Saves the metadata of the staking token, registering it in the bank module.
+///
+/// The metadata is written directly to the storage of the bank module,
+/// because the staking module has no access to the router during setup.
+pub(crate) fn save_staking_denom_metadata(
+    storage: &mut dyn Storage,
+    bonded_denom: &str,
+    metadata: &DenomMetadata,
+) -> AnyResult<()> {
+    ensure_eq!(
+        metadata.base,
+        bonded_denom,
+        anyhow!(
+            "denom metadata of {} does not match staking denominator {}",
+            metadata.base,
+            bonded_denom
+        )
+    );
+    save_denom_metadata(storage, metadata)
+}
+
+/// Saves general staking parameters in the staking module's storage.
+pub(crate) fn save_staking_info(
+    storage: &mut dyn Storage,
+    staking_info: &StakingInfo,
+) -> AnyResult<()> {
+    let mut storage = prefixed(storage, NAMESPACE_STAKING);
+    STAKING_INFO.save(&mut storage, staking_info)?;
+    Ok(())
+}
+
 /// Staking privileged action definition.
 ///
 /// We need to expand on this, but we will need this to properly test out staking
-#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum StakingSudo {
     /// Slashes the given percentage of the validator's stake.
     /// For now, you cannot slash retrospectively in tests.
@@ -125,6 +268,35 @@ pub enum StakingSudo {
         /// Percentage of the validator's stake.
         percentage: Decimal,
     },
+    /// Jails the validator. Jailed validator does not accrue rewards
+    /// and does not accept new delegations.
+    Jail {
+        /// Validator's address.
+        validator: String,
+    },
+    /// Unjails the previously jailed validator.
+    Unjail {
+        /// Validator's address.
+        validator: String,
+    },
+    /// Advances the manual rewards clock by the given number of seconds.
+    /// Fails when the stake keeper does not use the [`RewardsClock::Manual`].
+    AdvanceRewardsClock {
+        /// Number of seconds to advance the rewards clock.
+        seconds: u64,
+    },
+}
+
+/// Source of the time used for staking rewards accrual.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RewardsClock {
+    /// Rewards accrue with the block time (default).
+    #[default]
+    BlockTime,
+    /// Rewards accrue only when the clock is advanced
+    /// with [`StakingSudo::AdvanceRewardsClock`], regardless of the block time.
+    /// The clock starts at the block time of the first staking operation.
+    Manual,
 }
 
 /// A trait defining a behavior of the stake keeper.
@@ -144,23 +316,41 @@ pub trait Staking: Module<ExecT = StakingMsg, QueryT = StakingQuery, SudoT = Sta
     ) -> AnyResult<AppResponse> {
         Ok(AppResponse::default())
     }
+
+    /// Returns the address of the module account holding all staked tokens,
+    /// listed in the [AddressBook](crate::AddressBook).
+    ///
+    /// The default implementation returns `None`.
+    fn module_account(&self, _api: &dyn Api) -> Option<Addr> {
+        None
+    }
 }
 
 /// A trait defining a behavior of the distribution keeper.
 pub trait Distribution: Module<ExecT = DistributionMsg, QueryT = Empty, SudoT = Empty> {}
 
 /// A structure representing a default stake keeper.
+#[derive(Clone)]
 pub struct StakeKeeper {
-    /// Module address of a default stake keeper.
-    module_addr: Addr,
+    /// Module address of a default stake keeper,
+    /// derived from the module name when not set explicitly.
+    module_addr: Option<Addr>,
+    /// Source of the time used for rewards accrual.
+    rewards_clock: RewardsClock,
+    /// Settings of the rewards accrual.
+    rewards_settings: RewardsSettings,
+    /// Metadata of the staking token, registered in the bank module during setup.
+    denom_metadata: Option<DenomMetadata>,
 }
 
 impl Default for StakeKeeper {
     /// Creates a new stake keeper with default settings.
     fn default() -> Self {
         StakeKeeper {
-            // The address of the staking module. This holds all staked tokens.
-            module_addr: Addr::unchecked("staking_module"),
+            module_addr: None,
+            rewards_clock: RewardsClock::default(),
+            rewards_settings: RewardsSettings::default(),
+            denom_metadata: None,
         }
     }
 }
@@ -171,11 +361,87 @@ impl StakeKeeper {
         Self::default()
     }
 
-    /// Provides some general parameters to the stake keeper
+    /// Sets the address of the staking module account, holding all staked tokens.
+    ///
+    /// By default, the address is derived from the module name
+    /// using [module_address](crate::module_address).
+    pub fn with_module_address(mut self, module_addr: Addr) -> Self {
+        self.module_addr = Some(module_addr);
+        self
+    }
+
+    /// Returns the address of the staking module account, holding all staked tokens.
+    ///
+    /// Unless set with [with_module_address](Self::with_module_address), the address
+    /// is derived from the module name, so it is a valid address with the chain prefix.
+    pub fn module_address(&self, api: &dyn Api) -> AnyResult<Addr> {
+        match &self.module_addr {
+            Some(module_addr) => Ok(module_addr.clone()),
+            None => module_address(api, STAKING_MODULE_NAME),
+        }
+    }
+
+    /// Sets the source of the time used for rewards accrual.
+    ///
+    /// Unbonding is always processed using the block time.
+    pub fn with_rewards_clock(mut self, rewards_clock: RewardsClock) -> Self {
+        self.rewards_clock = rewards_clock;
+        self
+    }
+
+    /// Sets the model of the staking rewards accrual.
+    ///
+    /// By default, the rewards accrue with the annual percentage rate
+    /// set in [StakingInfo::apr], like [RewardsModel::Apr].
+    pub fn with_rewards_model(mut self, rewards_model: RewardsModel) -> Self {
+        self.rewards_settings.model = Some(rewards_model);
+        self
+    }
+
+    /// Enables the compatibility mode truncating the rewards like the F1 fee distribution
+    /// of the Cosmos SDK's distribution module.
+    ///
+    /// When disabled (default), every delegator accrues its share of the rewards
+    /// with the full [Decimal] precision, and the accrued amount is truncated
+    /// only when the rewards are queried or withdrawn.
+    ///
+    /// When enabled, the rewards are accounted with the cumulative reward ratio
+    /// (rewards per staked token) of each validator, like in the SDK:
+    /// - the rewards accrued by the validator since the last calculation are divided
+    ///   by the validator's stake and the ratio is truncated to 18 decimal places,
+    /// - the rewards of a delegator are calculated as its stake multiplied by the growth
+    ///   of the ratio since its stake last changed, truncated to 18 decimal places,
+    /// - the withdrawn rewards are truncated to whole tokens, the remainder is dropped.
+    ///
+    /// As a result, the delegators may receive a few tokens less than in the default mode,
+    /// e.g. a single delegator staking 3 tokens with a validator accruing 10 tokens
+    /// receives 9 tokens, because `3 * (10 / 3)` is truncated to `9.999999999999999999`.
+    pub fn with_sdk_rounding(mut self, sdk_rounding: bool) -> Self {
+        self.rewards_settings.sdk_rounding = sdk_rounding;
+        self
+    }
+
+    /// Sets the metadata of the staking token (symbol, display unit and its exponent).
+    ///
+    /// The metadata is registered in the bank module during [setup](Self::setup),
+    /// so it can be queried by contracts. The `base` denomination of the metadata
+    /// must be the same as [StakingInfo::bonded_denom].
+    pub fn with_denom_metadata(mut self, metadata: DenomMetadata) -> Self {
+        self.denom_metadata = Some(metadata);
+        self
+    }
+
+    /// Provides some general parameters to the stake keeper.
+    ///
+    /// When the metadata of the staking token is set with
+    /// [with_denom_metadata](Self::with_denom_metadata), it is registered
+    /// in the bank module, so it can be queried by contracts.
     pub fn setup(&self, storage: &mut dyn Storage, staking_info: StakingInfo) -> AnyResult<()> {
-        let mut storage = prefixed(storage, NAMESPACE_STAKING);
-        STAKING_INFO.save(&mut storage, &staking_info)?;
-        Ok(())
+        if let Some(metadata) = &self.denom_metadata {
+            save_staking_denom_metadata(storage, &staking_info.bonded_denom, metadata)?;
+        }
+        save_staking_info(storage, &staking_info)?;
+        self.save_rewards_settings(&mut prefixed(storage, NAMESPACE_STAKING))
     }
 
     /// Add a new validator available for staking.
@@ -187,6 +453,8 @@ impl StakeKeeper {
         validator: Validator,
     ) -> AnyResult<()> {
         let mut storage = prefixed(storage, NAMESPACE_STAKING);
+        self.init_rewards_clock(&mut storage, block)?;
+        self.save_rewards_settings(&mut storage)?;
         if VALIDATOR_MAP
             .may_load(&storage, &validator.address)?
             .is_some()
@@ -198,14 +466,98 @@ impl StakeKeeper {
         }
         VALIDATOR_MAP.save(&mut storage, &validator.address, &validator)?;
         VALIDATORS.push_back(&mut storage, &validator)?;
+        let rewards_time = Self::rewards_time(&storage, block)?;
         VALIDATOR_INFO.save(
             &mut storage,
             &validator.address,
-            &ValidatorInfo::new(block.time),
+            &ValidatorInfo::new(rewards_time, block.height),
         )?;
         Ok(())
     }
 
+    /// Add a new validator available for staking, together with its descriptive metadata.
+    pub fn add_validator_with_metadata(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        validator: Validator,
+        metadata: ValidatorMetadata,
+    ) -> AnyResult<()> {
+        let validator_addr = validator.address.clone();
+        self.add_validator(api, storage, block, validator)?;
+        let mut storage = prefixed(storage, NAMESPACE_STAKING);
+        VALIDATOR_METADATA.save(&mut storage, &validator_addr, &metadata)?;
+        Ok(())
+    }
+
+    /// Returns the metadata of the validator with the given address.
+    ///
+    /// Returns `None` when the validator does not exist or was added
+    /// without metadata using [add_validator](Self::add_validator).
+    pub fn validator_metadata(
+        &self,
+        storage: &dyn Storage,
+        validator: &str,
+    ) -> AnyResult<Option<ValidatorMetadata>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Ok(VALIDATOR_METADATA.may_load(&staking_storage, validator)?)
+    }
+
+    /// Starts the manual rewards clock at the current block time, when not started yet.
+    fn init_rewards_clock(
+        &self,
+        staking_storage: &mut dyn Storage,
+        block: &BlockInfo,
+    ) -> AnyResult<()> {
+        if self.rewards_clock == RewardsClock::Manual
+            && REWARDS_CLOCK.may_load(staking_storage)?.is_none()
+        {
+            REWARDS_CLOCK.save(staking_storage, &block.time)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the rewards settings of this keeper, unless they are already saved.
+    ///
+    /// The default settings are not saved when no settings were saved before.
+    fn save_rewards_settings(&self, staking_storage: &mut dyn Storage) -> AnyResult<()> {
+        if REWARDS_SETTINGS
+            .may_load(staking_storage)?
+            .unwrap_or_default()
+            != self.rewards_settings
+        {
+            REWARDS_SETTINGS.save(staking_storage, &self.rewards_settings)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the model of the rewards accrual.
+    fn rewards_model(
+        staking_storage: &dyn Storage,
+        staking_info: &StakingInfo,
+    ) -> AnyResult<RewardsModel> {
+        Ok(REWARDS_SETTINGS
+            .may_load(staking_storage)?
+            .and_then(|settings| settings.model)
+            .unwrap_or(RewardsModel::Apr(staking_info.apr)))
+    }
+
+    /// Returns `true` when the rewards are truncated like in the Cosmos SDK,
+    /// see [with_sdk_rounding](Self::with_sdk_rounding).
+    fn sdk_rounding(staking_storage: &dyn Storage) -> AnyResult<bool> {
+        Ok(REWARDS_SETTINGS
+            .may_load(staking_storage)?
+            .is_some_and(|settings| settings.sdk_rounding))
+    }
+
+    /// Returns the time used for rewards accrual.
+    fn rewards_time(staking_storage: &dyn Storage, block: &BlockInfo) -> AnyResult<Timestamp> {
+        Ok(REWARDS_CLOCK
+            .may_load(staking_storage)?
+            .unwrap_or(block.time))
+    }
+
     fn get_staking_info(staking_storage: &dyn Storage) -> AnyResult<StakingInfo> {
         Ok(STAKING_INFO.may_load(staking_storage)?.unwrap_or_default())
     }
@@ -249,19 +601,33 @@ impl StakeKeeper {
         validator_info: &ValidatorInfo,
     ) -> AnyResult<Coin> {
         let staking_info = Self::get_staking_info(staking_storage)?;
+        let rewards_time = Self::rewards_time(staking_storage, block)?;
 
         // calculate missing rewards without updating the validator to reduce rounding errors
-        let new_validator_rewards = Self::calculate_rewards(
-            block.time,
-            validator_info.last_rewards_calculation,
-            staking_info.apr,
-            validator.commission,
-            validator_info.stake,
-        );
+        let mut new_validator_rewards = if validator_info.jailed {
+            Decimal::zero()
+        } else {
+            Self::calculate_rewards(
+                &Self::rewards_model(staking_storage, &staking_info)?,
+                rewards_time,
+                block.height,
+                validator_info,
+                validator.commission,
+            )
+        };
+        // externally funded rewards are distributed only when there is any stake
+        if !validator_info.stake.is_zero() {
+            new_validator_rewards += validator_info.external_rewards;
+        }
 
         // calculate the delegator's share of those
-        let delegator_rewards =
-            shares.rewards + shares.share_of_rewards(validator_info, new_validator_rewards);
+        let delegator_rewards = if Self::sdk_rounding(staking_storage)? {
+            let cumulative_ratio = validator_info.cumulative_ratio
+                + reward_ratio(validator_info, new_validator_rewards);
+            shares.rewards + shares.rewards_since_settled(cumulative_ratio)
+        } else {
+            shares.rewards + shares.share_of_rewards(validator_info, new_validator_rewards)
+        };
 
         Ok(Coin {
             denom: staking_info.bonded_denom,
@@ -271,20 +637,34 @@ impl StakeKeeper {
 
     /// Calculates the rewards that are due since the last calculation.
     fn calculate_rewards(
+        rewards_model: &RewardsModel,
         current_time: Timestamp,
-        since: Timestamp,
-        interest_rate: Decimal,
+        current_height: u64,
+        validator_info: &ValidatorInfo,
         validator_commission: Decimal,
-        stake: Uint128,
     ) -> Decimal {
-        // calculate time since last update (in seconds)
-        let time_diff = current_time.minus_seconds(since.seconds()).seconds();
-
+        let stake = validator_info.stake;
         // using decimal here to reduce rounding error when calling this function a lot
-        let reward = Decimal::from_ratio(stake, 1u128)
-            * interest_rate
-            * Decimal::from_ratio(time_diff, 1u128)
-            / Decimal::from_ratio(YEAR, 1u128);
+        let reward = match rewards_model {
+            RewardsModel::Apr(interest_rate) => {
+                // calculate time since last update (in seconds)
+                let time_diff = current_time
+                    .seconds()
+                    .saturating_sub(validator_info.last_rewards_calculation.seconds());
+                Decimal::from_ratio(stake, 1u128)
+                    * interest_rate
+                    * Decimal::from_ratio(time_diff, 1u128)
+                    / Decimal::from_ratio(YEAR, 1u128)
+            }
+            RewardsModel::PerBlock { amount_per_block } => {
+                if stake.is_zero() {
+                    return Decimal::zero();
+                }
+                // calculate blocks since last update
+                let blocks = current_height.saturating_sub(validator_info.last_rewards_height);
+                Decimal::from_ratio(*amount_per_block, 1u128) * Decimal::from_ratio(blocks, 1u128)
+            }
+        };
         let commission = reward * validator_commission;
 
         reward - commission
@@ -307,21 +687,42 @@ impl StakeKeeper {
             .ok_or_else(|| anyhow!("validator does not exist"))?;
 
         let validator_obj = VALIDATOR_MAP.load(staking_storage, validator)?;
+        let rewards_time = Self::rewards_time(staking_storage, block)?;
 
-        if validator_info.last_rewards_calculation >= block.time {
+        if validator_info.last_rewards_calculation >= rewards_time
+            && validator_info.last_rewards_height >= block.height
+            && validator_info.external_rewards.is_zero()
+        {
             return Ok(());
         }
 
-        let new_rewards = Self::calculate_rewards(
-            block.time,
-            validator_info.last_rewards_calculation,
-            staking_info.apr,
-            validator_obj.commission,
-            validator_info.stake,
-        );
+        // jailed validators do not accrue rewards
+        let mut new_rewards = if validator_info.jailed {
+            Decimal::zero()
+        } else {
+            Self::calculate_rewards(
+                &Self::rewards_model(staking_storage, &staking_info)?,
+                rewards_time,
+                block.height,
+                &validator_info,
+                validator_obj.commission,
+            )
+        };
+        // externally funded rewards are distributed only when there is any stake
+        if !validator_info.stake.is_zero() {
+            new_rewards += std::mem::take(&mut validator_info.external_rewards);
+        }
 
         // update validator info
-        validator_info.last_rewards_calculation = block.time;
+        validator_info.last_rewards_calculation =
+            validator_info.last_rewards_calculation.max(rewards_time);
+        validator_info.last_rewards_height = validator_info.last_rewards_height.max(block.height);
+        if Self::sdk_rounding(staking_storage)? {
+            // delegators are settled only when their stake changes or rewards are withdrawn
+            validator_info.cumulative_ratio += reward_ratio(&validator_info, new_rewards);
+            VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
+            return Ok(());
+        }
         VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
 
         // update delegators
@@ -343,6 +744,24 @@ impl StakeKeeper {
         Ok(())
     }
 
+    /// Adds externally funded rewards to the validator's rewards pool
+    /// and distributes them among the validator's stakers.
+    fn fund_rewards(
+        api: &dyn Api,
+        staking_storage: &mut dyn Storage,
+        block: &BlockInfo,
+        validator: &str,
+        amount: Uint128,
+    ) -> AnyResult<()> {
+        // settle the rewards accrued so far
+        Self::update_rewards(api, staking_storage, block, validator)?;
+        let mut validator_info = VALIDATOR_INFO.load(staking_storage, validator)?;
+        validator_info.external_rewards += Decimal::from_ratio(amount, 1u128);
+        VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
+        // distribute the funded rewards
+        Self::update_rewards(api, staking_storage, block, validator)
+    }
+
     /// Returns the single validator with the given address (or `None` if there is no such validator).
     fn get_validator(
         &self,
@@ -385,6 +804,9 @@ impl StakeKeeper {
         amount: Coin,
     ) -> AnyResult<()> {
         self.validate_denom(staking_storage, &amount)?;
+        if Self::is_jailed_internal(staking_storage, validator)? {
+            bail!("validator for this address is currently jailed");
+        }
         self.update_stake(
             api,
             staking_storage,
@@ -435,7 +857,10 @@ impl StakeKeeper {
         // now, we can update the stake of the delegator and validator
         let mut validator_info = VALIDATOR_INFO
             .may_load(staking_storage, validator)?
-            .unwrap_or_else(|| ValidatorInfo::new(block.time));
+            .unwrap_or(ValidatorInfo::new(
+                Self::rewards_time(staking_storage, block)?,
+                block.height,
+            ));
         let shares = STAKES.may_load(staking_storage, (delegator, validator))?;
         let mut shares = if sub {
             // see https://github.com/cosmos/cosmos-sdk/blob/3c5387048f75d7e78b40c5b8d2421fdb8f5d973a/x/staking/keeper/delegation.go#L1005-L1007
@@ -444,6 +869,9 @@ impl StakeKeeper {
         } else {
             shares.unwrap_or_default()
         };
+        if Self::sdk_rounding(staking_storage)? {
+            shares.settle_rewards(&validator_info);
+        }
 
         let amount_dec = Decimal::from_ratio(amount, 1u128);
         if sub {
@@ -489,6 +917,21 @@ impl StakeKeeper {
             .may_load(staking_storage, validator)?
             .unwrap();
 
+        // settle the rewards of all stakers before their stake is reduced
+        if Self::sdk_rounding(staking_storage)? {
+            for delegator in validator_info.stakers.iter() {
+                STAKES.update(
+                    staking_storage,
+                    (delegator, validator),
+                    |stake| -> AnyResult<_> {
+                        let mut stake = stake.expect("all stakers in validator_info should exist");
+                        stake.settle_rewards(&validator_info);
+                        Ok(stake)
+                    },
+                )?;
+            }
+        }
+
         let remaining_percentage = Decimal::one() - percentage;
         validator_info.stake = validator_info.stake.mul_floor(remaining_percentage);
 
@@ -531,6 +974,41 @@ impl StakeKeeper {
         Ok(())
     }
 
+    /// Returns `true` when the validator with the given address is jailed.
+    pub fn is_jailed(&self, storage: &dyn Storage, validator: &str) -> AnyResult<bool> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Self::is_jailed_internal(&staking_storage, validator)
+    }
+
+    fn is_jailed_internal(staking_storage: &dyn Storage, validator: &str) -> AnyResult<bool> {
+        Ok(VALIDATOR_INFO
+            .may_load(staking_storage, validator)?
+            .is_some_and(|validator_info| validator_info.jailed))
+    }
+
+    /// Jails or unjails the validator, rewards accrued until now are preserved.
+    fn set_jailed(
+        &self,
+        api: &dyn Api,
+        staking_storage: &mut dyn Storage,
+        block: &BlockInfo,
+        validator: &str,
+        jailed: bool,
+    ) -> AnyResult<()> {
+        // calculate rewards before changing the jailed status
+        Self::update_rewards(api, staking_storage, block, validator)?;
+        let mut validator_info = VALIDATOR_INFO.load(staking_storage, validator)?;
+        if validator_info.jailed == jailed {
+            if jailed {
+                bail!("validator already jailed");
+            }
+            bail!("validator not jailed; cannot be unjailed");
+        }
+        validator_info.jailed = jailed;
+        VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
+        Ok(())
+    }
+
     // Asserts that the given coin has the proper denominator
     fn validate_denom(&self, staking_storage: &dyn Storage, amount: &Coin) -> AnyResult<()> {
         let staking_info = Self::get_staking_info(staking_storage)?;
@@ -602,7 +1080,7 @@ impl StakeKeeper {
                             api,
                             storage,
                             block,
-                            self.module_addr.clone(),
+                            self.module_address(api)?,
                             BankMsg::Send {
                                 to_address: delegator.into_string(),
                                 amount: vec![coin(amount.u128(), &staking_info.bonded_denom)],
@@ -630,6 +1108,10 @@ impl Staking for StakeKeeper {
     ) -> AnyResult<AppResponse> {
         self.process_queue(api, storage, router, block)
     }
+
+    fn module_account(&self, api: &dyn Api) -> Option<Addr> {
+        self.module_address(api).ok()
+    }
 }
 
 impl Module for StakeKeeper {
@@ -647,6 +1129,8 @@ impl Module for StakeKeeper {
         msg: StakingMsg,
     ) -> AnyResult<AppResponse> {
         let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        self.init_rewards_clock(&mut staking_storage, block)?;
+        self.save_rewards_settings(&mut staking_storage)?;
         match msg {
             StakingMsg::Delegate { validator, amount } => {
                 // see https://github.com/cosmos/cosmos-sdk/blob/3c5387048f75d7e78b40c5b8d2421fdb8f5d973a/x/staking/types/msg.go#L202-L207
@@ -674,7 +1158,7 @@ impl Module for StakeKeeper {
                     block,
                     sender,
                     BankMsg::Send {
-                        to_address: self.module_addr.to_string(),
+                        to_address: self.module_address(api)?.to_string(),
                         amount: vec![amount],
                     }
                     .into(),
@@ -707,12 +1191,18 @@ impl Module for StakeKeeper {
                 let mut unbonding_queue = UNBONDING_QUEUE
                     .may_load(&staking_storage)?
                     .unwrap_or_default();
-                unbonding_queue.push_back(Unbonding {
-                    delegator: sender.clone(),
-                    validator,
-                    amount: amount.amount,
-                    payout_at: block.time.plus_seconds(staking_info.unbonding_time),
-                });
+                // keep the queue sorted by payout time, the unbonding time may have changed
+                let payout_at = block.time.plus_seconds(staking_info.unbonding_time);
+                let index = unbonding_queue.partition_point(|u| u.payout_at <= payout_at);
+                unbonding_queue.insert(
+                    index,
+                    Unbonding {
+                        delegator: sender.clone(),
+                        validator,
+                        amount: amount.amount,
+                        payout_at,
+                    },
+                );
                 UNBONDING_QUEUE.save(&mut staking_storage, &unbonding_queue)?;
                 Ok(AppResponse { events, data: None })
             }
@@ -833,9 +1323,16 @@ impl Module for StakeKeeper {
                 let res = to_json_binary(&full_delegation_response)?;
                 Ok(res)
             }
-            StakingQuery::AllValidators {} => Ok(to_json_binary(&AllValidatorsResponse::new(
-                self.get_validators(&staking_storage)?,
-            ))?),
+            StakingQuery::AllValidators {} => {
+                // like in wasmd, only bonded (not jailed) validators are returned
+                let mut validators = vec![];
+                for validator in self.get_validators(&staking_storage)? {
+                    if !Self::is_jailed_internal(&staking_storage, &validator.address)? {
+                        validators.push(validator);
+                    }
+                }
+                Ok(to_json_binary(&AllValidatorsResponse::new(validators))?)
+            }
             StakingQuery::Validator { address } => Ok(to_json_binary(&ValidatorResponse::new(
                 self.get_validator(&staking_storage, &address)?,
             ))?),
@@ -858,9 +1355,35 @@ impl Module for StakeKeeper {
             } => {
                 let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
                 self.validate_percentage(percentage)?;
+                self.save_rewards_settings(&mut staking_storage)?;
                 self.slash(api, &mut staking_storage, block, &validator, percentage)?;
                 Ok(AppResponse::default())
             }
+            StakingSudo::Jail { validator } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                self.init_rewards_clock(&mut staking_storage, block)?;
+                self.save_rewards_settings(&mut staking_storage)?;
+                self.set_jailed(api, &mut staking_storage, block, &validator, true)?;
+                Ok(AppResponse::default())
+            }
+            StakingSudo::Unjail { validator } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                self.init_rewards_clock(&mut staking_storage, block)?;
+                self.save_rewards_settings(&mut staking_storage)?;
+                self.set_jailed(api, &mut staking_storage, block, &validator, false)?;
+                Ok(AppResponse::default())
+            }
+            StakingSudo::AdvanceRewardsClock { seconds } => {
+                if self.rewards_clock != RewardsClock::Manual {
+                    bail!("rewards clock is not manual");
+                }
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                self.init_rewards_clock(&mut staking_storage, block)?;
+                REWARDS_CLOCK.update(&mut staking_storage, |time| -> AnyResult<_> {
+                    Ok(time.plus_seconds(seconds))
+                })?;
+                Ok(AppResponse::default())
+            }
         }
     }
 }
@@ -870,7 +1393,7 @@ impl Module for StakeKeeper {
 /// This module likely manages the distribution of rewards and fees within the blockchain network.
 /// It could handle tasks like distributing block rewards to validators and delegators,
 /// and managing community funding mechanisms.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct DistributionKeeper {}
 
 impl DistributionKeeper {
@@ -894,6 +1417,10 @@ impl DistributionKeeper {
 
         // load updated rewards for delegator
         let mut shares = STAKES.load(&staking_storage, (delegator, validator))?;
+        if StakeKeeper::sdk_rounding(&staking_storage)? {
+            let validator_info = VALIDATOR_INFO.load(&staking_storage, validator)?;
+            shares.settle_rewards(&validator_info);
+        }
         let rewards = Uint128::new(1).mul_floor(shares.rewards); // convert to Uint128
 
         // remove rewards from delegator
@@ -903,6 +1430,87 @@ impl DistributionKeeper {
         Ok(rewards)
     }
 
+    /// Returns the outstanding rewards of the given validator: rewards accrued by all its
+    /// delegators up to the last rewards calculation and not withdrawn yet,
+    /// together with the externally funded rewards not distributed yet.
+    pub fn outstanding_rewards(
+        &self,
+        storage: &dyn Storage,
+        validator: &str,
+    ) -> AnyResult<Vec<Coin>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let staking_info = StakeKeeper::get_staking_info(&staking_storage)?;
+        let validator_info = VALIDATOR_INFO
+            .may_load(&staking_storage, validator)?
+            .ok_or_else(|| anyhow!("validator {} not found", validator))?;
+        let mut rewards = validator_info.external_rewards;
+        for staker in &validator_info.stakers {
+            let shares = STAKES.load(&staking_storage, (staker, validator))?;
+            rewards += shares.rewards;
+            if StakeKeeper::sdk_rounding(&staking_storage)? {
+                rewards += shares.rewards_since_settled(validator_info.cumulative_ratio);
+            }
+        }
+        let amount = Uint128::new(1).mul_floor(rewards); // convert to Uint128
+        Ok(if amount.is_zero() {
+            vec![]
+        } else {
+            vec![Coin::new(amount, staking_info.bonded_denom)]
+        })
+    }
+
+    /// Funds the rewards pool of the validator, like `MsgFundValidatorRewardsPool` does.
+    ///
+    /// The amount is taken from the funder and distributed among the validator's delegators
+    /// proportionally to their stake, in addition to the regularly accrued rewards.
+    /// When the validator has no stake, the amount waits for the first delegation.
+    /// Only the staking token is accepted.
+    pub fn fund_validator_rewards<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        funder: &Addr,
+        validator: &str,
+        amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let staking_info = StakeKeeper::get_staking_info(&staking_storage)?;
+        let mut total = Uint128::zero();
+        for coin in &amount {
+            ensure_eq!(
+                coin.denom,
+                staking_info.bonded_denom,
+                anyhow!(
+                    "cannot fund rewards with coins of denominator {}, only of {}",
+                    coin.denom,
+                    staking_info.bonded_denom
+                )
+            );
+            total += coin.amount;
+        }
+        // take the funds from the funder, rewards are minted when withdrawn
+        let burn_response = router.execute(
+            api,
+            storage,
+            block,
+            funder.clone(),
+            BankMsg::Burn { amount }.into(),
+        )?;
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        StakeKeeper::fund_rewards(api, &mut staking_storage, block, validator, total)?;
+
+        let mut events = burn_response.events;
+        events.push(
+            Event::new("fund_validator_rewards_pool")
+                .add_attribute("validator", validator)
+                .add_attribute("depositor", funder)
+                .add_attribute("amount", format!("{}{}", total, staking_info.bonded_denom)),
+        );
+        Ok(AppResponse { events, data: None })
+    }
+
     /// Returns the withdrawal address for specified delegator.
     pub fn get_withdraw_address(storage: &dyn Storage, delegator: &Addr) -> AnyResult<Addr> {
         Ok(match WITHDRAW_ADDRESS.may_load(storage, delegator)? {
@@ -956,7 +1564,7 @@ impl Module for DistributionKeeper {
                 let staking_info = StakeKeeper::get_staking_info(&staking_storage)?;
                 let receiver = Self::get_withdraw_address(&distribution_storage, &sender)?;
                 // directly mint rewards to delegator
-                router.sudo(
+                let mint_response = router.sudo(
                     api,
                     storage,
                     block,
@@ -970,13 +1578,16 @@ impl Module for DistributionKeeper {
                     .into(),
                 )?;
 
-                let events = vec![Event::new("withdraw_delegator_reward")
-                    .add_attribute("validator", &validator)
-                    .add_attribute("sender", &sender)
-                    .add_attribute(
-                        "amount",
-                        format!("{}{}", rewards, staking_info.bonded_denom),
-                    )];
+                let mut events = mint_response.events;
+                events.push(
+                    Event::new("withdraw_delegator_reward")
+                        .add_attribute("validator", &validator)
+                        .add_attribute("sender", &sender)
+                        .add_attribute(
+                            "amount",
+                            format!("{}{}", rewards, staking_info.bonded_denom),
+                        ),
+                );
                 Ok(AppResponse { events, data: None })
             }
             DistributionMsg::SetWithdrawAddress { address } => {
@@ -1032,6 +1643,10 @@ mod test {
     };
     use serde::de::DeserializeOwned;
 
+    /// Denominator of the staking token used in tests, different from the default one,
+    /// so all tested operations must use the denominator stored in [StakingInfo].
+    const BONDED_DENOM: &str = "ustake";
+
     /// Utility structure for combining validator properties,
     /// used mainly for validator initialization.
     struct ValidatorProperties {
@@ -1103,6 +1718,9 @@ mod test {
                 ibc: IbcFailingModule::new(),
                 gov: GovFailingModule::new(),
                 stargate: StargateFailing,
+                sudo_handlers: Default::default(),
+                event_provenance: false,
+                disabled_queries: Default::default(),
             };
             let mut storage = MockStorage::new();
             let block = mock_env().block;
@@ -1114,7 +1732,13 @@ mod test {
             // configure basic staking parameters
             router
                 .staking
-                .setup(&mut storage, StakingInfo::default())
+                .setup(
+                    &mut storage,
+                    StakingInfo {
+                        bonded_denom: BONDED_DENOM.to_string(),
+                        ..Default::default()
+                    },
+                )
                 .unwrap();
 
             // create validator no. 1
@@ -1322,6 +1946,97 @@ mod test {
         assert_eq!(val, validator);
     }
 
+    #[test]
+    fn add_validator_with_metadata() {
+        let mut env = TestEnv::new(vp(10, 100, 1), vp(0, 20, 1));
+
+        let validator_addr_3 = env.validator_addr_3();
+
+        let validator = Validator::new(
+            validator_addr_3.to_string(),
+            Decimal::percent(1),
+            Decimal::percent(10),
+            Decimal::percent(1),
+        );
+        let metadata = ValidatorMetadata {
+            moniker: "validator three".to_string(),
+            website: "https://validator.three".to_string(),
+            details: "third validator".to_string(),
+            identity: "ABCDEF0123456789".to_string(),
+        };
+        env.router
+            .staking
+            .add_validator_with_metadata(
+                &env.api,
+                &mut env.storage,
+                &env.block,
+                validator.clone(),
+                metadata.clone(),
+            )
+            .unwrap();
+
+        // the validator is registered like with plain `add_validator`
+        let staking_storage = prefixed_read(&env.storage, NAMESPACE_STAKING);
+        let val = env
+            .router
+            .staking
+            .get_validator(&staking_storage, &validator_addr_3)
+            .unwrap()
+            .unwrap();
+        assert_eq!(val, validator);
+
+        // metadata is available
+        let stored = env
+            .router
+            .staking
+            .validator_metadata(&env.storage, &validator_addr_3)
+            .unwrap();
+        assert_eq!(Some(metadata.clone()), stored);
+
+        // adding a validator with existing address fails and keeps the original metadata
+        env.router
+            .staking
+            .add_validator_with_metadata(
+                &env.api,
+                &mut env.storage,
+                &env.block,
+                validator,
+                ValidatorMetadata::default(),
+            )
+            .unwrap_err();
+        let stored = env
+            .router
+            .staking
+            .validator_metadata(&env.storage, &validator_addr_3)
+            .unwrap();
+        assert_eq!(Some(metadata), stored);
+    }
+
+    #[test]
+    fn validator_metadata_should_be_absent() {
+        let env = TestEnv::new(vp(10, 100, 1), vp(0, 20, 1));
+
+        // validator added with plain `add_validator`
+        let validator_addr_1 = env.validator_addr_1();
+        assert_eq!(
+            None,
+            env.router
+                .staking
+                .validator_metadata(&env.storage, &validator_addr_1)
+                .unwrap()
+        );
+
+        // validator that does not exist
+        let validator_addr_3 = env.validator_addr_3();
+        assert_eq!(
+            None,
+            env.router
+                .staking
+                .validator_metadata(&env.storage, &validator_addr_3)
+                .unwrap()
+        );
+    }
+
     #[test]
     fn validator_slashing() {
         let mut env = TestEnv::new(vp(10, 20, 1), vp(10, 20, 1));
@@ -1955,7 +2670,7 @@ mod test {
         .unwrap_err();
         assert_eq!(
             error_result.to_string(),
-            "cannot delegate coins of denominator FAKE, only of TOKEN",
+            "cannot delegate coins of denominator FAKE, only of ustake",
         );
     }
 
@@ -36,6 +36,9 @@ pub trait Stargate {
     }
 
     /// Processes `QueryRequest::Stargate` query.
+    ///
+    /// This is the legacy query form, some chains historically returned
+    /// JSON encoded responses for such queries.
     fn query_stargate(
         &self,
         _api: &dyn Api,
@@ -66,6 +69,8 @@ pub trait Stargate {
     }
 
     /// Processes `QueryRequest::Grpc` query.
+    ///
+    /// Responses to such queries are always raw protobuf encoded bytes.
     fn query_grpc(
         &self,
         _api: &dyn Api,
@@ -79,11 +84,13 @@ pub trait Stargate {
 }
 
 /// Always failing handler for `Stargate`/`Any` message variants and `Stargate`/`Grpc` queries.
+#[derive(Clone)]
 pub struct StargateFailing;
 
 impl Stargate for StargateFailing {}
 
 /// Always accepting handler for `Stargate`/`Any` message variants and `Stargate`/`Grpc` queries.
+#[derive(Clone)]
 pub struct StargateAccepting;
 
 impl Stargate for StargateAccepting {
@@ -0,0 +1,136 @@
+//! # Contract storage access statistics
+//!
+//! Used to count storage operations performed by contracts, a deterministic proxy
+//! of the gas consumption for catching regressions in tests, without modeling gas prices.
+
+use cosmwasm_std::{Addr, Order, Record, Storage};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+/// Numbers of storage operations performed by a contract.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StorageAccessCounts {
+    /// Number of reads of single keys.
+    pub reads: u64,
+    /// Number of writes.
+    pub writes: u64,
+    /// Number of removals.
+    pub removals: u64,
+    /// Number of started range iterations.
+    pub range_iterations: u64,
+    /// Number of key-value pairs yielded by range iterations.
+    pub items_yielded: u64,
+}
+
+impl StorageAccessCounts {
+    fn add(&mut self, other: &Self) {
+        self.reads += other.reads;
+        self.writes += other.writes;
+        self.removals += other.removals;
+        self.range_iterations += other.range_iterations;
+        self.items_yielded += other.items_yielded;
+    }
+}
+
+/// Storage operations performed by contracts during the last top-level execution,
+/// counted per contract address.
+///
+/// Statistics are collected by [WasmKeeper](crate::WasmKeeper) only when enabled
+/// with [AppBuilder::with_storage_access_stats](crate::AppBuilder::with_storage_access_stats).
+/// Operations performed in all entry-points are counted, including replies,
+/// and operations performed while answering queries sent by other contracts
+/// are counted against the queried contract.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StorageAccessStats {
+    counts: BTreeMap<Addr, StorageAccessCounts>,
+}
+
+impl StorageAccessStats {
+    /// Returns the numbers of storage operations performed by specified contract.
+    pub fn contract(&self, address: &Addr) -> StorageAccessCounts {
+        self.counts.get(address).copied().unwrap_or_default()
+    }
+
+    /// Returns the numbers of storage operations performed by all contracts.
+    pub fn total(&self) -> StorageAccessCounts {
+        self.counts
+            .values()
+            .fold(StorageAccessCounts::default(), |mut total, counts| {
+                total.add(counts);
+                total
+            })
+    }
+
+    /// Returns the addresses of contracts that accessed the storage.
+    pub fn contracts(&self) -> Vec<Addr> {
+        self.counts.keys().cloned().collect()
+    }
+
+    /// Returns `true` when no storage operations were counted.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    fn counts_mut(&mut self, address: &Addr) -> &mut StorageAccessCounts {
+        self.counts.entry(address.clone()).or_default()
+    }
+}
+
+/// Contract storage counting the operations performed on it.
+pub(crate) struct CountingStorage<'a> {
+    /// Contract storage.
+    storage: Box<dyn Storage + 'a>,
+    /// Address of the contract the operations are counted against.
+    address: Addr,
+    /// Collected statistics.
+    stats: &'a RefCell<StorageAccessStats>,
+}
+
+impl<'a> CountingStorage<'a> {
+    pub(crate) fn new(
+        storage: Box<dyn Storage + 'a>,
+        address: &Addr,
+        stats: &'a RefCell<StorageAccessStats>,
+    ) -> Self {
+        Self {
+            storage,
+            address: address.clone(),
+            stats,
+        }
+    }
+
+    fn count(&self, f: impl FnOnce(&mut StorageAccessCounts)) {
+        f(self.stats.borrow_mut().counts_mut(&self.address));
+    }
+}
+
+impl Storage for CountingStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.count(|counts| counts.reads += 1);
+        self.storage.get(key)
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        self.count(|counts| counts.range_iterations += 1);
+        Box::new(
+            self.storage
+                .range(start, end, order)
+                .inspect(move |_| self.count(|counts| counts.items_yielded += 1)),
+        )
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.count(|counts| counts.writes += 1);
+        self.storage.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.count(|counts| counts.removals += 1);
+        self.storage.remove(key);
+    }
+}
@@ -0,0 +1,167 @@
+//! # Contract storage statistics
+//!
+//! Used to measure the number and size of keys written by a contract
+//! and to assert them against a storage budget in regression tests.
+
+use cosmwasm_std::Record;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Number and size of key-value pairs sharing the same storage prefix.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PrefixStats {
+    /// Number of keys.
+    pub keys: usize,
+    /// Total number of bytes in keys.
+    pub total_key_bytes: usize,
+    /// Total number of bytes in values.
+    pub total_value_bytes: usize,
+}
+
+impl PrefixStats {
+    /// Returns the total number of bytes in keys and values.
+    pub fn total_bytes(&self) -> usize {
+        self.total_key_bytes + self.total_value_bytes
+    }
+
+    fn add(&mut self, key: &[u8], value: &[u8]) {
+        self.keys += 1;
+        self.total_key_bytes += key.len();
+        self.total_value_bytes += value.len();
+    }
+}
+
+/// Number and size of key-value pairs held by a contract.
+///
+/// Key-value pairs are grouped by the namespace used by `cw-storage-plus`:
+/// - keys of `Map`, `IndexedMap` and `Deque` start with a length-prefixed namespace,
+///   so they are grouped by this namespace (without the length prefix),
+/// - keys of `Item` are the namespace itself, so each `Item` is a group of its own.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Number of keys.
+    pub keys: usize,
+    /// Total number of bytes in keys.
+    pub total_key_bytes: usize,
+    /// Total number of bytes in values.
+    pub total_value_bytes: usize,
+    /// Statistics grouped by the storage namespace.
+    pub by_prefix: BTreeMap<Vec<u8>, PrefixStats>,
+}
+
+impl StorageStats {
+    /// Computes statistics from the raw state dump of a contract.
+    pub fn from_records(records: &[Record]) -> Self {
+        let mut stats = Self::default();
+        for (key, value) in records {
+            stats.keys += 1;
+            stats.total_key_bytes += key.len();
+            stats.total_value_bytes += value.len();
+            stats
+                .by_prefix
+                .entry(namespace(key).to_vec())
+                .or_default()
+                .add(key, value);
+        }
+        stats
+    }
+
+    /// Returns the total number of bytes in keys and values.
+    pub fn total_bytes(&self) -> usize {
+        self.total_key_bytes + self.total_value_bytes
+    }
+
+    /// Returns statistics of key-value pairs stored under specified namespace.
+    pub fn prefix(&self, namespace: impl AsRef<[u8]>) -> Option<&PrefixStats> {
+        self.by_prefix.get(namespace.as_ref())
+    }
+
+    /// Returns the report of exceeding specified budget, `None` when the budget is kept.
+    pub(crate) fn budget_violation(&self, budget: &StorageBudget) -> Option<String> {
+        if self.keys <= budget.max_keys && self.total_bytes() <= budget.max_bytes {
+            return None;
+        }
+        let mut report = format!(
+            "{} keys (limit {}), {} bytes (limit {}), per prefix:",
+            self.keys,
+            budget.max_keys,
+            self.total_bytes(),
+            budget.max_bytes
+        );
+        for (prefix, stats) in &self.by_prefix {
+            let _ = write!(
+                report,
+                "\n  {:?}: {} keys, {} bytes ({} in keys, {} in values)",
+                String::from_utf8_lossy(prefix),
+                stats.keys,
+                stats.total_bytes(),
+                stats.total_key_bytes,
+                stats.total_value_bytes
+            );
+        }
+        Some(report)
+    }
+}
+
+/// Limits of the number of keys and the total size of key-value pairs held by a contract.
+///
+/// Asserting the budget in regression tests helps to catch changes that start writing
+/// more data than expected, for example one extra key per user:
+///
+/// ```
+/// use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+/// use cw_multi_test::{App, ContractWrapper, Executor, IntoAddr, StorageBudget};
+///
+/// fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///     Ok(Response::new())
+/// }
+///
+/// fn execute(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///     deps.storage.set(b"config", b"{}");
+///     Ok(Response::new())
+/// }
+///
+/// fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+///     Ok(Binary::default())
+/// }
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+/// let owner = "owner".into_addr();
+/// let contract_addr = app
+///     .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "config", None)
+///     .unwrap();
+///
+/// app.execute_contract(owner, contract_addr.clone(), &Empty {}, &[])
+///     .unwrap();
+///
+/// // the key `config` (6 bytes) with the value `{}` (2 bytes)
+/// app.assert_storage_budget(
+///     &contract_addr,
+///     StorageBudget {
+///         max_keys: 1,
+///         max_bytes: 8,
+///     },
+/// );
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StorageBudget {
+    /// Maximum number of keys.
+    pub max_keys: usize,
+    /// Maximum total number of bytes in keys and values.
+    pub max_bytes: usize,
+}
+
+/// Returns the `cw-storage-plus` namespace of the key.
+///
+/// When the key starts with a length-prefixed namespace, the namespace is returned,
+/// otherwise the whole key is treated as the namespace of an `Item`.
+fn namespace(key: &[u8]) -> &[u8] {
+    if key.len() > 2 {
+        let len = u16::from_be_bytes([key[0], key[1]]) as usize;
+        if len > 0 && 2 + len <= key.len() {
+            return &key[2..2 + len];
+        }
+    }
+    key
+}
@@ -27,3 +27,18 @@ pub fn contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(execute, instantiate, query);
     Box::new(contract)
 }
+
+fn execute_vote(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msgs: Vec<GovMsg>,
+) -> StdResult<Response> {
+    Ok(Response::new().add_messages(msgs))
+}
+
+/// Contract casting the votes passed in the execute message.
+pub fn voter() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute_vote, instantiate, query);
+    Box::new(contract)
+}
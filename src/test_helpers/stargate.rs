@@ -1,10 +1,22 @@
 #![cfg(feature = "stargate")]
 
 use crate::{Contract, ContractWrapper};
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+    to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, QueryRequest,
+    Response, StdResult,
 };
 
+/// Queries forwarded by the querying contract to the stargate handler.
+#[cw_serde]
+pub enum QueryMsg {
+    /// Forwarded as `QueryRequest::Stargate`.
+    Stargate { path: String },
+    /// Forwarded as `QueryRequest::Grpc`.
+    #[cfg(feature = "cosmwasm_2_0")]
+    Grpc { path: String },
+}
+
 fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
     Ok(Response::new())
 }
@@ -26,3 +38,29 @@ pub fn contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(execute, instantiate, query);
     Box::new(contract)
 }
+
+fn forwarding_query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Stargate { path } => {
+            // legacy stargate responses are expected to be JSON encoded
+            #[allow(deprecated)]
+            let request = QueryRequest::Stargate {
+                path,
+                data: Binary::default(),
+            };
+            to_json_binary(&deps.querier.query::<String>(&request)?)
+        }
+        #[cfg(feature = "cosmwasm_2_0")]
+        QueryMsg::Grpc { path } => {
+            // grpc responses are returned as raw bytes
+            let response = deps.querier.query_grpc(path, Binary::default())?;
+            to_json_binary(&String::from_utf8_lossy(response.as_slice()))
+        }
+    }
+}
+
+/// Contract forwarding stargate and grpc queries.
+pub fn query_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute, instantiate, forwarding_query);
+    Box::new(contract)
+}
@@ -1,8 +1,13 @@
 #![cfg(test)]
 
 mod test_app;
+mod test_authz;
+mod test_contract_state;
 mod test_custom_handler;
+mod test_determinism;
 mod test_error;
+mod test_events;
+mod test_feegrant;
 mod test_gov;
 mod test_ibc;
 mod test_stargate;
@@ -0,0 +1,401 @@
+#![cfg(feature = "stargate")]
+
+use crate::authz::{
+    MsgExec, MsgGrant, MsgRevoke, ProtoGenericAuthorization, ProtoGrant, ProtoMsgSend,
+    ProtoSendAuthorization, QueryGrantAuthorizationsResponse, QueryGranteeGrantsRequest,
+    QueryGrantsRequest, QueryGrantsResponse, GENERIC_AUTHORIZATION, MSG_EXEC, MSG_GRANT,
+    MSG_REVOKE, MSG_SEND, QUERY_GRANTEE_GRANTS, QUERY_GRANTS, SEND_AUTHORIZATION,
+};
+use crate::error::AnyResult;
+use crate::featured::staking::{DistributionKeeper, StakeKeeper};
+use crate::feegrant::{coins_to_proto, ProtoAny};
+use crate::test_helpers::reflect;
+use crate::{
+    no_init, App, AppBuilder, AppResponse, AuthzKeeper, BankKeeper, Executor, FailingModule,
+    GovFailingModule, IbcFailingModule, WasmKeeper,
+};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coin, coins, to_json_vec, Addr, Coin, ContractResult, CosmosMsg, Empty, Event, Querier,
+    QueryRequest, SubMsg, SystemResult,
+};
+use prost::Message;
+
+type AuthzApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    AuthzKeeper,
+>;
+
+const DENOM: &str = "uatom";
+
+fn stargate_msg(type_url: &str, value: Vec<u8>) -> CosmosMsg {
+    #[allow(deprecated)]
+    let msg = CosmosMsg::Stargate {
+        type_url: type_url.to_string(),
+        value: value.into(),
+    };
+    msg
+}
+
+fn grant_msg(granter: &Addr, grantee: &Addr, authorization: ProtoAny) -> CosmosMsg {
+    let value = MsgGrant {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+        grant: Some(ProtoGrant {
+            authorization: Some(authorization),
+            expiration: None,
+        }),
+    }
+    .encode_to_vec();
+    stargate_msg(MSG_GRANT, value)
+}
+
+fn send_authorization(spend_limit: &[Coin]) -> ProtoAny {
+    ProtoAny {
+        type_url: SEND_AUTHORIZATION.to_string(),
+        value: ProtoSendAuthorization {
+            spend_limit: coins_to_proto(spend_limit),
+            allow_list: vec![],
+        }
+        .encode_to_vec(),
+    }
+}
+
+fn generic_authorization(msg: &str) -> ProtoAny {
+    ProtoAny {
+        type_url: GENERIC_AUTHORIZATION.to_string(),
+        value: ProtoGenericAuthorization {
+            msg: msg.to_string(),
+        }
+        .encode_to_vec(),
+    }
+}
+
+fn revoke_msg(granter: &Addr, grantee: &Addr, msg_type_url: &str) -> CosmosMsg {
+    let value = MsgRevoke {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+        msg_type_url: msg_type_url.to_string(),
+    }
+    .encode_to_vec();
+    stargate_msg(MSG_REVOKE, value)
+}
+
+/// Creates `MsgExec` with a bank send from the granter to the recipient.
+fn exec_send_msg(grantee: &Addr, granter: &Addr, recipient: &Addr, amount: u128) -> CosmosMsg {
+    let value = MsgExec {
+        grantee: grantee.to_string(),
+        msgs: vec![ProtoAny {
+            type_url: MSG_SEND.to_string(),
+            value: ProtoMsgSend {
+                from_address: granter.to_string(),
+                to_address: recipient.to_string(),
+                amount: coins_to_proto(&coins(amount, DENOM)),
+            }
+            .encode_to_vec(),
+        }],
+    }
+    .encode_to_vec();
+    stargate_msg(MSG_EXEC, value)
+}
+
+fn query<T: Message + Default>(
+    querier: &dyn Querier,
+    path: &str,
+    data: Vec<u8>,
+) -> Result<T, String> {
+    #[allow(deprecated)]
+    let request = QueryRequest::<Empty>::Stargate {
+        path: path.to_string(),
+        data: data.into(),
+    };
+    match querier.raw_query(&to_json_vec(&request).unwrap()) {
+        SystemResult::Ok(ContractResult::Ok(response)) => {
+            Ok(T::decode(response.as_slice()).unwrap())
+        }
+        SystemResult::Ok(ContractResult::Err(error)) => Err(error),
+        SystemResult::Err(error) => panic!("unexpected system error: {:?}", error),
+    }
+}
+
+/// Returns the remaining spend limit of the send authorization.
+fn spend_limit(app: &AuthzApp, granter: &Addr, grantee: &Addr) -> Result<Vec<Coin>, String> {
+    let data = QueryGrantsRequest {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+        msg_type_url: MSG_SEND.to_string(),
+    }
+    .encode_to_vec();
+    let response: QueryGrantsResponse = query(app, QUERY_GRANTS, data)?;
+    let authorization = response.grants[0].authorization.clone().unwrap();
+    assert_eq!(SEND_AUTHORIZATION, authorization.type_url);
+    let send = ProtoSendAuthorization::decode(authorization.value.as_slice()).unwrap();
+    Ok(send
+        .spend_limit
+        .into_iter()
+        .map(|c| coin(c.amount.parse().unwrap(), c.denom))
+        .collect())
+}
+
+fn balance(app: &AuthzApp, addr: &Addr) -> u128 {
+    app.wrap().query_balance(addr, DENOM).unwrap().amount.u128()
+}
+
+/// Creates the application with the granter having some funds and a reflecting contract
+/// acting as a grantee, returns the application, the granter and the grantee addresses.
+fn setup() -> (AuthzApp, Addr, Addr) {
+    let mut app = AppBuilder::default()
+        .with_stargate(AuthzKeeper::new())
+        .build(no_init);
+    let granter = app.api().addr_make("granter");
+    let owner = app.api().addr_make("owner");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &granter, coins(1000, DENOM))
+            .unwrap();
+    });
+    let code_id = app.store_code(reflect::contract());
+    let grantee = app
+        .instantiate_contract(code_id, owner, &Empty {}, &[], "grantee", None)
+        .unwrap();
+    (app, granter, grantee)
+}
+
+/// Makes the grantee contract send the message.
+fn exec_by_grantee(app: &mut AuthzApp, grantee: &Addr, msg: CosmosMsg) -> AnyResult<AppResponse> {
+    let sender = app.api().addr_make("sender");
+    app.execute_contract(
+        sender,
+        grantee.clone(),
+        &reflect::ExecMessage {
+            sub_msg: vec![SubMsg::new(msg)],
+        },
+        &[],
+    )
+}
+
+#[test]
+fn contract_should_send_on_behalf_of_granter_within_spend_limit() {
+    let (mut app, granter, grantee) = setup();
+    let recipient = app.api().addr_make("recipient");
+
+    // grant the send authorization, the event is emitted
+    let res = app
+        .execute(
+            granter.clone(),
+            grant_msg(&granter, &grantee, send_authorization(&coins(100, DENOM))),
+        )
+        .unwrap();
+    res.assert_event(
+        &Event::new("cosmos.authz.v1beta1.EventGrant")
+            .add_attribute("msg_type_url", MSG_SEND)
+            .add_attribute("granter", granter.as_str())
+            .add_attribute("grantee", grantee.as_str()),
+    );
+
+    // the contract sends tokens from the granter's account, the spend limit is decreased
+    let res = exec_by_grantee(
+        &mut app,
+        &grantee,
+        exec_send_msg(&grantee, &granter, &recipient, 60),
+    )
+    .unwrap();
+    res.assert_event(&Event::new("transfer").add_attribute("authz_msg_index", "0"));
+    assert_eq!(940, balance(&app, &granter));
+    assert_eq!(60, balance(&app, &recipient));
+    assert_eq!(
+        coins(40, DENOM),
+        spend_limit(&app, &granter, &grantee).unwrap()
+    );
+
+    // sending beyond the spend limit fails and nothing is sent
+    let err = exec_by_grantee(
+        &mut app,
+        &grantee,
+        exec_send_msg(&grantee, &granter, &recipient, 50),
+    )
+    .unwrap_err();
+    assert_eq!(
+        "requested amount is more than spend limit",
+        err.root_cause().to_string()
+    );
+    assert_eq!(940, balance(&app, &granter));
+    assert_eq!(
+        coins(40, DENOM),
+        spend_limit(&app, &granter, &grantee).unwrap()
+    );
+
+    // spending the whole remaining limit removes the grant
+    let res = exec_by_grantee(
+        &mut app,
+        &grantee,
+        exec_send_msg(&grantee, &granter, &recipient, 40),
+    )
+    .unwrap();
+    res.assert_event(
+        &Event::new("cosmos.authz.v1beta1.EventRevoke")
+            .add_attribute("msg_type_url", MSG_SEND)
+            .add_attribute("granter", granter.as_str())
+            .add_attribute("grantee", grantee.as_str()),
+    );
+    assert_eq!(900, balance(&app, &granter));
+    assert_eq!(
+        format!("authorization not found for {} type", MSG_SEND),
+        spend_limit(&app, &granter, &grantee).unwrap_err()
+    );
+}
+
+#[test]
+fn contract_should_not_send_after_revocation() {
+    let (mut app, granter, grantee) = setup();
+    let recipient = app.api().addr_make("recipient");
+
+    app.execute(
+        granter.clone(),
+        grant_msg(&granter, &grantee, generic_authorization(MSG_SEND)),
+    )
+    .unwrap();
+
+    // generic authorization has no spend limit
+    exec_by_grantee(
+        &mut app,
+        &grantee,
+        exec_send_msg(&grantee, &granter, &recipient, 700),
+    )
+    .unwrap();
+    assert_eq!(700, balance(&app, &recipient));
+
+    // revoke the authorization, the event is emitted
+    let res = app
+        .execute(granter.clone(), revoke_msg(&granter, &grantee, MSG_SEND))
+        .unwrap();
+    res.assert_event(
+        &Event::new("cosmos.authz.v1beta1.EventRevoke")
+            .add_attribute("msg_type_url", MSG_SEND)
+            .add_attribute("granter", granter.as_str())
+            .add_attribute("grantee", grantee.as_str()),
+    );
+
+    // sending after revocation fails
+    let err = exec_by_grantee(
+        &mut app,
+        &grantee,
+        exec_send_msg(&grantee, &granter, &recipient, 100),
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .ends_with("authorization not found"));
+    assert_eq!(700, balance(&app, &recipient));
+
+    // revoking not existing authorization fails
+    let err = app
+        .execute(granter.clone(), revoke_msg(&granter, &grantee, MSG_SEND))
+        .unwrap_err();
+    assert_eq!("authorization not found", err.to_string());
+}
+
+#[test]
+fn grants_should_be_validated() {
+    let (mut app, granter, grantee) = setup();
+
+    // only the granter can grant
+    let err = app
+        .execute(
+            grantee.clone(),
+            grant_msg(&granter, &grantee, generic_authorization(MSG_SEND)),
+        )
+        .unwrap_err();
+    assert!(err.to_string().starts_with("unauthorized"));
+
+    // self-grants are not allowed
+    let err = app
+        .execute(
+            granter.clone(),
+            grant_msg(&granter, &granter, generic_authorization(MSG_SEND)),
+        )
+        .unwrap_err();
+    assert_eq!("grantee and granter should be different", err.to_string());
+
+    // expired grants are not allowed
+    let value = MsgGrant {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+        grant: Some(ProtoGrant {
+            authorization: Some(generic_authorization(MSG_SEND)),
+            expiration: Some(app.block_info().time.minus_seconds(1).into()),
+        }),
+    }
+    .encode_to_vec();
+    let err = app
+        .execute(granter.clone(), stargate_msg(MSG_GRANT, value))
+        .unwrap_err();
+    assert_eq!(
+        "expiration must be after the current block time",
+        err.to_string()
+    );
+
+    // only the grantee can execute
+    let recipient = app.api().addr_make("recipient");
+    let err = app
+        .execute(
+            granter.clone(),
+            exec_send_msg(&grantee, &granter, &recipient, 1),
+        )
+        .unwrap_err();
+    assert!(err.to_string().starts_with("unauthorized"));
+}
+
+#[test]
+fn grants_should_expire_by_block_time() {
+    let (mut app, granter, grantee) = setup();
+    let recipient = app.api().addr_make("recipient");
+
+    let value = MsgGrant {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+        grant: Some(ProtoGrant {
+            authorization: Some(generic_authorization(MSG_SEND)),
+            expiration: Some(app.block_info().time.plus_seconds(10).into()),
+        }),
+    }
+    .encode_to_vec();
+    app.execute(granter.clone(), stargate_msg(MSG_GRANT, value))
+        .unwrap();
+
+    // the grant is listed for the grantee
+    let data = QueryGranteeGrantsRequest {
+        grantee: grantee.to_string(),
+    }
+    .encode_to_vec();
+    let response: QueryGrantAuthorizationsResponse =
+        query(&app, QUERY_GRANTEE_GRANTS, data.clone()).unwrap();
+    assert_eq!(1, response.grants.len());
+    assert_eq!(granter.as_str(), response.grants[0].granter);
+
+    // after the expiration the grant is neither listed nor usable
+    app.update_block(|block| block.time = block.time.plus_seconds(11));
+    let response: QueryGrantAuthorizationsResponse =
+        query(&app, QUERY_GRANTEE_GRANTS, data).unwrap();
+    assert!(response.grants.is_empty());
+    let err = exec_by_grantee(
+        &mut app,
+        &grantee,
+        exec_send_msg(&grantee, &granter, &recipient, 1),
+    )
+    .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .ends_with("authorization not found"));
+}
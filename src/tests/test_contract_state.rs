@@ -0,0 +1,196 @@
+#![cfg(feature = "stargate")]
+
+use crate::contract_state::{
+    PageRequest, QueryAllContractStateRequest, QueryAllContractStateResponse,
+    QUERY_ALL_CONTRACT_STATE,
+};
+use crate::{App, ContractWrapper, Executor, IntoAddr};
+use cosmwasm_std::{
+    to_json_vec, Addr, Binary, ContractResult, Deps, DepsMut, Empty, Env, MessageInfo, Querier,
+    QueryRequest, Record, Response, StdResult, SystemResult,
+};
+use prost::Message;
+
+const RECORDS: u32 = 250;
+
+/// Writes the configured number of records into the contract's storage.
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    for i in 0..RECORDS {
+        deps.storage
+            .set(format!("key{:03}", i).as_bytes(), &i.to_be_bytes());
+    }
+    Ok(Response::new())
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+fn app_with_contract() -> (App, Addr) {
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let contract_addr = app
+        .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "state", None)
+        .unwrap();
+    (app, contract_addr)
+}
+
+fn query_state(
+    app: &App,
+    address: &Addr,
+    pagination: PageRequest,
+) -> Result<QueryAllContractStateResponse, String> {
+    let data = QueryAllContractStateRequest {
+        address: address.to_string(),
+        pagination: Some(pagination),
+    };
+    #[allow(deprecated)]
+    let request = QueryRequest::<Empty>::Stargate {
+        path: QUERY_ALL_CONTRACT_STATE.to_string(),
+        data: data.encode_to_vec().into(),
+    };
+    match app.raw_query(&to_json_vec(&request).unwrap()) {
+        SystemResult::Ok(ContractResult::Ok(response)) => {
+            Ok(QueryAllContractStateResponse::decode(response.as_slice()).unwrap())
+        }
+        SystemResult::Ok(ContractResult::Err(error)) => Err(error),
+        SystemResult::Err(error) => panic!("unexpected system error: {:?}", error),
+    }
+}
+
+/// Syncs the full state of the contract page by page, like an indexer does.
+fn sync_state(app: &App, address: &Addr, limit: u64, reverse: bool) -> (Vec<Record>, usize) {
+    let mut records = vec![];
+    let mut pages = 0;
+    let mut key = vec![];
+    loop {
+        let pagination = PageRequest {
+            key,
+            limit,
+            reverse,
+            ..Default::default()
+        };
+        let response = query_state(app, address, pagination).unwrap();
+        pages += 1;
+        records.extend(
+            response
+                .models
+                .into_iter()
+                .map(|model| (model.key, model.value)),
+        );
+        key = response.pagination.unwrap().next_key;
+        if key.is_empty() {
+            return (records, pages);
+        }
+    }
+}
+
+#[test]
+fn state_should_be_synced_in_pages() {
+    let (app, contract_addr) = app_with_contract();
+
+    let (records, pages) = sync_state(&app, &contract_addr, 100, false);
+
+    assert_eq!(3, pages);
+    assert_eq!(RECORDS as usize, records.len());
+    assert_eq!(app.dump_wasm_raw(&contract_addr), records);
+}
+
+#[test]
+fn state_should_be_synced_in_reverse_order() {
+    let (app, contract_addr) = app_with_contract();
+
+    let (records, pages) = sync_state(&app, &contract_addr, 100, true);
+
+    let mut expected = app.dump_wasm_raw(&contract_addr);
+    expected.reverse();
+    assert_eq!(3, pages);
+    assert_eq!(expected, records);
+}
+
+#[test]
+fn default_page_limit_should_be_applied() {
+    let (app, contract_addr) = app_with_contract();
+
+    let response = query_state(
+        &app,
+        &contract_addr,
+        PageRequest {
+            count_total: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(100, response.models.len());
+    assert_eq!(b"key000".to_vec(), response.models[0].key);
+    let pagination = response.pagination.unwrap();
+    assert_eq!(b"key100".to_vec(), pagination.next_key);
+    assert_eq!(RECORDS as u64, pagination.total);
+}
+
+#[test]
+fn offset_should_be_applied() {
+    let (app, contract_addr) = app_with_contract();
+
+    let response = query_state(
+        &app,
+        &contract_addr,
+        PageRequest {
+            offset: 240,
+            limit: 100,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(10, response.models.len());
+    assert_eq!(b"key240".to_vec(), response.models[0].key);
+    assert!(response.pagination.unwrap().next_key.is_empty());
+
+    // offset and key can not be combined
+    query_state(
+        &app,
+        &contract_addr,
+        PageRequest {
+            key: b"key100".to_vec(),
+            offset: 1,
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn unknown_contract_should_fail() {
+    let (app, _) = app_with_contract();
+
+    query_state(&app, &"unknown".into_addr(), PageRequest::default()).unwrap_err();
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_2_0")]
+fn state_should_be_queried_with_grpc() {
+    use cosmwasm_std::GrpcQuery;
+
+    let (app, contract_addr) = app_with_contract();
+    let data = QueryAllContractStateRequest {
+        address: contract_addr.to_string(),
+        pagination: None,
+    };
+    let request = QueryRequest::<Empty>::Grpc(GrpcQuery {
+        path: QUERY_ALL_CONTRACT_STATE.to_string(),
+        data: data.encode_to_vec().into(),
+    });
+    let response = app
+        .raw_query(&to_json_vec(&request).unwrap())
+        .unwrap()
+        .unwrap();
+    let response = QueryAllContractStateResponse::decode(response.as_slice()).unwrap();
+
+    assert_eq!(100, response.models.len());
+}
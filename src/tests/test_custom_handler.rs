@@ -1,4 +1,4 @@
-use crate::custom_handler::CachingCustomHandler;
+use crate::custom_handler::{CachingCustomHandler, CustomRouter};
 use crate::test_helpers::CustomHelperMsg;
 use crate::{App, Module};
 use cosmwasm_std::testing::MockStorage;
@@ -87,3 +87,160 @@ fn custom_handler_has_no_sudo() {
             .to_string()
     );
 }
+
+#[test]
+fn custom_router_dispatches_to_matching_handlers() {
+    // prepare needed tools
+    let app = App::default();
+    let mut storage = MockStorage::default();
+
+    // create handlers for distinct message variants
+    let name_handler = CachingCustomHandler::<CustomHelperMsg, CustomHelperMsg>::new();
+    let age_handler = CachingCustomHandler::<CustomHelperMsg, CustomHelperMsg>::new();
+    let name_state = name_handler.state();
+    let age_state = age_handler.state();
+
+    // create custom router, not routed messages are processed by failing fallback
+    let custom_router = CustomRouter::<CustomHelperMsg, CustomHelperMsg>::new()
+        .route(
+            |msg| matches!(msg, CustomHelperMsg::SetName { .. }),
+            name_handler.clone(),
+        )
+        .route_query(
+            |request| matches!(request, CustomHelperMsg::SetName { .. }),
+            name_handler,
+        )
+        .add_route(
+            |msg| matches!(msg, CustomHelperMsg::SetAge { .. }),
+            |request| matches!(request, CustomHelperMsg::SetAge { .. }),
+            age_handler,
+        );
+
+    // prepare user addresses
+    let sender_addr = app.api().addr_make("sender");
+
+    // execute messages of both routed variants
+    for msg in [
+        CustomHelperMsg::SetName {
+            name: "John".to_string(),
+        },
+        CustomHelperMsg::SetAge { age: 32 },
+    ] {
+        custom_router
+            .execute(
+                app.api(),
+                &mut storage,
+                app.router(),
+                &app.block_info(),
+                sender_addr.clone(),
+                msg,
+            )
+            .unwrap();
+    }
+
+    // query both routed variants
+    for request in [
+        CustomHelperMsg::SetAge { age: 33 },
+        CustomHelperMsg::SetName {
+            name: "Jane".to_string(),
+        },
+    ] {
+        custom_router
+            .query(
+                app.api(),
+                &storage,
+                &(*app.wrap()),
+                &app.block_info(),
+                request,
+            )
+            .unwrap();
+    }
+
+    // each handler has seen only messages and queries routed to it
+    assert_eq!(
+        name_state.execs().to_owned(),
+        vec![CustomHelperMsg::SetName {
+            name: "John".to_string()
+        }]
+    );
+    assert_eq!(
+        name_state.queries().to_owned(),
+        vec![CustomHelperMsg::SetName {
+            name: "Jane".to_string()
+        }]
+    );
+    assert_eq!(
+        age_state.execs().to_owned(),
+        vec![CustomHelperMsg::SetAge { age: 32 }]
+    );
+    assert_eq!(
+        age_state.queries().to_owned(),
+        vec![CustomHelperMsg::SetAge { age: 33 }]
+    );
+
+    // not routed message and query are processed by the fallback handler
+    assert_eq!(
+        format!("Unexpected exec msg NoOp from {:?}", sender_addr),
+        custom_router
+            .execute(
+                app.api(),
+                &mut storage,
+                app.router(),
+                &app.block_info(),
+                sender_addr.clone(),
+                CustomHelperMsg::NoOp,
+            )
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        "Unexpected custom query NoOp",
+        custom_router
+            .query(
+                app.api(),
+                &storage,
+                &(*app.wrap()),
+                &app.block_info(),
+                CustomHelperMsg::NoOp,
+            )
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn custom_router_uses_configured_fallback() {
+    // prepare needed tools
+    let app = App::default();
+    let mut storage = MockStorage::default();
+
+    // create custom router with caching fallback handler
+    let fallback = CachingCustomHandler::<CustomHelperMsg, CustomHelperMsg>::new();
+    let fallback_state = fallback.state();
+    let custom_router = CustomRouter::<CustomHelperMsg, CustomHelperMsg>::new()
+        .route(
+            |msg| matches!(msg, CustomHelperMsg::SetAge { .. }),
+            CachingCustomHandler::new(),
+        )
+        .with_fallback(fallback);
+
+    // prepare user addresses
+    let sender_addr = app.api().addr_make("sender");
+
+    // not routed message is processed by the fallback handler
+    custom_router
+        .execute(
+            app.api(),
+            &mut storage,
+            app.router(),
+            &app.block_info(),
+            sender_addr,
+            CustomHelperMsg::NoOp,
+        )
+        .unwrap();
+    assert_eq!(
+        fallback_state.execs().to_owned(),
+        vec![CustomHelperMsg::NoOp]
+    );
+    assert!(fallback_state.queries().is_empty());
+}
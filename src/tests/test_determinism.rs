@@ -0,0 +1,133 @@
+use crate::test_helpers::{echo, reflect};
+use crate::{App, Executor};
+use cosmwasm_std::{coins, to_json_binary, Addr, BankMsg, Empty, Event, Record, SubMsg, WasmMsg};
+
+/// Result of running the scenario: all emitted events,
+/// all generated contract addresses and the final raw state of the reflect contract.
+type ScenarioResult = (Vec<Event>, Vec<Addr>, Vec<Record>);
+
+/// Runs a scenario with nested instantiations, bank transfers and reply chains.
+fn run_scenario() -> ScenarioResult {
+    let mut app = App::default();
+
+    let owner = app.api().addr_make("owner");
+    let receiver = app.api().addr_make("receiver");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, coins(1000, "uatom"))
+            .unwrap()
+    });
+
+    let reflect_id = app.store_code(reflect::contract::<Empty>());
+    let echo_id = app.store_code(echo::contract::<Empty>());
+
+    let reflect_addr = app
+        .instantiate_contract(
+            reflect_id,
+            owner.clone(),
+            &Empty {},
+            &coins(500, "uatom"),
+            "reflect",
+            None,
+        )
+        .unwrap();
+
+    // echo contract instantiating another echo contract and replying with its data
+    let inner_init = echo::InitMessage::<Empty> {
+        data: Some("inner".to_string()),
+        sub_msg: None,
+    };
+    let outer_init = echo::InitMessage::<Empty> {
+        data: Some("outer".to_string()),
+        sub_msg: Some(vec![SubMsg::reply_always(
+            WasmMsg::Instantiate {
+                admin: None,
+                code_id: echo_id,
+                msg: to_json_binary(&inner_init).unwrap(),
+                funds: vec![],
+                label: "inner".to_string(),
+            },
+            1,
+        )]),
+    };
+    let echo_exec = echo::ExecMessage::<Empty> {
+        data: Some("executed".to_string()),
+        events: vec![Event::new("echo").add_attribute("step", "exec")],
+        ..Default::default()
+    };
+
+    let msg = reflect::ExecMessage::<Empty> {
+        sub_msg: vec![
+            SubMsg::reply_always(
+                WasmMsg::Instantiate {
+                    admin: None,
+                    code_id: echo_id,
+                    msg: to_json_binary(&outer_init).unwrap(),
+                    funds: coins(100, "uatom"),
+                    label: "outer".to_string(),
+                },
+                1,
+            ),
+            SubMsg::reply_on_success(
+                BankMsg::Send {
+                    to_address: receiver.to_string(),
+                    amount: coins(50, "uatom"),
+                },
+                2,
+            ),
+            SubMsg::reply_always(
+                WasmMsg::Instantiate {
+                    admin: None,
+                    code_id: echo_id,
+                    msg: to_json_binary(&echo::InitMessage::<Empty>::default()).unwrap(),
+                    funds: vec![],
+                    label: "sibling".to_string(),
+                },
+                3,
+            ),
+        ],
+    };
+    let mut events = app
+        .execute_contract(owner.clone(), reflect_addr.clone(), &msg, &[])
+        .unwrap()
+        .events;
+
+    // all instantiated contracts, in order of instantiation
+    let mut addresses = vec![reflect_addr.clone()];
+    for event in events.iter().filter(|e| e.ty == "instantiate") {
+        for attr in event
+            .attributes
+            .iter()
+            .filter(|a| a.key == "_contract_address")
+        {
+            addresses.push(Addr::unchecked(&attr.value));
+        }
+    }
+
+    // execute the first instantiated echo contract
+    let echo_addr = addresses[1].clone();
+    events.extend(
+        app.execute_contract(owner, echo_addr, &echo_exec, &[])
+            .unwrap()
+            .events,
+    );
+
+    (events, addresses, app.dump_wasm_raw(&reflect_addr))
+}
+
+#[test]
+fn identical_scenarios_should_produce_identical_results() {
+    let (events1, addresses1, state1) = run_scenario();
+    let (events2, addresses2, state2) = run_scenario();
+
+    // the scenario generated some events, addresses and state
+    assert!(events1.len() > 10);
+    assert_eq!(4, addresses1.len());
+    assert!(!state1.is_empty());
+
+    // full ordered event lists, all generated addresses and the final state are identical
+    assert_eq!(events1, events2);
+    assert_eq!(addresses1, addresses2);
+    assert_eq!(state1, state2);
+}
@@ -40,4 +40,10 @@ fn instantiating_error_should_work() {
         "Contract with this address already exists: contract1984",
         Error::duplicated_contract_address("contract1984").to_string()
     );
+    assert_eq!(
+        "Contract with this address already exists: contract1984, instantiated from code id 3 \
+         with the same checksum, creator and salt; \
+         use `duplicate_code_with_new_checksum` to get a different address",
+        Error::duplicated_contract_address_same_checksum("contract1984", 3).to_string()
+    );
 }
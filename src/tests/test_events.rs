@@ -0,0 +1,316 @@
+use crate::test_helpers::echo;
+use crate::{normalize_events, App, AppResponse, EventNormalization, Executor};
+use cosmwasm_std::{coins, Addr, Attribute, Empty, Event};
+
+/// Wraps events into response.
+fn response(events: Vec<Event>) -> AppResponse {
+    AppResponse { events, data: None }
+}
+
+/// Adds `msg_index` attribute to all events, like Cosmos SDK v0.50+ does.
+fn with_msg_index(events: &[Event]) -> Vec<Event> {
+    events
+        .iter()
+        .cloned()
+        .map(|event| event.add_attribute("msg_index", "0"))
+        .collect()
+}
+
+/// Returns events emitted by `wasmd` for transferring tokens.
+fn wasmd_transfer(sender: &Addr, recipient: &Addr, amount: &str) -> Vec<Event> {
+    vec![
+        Event::new("coin_spent")
+            .add_attribute("spender", sender)
+            .add_attribute("amount", amount),
+        Event::new("coin_received")
+            .add_attribute("receiver", recipient)
+            .add_attribute("amount", amount),
+        Event::new("transfer")
+            .add_attribute("recipient", recipient)
+            .add_attribute("sender", sender)
+            .add_attribute("amount", amount),
+        Event::new("message").add_attribute("sender", sender),
+    ]
+}
+
+/// Asserts that events emitted by MultiTest and by `wasmd`
+/// can be normalized into each other in both directions.
+#[track_caller]
+fn assert_round_trip(multi_test_events: &[Event], wasmd_events: &[Event]) {
+    assert_eq!(
+        wasmd_events,
+        normalize_events(
+            &response(multi_test_events.to_vec()),
+            EventNormalization::Wasmd
+        )
+    );
+    assert_eq!(
+        multi_test_events,
+        normalize_events(
+            &response(wasmd_events.to_vec()),
+            EventNormalization::MultiTest
+        )
+    );
+    // events emitted by Cosmos SDK v0.50+ are normalized the same way
+    assert_eq!(
+        multi_test_events,
+        normalize_events(
+            &response(with_msg_index(wasmd_events)),
+            EventNormalization::MultiTest
+        )
+    );
+}
+
+#[test]
+fn bank_send_events_should_be_normalized() {
+    let mut app = App::default();
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &alice, coins(100, "uatom"))
+            .unwrap()
+    });
+
+    let res = app
+        .send_tokens(alice.clone(), bob.clone(), &coins(10, "uatom"))
+        .unwrap();
+
+    assert_round_trip(&res.events, &wasmd_transfer(&alice, &bob, "10uatom"));
+}
+
+#[test]
+fn instantiate_events_should_be_normalized() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, coins(100, "uatom"))
+            .unwrap()
+    });
+    let code_id = app.store_code(echo::contract::<Empty>());
+
+    let (contract_addr, res) = app
+        .instantiate_contract_with_response(
+            code_id,
+            owner.clone(),
+            &echo::InitMessage::<Empty>::default(),
+            &coins(10, "uatom"),
+            "echo",
+            None,
+        )
+        .unwrap();
+
+    // bank events for funds sent with the instantiate message precede the instantiate event
+    let mut wasmd_events = wasmd_transfer(&owner, &contract_addr, "10uatom");
+    wasmd_events.push(
+        Event::new("instantiate")
+            .add_attribute("_contract_address", &contract_addr)
+            .add_attribute("code_id", code_id.to_string()),
+    );
+    assert_round_trip(&res.events, &wasmd_events);
+}
+
+#[test]
+fn execute_with_funds_should_emit_transfer_event_first() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, coins(100, "uatom"))
+            .unwrap()
+    });
+    let code_id = app.store_code(echo::contract::<Empty>());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner.clone(),
+            &echo::InitMessage::<Empty>::default(),
+            &[],
+            "echo",
+            None,
+        )
+        .unwrap();
+
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &echo::ExecMessage::<Empty>::default(),
+            &coins(10, "uatom"),
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            Event::new("transfer")
+                .add_attribute("recipient", &contract_addr)
+                .add_attribute("sender", &owner)
+                .add_attribute("amount", "10uatom"),
+            Event::new("execute").add_attribute("_contract_address", &contract_addr),
+        ],
+        res.events
+    );
+}
+
+#[test]
+fn execute_without_funds_should_not_emit_transfer_event() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(echo::contract::<Empty>());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner.clone(),
+            &echo::InitMessage::<Empty>::default(),
+            &[],
+            "echo",
+            None,
+        )
+        .unwrap();
+
+    let res = app
+        .execute_contract(
+            owner,
+            contract_addr.clone(),
+            &echo::ExecMessage::<Empty>::default(),
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec![Event::new("execute").add_attribute("_contract_address", &contract_addr)],
+        res.events
+    );
+}
+
+#[test]
+fn execute_events_should_be_normalized() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(echo::contract::<Empty>());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner.clone(),
+            &echo::InitMessage::<Empty>::default(),
+            &[],
+            "echo",
+            None,
+        )
+        .unwrap();
+
+    let msg = echo::ExecMessage::<Empty> {
+        attributes: vec![Attribute::new("action", "echo")],
+        events: vec![Event::new("echoed").add_attribute("key", "value")],
+        ..Default::default()
+    };
+    let res = app
+        .execute_contract(owner, contract_addr.clone(), &msg, &[])
+        .unwrap();
+
+    let wasmd_events = vec![
+        Event::new("execute").add_attribute("_contract_address", &contract_addr),
+        Event::new("wasm")
+            .add_attribute("_contract_address", &contract_addr)
+            .add_attribute("action", "echo"),
+        Event::new("wasm-echoed")
+            .add_attribute("_contract_address", &contract_addr)
+            .add_attribute("key", "value"),
+    ];
+    assert_round_trip(&res.events, &wasmd_events);
+
+    // contract address attribute is moved to the first position
+    let captured = vec![Event::new("wasm-echoed")
+        .add_attribute("key", "value")
+        .add_attribute("_contract_address", &contract_addr)];
+    assert_eq!(
+        res.events[2..],
+        normalize_events(&response(captured), EventNormalization::MultiTest)
+    );
+}
+
+#[test]
+#[cfg(feature = "staking")]
+fn withdraw_rewards_events_should_be_normalized() {
+    use cosmwasm_std::{coin, Decimal, DistributionMsg, StakingMsg, Validator};
+
+    let mut app = App::default();
+    let delegator = app.api().addr_make("delegator");
+    let validator = app.api().addr_make("validator").to_string();
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &delegator, coins(100, "TOKEN"))
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator.clone(),
+                    Decimal::percent(10),
+                    Decimal::percent(90),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+
+    app.execute(
+        delegator.clone(),
+        StakingMsg::Delegate {
+            validator: validator.clone(),
+            amount: coin(100, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(60 * 60 * 24 * 365));
+
+    let res = app
+        .execute(
+            delegator.clone(),
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: validator.clone(),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    let amount = "9TOKEN";
+    let withdraw_event = Event::new("withdraw_rewards")
+        .add_attribute("amount", amount)
+        .add_attribute("validator", &validator)
+        .add_attribute("delegator", &delegator);
+
+    // wasmd transfers the rewards from the distribution module account
+    let distribution_module = app.api().addr_make("distribution");
+    let mut wasmd_events = wasmd_transfer(&distribution_module, &delegator, amount);
+    wasmd_events.push(withdraw_event.clone());
+
+    // MultiTest mints the rewards, reporting the minted coins
+    let multi_test_events = normalize_events(&res, EventNormalization::MultiTest);
+    assert_eq!(
+        vec![Event::new("withdraw_delegator_reward")
+            .add_attribute("validator", &validator)
+            .add_attribute("sender", &delegator)
+            .add_attribute("amount", amount)],
+        multi_test_events
+    );
+    assert_eq!(
+        vec![withdraw_event.clone()],
+        normalize_events(&res, EventNormalization::Wasmd)
+    );
+    assert_eq!(
+        multi_test_events,
+        normalize_events(&response(wasmd_events), EventNormalization::MultiTest)
+    );
+    assert_round_trip(&multi_test_events, &[withdraw_event]);
+}
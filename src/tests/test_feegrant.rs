@@ -0,0 +1,245 @@
+#![cfg(feature = "stargate")]
+
+use crate::feegrant::{
+    MsgGrantAllowance, MsgRevokeAllowance, ProtoAny, ProtoBasicAllowance, QueryAllowanceRequest,
+    QueryAllowanceResponse, BASIC_ALLOWANCE, MSG_GRANT_ALLOWANCE, MSG_REVOKE_ALLOWANCE,
+    QUERY_ALLOWANCE,
+};
+use crate::{no_init, AppBuilder, BasicAllowance, Executor, FeeAllowance, FeeGrantKeeper};
+use cosmwasm_std::{
+    coin, to_json_vec, Addr, ContractResult, CosmosMsg, Empty, Event, Querier, QueryRequest,
+    SystemResult,
+};
+use prost::Message;
+
+fn grant_msg(granter: &Addr, grantee: &Addr, allowance: &BasicAllowance) -> CosmosMsg {
+    let value = MsgGrantAllowance {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+        allowance: Some(ProtoAny {
+            type_url: BASIC_ALLOWANCE.to_string(),
+            value: ProtoBasicAllowance::from(allowance).encode_to_vec(),
+        }),
+    }
+    .encode_to_vec();
+    #[allow(deprecated)]
+    let msg = CosmosMsg::Stargate {
+        type_url: MSG_GRANT_ALLOWANCE.to_string(),
+        value: value.into(),
+    };
+    msg
+}
+
+fn revoke_msg(granter: &Addr, grantee: &Addr) -> CosmosMsg {
+    let value = MsgRevokeAllowance {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+    }
+    .encode_to_vec();
+    #[allow(deprecated)]
+    let msg = CosmosMsg::Stargate {
+        type_url: MSG_REVOKE_ALLOWANCE.to_string(),
+        value: value.into(),
+    };
+    msg
+}
+
+fn query_allowance(
+    querier: &dyn Querier,
+    granter: &Addr,
+    grantee: &Addr,
+) -> Result<BasicAllowance, String> {
+    let data = QueryAllowanceRequest {
+        granter: granter.to_string(),
+        grantee: grantee.to_string(),
+    }
+    .encode_to_vec();
+    #[allow(deprecated)]
+    let request = QueryRequest::<Empty>::Stargate {
+        path: QUERY_ALLOWANCE.to_string(),
+        data: data.into(),
+    };
+    match querier.raw_query(&to_json_vec(&request).unwrap()) {
+        SystemResult::Ok(ContractResult::Ok(response)) => {
+            let grant = QueryAllowanceResponse::decode(response.as_slice())
+                .unwrap()
+                .allowance
+                .unwrap();
+            assert_eq!(granter.as_str(), grant.granter);
+            assert_eq!(grantee.as_str(), grant.grantee);
+            let allowance = grant.allowance.unwrap();
+            assert_eq!(BASIC_ALLOWANCE, allowance.type_url);
+            Ok(ProtoBasicAllowance::decode(allowance.value.as_slice())
+                .unwrap()
+                .try_into()
+                .unwrap())
+        }
+        SystemResult::Ok(ContractResult::Err(error)) => Err(error),
+        SystemResult::Err(error) => panic!("unexpected system error: {:?}", error),
+    }
+}
+
+#[test]
+fn granting_and_revoking_allowance_should_work() {
+    let mut app = AppBuilder::default()
+        .with_stargate(FeeGrantKeeper::new())
+        .build(no_init);
+
+    let granter = app.api().addr_make("granter");
+    let grantee = app.api().addr_make("grantee");
+
+    let allowance = BasicAllowance {
+        spend_limit: vec![coin(1000, "uatom")],
+        expiration: None,
+    };
+
+    // grant the allowance, the event is emitted
+    let res = app
+        .execute(granter.clone(), grant_msg(&granter, &grantee, &allowance))
+        .unwrap();
+    res.assert_event(
+        &Event::new("set_feegrant")
+            .add_attribute("granter", granter.as_str())
+            .add_attribute("grantee", grantee.as_str()),
+    );
+
+    // the allowance is available via stargate query and directly from the keeper
+    assert_eq!(
+        allowance,
+        query_allowance(&app, &granter, &grantee).unwrap()
+    );
+    assert_eq!(
+        Some(FeeAllowance::Basic(allowance.clone())),
+        app.router()
+            .stargate
+            .allowance(app.storage(), &app.block_info(), &granter, &grantee)
+            .unwrap()
+    );
+
+    // granting the same allowance again fails
+    let err = app
+        .execute(granter.clone(), grant_msg(&granter, &grantee, &allowance))
+        .unwrap_err();
+    assert_eq!("fee allowance already exists", err.to_string());
+
+    // revoke the allowance
+    app.execute(granter.clone(), revoke_msg(&granter, &grantee))
+        .unwrap();
+    assert_eq!(
+        "fee-grant not found",
+        query_allowance(&app, &granter, &grantee).unwrap_err()
+    );
+
+    // revoking not existing allowance fails
+    let err = app
+        .execute(granter.clone(), revoke_msg(&granter, &grantee))
+        .unwrap_err();
+    assert_eq!("fee-grant not found", err.to_string());
+}
+
+#[test]
+fn only_granter_can_grant_allowance() {
+    let mut app = AppBuilder::default()
+        .with_stargate(FeeGrantKeeper::new())
+        .build(no_init);
+
+    let granter = app.api().addr_make("granter");
+    let grantee = app.api().addr_make("grantee");
+
+    let err = app
+        .execute(
+            grantee.clone(),
+            grant_msg(&granter, &grantee, &BasicAllowance::default()),
+        )
+        .unwrap_err();
+    assert!(err.to_string().starts_with("unauthorized"));
+}
+
+#[test]
+fn allowance_should_expire_by_block_time() {
+    let mut app = AppBuilder::default()
+        .with_stargate(FeeGrantKeeper::new())
+        .build(no_init);
+
+    let granter = app.api().addr_make("granter");
+    let grantee = app.api().addr_make("grantee");
+
+    // granting already expired allowance fails
+    let expired = BasicAllowance {
+        spend_limit: vec![],
+        expiration: Some(app.block_info().time.minus_seconds(1)),
+    };
+    let err = app
+        .execute(granter.clone(), grant_msg(&granter, &grantee, &expired))
+        .unwrap_err();
+    assert_eq!("expiration is before current block time", err.to_string());
+
+    // grant the allowance expiring in 10 seconds
+    let allowance = BasicAllowance {
+        spend_limit: vec![],
+        expiration: Some(app.block_info().time.plus_seconds(10)),
+    };
+    app.execute(granter.clone(), grant_msg(&granter, &grantee, &allowance))
+        .unwrap();
+    assert!(query_allowance(&app, &granter, &grantee).is_ok());
+
+    // the allowance is still valid exactly at the expiration time
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    assert!(query_allowance(&app, &granter, &grantee).is_ok());
+
+    // the allowance is not available after the expiration time
+    app.update_block(|block| block.time = block.time.plus_seconds(1));
+    assert_eq!(
+        "fee-grant not found",
+        query_allowance(&app, &granter, &grantee).unwrap_err()
+    );
+
+    // an expired allowance can be granted again
+    let allowance = BasicAllowance {
+        spend_limit: vec![],
+        expiration: Some(app.block_info().time.plus_seconds(10)),
+    };
+    app.execute(granter.clone(), grant_msg(&granter, &grantee, &allowance))
+        .unwrap();
+    assert!(query_allowance(&app, &granter, &grantee).is_ok());
+}
+
+#[test]
+fn using_allowance_should_decrease_spend_limit() {
+    let mut app = AppBuilder::default()
+        .with_stargate(FeeGrantKeeper::new())
+        .build(no_init);
+
+    let granter = app.api().addr_make("granter");
+    let grantee = app.api().addr_make("grantee");
+
+    let allowance = BasicAllowance {
+        spend_limit: vec![coin(100, "uatom")],
+        expiration: None,
+    };
+    app.execute(granter.clone(), grant_msg(&granter, &grantee, &allowance))
+        .unwrap();
+
+    let block = app.block_info();
+    app.init_modules(|router, _, storage| {
+        let keeper = &router.stargate;
+        keeper
+            .use_allowance(storage, &block, &granter, &grantee, &[coin(60, "uatom")])
+            .unwrap();
+        assert_eq!(
+            "fee limit exceeded",
+            keeper
+                .use_allowance(storage, &block, &granter, &grantee, &[coin(60, "uatom")])
+                .unwrap_err()
+                .to_string()
+        );
+        // fully spent allowance is removed
+        keeper
+            .use_allowance(storage, &block, &granter, &grantee, &[coin(40, "uatom")])
+            .unwrap();
+    });
+    assert_eq!(
+        "fee-grant not found",
+        query_allowance(&app, &granter, &grantee).unwrap_err()
+    );
+}
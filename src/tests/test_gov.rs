@@ -1,8 +1,11 @@
 #![cfg(feature = "stargate")]
 
 use crate::test_helpers::gov;
-use crate::{no_init, App, AppBuilder, Executor, GovAcceptingModule};
-use cosmwasm_std::Empty;
+use crate::{
+    no_init, App, AppBuilder, AppResponse, Executor, GovAcceptingModule, GovRecordingModule,
+    RecordedVote,
+};
+use cosmwasm_std::{Addr, Decimal, Empty, GovMsg, VoteOption};
 
 #[test]
 fn default_gov() {
@@ -37,3 +40,181 @@ fn accepting_gov() {
     app.execute_contract(owner_addr, contract, &Empty {}, &[])
         .unwrap();
 }
+
+/// Returns the value of the attribute with the specified key from `proposal_vote` events.
+fn vote_attributes(res: &AppResponse, key: &str) -> Vec<String> {
+    res.events
+        .iter()
+        .filter(|event| event.ty == "proposal_vote")
+        .map(|event| {
+            event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .unwrap()
+                .value
+                .clone()
+        })
+        .collect()
+}
+
+fn vote(proposal_id: u64, option: VoteOption) -> GovMsg {
+    GovMsg::Vote {
+        proposal_id,
+        option,
+    }
+}
+
+fn simple_vote(proposal_id: u64, voter: &Addr, option: VoteOption) -> RecordedVote {
+    RecordedVote {
+        proposal_id,
+        voter: voter.clone(),
+        option: Some(option.clone()),
+        weighted_options: vec![(option, Decimal::one())],
+    }
+}
+
+#[test]
+fn recording_gov_should_record_votes() {
+    let mut app = AppBuilder::new()
+        .with_gov(GovRecordingModule::new())
+        .build(no_init);
+
+    let creator_addr = app.api().addr_make("creator");
+    let code = app.store_code_with_creator(creator_addr, gov::voter());
+    let owner_addr = app.api().addr_make("owner");
+    let contract = app
+        .instantiate_contract(code, owner_addr.clone(), &Empty {}, &[], "voter", None)
+        .unwrap();
+
+    let res = app
+        .execute_contract(
+            owner_addr,
+            contract.clone(),
+            &[vote(1, VoteOption::Yes), vote(2, VoteOption::NoWithVeto)],
+            &[],
+        )
+        .unwrap();
+
+    // votes are accessible via the module
+    let votes = app.read_module(|router, _, storage| router.gov.votes(storage).unwrap());
+    assert_eq!(
+        vec![
+            simple_vote(1, &contract, VoteOption::Yes),
+            simple_vote(2, &contract, VoteOption::NoWithVeto),
+        ],
+        votes
+    );
+    let vote_of =
+        app.read_module(|router, _, storage| router.gov.vote_of(storage, 2, &contract).unwrap());
+    assert_eq!(
+        Some(simple_vote(2, &contract, VoteOption::NoWithVeto)),
+        vote_of
+    );
+    let vote_of =
+        app.read_module(|router, _, storage| router.gov.vote_of(storage, 3, &contract).unwrap());
+    assert_eq!(None, vote_of);
+
+    // votes are reported in events
+    assert_eq!(
+        vec![contract.to_string(), contract.to_string()],
+        vote_attributes(&res, "voter")
+    );
+    assert_eq!(vec!["1", "2"], vote_attributes(&res, "proposal_id"));
+    assert_eq!(
+        vec![
+            "option:VOTE_OPTION_YES weight:\"1.000000000000000000\"",
+            "option:VOTE_OPTION_NO_WITH_VETO weight:\"1.000000000000000000\"",
+        ],
+        vote_attributes(&res, "option")
+    );
+}
+
+#[test]
+fn recording_gov_should_overwrite_votes() {
+    let mut app = AppBuilder::new()
+        .with_gov(GovRecordingModule::new())
+        .build(no_init);
+
+    let creator_addr = app.api().addr_make("creator");
+    let code = app.store_code_with_creator(creator_addr, gov::voter());
+    let owner_addr = app.api().addr_make("owner");
+    let contract = app
+        .instantiate_contract(code, owner_addr.clone(), &Empty {}, &[], "voter", None)
+        .unwrap();
+
+    app.execute_contract(
+        owner_addr.clone(),
+        contract.clone(),
+        &[vote(1, VoteOption::Yes)],
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner_addr,
+        contract.clone(),
+        &[vote(1, VoteOption::Abstain)],
+        &[],
+    )
+    .unwrap();
+
+    let votes = app.read_module(|router, _, storage| router.gov.votes(storage).unwrap());
+    assert_eq!(vec![simple_vote(1, &contract, VoteOption::Abstain)], votes);
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_2")]
+fn recording_gov_should_record_weighted_votes() {
+    use cosmwasm_std::WeightedVoteOption;
+
+    let mut app = AppBuilder::new()
+        .with_gov(GovRecordingModule::new())
+        .build(no_init);
+
+    let creator_addr = app.api().addr_make("creator");
+    let code = app.store_code_with_creator(creator_addr, gov::voter());
+    let owner_addr = app.api().addr_make("owner");
+    let contract = app
+        .instantiate_contract(code, owner_addr.clone(), &Empty {}, &[], "voter", None)
+        .unwrap();
+
+    let res = app
+        .execute_contract(
+            owner_addr,
+            contract.clone(),
+            &[GovMsg::VoteWeighted {
+                proposal_id: 7,
+                options: vec![
+                    WeightedVoteOption {
+                        option: VoteOption::Yes,
+                        weight: Decimal::percent(75),
+                    },
+                    WeightedVoteOption {
+                        option: VoteOption::No,
+                        weight: Decimal::percent(25),
+                    },
+                ],
+            }],
+            &[],
+        )
+        .unwrap();
+
+    let vote_of =
+        app.read_module(|router, _, storage| router.gov.vote_of(storage, 7, &contract).unwrap());
+    assert_eq!(
+        Some(RecordedVote {
+            proposal_id: 7,
+            voter: contract,
+            option: None,
+            weighted_options: vec![
+                (VoteOption::Yes, Decimal::percent(75)),
+                (VoteOption::No, Decimal::percent(25)),
+            ],
+        }),
+        vote_of
+    );
+    assert_eq!(
+        vec!["option:VOTE_OPTION_YES weight:\"0.750000000000000000\"\noption:VOTE_OPTION_NO weight:\"0.250000000000000000\""],
+        vote_attributes(&res, "option")
+    );
+}
@@ -1,8 +1,9 @@
 #![cfg(feature = "stargate")]
 
+use crate::error::AnyResult;
 use crate::test_helpers::stargate;
-use crate::{no_init, App, AppBuilder, Executor, StargateAccepting};
-use cosmwasm_std::Empty;
+use crate::{no_init, App, AppBuilder, Executor, Stargate, StargateAccepting};
+use cosmwasm_std::{to_json_binary, Api, Binary, BlockInfo, Empty, GrpcQuery, Querier, Storage};
 
 #[test]
 fn default_failing_stargate_handler_should_work() {
@@ -54,3 +55,71 @@ fn accepting_stargate_handler_should_work() {
         .execute_contract(owner_addr, contract_addr, &Empty {}, &[])
         .is_ok());
 }
+
+/// Stargate handler returning different payloads for `Stargate` and `Grpc` queries.
+struct QueryFormKeeper;
+
+impl Stargate for QueryFormKeeper {
+    fn query_stargate(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        path: String,
+        _data: Binary,
+    ) -> AnyResult<Binary> {
+        Ok(to_json_binary(&format!("stargate:{path}"))?)
+    }
+
+    fn query_grpc(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> AnyResult<Binary> {
+        Ok(format!("grpc:{}", request.path).into_bytes().into())
+    }
+}
+
+#[test]
+fn stargate_and_grpc_queries_should_be_routed_separately() {
+    let mut app = AppBuilder::default()
+        .with_stargate(QueryFormKeeper)
+        .build(no_init);
+
+    let code = app.store_code(stargate::query_contract());
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code, owner_addr, &Empty {}, &[], "forwarder", None)
+        .unwrap();
+
+    // legacy stargate query is handled by `query_stargate`
+    let response: String = app
+        .wrap()
+        .query_wasm_smart(
+            &contract_addr,
+            &stargate::QueryMsg::Stargate {
+                path: "/cosmos.bank.v1beta1.Query/Params".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!("stargate:/cosmos.bank.v1beta1.Query/Params", response);
+
+    // grpc query is handled by `query_grpc`
+    #[cfg(feature = "cosmwasm_2_0")]
+    {
+        let response: String = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_addr,
+                &stargate::QueryMsg::Grpc {
+                    path: "/cosmos.bank.v1beta1.Query/Params".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!("grpc:/cosmos.bank.v1beta1.Query/Params", response);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::error::AnyResult;
 use cosmwasm_std::Storage;
 use cosmwasm_std::{Order, Record};
+use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::iter;
 use std::iter::Peekable;
 use std::ops::{Bound, RangeBounds};
@@ -11,6 +12,55 @@ use std::ops::{Bound, RangeBounds};
 /// This is internal as it can change any time if the map implementation is swapped out.
 type BTreeMapPairRef<'a, T = Vec<u8>> = (&'a Vec<u8>, &'a T);
 
+/// Executes the `action` atomically on top of the `base` storage.
+///
+/// All writes made by the `action` to the storage passed as its first argument are buffered
+/// in a [StorageTransaction] and written to the `base` storage only when the `action`
+/// succeeds. When the `action` returns an error, the `base` storage is left untouched
+/// and the error is returned. The second argument of the `action` gives read-only access
+/// to the `base` storage, i.e. to the state from before the transaction was started.
+///
+/// Transactions can be nested: the `base` storage may itself be a transaction,
+/// in which case the changes made by the inner transaction become visible to the outer one
+/// when the inner `action` succeeds, and are persisted only when all outer transactions succeed.
+///
+/// [App](crate::App) already runs every executed message and every privileged action
+/// in its own transaction, and [WasmKeeper](crate::WasmKeeper) does the same for every
+/// submessage, so returning an error from a module always discards all of its changes.
+/// A custom module needs its own (nested) transaction only when it has to recover
+/// from a failure of a group of operations, e.g. several router calls that must
+/// all succeed or none, while still returning a successful response.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::testing::MockStorage;
+/// use cosmwasm_std::Storage;
+/// use cw_multi_test::error::{bail, AnyResult};
+/// use cw_multi_test::transactional;
+///
+/// let mut storage = MockStorage::new();
+///
+/// // changes made by the succeeding action are committed
+/// transactional(&mut storage, |cache, _| {
+///     cache.set(b"first", b"1");
+///     Ok(())
+/// })
+/// .unwrap();
+/// assert_eq!(Some(b"1".to_vec()), storage.get(b"first"));
+///
+/// // changes made by the failing action are discarded, also those made before the failure
+/// let result: AnyResult<()> = transactional(&mut storage, |cache, base| {
+///     cache.set(b"first", b"2");
+///     cache.set(b"second", b"2");
+///     // the state from before the transaction is still available
+///     assert_eq!(Some(b"1".to_vec()), base.get(b"first"));
+///     bail!("failure")
+/// });
+/// assert!(result.is_err());
+/// assert_eq!(Some(b"1".to_vec()), storage.get(b"first"));
+/// assert_eq!(None, storage.get(b"second"));
+/// ```
 pub fn transactional<F, T>(base: &mut dyn Storage, action: F) -> AnyResult<T>
 where
     F: FnOnce(&mut dyn Storage, &dyn Storage) -> AnyResult<T>,
@@ -21,6 +71,13 @@ where
     Ok(res)
 }
 
+/// Storage buffering all changes on top of read-only backing storage.
+///
+/// Reads (including iteration with `range`) see both the backing storage
+/// and the changes buffered in the transaction. The buffered changes are written
+/// to the backing storage by committing the [RepLog] returned from [prepare](Self::prepare),
+/// or discarded by dropping the transaction.
+/// In most cases it is more convenient to use [transactional].
 pub struct StorageTransaction<'a> {
     /// read-only access to backing storage
     storage: &'a dyn Storage,
@@ -31,6 +88,7 @@ pub struct StorageTransaction<'a> {
 }
 
 impl<'a> StorageTransaction<'a> {
+    /// Creates a new transaction on top of the provided backing storage.
     pub fn new(storage: &'a dyn Storage) -> Self {
         StorageTransaction {
             storage,
@@ -104,6 +162,7 @@ impl Storage for StorageTransaction<'_> {
     }
 }
 
+/// A list of changes buffered by [StorageTransaction], to be written to the backing storage.
 pub struct RepLog {
     /// this is a list of changes to be written to backing storage upon commit
     ops_log: Vec<Op>,
@@ -246,6 +305,107 @@ where
     }
 }
 
+/// Number of records read at once by the iterator over [SharedStorage].
+const SHARED_RANGE_CHUNK: usize = 32;
+
+/// Storage accessed through multiple handles, each of them can read and write.
+///
+/// Used to give the querier of a contract call read access to the changes
+/// made by the contract during the same call.
+pub(crate) struct SharedStorage<'a> {
+    storage: RefCell<&'a mut dyn Storage>,
+}
+
+impl<'a> SharedStorage<'a> {
+    /// Creates a new shared storage, borrowing the underlying storage for its whole lifetime.
+    pub(crate) fn new(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: RefCell::new(storage),
+        }
+    }
+
+    /// Returns a new handle to the shared storage.
+    pub(crate) fn handle(&self) -> SharedStorageHandle<'_, 'a> {
+        SharedStorageHandle { shared: self }
+    }
+}
+
+/// Handle to [SharedStorage], the storage is borrowed only for the duration of a single operation.
+pub(crate) struct SharedStorageHandle<'b, 'a> {
+    shared: &'b SharedStorage<'a>,
+}
+
+impl Storage for SharedStorageHandle<'_, '_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.shared.storage.borrow().get(key)
+    }
+
+    fn range<'c>(
+        &'c self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'c> {
+        Box::new(SharedRange {
+            shared: self.shared,
+            start: start.map(<[u8]>::to_vec),
+            end: end.map(<[u8]>::to_vec),
+            order,
+            buffer: VecDeque::new(),
+            exhausted: false,
+        })
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.shared.storage.borrow_mut().set(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.shared.storage.borrow_mut().remove(key)
+    }
+}
+
+/// Iterator over [SharedStorage], reading records in chunks,
+/// so the storage is not borrowed between the calls to [Iterator::next].
+struct SharedRange<'b, 'a> {
+    shared: &'b SharedStorage<'a>,
+    /// Start of the range of keys not read yet (inclusive).
+    start: Option<Vec<u8>>,
+    /// End of the range of keys not read yet (exclusive).
+    end: Option<Vec<u8>>,
+    order: Order,
+    /// Records already read, but not returned yet.
+    buffer: VecDeque<Record>,
+    /// Flag indicating if all records in the range were already read.
+    exhausted: bool,
+}
+
+impl Iterator for SharedRange<'_, '_> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() && !self.exhausted {
+            let chunk = self
+                .shared
+                .storage
+                .borrow()
+                .range(self.start.as_deref(), self.end.as_deref(), self.order)
+                .take(SHARED_RANGE_CHUNK)
+                .collect::<Vec<_>>();
+            self.exhausted = chunk.len() < SHARED_RANGE_CHUNK;
+            if let Some((last_key, _)) = chunk.last() {
+                match self.order {
+                    // the smallest key greater than the last one read
+                    Order::Ascending => self.start = Some([last_key.as_slice(), &[0]].concat()),
+                    Order::Descending => self.end = Some(last_key.clone()),
+                }
+            }
+            self.buffer.extend(chunk);
+        }
+        self.buffer.pop_front()
+    }
+}
+
 fn range_bounds(start: Option<&[u8]>, end: Option<&[u8]>) -> impl RangeBounds<Vec<u8>> {
     (
         start.map_or(Bound::Unbounded, |x| Bound::Included(x.to_vec())),
@@ -570,4 +730,41 @@ mod test {
 
         assert_eq!(base.get(b"subtx"), None);
     }
+
+    #[test]
+    fn shared_storage_handles_see_each_other_writes() {
+        let mut base = MemoryStorage::new();
+        let shared = SharedStorage::new(&mut base);
+        let mut writer = shared.handle();
+        let reader = shared.handle();
+
+        writer.set(b"foo", b"bar");
+        assert_eq!(reader.get(b"foo"), Some(b"bar".to_vec()));
+        writer.remove(b"foo");
+        assert_eq!(reader.get(b"foo"), None);
+    }
+
+    #[test]
+    fn shared_storage_range_reads_all_chunks() {
+        let mut base = MemoryStorage::new();
+        // more records than in a single chunk, including keys being prefixes of other keys
+        for i in 0..(2 * SHARED_RANGE_CHUNK as u8 + 5) {
+            base.set(&[i], &[i]);
+            base.set(&[i, 0], &[i]);
+        }
+        let expected_asc: Vec<Record> = base.range(None, None, Order::Ascending).collect();
+        let expected_desc: Vec<Record> = base
+            .range(Some(&[3]), Some(&[70]), Order::Descending)
+            .collect();
+
+        let shared = SharedStorage::new(&mut base);
+        let handle = shared.handle();
+        let asc: Vec<Record> = handle.range(None, None, Order::Ascending).collect();
+        let desc: Vec<Record> = handle
+            .range(Some(&[3]), Some(&[70]), Order::Descending)
+            .collect();
+
+        assert_eq!(expected_asc, asc);
+        assert_eq!(expected_desc, desc);
+    }
 }
@@ -0,0 +1,242 @@
+//! # Typed view of token transfers reported in response events
+//!
+//! Used to assert bank transfers, mints, burns and forced transfers
+//! without matching event attributes.
+
+use crate::AppResponse;
+use cosmwasm_std::{Addr, Coin, Event, Uint128};
+
+/// Kind of the token flow reported by the bank module.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TransferKind {
+    /// Tokens sent from one account to another, reported in `transfer` events.
+    Send,
+    /// Tokens minted to an account, reported in `coin_received` events.
+    Mint,
+    /// Tokens burned from an account, reported in `burn` events
+    /// (emitted by [BankKeeper](crate::BankKeeper) when executing `BankMsg::Burn`).
+    Burn,
+    /// Tokens moved from one account to another without the owner's involvement, reported
+    /// in `force_transfer` events, see [BankSudo::ForceTransfer](crate::BankSudo::ForceTransfer).
+    ForceTransfer,
+}
+
+/// Single token flow reported by the bank module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferRecord {
+    /// Account the tokens were taken from, `None` for minted tokens.
+    pub from: Option<Addr>,
+    /// Account the tokens were given to, `None` for burned tokens.
+    pub to: Option<Addr>,
+    /// Transferred tokens.
+    pub amount: Vec<Coin>,
+    /// Kind of the token flow.
+    pub kind: TransferKind,
+}
+
+impl TransferRecord {
+    /// Creates a record of tokens sent from one account to another.
+    pub fn send(from: &Addr, to: &Addr, amount: &[Coin]) -> Self {
+        Self {
+            from: Some(from.clone()),
+            to: Some(to.clone()),
+            amount: amount.to_vec(),
+            kind: TransferKind::Send,
+        }
+    }
+
+    /// Creates a record of tokens minted to an account.
+    pub fn mint(to: &Addr, amount: &[Coin]) -> Self {
+        Self {
+            from: None,
+            to: Some(to.clone()),
+            amount: amount.to_vec(),
+            kind: TransferKind::Mint,
+        }
+    }
+
+    /// Creates a record of tokens burned from an account.
+    pub fn burn(from: &Addr, amount: &[Coin]) -> Self {
+        Self {
+            from: Some(from.clone()),
+            to: None,
+            amount: amount.to_vec(),
+            kind: TransferKind::Burn,
+        }
+    }
+
+    /// Creates a record of tokens moved from one account to another
+    /// without the owner's involvement.
+    pub fn force_transfer(from: &Addr, to: &Addr, amount: &[Coin]) -> Self {
+        Self {
+            from: Some(from.clone()),
+            to: Some(to.clone()),
+            amount: amount.to_vec(),
+            kind: TransferKind::ForceTransfer,
+        }
+    }
+
+    /// Returns `true` when the specified account sent or received tokens in this record.
+    pub fn involves(&self, addr: &Addr) -> bool {
+        self.from.as_ref() == Some(addr) || self.to.as_ref() == Some(addr)
+    }
+
+    /// Parses the record from the event emitted by the bank module,
+    /// returns `None` for other events.
+    fn from_event(event: &Event) -> Option<Self> {
+        let attr = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attribute| attribute.key == key)
+                .map(|attribute| attribute.value.as_str())
+        };
+        let amount = parse_coins(attr("amount")?)?;
+        match event.ty.as_str() {
+            "transfer" => Some(Self {
+                from: Some(Addr::unchecked(attr("sender")?)),
+                to: Some(Addr::unchecked(attr("recipient")?)),
+                amount,
+                kind: TransferKind::Send,
+            }),
+            "coin_received" => Some(Self {
+                from: None,
+                to: Some(Addr::unchecked(attr("receiver")?)),
+                amount,
+                kind: TransferKind::Mint,
+            }),
+            "burn" => Some(Self {
+                from: Some(Addr::unchecked(attr("burner")?)),
+                to: None,
+                amount,
+                kind: TransferKind::Burn,
+            }),
+            "force_transfer" => Some(Self {
+                from: Some(Addr::unchecked(attr("sender")?)),
+                to: Some(Addr::unchecked(attr("recipient")?)),
+                amount,
+                kind: TransferKind::ForceTransfer,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Token transfers, mints, burns and forced transfers reported by the bank module
+/// in response events, in the order they were emitted.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::coins;
+/// use cw_multi_test::{App, BankTransfers, Executor, IntoAddr, TransferRecord};
+///
+/// let owner = "owner".into_addr();
+/// let recipient = "recipient".into_addr();
+/// let mut app = App::new(|router, _, storage| {
+///     router.bank.init_balance(storage, &owner, coins(100, "uatom")).unwrap();
+/// });
+///
+/// let response = app.send_tokens(owner.clone(), recipient.clone(), &coins(40, "uatom")).unwrap();
+///
+/// BankTransfers::from(&response).assert_exact(&[TransferRecord::send(
+///     &owner,
+///     &recipient,
+///     &coins(40, "uatom"),
+/// )]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BankTransfers {
+    records: Vec<TransferRecord>,
+}
+
+impl BankTransfers {
+    /// Returns all records in the order they were emitted.
+    pub fn records(&self) -> &[TransferRecord] {
+        &self.records
+    }
+
+    /// Asserts that exactly the expected records were reported, in the same order.
+    #[track_caller]
+    pub fn assert_exact(&self, expected: &[TransferRecord]) {
+        assert_eq!(
+            expected,
+            self.records.as_slice(),
+            "Expected bank transfers {:?}, but reported: {:?}",
+            expected,
+            self.records
+        );
+    }
+
+    /// Asserts that the expected record was reported.
+    #[track_caller]
+    pub fn assert_contains(&self, expected: &TransferRecord) {
+        assert!(
+            self.records.contains(expected),
+            "Expected to find bank transfer {:?}, but reported: {:?}",
+            expected,
+            self.records
+        );
+    }
+
+    /// Asserts that no record involves the specified account.
+    #[track_caller]
+    pub fn assert_none_involving(&self, addr: &Addr) {
+        let involving = self
+            .records
+            .iter()
+            .filter(|record| record.involves(addr))
+            .collect::<Vec<_>>();
+        assert!(
+            involving.is_empty(),
+            "Expected no bank transfers involving {}, but reported: {:?}",
+            addr,
+            involving
+        );
+    }
+}
+
+impl From<&AppResponse> for BankTransfers {
+    fn from(response: &AppResponse) -> Self {
+        Self {
+            records: response
+                .events
+                .iter()
+                .filter_map(TransferRecord::from_event)
+                .collect(),
+        }
+    }
+}
+
+/// Parses coins in the format used in bank events, like `100uatom,5ibc/27394FB0...`.
+fn parse_coins(value: &str) -> Option<Vec<Coin>> {
+    if value.is_empty() {
+        return Some(vec![]);
+    }
+    value
+        .split(',')
+        .map(|coin| {
+            let position = coin.find(|c: char| !c.is_ascii_digit())?;
+            let (amount, denom) = coin.split_at(position);
+            let amount = amount.parse::<u128>().ok()?;
+            Some(Coin::new(Uint128::new(amount), denom))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cosmwasm_std::coin;
+
+    #[test]
+    fn coins_should_be_parsed() {
+        assert_eq!(Some(vec![]), parse_coins(""));
+        assert_eq!(
+            Some(vec![coin(100, "uatom"), coin(5, "ibc/27394FB0")]),
+            parse_coins("100uatom,5ibc/27394FB0")
+        );
+        assert_eq!(None, parse_coins("uatom"));
+        assert_eq!(None, parse_coins("100"));
+    }
+}
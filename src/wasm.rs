@@ -1,17 +1,22 @@
-use crate::addresses::{AddressGenerator, SimpleAddressGenerator};
+use crate::address_book::check_pinned_address;
+use crate::addresses::{AddressGenerator, ContractInstantiationInfo, SimpleAddressGenerator};
 use crate::app::{CosmosRouter, RouterQuerier};
+use crate::call_stats::{CallStats, EntryPoint};
 use crate::checksums::{ChecksumGenerator, SimpleChecksumGenerator};
 use crate::contracts::Contract;
 use crate::error::{bail, AnyContext, AnyError, AnyResult, Error};
 use crate::executor::AppResponse;
 use crate::prefixed_storage::{prefixed, prefixed_read, PrefixedStorage, ReadonlyPrefixedStorage};
-use crate::transactions::transactional;
+use crate::reply_traces::ReplyTrace;
+use crate::storage_access::{CountingStorage, StorageAccessStats};
+use crate::transactions::{transactional, SharedStorage};
+use crate::wasm_permissions::check_instantiate;
 use cosmwasm_std::testing::mock_wasmd_attr;
 use cosmwasm_std::{
     to_json_binary, Addr, Api, Attribute, BankMsg, Binary, BlockInfo, Checksum, Coin, ContractInfo,
-    ContractInfoResponse, CustomMsg, CustomQuery, Deps, DepsMut, Env, Event, MessageInfo, Order,
-    Querier, QuerierWrapper, Record, Reply, ReplyOn, Response, StdResult, Storage, SubMsg,
-    SubMsgResponse, SubMsgResult, TransactionInfo, WasmMsg, WasmQuery,
+    ContractInfoResponse, CustomMsg, CustomQuery, Deps, DepsMut, Env, Event, HexBinary,
+    MessageInfo, Order, Querier, QuerierWrapper, Record, Reply, ReplyOn, Response, StdResult,
+    Storage, SubMsg, SubMsgResponse, SubMsgResult, TransactionInfo, WasmMsg, WasmQuery,
 };
 use cw_storage_plus::Map;
 use prost::Message;
@@ -19,14 +24,29 @@ use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
+use std::cell::{Cell, RefCell};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Default maximum depth of nested smart queries, the same as in `wasmd`.
+const DEFAULT_MAX_QUERY_DEPTH: usize = 10;
+
+/// Default maximum depth of nested contract calls, the same as in `wasmd`.
+const DEFAULT_MAX_CALL_DEPTH: usize = 500;
 
 /// Contract state kept in storage, separate from the contracts themselves (contract code).
 const CONTRACTS: Map<&Addr, ContractData> = Map::new("contracts");
 
+/// Contract history entries, kept in storage only when the contract history is enabled.
+const CONTRACT_HISTORY: Map<&Addr, Vec<ContractHistoryEntry>> = Map::new("contract_history");
+
+/// Custom metadata attached to contracts by test frameworks, kept separately from [ContractData],
+/// so it is preserved across migrations and admin changes.
+const CONTRACT_EXTENSIONS: Map<&Addr, BTreeMap<String, String>> = Map::new("contract_extensions");
+
 /// Wasm module namespace.
-const NAMESPACE_WASM: &[u8] = b"wasm";
+pub(crate) const NAMESPACE_WASM: &[u8] = b"wasm";
 
 /// Contract [address namespace].
 ///
@@ -34,7 +54,7 @@ const NAMESPACE_WASM: &[u8] = b"wasm";
 const CONTRACT_ATTR: &str = "_contract_address";
 
 /// A structure representing a privileged message.
-#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct WasmSudo {
     /// Address of a contract the privileged action will be sent to.
     pub contract_addr: Addr,
@@ -52,6 +72,209 @@ impl WasmSudo {
     }
 }
 
+/// Behavior of the re-entrancy guard in [WasmKeeper].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GuardMode {
+    /// Re-entrant calls fail with an error.
+    Deny,
+    /// Re-entrant calls are executed, a warning is recorded for each of them.
+    Warn,
+}
+
+/// Maximum size of data returned from a contract call (64 MiB), like in CosmWasm VM.
+const MAX_LENGTH_RESPONSE: usize = 64 * 1024 * 1024;
+
+/// Limits of data returned from contract calls, checked by [WasmKeeper]
+/// after each call of contract's entry-point.
+///
+/// Limits set to `None` are not checked.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ResponseLimits {
+    /// Maximum size of a smart query response in bytes.
+    pub max_query_response_bytes: Option<usize>,
+    /// Maximum size of data set in a response of `execute`, `instantiate`, `migrate`,
+    /// `sudo` or `reply` entry-point, in bytes.
+    pub max_data_bytes: Option<usize>,
+    /// Maximum number of custom events in a response.
+    pub max_events: Option<usize>,
+    /// Maximum number of attributes in a single event, including attributes
+    /// of the response reported in the `wasm` event.
+    pub max_attributes_per_event: Option<usize>,
+    /// Maximum size of an attribute key in bytes, including attributes of the response.
+    pub max_attribute_key_bytes: Option<usize>,
+    /// Maximum size of an attribute value in bytes, including attributes of the response.
+    pub max_attribute_value_bytes: Option<usize>,
+}
+
+impl Default for ResponseLimits {
+    /// Returns limits mirroring CosmWasm VM, where the size of data returned from a contract
+    /// is limited to 64 MiB. Numbers of events and attributes, as well as sizes of attribute
+    /// keys and values, are not limited by `wasmd` (only by gas and the size of the block),
+    /// so they are not checked by default.
+    fn default() -> Self {
+        Self {
+            max_query_response_bytes: Some(MAX_LENGTH_RESPONSE),
+            max_data_bytes: Some(MAX_LENGTH_RESPONSE),
+            max_events: None,
+            max_attributes_per_event: None,
+            max_attribute_key_bytes: None,
+            max_attribute_value_bytes: None,
+        }
+    }
+}
+
+impl ResponseLimits {
+    /// Returns limits that are never exceeded.
+    pub fn unlimited() -> Self {
+        Self {
+            max_query_response_bytes: None,
+            max_data_bytes: None,
+            max_events: None,
+            max_attributes_per_event: None,
+            max_attribute_key_bytes: None,
+            max_attribute_value_bytes: None,
+        }
+    }
+
+    /// Checks the size of a smart query response.
+    fn check_query_response(&self, response: &Binary) -> AnyResult<()> {
+        match self.max_query_response_bytes {
+            Some(limit) if response.len() > limit => {
+                bail!(Error::query_response_too_large(response.len(), limit))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the data, events and attributes of a contract response.
+    fn check_response<T>(&self, response: &Response<T>) -> AnyResult<()> {
+        if let (Some(limit), Some(data)) = (self.max_data_bytes, &response.data) {
+            if data.len() > limit {
+                bail!(Error::response_data_too_large(data.len(), limit));
+            }
+        }
+        if let Some(limit) = self.max_events {
+            if response.events.len() > limit {
+                bail!(Error::too_many_events(response.events.len(), limit));
+            }
+        }
+        self.check_attributes("wasm", &response.attributes)?;
+        for event in &response.events {
+            self.check_attributes(&event.ty, &event.attributes)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the number and sizes of attributes of a single event,
+    /// attributes of the response are reported in the `wasm` event.
+    fn check_attributes(&self, ty: &str, attributes: &[Attribute]) -> AnyResult<()> {
+        if let Some(limit) = self.max_attributes_per_event {
+            if attributes.len() > limit {
+                bail!(Error::too_many_attributes(ty, attributes.len(), limit));
+            }
+        }
+        for attr in attributes {
+            if let Some(limit) = self.max_attribute_key_bytes {
+                if attr.key.len() > limit {
+                    bail!(Error::attribute_key_too_long(
+                        ty,
+                        &attr.key,
+                        attr.key.len(),
+                        limit,
+                    ));
+                }
+            }
+            if let Some(limit) = self.max_attribute_value_bytes {
+                if attr.value.len() > limit {
+                    bail!(Error::attribute_value_too_long(
+                        ty,
+                        &attr.key,
+                        attr.value.len(),
+                        limit,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maximum length of a contract label in bytes, like in `wasmd`.
+const MAX_LABEL_SIZE: usize = 128;
+
+/// Function validating a contract label, returns the reason of rejecting the label.
+type LabelValidator = Arc<dyn Fn(&str) -> Result<(), String>>;
+
+/// Validation of contract labels in [WasmKeeper], applied when instantiating contracts.
+#[derive(Clone, Default)]
+pub enum LabelValidation {
+    /// Rules applied by `wasmd`: the label must not be empty, must not start or end
+    /// with whitespace and must not be longer than 128 bytes (non-ASCII characters count
+    /// as several bytes). Additionally, control characters are rejected.
+    #[default]
+    Wasmd,
+    /// Only empty labels are rejected.
+    Permissive,
+    /// Chain-specific rules, the validator returns the reason of rejecting the label.
+    /// No other rules are applied, see [custom](Self::custom).
+    Custom(LabelValidator),
+}
+
+impl LabelValidation {
+    /// Creates a label validation with chain-specific rules.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::LabelValidation;
+    ///
+    /// let validation = LabelValidation::custom(|label| {
+    ///     if label.starts_with("dao-") {
+    ///         Ok(())
+    ///     } else {
+    ///         Err("label must start with dao-".to_string())
+    ///     }
+    /// });
+    ///
+    /// assert!(validation.validate("dao-core").is_ok());
+    /// assert!(validation.validate("core").is_err());
+    /// ```
+    pub fn custom(validator: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        Self::Custom(Arc::new(validator))
+    }
+
+    /// Validates the label, returns an error including the label when it is rejected.
+    pub fn validate(&self, label: &str) -> AnyResult<()> {
+        let result = match self {
+            Self::Wasmd => {
+                if label.is_empty() {
+                    Err("label is required".to_string())
+                } else if label.trim() != label {
+                    Err("label must not start or end with whitespace".to_string())
+                } else if label.len() > MAX_LABEL_SIZE {
+                    Err(format!(
+                        "label cannot be longer than {} bytes",
+                        MAX_LABEL_SIZE
+                    ))
+                } else if label.chars().any(char::is_control) {
+                    Err("label must not contain control characters".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            Self::Permissive => {
+                if label.is_empty() {
+                    Err("label is required".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            Self::Custom(validator) => validator(label),
+        };
+        result.map_err(|reason| Error::invalid_label(label, reason).into())
+    }
+}
+
 /// Contract data includes information about contract,
 /// equivalent of `ContractInfo` in `wasmd` interface.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -68,7 +291,36 @@ pub struct ContractData {
     pub created: u64,
 }
 
+/// Type of operation recorded in the contract history,
+/// equivalent of `ContractCodeHistoryOperationType` in `wasmd` interface.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum ContractHistoryOperation {
+    /// Contract was instantiated.
+    Init,
+    /// Contract was migrated to a new code.
+    Migrate,
+    /// Contract was imported in genesis.
+    ///
+    /// [WasmKeeper] never records this operation, it is provided for custom wasm modules.
+    Genesis,
+}
+
+/// Contract history entry,
+/// equivalent of `ContractCodeHistoryEntry` in `wasmd` interface.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ContractHistoryEntry {
+    /// Type of the recorded operation.
+    pub operation: ContractHistoryOperation,
+    /// Identifier of the contract code used in the operation.
+    pub code_id: u64,
+    /// Message passed to the contract in the operation.
+    pub msg: Binary,
+    /// Blockchain height in the moment of the operation.
+    pub height: u64,
+}
+
 /// Contract code base data.
+#[derive(Clone)]
 struct CodeData {
     /// Address of an account that initially stored the contract code.
     creator: Addr,
@@ -125,14 +377,56 @@ pub trait Wasm<ExecC, QueryC> {
 
     /// Duplicates the contract's code with specified identifier
     /// and returns an identifier of the copy of the contract's code.
+    ///
+    /// The copy has the same checksum as the original code, so instantiating both codes
+    /// with `Instantiate2` by the same creator and with the same salt
+    /// results in the same contract address.
     fn duplicate_code(&mut self, code_id: u64) -> AnyResult<u64>;
 
+    /// Duplicates the contract's code with specified identifier, assigning a new checksum
+    /// to the copy, and returns an identifier of the copy of the contract's code.
+    ///
+    /// The default implementation returns an error.
+    fn duplicate_code_with_new_checksum(&mut self, code_id: u64) -> AnyResult<u64> {
+        bail!(
+            "duplicating code {} with a new checksum is not supported",
+            code_id
+        )
+    }
+
+    /// Returns the address of the creator of the contract's code with specified identifier.
+    ///
+    /// The default implementation returns an error.
+    fn code_creator(&self, code_id: u64) -> AnyResult<Addr> {
+        bail!("creator of code {} is not available", code_id)
+    }
+
     /// Returns `ContractData` for the contract with specified address.
     fn contract_data(&self, storage: &dyn Storage, address: &Addr) -> AnyResult<ContractData>;
 
+    /// Returns addresses and `ContractData` of all contracts, ordered by address.
+    ///
+    /// The default implementation returns an error.
+    fn contracts(&self, storage: &dyn Storage) -> AnyResult<Vec<(Addr, ContractData)>> {
+        let _ = storage;
+        bail!("listing contracts is not supported")
+    }
+
     /// Returns a raw state dump of all key-values held by a contract with specified address.
     fn dump_wasm_raw(&self, storage: &dyn Storage, address: &Addr) -> Vec<Record>;
 
+    /// Returns the history of the contract with specified address, oldest entry first.
+    ///
+    /// The default implementation returns an empty history.
+    fn contract_history(
+        &self,
+        storage: &dyn Storage,
+        address: &Addr,
+    ) -> AnyResult<Vec<ContractHistoryEntry>> {
+        let _ = (storage, address);
+        Ok(vec![])
+    }
+
     /// Returns the namespace of the contract storage.
     fn contract_namespace(&self, contract: &Addr) -> Vec<u8> {
         let mut name = b"contract_data/".to_vec();
@@ -165,18 +459,80 @@ pub trait Wasm<ExecC, QueryC> {
         let storage = PrefixedStorage::multilevel(storage, &[NAMESPACE_WASM, &namespace]);
         Box::new(storage)
     }
+
+    /// Returns statistics of contract calls, empty when statistics are not collected.
+    fn call_stats(&self) -> CallStats {
+        CallStats::default()
+    }
+
+    /// Clears the statistics of contract calls.
+    fn reset_call_stats(&self) {}
+
+    /// Returns statistics of storage operations performed by contracts,
+    /// empty when statistics are not collected.
+    fn storage_access_stats(&self) -> StorageAccessStats {
+        StorageAccessStats::default()
+    }
+
+    /// Clears the statistics of storage operations performed by contracts.
+    fn reset_storage_access_stats(&self) {}
+
+    /// Returns traces of reply invocations, empty when traces are not recorded.
+    fn reply_traces(&self) -> Vec<ReplyTrace> {
+        vec![]
+    }
+
+    /// Returns warnings about re-entrant calls into contracts.
+    fn reentrancy_warnings(&self) -> Vec<String> {
+        vec![]
+    }
 }
 
 /// A structure representing a default wasm keeper.
 pub struct WasmKeeper<ExecC, QueryC> {
-    /// Contract codes that stand for wasm code in real-life blockchain.
-    code_base: Vec<Box<dyn Contract<ExecC, QueryC>>>,
+    /// Contract codes that stand for wasm code in real-life blockchain,
+    /// shared between forked applications.
+    code_base: Vec<Arc<dyn Contract<ExecC, QueryC>>>,
     /// Code data with code base identifier and additional attributes.
     code_data: BTreeMap<u64, CodeData>,
     /// Contract's address generator.
-    address_generator: Box<dyn AddressGenerator>,
+    address_generator: Arc<dyn AddressGenerator>,
     /// Contract's code checksum generator.
-    checksum_generator: Box<dyn ChecksumGenerator>,
+    checksum_generator: Arc<dyn ChecksumGenerator>,
+    /// Maximum depth of nested smart queries.
+    max_query_depth: usize,
+    /// Addresses of contracts currently processing smart queries, outermost first.
+    query_stack: RefCell<Vec<Addr>>,
+    /// Statistics of contract calls, collected only when enabled.
+    call_stats: Option<RefCell<CallStats>>,
+    /// Statistics of storage operations performed by contracts, collected only when enabled.
+    storage_access_stats: Option<RefCell<StorageAccessStats>>,
+    /// Traces of reply invocations, recorded only when enabled.
+    reply_traces: Option<RefCell<Vec<ReplyTrace>>>,
+    /// Behavior of the re-entrancy guard, disabled when not set.
+    reentrancy_guard: Option<GuardMode>,
+    /// Maximum depth of nested contract calls.
+    max_call_depth: usize,
+    /// Maximum number of submessages processed in a single top-level contract call.
+    max_submessages: Option<usize>,
+    /// Number of submessages processed in the current top-level contract call.
+    submessage_count: Cell<usize>,
+    /// Addresses of contracts currently executing, outermost first.
+    call_stack: RefCell<Vec<Addr>>,
+    /// Warnings about re-entrant calls, recorded in [GuardMode::Warn].
+    reentrancy_warnings: RefCell<Vec<String>>,
+    /// Flag indicating if the history of contracts is recorded.
+    contract_history: bool,
+    /// Validation of contract labels.
+    label_validation: LabelValidation,
+    /// Limits of data returned from contract calls.
+    response_limits: ResponseLimits,
+    /// Flag indicating if the sender of messages sent by contracts is verified.
+    sender_checks: bool,
+    /// Flag indicating if the `migrate` event reports the code id the contract migrated from.
+    old_code_id_in_migrate_event: bool,
+    /// Sender expected in the wasm message dispatched from the current submessage.
+    expected_sender: RefCell<Option<Addr>>,
     /// Just markers to make type elision fork when using it as `Wasm` trait
     _p: std::marker::PhantomData<QueryC>,
 }
@@ -187,8 +543,56 @@ impl<ExecC, QueryC> Default for WasmKeeper<ExecC, QueryC> {
         Self {
             code_base: Vec::default(),
             code_data: BTreeMap::default(),
-            address_generator: Box::new(SimpleAddressGenerator),
-            checksum_generator: Box::new(SimpleChecksumGenerator),
+            address_generator: Arc::new(SimpleAddressGenerator),
+            checksum_generator: Arc::new(SimpleChecksumGenerator),
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            query_stack: RefCell::default(),
+            call_stats: None,
+            storage_access_stats: None,
+            reply_traces: None,
+            reentrancy_guard: None,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            max_submessages: None,
+            submessage_count: Cell::default(),
+            call_stack: RefCell::default(),
+            reentrancy_warnings: RefCell::default(),
+            contract_history: false,
+            label_validation: LabelValidation::default(),
+            response_limits: ResponseLimits::default(),
+            sender_checks: false,
+            old_code_id_in_migrate_event: false,
+            expected_sender: RefCell::default(),
+            _p: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<ExecC, QueryC> Clone for WasmKeeper<ExecC, QueryC> {
+    /// Returns a copy of this keeper sharing the contract codes and generators with the original,
+    /// used when forking an application, see [App::fork](crate::App::fork).
+    fn clone(&self) -> Self {
+        Self {
+            code_base: self.code_base.clone(),
+            code_data: self.code_data.clone(),
+            address_generator: self.address_generator.clone(),
+            checksum_generator: self.checksum_generator.clone(),
+            max_query_depth: self.max_query_depth,
+            query_stack: self.query_stack.clone(),
+            call_stats: self.call_stats.clone(),
+            storage_access_stats: self.storage_access_stats.clone(),
+            reply_traces: self.reply_traces.clone(),
+            reentrancy_guard: self.reentrancy_guard,
+            max_call_depth: self.max_call_depth,
+            max_submessages: self.max_submessages,
+            submessage_count: self.submessage_count.clone(),
+            call_stack: self.call_stack.clone(),
+            reentrancy_warnings: self.reentrancy_warnings.clone(),
+            contract_history: self.contract_history,
+            label_validation: self.label_validation.clone(),
+            response_limits: self.response_limits,
+            sender_checks: self.sender_checks,
+            old_code_id_in_migrate_event: self.old_code_id_in_migrate_event,
+            expected_sender: self.expected_sender.clone(),
             _p: std::marker::PhantomData,
         }
     }
@@ -254,7 +658,7 @@ where
                 );
                 to_json_binary(&res).map_err(Into::into)
             }
-            _ => unimplemented!("{}", Error::unsupported_wasm_query(request)),
+            _ => bail!(Error::unsupported_wasm_query(request)),
         }
     }
 
@@ -266,17 +670,19 @@ where
         block: &BlockInfo,
         msg: WasmSudo,
     ) -> AnyResult<AppResponse> {
-        let custom_event = Event::new("sudo").add_attribute(CONTRACT_ATTR, &msg.contract_addr);
-        let res = self.call_sudo(
-            msg.contract_addr.clone(),
-            api,
-            storage,
-            router,
-            block,
-            msg.message.to_vec(),
-        )?;
-        let (res, msgs) = self.build_app_response(&msg.contract_addr, custom_event, res);
-        self.process_response(api, router, storage, block, msg.contract_addr, res, msgs)
+        self.with_call_frame(&msg.contract_addr.clone(), || {
+            let custom_event = Event::new("sudo").add_attribute(CONTRACT_ATTR, &msg.contract_addr);
+            let res = self.call_sudo(
+                msg.contract_addr.clone(),
+                api,
+                storage,
+                router,
+                block,
+                msg.message.to_vec(),
+            )?;
+            let (res, msgs) = self.build_app_response(&msg.contract_addr, custom_event, res);
+            self.process_response(api, router, storage, block, msg.contract_addr, res, msgs)
+        })
     }
 
     /// Stores the contract's code in the in-memory lookup table.
@@ -323,6 +729,33 @@ where
         Ok(new_code_id)
     }
 
+    /// Duplicates the contract's code with specified identifier,
+    /// the checksum of the copy is generated using the checksum generator.
+    /// Returns an identifier of the copy of the contract's code.
+    fn duplicate_code_with_new_checksum(&mut self, code_id: u64) -> AnyResult<u64> {
+        let code_data = self.code_data(code_id)?;
+        let new_code_id = self
+            .next_code_id()
+            .ok_or_else(Error::no_more_code_id_available)?;
+        let checksum = self
+            .checksum_generator
+            .checksum(&code_data.creator, new_code_id);
+        self.code_data.insert(
+            new_code_id,
+            CodeData {
+                creator: code_data.creator.clone(),
+                checksum,
+                source_id: code_data.source_id,
+            },
+        );
+        Ok(new_code_id)
+    }
+
+    /// Returns the address of the creator of the contract's code with specified identifier.
+    fn code_creator(&self, code_id: u64) -> AnyResult<Addr> {
+        Ok(self.code_data(code_id)?.creator.clone())
+    }
+
     /// Returns `ContractData` for the contract with specified address.
     fn contract_data(&self, storage: &dyn Storage, address: &Addr) -> AnyResult<ContractData> {
         CONTRACTS
@@ -330,11 +763,79 @@ where
             .map_err(Into::into)
     }
 
+    /// Returns addresses and `ContractData` of all contracts, ordered by address.
+    fn contracts(&self, storage: &dyn Storage) -> AnyResult<Vec<(Addr, ContractData)>> {
+        CONTRACTS
+            .range(
+                &prefixed_read(storage, NAMESPACE_WASM),
+                None,
+                None,
+                Order::Ascending,
+            )
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
     /// Returns a raw state dump of all key-values held by a contract with specified address.
     fn dump_wasm_raw(&self, storage: &dyn Storage, address: &Addr) -> Vec<Record> {
         let storage = self.contract_storage(storage, address);
         storage.range(None, None, Order::Ascending).collect()
     }
+
+    /// Returns the history of the contract with specified address, oldest entry first.
+    fn contract_history(
+        &self,
+        storage: &dyn Storage,
+        address: &Addr,
+    ) -> AnyResult<Vec<ContractHistoryEntry>> {
+        Ok(CONTRACT_HISTORY
+            .may_load(&prefixed_read(storage, NAMESPACE_WASM), address)?
+            .unwrap_or_default())
+    }
+
+    /// Returns statistics of contract calls, empty when statistics are not collected.
+    fn call_stats(&self) -> CallStats {
+        self.call_stats
+            .as_ref()
+            .map(|call_stats| call_stats.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Clears the statistics of contract calls.
+    fn reset_call_stats(&self) {
+        if let Some(call_stats) = &self.call_stats {
+            call_stats.take();
+        }
+    }
+
+    /// Returns statistics of storage operations performed by contracts,
+    /// empty when statistics are not collected.
+    fn storage_access_stats(&self) -> StorageAccessStats {
+        self.storage_access_stats
+            .as_ref()
+            .map(|stats| stats.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Clears the statistics of storage operations performed by contracts.
+    fn reset_storage_access_stats(&self) {
+        if let Some(stats) = &self.storage_access_stats {
+            stats.take();
+        }
+    }
+
+    /// Returns traces of reply invocations, empty when traces are not recorded.
+    fn reply_traces(&self) -> Vec<ReplyTrace> {
+        self.reply_traces
+            .as_ref()
+            .map(|reply_traces| reply_traces.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns warnings about re-entrant calls into contracts.
+    fn reentrancy_warnings(&self) -> Vec<String> {
+        self.reentrancy_warnings.borrow().clone()
+    }
 }
 
 impl<ExecC, QueryC> WasmKeeper<ExecC, QueryC>
@@ -394,7 +895,7 @@ where
         mut self,
         address_generator: impl AddressGenerator + 'static,
     ) -> Self {
-        self.address_generator = Box::new(address_generator);
+        self.address_generator = Arc::new(address_generator);
         self
     }
 
@@ -425,10 +926,353 @@ where
         mut self,
         checksum_generator: impl ChecksumGenerator + 'static,
     ) -> Self {
-        self.checksum_generator = Box::new(checksum_generator);
+        self.checksum_generator = Arc::new(checksum_generator);
+        self
+    }
+
+    /// Sets the maximum depth of nested smart queries.
+    ///
+    /// When a smart query issued by a contract exceeds this depth (like in a loop of contracts
+    /// querying each other), an error listing all queried contracts is returned.
+    /// Default value is the same as in `wasmd` (10).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_max_query_depth(5);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_max_query_depth(mut self, max_query_depth: usize) -> Self {
+        self.max_query_depth = max_query_depth;
+        self
+    }
+
+    /// Changes the maximum depth of nested smart queries in an existing [WasmKeeper],
+    /// e.g. in a behavior change scheduled with
+    /// [App::schedule_behavior_change](crate::App::schedule_behavior_change).
+    pub fn set_max_query_depth(&mut self, max_query_depth: usize) {
+        self.max_query_depth = max_query_depth;
+    }
+
+    /// Sets the maximum depth of nested contract calls.
+    ///
+    /// The depth is the number of contract calls (`instantiate`, `execute`, `migrate`
+    /// or `sudo`) nested within a single top-level contract call, the top-level call has depth 1.
+    /// When a call exceeds this depth (like a contract recursively calling itself), an error
+    /// with the reached depth and all called contracts is returned.
+    /// Default value is the same as in `wasmd` (500).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_max_call_depth(10);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_max_call_depth(mut self, max_call_depth: usize) -> Self {
+        self.max_call_depth = max_call_depth;
+        self
+    }
+
+    /// Changes the maximum depth of nested contract calls in an existing [WasmKeeper],
+    /// e.g. in a behavior change scheduled with
+    /// [App::schedule_behavior_change](crate::App::schedule_behavior_change).
+    pub fn set_max_call_depth(&mut self, max_call_depth: usize) {
+        self.max_call_depth = max_call_depth;
+    }
+
+    /// Sets the maximum number of submessages processed in a single top-level contract call,
+    /// including submessages returned by all nested calls. There is no limit by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_max_submessages(Some(100));
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_max_submessages(mut self, max_submessages: Option<usize>) -> Self {
+        self.max_submessages = max_submessages;
+        self
+    }
+
+    /// Enables or disables collecting statistics of contract calls.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_call_stats(true);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// assert!(app.call_stats().is_empty());
+    /// ```
+    pub fn with_call_stats(mut self, enabled: bool) -> Self {
+        self.call_stats = enabled.then(RefCell::default);
+        self
+    }
+
+    /// Enables or disables counting storage operations performed by contracts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_storage_access_stats(true);
+    ///
+    /// let app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// assert!(app.last_execution_stats().is_empty());
+    /// ```
+    pub fn with_storage_access_stats(mut self, enabled: bool) -> Self {
+        self.storage_access_stats = enabled.then(RefCell::default);
         self
     }
 
+    /// Enables or disables recording traces of reply invocations,
+    /// including payloads of submessages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_reply_traces(true);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// assert!(app.reply_traces().is_empty());
+    /// ```
+    pub fn with_reply_traces(mut self, enabled: bool) -> Self {
+        self.reply_traces = enabled.then(RefCell::default);
+        self
+    }
+
+    /// Enables the guard detecting re-entrant calls into contracts.
+    ///
+    /// The guard tracks the stack of contracts currently processing `execute`, `instantiate`,
+    /// `migrate` or `sudo` calls (including messages returned from these calls). A call into
+    /// a contract already present on this stack is re-entrant, including messages sent
+    /// by a contract to itself. Replies and queries are not considered re-entrant calls.
+    /// Depending on the [GuardMode], re-entrant calls fail or a warning is recorded, available by calling
+    /// [App::reentrancy_warnings](crate::App::reentrancy_warnings).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, GuardMode, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_reentrancy_guard(GuardMode::Deny);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_reentrancy_guard(mut self, mode: GuardMode) -> Self {
+        self.reentrancy_guard = Some(mode);
+        self
+    }
+
+    /// Enables recording the history of contracts.
+    ///
+    /// When enabled, each instantiation and migration of a contract is recorded together
+    /// with the message passed to the contract. The history is available by calling
+    /// [App::contract_history](crate::App::contract_history).
+    /// When disabled (default), the history is not stored and is always empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_contract_history(true);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_contract_history(mut self, enabled: bool) -> Self {
+        self.contract_history = enabled;
+        self
+    }
+
+    /// Sets the validation of contract labels, by default the rules of `wasmd` are applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, LabelValidation, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_label_validation(LabelValidation::Permissive);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_label_validation(mut self, label_validation: LabelValidation) -> Self {
+        self.label_validation = label_validation;
+        self
+    }
+
+    /// Sets the limits of data returned from contract calls,
+    /// by default the limits of CosmWasm VM are applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, ResponseLimits, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_response_limits(ResponseLimits {
+    ///     max_events: Some(10),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_response_limits(mut self, response_limits: ResponseLimits) -> Self {
+        self.response_limits = response_limits;
+        self
+    }
+
+    /// Enables or disables verifying the sender of messages sent by contracts.
+    ///
+    /// When enabled, every submessage is checked to be sent by the contract currently
+    /// being executed, and every wasm message dispatched from a submessage is checked
+    /// to have the emitting contract as its sender (`info.sender` of the called contract).
+    /// A violation of these invariants is a bug in the multi-test and is reported as an error.
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_sender_checks(true);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_sender_checks(mut self, enabled: bool) -> Self {
+        self.sender_checks = enabled;
+        self
+    }
+
+    /// Enables or disables reporting the code id the contract migrated from
+    /// in the `old_code_id` attribute of the `migrate` event.
+    ///
+    /// `wasmd` does not emit this attribute, so it is disabled by default to keep
+    /// the events identical to the events emitted by the chain.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{no_init, AppBuilder, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_old_code_id_in_migrate_event(true);
+    ///
+    /// let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_old_code_id_in_migrate_event(mut self, enabled: bool) -> Self {
+        self.old_code_id_in_migrate_event = enabled;
+        self
+    }
+
+    /// Verifies that the wasm message dispatched from a submessage
+    /// is sent by the contract that emitted the submessage.
+    fn check_sender(&self, sender: &Addr) -> AnyResult<()> {
+        if let Some(expected) = self.expected_sender.borrow_mut().take() {
+            if expected != *sender {
+                bail!(Error::wasm_message_sender_mismatch(expected, sender));
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends an entry to the history of the contract, when the contract history is enabled.
+    fn append_contract_history(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        operation: ContractHistoryOperation,
+        code_id: u64,
+        msg: &Binary,
+        block: &BlockInfo,
+    ) -> AnyResult<()> {
+        if !self.contract_history {
+            return Ok(());
+        }
+        let entry = ContractHistoryEntry {
+            operation,
+            code_id,
+            msg: msg.clone(),
+            height: block.height,
+        };
+        CONTRACT_HISTORY.update(
+            &mut prefixed(storage, NAMESPACE_WASM),
+            address,
+            |history| -> StdResult<_> {
+                let mut history = history.unwrap_or_default();
+                history.push(entry);
+                Ok(history)
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Runs the call into the contract with specified address,
+    /// keeping the address on the call stack checked by the call depth limit
+    /// and the re-entrancy guard.
+    fn with_call_frame<T>(
+        &self,
+        address: &Addr,
+        call: impl FnOnce() -> AnyResult<T>,
+    ) -> AnyResult<T> {
+        {
+            let mut call_stack = self.call_stack.borrow_mut();
+            let call_chain = || {
+                call_stack
+                    .iter()
+                    .chain([address])
+                    .map(Addr::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            };
+            if call_stack.len() >= self.max_call_depth {
+                bail!(Error::max_call_depth_exceeded(
+                    call_stack.len() + 1,
+                    self.max_call_depth,
+                    call_chain()
+                ));
+            }
+            if let Some(mode) = self.reentrancy_guard {
+                if call_stack.contains(address) {
+                    let err = Error::reentrant_call(address, call_chain());
+                    match mode {
+                        GuardMode::Deny => bail!(err),
+                        GuardMode::Warn => {
+                            self.reentrancy_warnings.borrow_mut().push(err.to_string())
+                        }
+                    }
+                }
+            }
+            if call_stack.is_empty() {
+                // new top-level contract call
+                self.submessage_count.set(0);
+            }
+            call_stack.push(address.clone());
+        }
+        let result = call();
+        self.call_stack.borrow_mut().pop();
+        result
+    }
+
+    /// Increments the call counter of the contract's entry-point, when statistics are enabled.
+    fn record_call(&self, address: &Addr, entry_point: EntryPoint) {
+        if let Some(call_stats) = &self.call_stats {
+            call_stats.borrow_mut().record(address, entry_point);
+        }
+    }
+
     /// Returns a handler to code of the contract with specified code id.
     pub fn contract_code(&self, code_id: u64) -> AnyResult<&dyn Contract<ExecC, QueryC>> {
         let code_data = self.code_data(code_id)?;
@@ -465,10 +1309,11 @@ where
         Ok(())
     }
 
-    fn verify_response<T>(response: Response<T>) -> AnyResult<Response<T>>
+    fn verify_response<T>(&self, response: Response<T>) -> AnyResult<Response<T>>
     where
         T: CustomMsg,
     {
+        self.response_limits.check_response(&response)?;
         Self::verify_attributes(&response.attributes)?;
 
         for event in &response.events {
@@ -495,7 +1340,7 @@ where
             .checksum()
             .unwrap_or(self.checksum_generator.checksum(&creator, code_id));
         // store the 'source' code of the contract
-        self.code_base.push(code);
+        self.code_base.push(Arc::from(code));
         // store the additional code attributes like creator address and checksum
         self.code_data.insert(
             code_id,
@@ -514,6 +1359,10 @@ where
     }
 
     /// Executes the contract's `query` entry-point.
+    ///
+    /// Errors returned by the contract are wrapped in the context
+    /// [QueryContractFailed](Error::QueryContractFailed), so the original error
+    /// can still be retrieved by downcasting.
     pub fn query_smart(
         &self,
         address: Addr,
@@ -523,14 +1372,41 @@ where
         block: &BlockInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Binary> {
-        self.with_storage_readonly(
+        {
+            let mut query_stack = self.query_stack.borrow_mut();
+            if query_stack.len() >= self.max_query_depth {
+                let query_chain = query_stack
+                    .iter()
+                    .chain([&address])
+                    .map(Addr::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                bail!(Error::max_query_depth_exceeded(
+                    self.max_query_depth,
+                    query_chain
+                ));
+            }
+            query_stack.push(address.clone());
+        }
+        self.record_call(&address, EntryPoint::Query);
+        let contract_addr = address.clone();
+        let result = self.with_storage_readonly(
             api,
             storage,
             querier,
             block,
             address,
-            |handler, deps, env| handler.query(deps, env, msg),
-        )
+            |handler, deps, env| {
+                handler.query(deps, env, msg).map_err(|err| {
+                    let context = Error::query_contract_failed(contract_addr, err.to_string());
+                    err.context(context)
+                })
+            },
+        );
+        self.query_stack.borrow_mut().pop();
+        let response = result?;
+        self.response_limits.check_query_response(&response)?;
+        Ok(response)
     }
 
     /// Returns the value stored under specified key in contracts storage.
@@ -604,44 +1480,60 @@ where
         sender: Addr,
         msg: WasmMsg,
     ) -> AnyResult<AppResponse> {
+        if self.sender_checks {
+            self.check_sender(&sender)?;
+        }
         match msg {
             WasmMsg::Execute {
                 contract_addr,
                 msg,
                 funds,
             } => {
+                // the order of checks is the same as in `wasmd`: contract address,
+                // contract existence, funds transfer and finally the contract call
                 let contract_addr = api.addr_validate(&contract_addr)?;
-                // first move the cash
-                self.send(
-                    api,
-                    storage,
-                    router,
-                    block,
-                    sender.clone(),
-                    contract_addr.clone().into(),
-                    &funds,
-                )?;
-
-                // then call the contract
-                let info = MessageInfo { sender, funds };
-                let res = self.call_execute(
-                    api,
-                    storage,
-                    contract_addr.clone(),
-                    router,
-                    block,
-                    info,
-                    msg.to_vec(),
-                )?;
-
-                let custom_event =
-                    Event::new("execute").add_attribute(CONTRACT_ATTR, &contract_addr);
-
-                let (res, msgs) = self.build_app_response(&contract_addr, custom_event, res);
-                let mut res =
-                    self.process_response(api, router, storage, block, contract_addr, res, msgs)?;
-                res.data = execute_response(res.data);
-                Ok(res)
+                self.contract_data(storage, &contract_addr)?;
+                self.with_call_frame(&contract_addr.clone(), || {
+                    // first move the cash, bank events are reported before the execute event
+                    let transfer = self.send(
+                        api,
+                        storage,
+                        router,
+                        block,
+                        sender.clone(),
+                        contract_addr.clone().into(),
+                        &funds,
+                    )?;
+
+                    // then call the contract
+                    let info = MessageInfo { sender, funds };
+                    let res = self.call_execute(
+                        api,
+                        storage,
+                        contract_addr.clone(),
+                        router,
+                        block,
+                        info,
+                        msg.to_vec(),
+                    )?;
+
+                    let custom_event =
+                        Event::new("execute").add_attribute(CONTRACT_ATTR, &contract_addr);
+
+                    let (res, msgs) = self.build_app_response(&contract_addr, custom_event, res);
+                    let mut res = self.process_response(
+                        api,
+                        router,
+                        storage,
+                        block,
+                        contract_addr,
+                        res,
+                        msgs,
+                    )?;
+                    res.events.splice(0..0, transfer.events);
+                    res.data = execute_response(res.data);
+                    Ok(res)
+                })
             }
             WasmMsg::Instantiate {
                 admin,
@@ -681,34 +1573,56 @@ where
                 let contract_addr = api.addr_validate(&contract_addr)?;
 
                 // check admin status and update the stored code_id
-                if new_code_id as usize > self.code_data.len() {
+                if !self.code_data.contains_key(&new_code_id) {
                     bail!("Cannot migrate contract to unregistered code id");
                 }
                 let mut data = self.contract_data(storage, &contract_addr)?;
                 if data.admin != Some(sender) {
                     bail!("Only admin can migrate contract: {:?}", data.admin);
                 }
+                let old_code_id = data.code_id;
                 data.code_id = new_code_id;
                 self.save_contract(storage, &contract_addr, &data)?;
-
-                // then call migrate
-                let res = self.call_migrate(
-                    contract_addr.clone(),
-                    api,
+                self.append_contract_history(
                     storage,
-                    router,
+                    &contract_addr,
+                    ContractHistoryOperation::Migrate,
+                    new_code_id,
+                    &msg,
                     block,
-                    msg.to_vec(),
                 )?;
 
-                let custom_event = Event::new("migrate")
-                    .add_attribute(CONTRACT_ATTR, &contract_addr)
-                    .add_attribute("code_id", new_code_id.to_string());
-                let (res, msgs) = self.build_app_response(&contract_addr, custom_event, res);
-                let mut res =
-                    self.process_response(api, router, storage, block, contract_addr, res, msgs)?;
-                res.data = execute_response(res.data);
-                Ok(res)
+                // then call migrate
+                self.with_call_frame(&contract_addr.clone(), || {
+                    let res = self.call_migrate(
+                        contract_addr.clone(),
+                        api,
+                        storage,
+                        router,
+                        block,
+                        msg.to_vec(),
+                    )?;
+
+                    let mut custom_event = Event::new("migrate")
+                        .add_attribute(CONTRACT_ATTR, &contract_addr)
+                        .add_attribute("code_id", new_code_id.to_string());
+                    if self.old_code_id_in_migrate_event {
+                        custom_event =
+                            custom_event.add_attribute("old_code_id", old_code_id.to_string());
+                    }
+                    let (res, msgs) = self.build_app_response(&contract_addr, custom_event, res);
+                    let mut res = self.process_response(
+                        api,
+                        router,
+                        storage,
+                        block,
+                        contract_addr,
+                        res,
+                        msgs,
+                    )?;
+                    res.data = execute_response(res.data);
+                    Ok(res)
+                })
             }
             WasmMsg::UpdateAdmin {
                 contract_addr,
@@ -717,11 +1631,21 @@ where
             WasmMsg::ClearAdmin { contract_addr } => {
                 self.update_admin(api, storage, sender, &contract_addr, None)
             }
-            _ => unimplemented!("{}", Error::unsupported_wasm_message(msg)),
+            _ => bail!(Error::unsupported_wasm_message(msg)),
         }
     }
 
     /// Processes WasmMsg::Instantiate and WasmMsg::Instantiate2 messages.
+    ///
+    /// When several problems occur at once, the reported error is the same as in `wasmd`,
+    /// because the validation steps are performed in the same order:
+    /// 1. code identifier is not zero,
+    /// 2. label validation,
+    /// 3. code existence,
+    /// 4. instantiate permission, see [WasmPermissionsSudo](crate::WasmPermissionsSudo),
+    /// 5. contract address generation (address must not be already taken),
+    /// 6. funds transfer,
+    /// 7. contract call.
     fn process_wasm_msg_instantiate(
         &self,
         api: &dyn Api,
@@ -736,8 +1660,15 @@ where
         label: String,
         salt: Option<Binary>,
     ) -> AnyResult<AppResponse> {
-        if label.is_empty() {
-            bail!("Label is required on all contracts");
+        if code_id < 1 {
+            bail!(Error::invalid_code_id());
+        }
+
+        self.label_validation.validate(&label)?;
+
+        // unregistered codes are reported when registering the contract
+        if self.code_data.contains_key(&code_id) {
+            check_instantiate(storage, code_id, &sender)?;
         }
 
         let contract_addr = self.register_contract(
@@ -750,46 +1681,57 @@ where
             block.height,
             salt,
         )?;
-
-        // move the cash
-        self.send(
-            api,
+        self.append_contract_history(
             storage,
-            router,
+            &contract_addr,
+            ContractHistoryOperation::Init,
+            code_id,
+            &msg,
             block,
-            sender.clone(),
-            contract_addr.clone().into(),
-            &funds,
         )?;
 
-        // then call the contract
-        let info = MessageInfo { sender, funds };
-        let res = self.call_instantiate(
-            contract_addr.clone(),
-            api,
-            storage,
-            router,
-            block,
-            info,
-            msg.to_vec(),
-        )?;
+        self.with_call_frame(&contract_addr.clone(), || {
+            // move the cash, bank events are reported before the instantiate event
+            let transfer = self.send(
+                api,
+                storage,
+                router,
+                block,
+                sender.clone(),
+                contract_addr.clone().into(),
+                &funds,
+            )?;
+
+            // then call the contract
+            let info = MessageInfo { sender, funds };
+            let res = self.call_instantiate(
+                contract_addr.clone(),
+                api,
+                storage,
+                router,
+                block,
+                info,
+                msg.to_vec(),
+            )?;
 
-        let custom_event = Event::new("instantiate")
-            .add_attribute(CONTRACT_ATTR, &contract_addr)
-            .add_attribute("code_id", code_id.to_string());
+            let custom_event = Event::new("instantiate")
+                .add_attribute(CONTRACT_ATTR, &contract_addr)
+                .add_attribute("code_id", code_id.to_string());
 
-        let (res, msgs) = self.build_app_response(&contract_addr, custom_event, res);
-        let mut res = self.process_response(
-            api,
-            router,
-            storage,
-            block,
-            contract_addr.clone(),
-            res,
-            msgs,
-        )?;
-        res.data = Some(instantiate_response(res.data, &contract_addr));
-        Ok(res)
+            let (res, msgs) = self.build_app_response(&contract_addr, custom_event, res);
+            let mut res = self.process_response(
+                api,
+                router,
+                storage,
+                block,
+                contract_addr.clone(),
+                res,
+                msgs,
+            )?;
+            res.events.splice(0..0, transfer.events);
+            res.data = Some(instantiate_response(res.data, &contract_addr));
+            Ok(res)
+        })
     }
 
     /// This will execute the given messages, making all changes to the local cache.
@@ -818,10 +1760,31 @@ where
             ..
         } = msg;
 
+        let submessage_count = self.submessage_count.get() + 1;
+        if let Some(max_submessages) = self.max_submessages {
+            if submessage_count > max_submessages {
+                bail!(Error::too_many_submessages(max_submessages));
+            }
+        }
+        self.submessage_count.set(submessage_count);
+
+        if self.sender_checks {
+            // submessages are sent only by the contract being currently executed
+            let current = self.call_stack.borrow().last().cloned();
+            if current.as_ref() != Some(&contract) {
+                let executing = current.map_or("no contract".to_string(), Addr::into_string);
+                bail!(Error::submessage_sender_mismatch(&contract, executing));
+            }
+            if matches!(msg, cosmwasm_std::CosmosMsg::Wasm(_)) {
+                *self.expected_sender.borrow_mut() = Some(contract.clone());
+            }
+        }
+
         // execute in cache
         let res = transactional(storage, |write_cache, _| {
             router.execute(api, write_cache, block, contract.clone(), msg)
         });
+        self.expected_sender.borrow_mut().take();
 
         // call reply if meaningful
         if let Ok(mut r) = res {
@@ -980,37 +1943,62 @@ where
             bail!("Cannot init contract with unregistered code id");
         }
 
-        // generate a new contract address
+        // collect all details of the contract instantiation
+        let admin = admin.into();
+        let code_data = self.code_data(code_id)?;
         let instance_id = self.instance_count(storage) as u64;
-        let addr = if let Some(salt_binary) = salt.into() {
+        let instantiation_info = ContractInstantiationInfo {
+            code_id,
+            instance_id,
+            label: &label,
+            creator: &creator,
+            admin: admin.as_ref(),
+            checksum: &code_data.checksum,
+        };
+
+        // generate a new contract address
+        let salt = salt.into();
+        let addr = if let Some(salt_binary) = &salt {
             // generate predictable contract address when salt is provided
-            let code_data = self.code_data(code_id)?;
-            let canonical_addr = &api.addr_canonicalize(creator.as_ref())?;
-            self.address_generator.predictable_contract_address(
-                api,
-                storage,
-                code_id,
-                instance_id,
-                code_data.checksum.as_slice(),
-                canonical_addr,
-                salt_binary.as_slice(),
-            )?
+            self.address_generator
+                .predictable_contract_address_with_info(
+                    api,
+                    storage,
+                    &instantiation_info,
+                    salt_binary.as_slice(),
+                )?
         } else {
             // generate non-predictable contract address
             self.address_generator
-                .contract_address(api, storage, code_id, instance_id)?
+                .contract_address_with_info(api, storage, &instantiation_info)?
         };
 
         // contract with the same address must not already exist
-        if self.contract_data(storage, &addr).is_ok() {
+        if let Ok(existing) = self.contract_data(storage, &addr) {
+            // report when the address collides because the code was duplicated with the same checksum
+            if salt.is_some()
+                && existing.code_id != code_id
+                && existing.creator == creator
+                && self
+                    .code_data(existing.code_id)
+                    .is_ok_and(|existing_code| existing_code.checksum == code_data.checksum)
+            {
+                bail!(Error::duplicated_contract_address_same_checksum(
+                    addr,
+                    existing.code_id
+                ));
+            }
             bail!(Error::duplicated_contract_address(addr));
         }
 
+        // the address must match the one pinned for the label in the address book
+        check_pinned_address(storage, &label, &addr)?;
+
         // prepare contract data and save new contract instance
         let info = ContractData {
             code_id,
             creator,
-            admin: admin.into(),
+            admin,
             label,
             created,
         };
@@ -1029,7 +2017,8 @@ where
         info: MessageInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        self.record_call(&address, EntryPoint::Execute);
+        self.verify_response(self.with_storage(
             api,
             storage,
             router,
@@ -1050,7 +2039,8 @@ where
         info: MessageInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        self.record_call(&address, EntryPoint::Instantiate);
+        self.verify_response(self.with_storage(
             api,
             storage,
             router,
@@ -1070,7 +2060,15 @@ where
         block: &BlockInfo,
         reply: Reply,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        self.record_call(&address, EntryPoint::Reply);
+        if let Some(reply_traces) = &self.reply_traces {
+            reply_traces.borrow_mut().push(ReplyTrace::new(
+                address.clone(),
+                reply.id,
+                reply.payload.clone(),
+            ));
+        }
+        self.verify_response(self.with_storage(
             api,
             storage,
             router,
@@ -1090,7 +2088,8 @@ where
         block: &BlockInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        self.record_call(&address, EntryPoint::Sudo);
+        self.verify_response(self.with_storage(
             api,
             storage,
             router,
@@ -1110,7 +2109,8 @@ where
         block: &BlockInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        self.record_call(&address, EntryPoint::Migrate);
+        self.verify_response(self.with_storage(
             api,
             storage,
             router,
@@ -1144,15 +2144,24 @@ where
     {
         let contract = self.contract_data(storage, &address)?;
         let handler = self.contract_code(contract.code_id)?;
-        let storage = self.contract_storage(storage, &address);
-        let env = self.get_env(address, block);
+        let storage = self.count_storage_access(self.contract_storage(storage, &address), &address);
+        let storage = QueryStorage::new(storage);
+        let env = self.get_env(address.clone(), block);
 
         let deps = Deps {
-            storage: storage.as_ref(),
+            storage: &storage,
             api,
             querier: QuerierWrapper::new(querier),
         };
-        action(handler, deps, env)
+        let result = action(handler, deps, env);
+        // writes are reported instead of the result, the query must not modify the storage
+        if let Some(key) = storage.attempted_write.into_inner() {
+            bail!(Error::query_storage_write(
+                address,
+                HexBinary::from(key).to_hex()
+            ));
+        }
+        result
     }
 
     fn with_storage<F, T>(
@@ -1173,11 +2182,18 @@ where
 
         // We don't actually need a transaction here, as it is already embedded in a transactional.
         // execute_submsg or App.execute_multi.
-        // However, we need to get write and read access to the same storage in two different objects,
-        // and this is the only way I know how to do so.
-        transactional(storage, |write_cache, read_store| {
-            let mut contract_storage = self.contract_storage_mut(write_cache, &address);
-            let querier = RouterQuerier::new(router, api, read_store, block);
+        // However, the contract writes to its storage and queries (possibly itself) in the same call,
+        // so the write cache is shared by the contract storage and the querier, this way queries
+        // see the changes already made by the contract during this call.
+        transactional(storage, |write_cache, _| {
+            let shared_storage = SharedStorage::new(write_cache);
+            let mut write_handle = shared_storage.handle();
+            let read_handle = shared_storage.handle();
+            let mut contract_storage = self.count_storage_access(
+                self.contract_storage_mut(&mut write_handle, &address),
+                &address,
+            );
+            let querier = RouterQuerier::new(router, api, &read_handle, block);
             let env = self.get_env(address, block);
 
             let deps = DepsMut {
@@ -1189,6 +2205,18 @@ where
         })
     }
 
+    /// Wraps the contract storage to count storage operations, when statistics are collected.
+    fn count_storage_access<'a>(
+        &'a self,
+        storage: Box<dyn Storage + 'a>,
+        address: &Addr,
+    ) -> Box<dyn Storage + 'a> {
+        match &self.storage_access_stats {
+            Some(stats) => Box::new(CountingStorage::new(storage, address, stats)),
+            None => storage,
+        }
+    }
+
     /// Saves contract data in a storage under specified address.
     pub fn save_contract(
         &self,
@@ -1201,6 +2229,36 @@ where
             .map_err(Into::into)
     }
 
+    /// Returns the custom metadata attached to the contract with specified address,
+    /// empty when no metadata was attached.
+    pub fn contract_extensions(
+        &self,
+        storage: &dyn Storage,
+        address: &Addr,
+    ) -> AnyResult<BTreeMap<String, String>> {
+        Ok(CONTRACT_EXTENSIONS
+            .may_load(&prefixed_read(storage, NAMESPACE_WASM), address)?
+            .unwrap_or_default())
+    }
+
+    /// Attaches the custom metadata to the contract with specified address,
+    /// replacing the previously attached one.
+    pub fn save_contract_extensions(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        extensions: &BTreeMap<String, String>,
+    ) -> AnyResult<()> {
+        let mut storage = prefixed(storage, NAMESPACE_WASM);
+        if extensions.is_empty() {
+            CONTRACT_EXTENSIONS.remove(&mut storage, address);
+            return Ok(());
+        }
+        CONTRACT_EXTENSIONS
+            .save(&mut storage, address, extensions)
+            .map_err(Into::into)
+    }
+
     /// Returns the number of all contract instances.
     fn instance_count(&self, storage: &dyn Storage) -> usize {
         CONTRACTS
@@ -1214,6 +2272,55 @@ where
     }
 }
 
+/// Read-only contract storage passed to the `query` entry-point.
+///
+/// Attempted writes and removals are not performed, the key of the first one is recorded
+/// and reported as an error of the query, instead of aborting the whole test with a panic.
+struct QueryStorage<'a> {
+    /// Contract storage.
+    storage: Box<dyn Storage + 'a>,
+    /// Key of the first attempted write or removal.
+    attempted_write: RefCell<Option<Vec<u8>>>,
+}
+
+impl<'a> QueryStorage<'a> {
+    fn new(storage: Box<dyn Storage + 'a>) -> Self {
+        Self {
+            storage,
+            attempted_write: RefCell::default(),
+        }
+    }
+
+    fn record_write(&self, key: &[u8]) {
+        self.attempted_write
+            .borrow_mut()
+            .get_or_insert_with(|| key.to_vec());
+    }
+}
+
+impl Storage for QueryStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.storage.get(key)
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        self.storage.range(start, end, order)
+    }
+
+    fn set(&mut self, key: &[u8], _value: &[u8]) {
+        self.record_write(key);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.record_write(key);
+    }
+}
+
 #[derive(Clone, PartialEq, Message)]
 struct InstantiateResponse {
     #[prost(string, tag = "1")]
@@ -1294,6 +2401,9 @@ mod test {
             ibc: IbcFailingModule::new(),
             gov: GovFailingModule::new(),
             stargate: StargateFailing,
+            sudo_handlers: BTreeMap::new(),
+            event_provenance: false,
+            disabled_queries: Default::default(),
         }
     }
 
@@ -2136,4 +3246,46 @@ mod test {
             "custom address generator returned incorrect address"
         );
     }
+
+    #[test]
+    fn submessage_of_not_executed_contract_should_fail() {
+        let api = MockApi::default();
+        let mut storage = MockStorage::new();
+        let router = mock_router();
+        let block = mock_env().block;
+        let wasm_keeper = wasm_keeper().with_sender_checks(true);
+
+        let contract_addr = api.addr_make("contract");
+        let err = wasm_keeper
+            .execute_submsg(
+                &api,
+                &router,
+                &mut storage,
+                &block,
+                contract_addr.clone(),
+                SubMsg::new(BankMsg::Burn { amount: vec![] }),
+            )
+            .unwrap_err();
+        assert_eq!(
+            Error::submessage_sender_mismatch(&contract_addr, "no contract"),
+            err.downcast().unwrap()
+        );
+    }
+
+    #[test]
+    fn wasm_message_with_spoofed_sender_should_fail() {
+        let api = MockApi::default();
+        let wasm_keeper = wasm_keeper().with_sender_checks(true);
+
+        let contract_addr = api.addr_make("contract");
+        let spoofed_addr = api.addr_make("spoofed");
+        *wasm_keeper.expected_sender.borrow_mut() = Some(contract_addr.clone());
+        let err = wasm_keeper.check_sender(&spoofed_addr).unwrap_err();
+        assert_eq!(
+            Error::wasm_message_sender_mismatch(&contract_addr, &spoofed_addr),
+            err.downcast().unwrap()
+        );
+        // the expected sender is checked only once
+        wasm_keeper.check_sender(&spoofed_addr).unwrap();
+    }
 }
@@ -0,0 +1,188 @@
+//! # Wasm permissions
+//!
+//! Chain-level permissions of the wasm module, simulating permissioned chains
+//! where only approved addresses can store contract code and instantiating codes
+//! can be restricted, like with the `AccessConfig` of `wasmd`.
+//!
+//! Permissions are kept in the storage of the wasm module, so they are part of the state
+//! of the [App](crate::App), are set with [AppBuilder::with_wasm_permissions](crate::AppBuilder::with_wasm_permissions)
+//! and [AppBuilder::with_default_instantiate_permission](crate::AppBuilder::with_default_instantiate_permission),
+//! and changed at runtime with [WasmPermissionsSudo] messages, like governance proposals do.
+//! Instantiate permissions are enforced by [WasmKeeper](crate::WasmKeeper).
+
+use crate::error::{AnyResult, Error};
+use crate::executor::AppResponse;
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::wasm::NAMESPACE_WASM;
+use cosmwasm_std::{Addr, Storage};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Permissions to store contract code.
+const UPLOAD_PERMISSIONS: Item<WasmPermissions> = Item::new("upload_permissions");
+
+/// Instantiate permission of codes without their own instantiate permission.
+const DEFAULT_INSTANTIATE_PERMISSION: Item<InstantiatePermission> =
+    Item::new("default_instantiate_permission");
+
+/// Instantiate permissions set for specific codes, indexed by code identifier.
+const CODE_INSTANTIATE_PERMISSIONS: Map<u64, InstantiatePermission> =
+    Map::new("code_instantiate_permissions");
+
+/// Chain-level permissions to store contract code.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WasmPermissions {
+    /// Any address can store contract code.
+    #[default]
+    Permissionless,
+    /// Only listed addresses can store contract code.
+    PermissionedUpload {
+        /// Addresses allowed to store contract code.
+        allowed_uploaders: Vec<Addr>,
+    },
+}
+
+impl WasmPermissions {
+    /// Returns `true` when the address is allowed to store contract code.
+    pub fn can_upload(&self, uploader: &Addr) -> bool {
+        match self {
+            Self::Permissionless => true,
+            Self::PermissionedUpload { allowed_uploaders } => allowed_uploaders.contains(uploader),
+        }
+    }
+}
+
+/// Permission to instantiate contracts from a code.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InstantiatePermission {
+    /// Any address can instantiate contracts.
+    #[default]
+    Everybody,
+    /// No address can instantiate contracts.
+    Nobody,
+    /// Only listed addresses can instantiate contracts.
+    AnyOfAddresses(Vec<Addr>),
+}
+
+impl InstantiatePermission {
+    /// Returns `true` when the address is allowed to instantiate contracts.
+    pub fn allows(&self, sender: &Addr) -> bool {
+        match self {
+            Self::Everybody => true,
+            Self::Nobody => false,
+            Self::AnyOfAddresses(addresses) => addresses.contains(sender),
+        }
+    }
+}
+
+/// Privileged actions changing the chain-level permissions of the wasm module.
+///
+/// [WasmSudo](crate::WasmSudo) is addressed to the `sudo` entry-point of a contract,
+/// so permissions are changed with a message of their own, see [SudoMsg](crate::SudoMsg).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WasmPermissionsSudo {
+    /// Replaces the permissions to store contract code.
+    UpdatePermissions {
+        /// New permissions to store contract code.
+        permissions: WasmPermissions,
+    },
+    /// Replaces the instantiate permission of codes without their own instantiate permission.
+    UpdateDefaultInstantiatePermission {
+        /// New default instantiate permission.
+        permission: InstantiatePermission,
+    },
+    /// Sets the instantiate permission of the code, overriding the default one.
+    /// When the permission is `None`, the code falls back to the default instantiate permission.
+    UpdateCodeInstantiatePermission {
+        /// Identifier of the code.
+        code_id: u64,
+        /// New instantiate permission of the code.
+        permission: Option<InstantiatePermission>,
+    },
+}
+
+/// Saves the permissions to store contract code.
+pub(crate) fn save_wasm_permissions(
+    storage: &mut dyn Storage,
+    permissions: &WasmPermissions,
+) -> AnyResult<()> {
+    UPLOAD_PERMISSIONS.save(&mut prefixed(storage, NAMESPACE_WASM), permissions)?;
+    Ok(())
+}
+
+/// Saves the instantiate permission of codes without their own instantiate permission.
+pub(crate) fn save_default_instantiate_permission(
+    storage: &mut dyn Storage,
+    permission: &InstantiatePermission,
+) -> AnyResult<()> {
+    DEFAULT_INSTANTIATE_PERMISSION.save(&mut prefixed(storage, NAMESPACE_WASM), permission)?;
+    Ok(())
+}
+
+/// Returns the permissions to store contract code, permissionless by default.
+pub(crate) fn load_wasm_permissions(storage: &dyn Storage) -> AnyResult<WasmPermissions> {
+    Ok(UPLOAD_PERMISSIONS
+        .may_load(&prefixed_read(storage, NAMESPACE_WASM))?
+        .unwrap_or_default())
+}
+
+/// Fails when the address is not allowed to store contract code.
+pub(crate) fn check_upload(storage: &dyn Storage, uploader: &Addr) -> AnyResult<()> {
+    if load_wasm_permissions(storage)?.can_upload(uploader) {
+        Ok(())
+    } else {
+        Err(Error::upload_not_allowed(uploader).into())
+    }
+}
+
+/// Fails when the address is not allowed to instantiate contracts from the code.
+///
+/// The instantiate permission of the code is used when set,
+/// otherwise the default instantiate permission applies.
+pub(crate) fn check_instantiate(
+    storage: &dyn Storage,
+    code_id: u64,
+    sender: &Addr,
+) -> AnyResult<()> {
+    let storage = prefixed_read(storage, NAMESPACE_WASM);
+    let permission = match CODE_INSTANTIATE_PERMISSIONS.may_load(&storage, code_id)? {
+        Some(permission) => permission,
+        None => DEFAULT_INSTANTIATE_PERMISSION
+            .may_load(&storage)?
+            .unwrap_or_default(),
+    };
+    if permission.allows(sender) {
+        Ok(())
+    } else {
+        Err(Error::instantiate_not_allowed(code_id, sender).into())
+    }
+}
+
+/// Executes the privileged action changing the permissions of the wasm module.
+pub(crate) fn sudo(storage: &mut dyn Storage, msg: WasmPermissionsSudo) -> AnyResult<AppResponse> {
+    match msg {
+        WasmPermissionsSudo::UpdatePermissions { permissions } => {
+            save_wasm_permissions(storage, &permissions)?
+        }
+        WasmPermissionsSudo::UpdateDefaultInstantiatePermission { permission } => {
+            save_default_instantiate_permission(storage, &permission)?
+        }
+        WasmPermissionsSudo::UpdateCodeInstantiatePermission {
+            code_id,
+            permission,
+        } => {
+            let mut storage = prefixed(storage, NAMESPACE_WASM);
+            match permission {
+                Some(permission) => {
+                    CODE_INSTANTIATE_PERMISSIONS.save(&mut storage, code_id, &permission)?
+                }
+                None => CODE_INSTANTIATE_PERMISSIONS.remove(&mut storage, code_id),
+            }
+        }
+    }
+    Ok(AppResponse::default())
+}
@@ -3,12 +3,21 @@
 mod test_api;
 mod test_app;
 mod test_app_builder;
+#[cfg(feature = "async-helpers")]
+mod test_async_app;
 mod test_attributes;
 mod test_bank;
+mod test_catch_all;
+mod test_chaos;
 mod test_contract_storage;
+mod test_contract_wrapper;
+mod test_feature_matrix;
 mod test_module;
 mod test_payload;
 mod test_prefixed_storage;
+#[cfg(feature = "proptest-support")]
+mod test_proptest;
+mod test_security;
 #[cfg(feature = "staking")]
 mod test_staking;
 mod test_wasm;
@@ -80,4 +89,54 @@ mod test_contracts {
             )
         }
     }
+
+    pub mod noop {
+        use cosmwasm_std::{
+            to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response,
+            StdResult,
+        };
+        use cw_multi_test::{Contract, ContractWrapper};
+
+        pub fn instantiate(
+            _deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            _msg: Empty,
+        ) -> StdResult<Response> {
+            Ok(Response::default())
+        }
+
+        pub fn execute(
+            _deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            _msg: Empty,
+        ) -> StdResult<Response> {
+            Ok(Response::default())
+        }
+
+        pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+            to_json_binary(&Empty {})
+        }
+
+        pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+            Ok(Response::default())
+        }
+
+        pub fn sudo(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+            Ok(Response::default())
+        }
+
+        pub fn reply(_deps: DepsMut, _env: Env, _msg: Reply) -> StdResult<Response> {
+            Ok(Response::default())
+        }
+
+        /// Contract doing nothing, its migration does nothing as well.
+        pub fn contract() -> Box<dyn Contract<Empty>> {
+            Box::new(
+                ContractWrapper::new_with_empty(execute, instantiate, query)
+                    .with_migrate_empty(migrate),
+            )
+        }
+    }
 }
@@ -1,6 +1,27 @@
+mod test_address_book;
+mod test_all_funds;
+mod test_behavior_change;
 mod test_block_info;
+mod test_chain_backend;
+mod test_chain_halt;
+mod test_code_id;
+mod test_contract_metadata;
+mod test_custom_mismatch;
+mod test_disabled_queries;
+mod test_duplicate_code;
+mod test_fork;
+mod test_gov_authority;
 mod test_initialize_app;
 mod test_instantiate2;
+mod test_instantiate_with_response;
+mod test_operation_log;
+mod test_redacted_response;
+mod test_scheduled_execute;
+mod test_scheduled_sudo;
+mod test_setup_order;
+mod test_storage_layout;
 mod test_store_code;
 mod test_store_code_with_creator;
 mod test_store_code_with_id;
+mod test_sudo_named;
+mod test_update_contract_data;
@@ -0,0 +1,84 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::error::{AnyResult, Error};
+use cw_multi_test::{no_init, AddressBook, App, AppBuilder, Executor, IntoAddr};
+
+/// Deploys the counter contract twice, with labels `counter` and `vault`.
+fn deploy(app: &mut App) -> AnyResult<(Addr, Addr)> {
+    let owner = "owner".into_addr();
+    let code_id = app.store_code(counter::contract());
+    let counter_addr =
+        app.instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "counter", None)?;
+    let vault_addr = app.instantiate_contract(code_id, owner, &Empty {}, &[], "vault", None)?;
+    Ok((counter_addr, vault_addr))
+}
+
+/// Exports the address book of the deployment and passes it through JSON,
+/// the way it is shared with deployment scripts.
+fn exported_address_book() -> AddressBook {
+    let mut app = App::default();
+    app.register_address("alice", "alice".into_addr()).unwrap();
+    deploy(&mut app).unwrap();
+    let json = serde_json::to_string(&app.export_address_book().unwrap()).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn exported_address_book_should_list_users_contracts_and_module_accounts() {
+    let mut app = App::default();
+    app.register_address("alice", "alice".into_addr()).unwrap();
+    let (counter_addr, vault_addr) = deploy(&mut app).unwrap();
+
+    let book = app.export_address_book().unwrap();
+    assert_eq!("alice".into_addr(), book.users["alice"]);
+    assert_eq!(2, book.contracts.len());
+    for (label, addr) in [("counter", &counter_addr), ("vault", &vault_addr)] {
+        let contract = book.contracts.iter().find(|c| c.label == label).unwrap();
+        assert_eq!(addr, &contract.address);
+        assert_eq!(1, contract.code_id);
+    }
+    assert_eq!(app.gov_authority().unwrap(), book.module_accounts["gov"]);
+}
+
+#[test]
+fn repeated_deployment_should_match_imported_address_book() {
+    let book = exported_address_book();
+
+    let mut app = AppBuilder::default()
+        .with_address_book(book.clone())
+        .build(no_init);
+    deploy(&mut app).unwrap();
+
+    assert_eq!(book, app.export_address_book().unwrap());
+}
+
+#[test]
+fn different_deployment_should_report_address_mismatch() {
+    let book = exported_address_book();
+    let address_of = |label: &str| {
+        book.contracts
+            .iter()
+            .find(|c| c.label == label)
+            .unwrap()
+            .address
+            .clone()
+    };
+    let counter_addr = address_of("counter");
+    let vault_addr = address_of("vault");
+
+    let mut app = AppBuilder::default().with_address_book(book).build(no_init);
+    // an additional contract instantiated first shifts the addresses of the deployment,
+    // so the counter gets the address the vault had in the exported deployment
+    let owner = "owner".into_addr();
+    let code_id = app.store_code(counter::contract());
+    app.instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "extra", None)
+        .unwrap();
+
+    let err = app
+        .instantiate_contract(code_id, owner, &Empty {}, &[], "counter", None)
+        .unwrap_err();
+    assert_eq!(
+        Error::address_book_mismatch("counter", &[counter_addr], vault_addr).to_string(),
+        err.root_cause().to_string()
+    );
+}
@@ -0,0 +1,146 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coin, coins, Addr, BankMsg, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor, IntoAddr};
+
+const DENOM: &str = "uatom";
+
+#[cw_serde]
+enum ExecuteMsg {
+    /// Keeps all received funds.
+    Deposit {},
+    /// Returns half of the received funds to the sender.
+    DepositHalf {},
+}
+
+fn execute(_deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Deposit {} => Ok(Response::default()),
+        ExecuteMsg::DepositHalf {} => {
+            let amount = info
+                .funds
+                .iter()
+                .map(|c| coin(c.amount.u128() / 2, &c.denom))
+                .collect();
+            Ok(Response::new().add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount,
+            }))
+        }
+    }
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, noop::instantiate, query))
+}
+
+fn setup(amount: u128) -> (App, Addr, Addr) {
+    let user_addr = "user".into_addr();
+    let mut app = App::new(|router, _, storage| {
+        if amount > 0 {
+            router
+                .bank
+                .init_balance(storage, &user_addr, coins(amount, DENOM))
+                .unwrap();
+        }
+    });
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "vault", None)
+        .unwrap();
+    (app, user_addr, contract_addr)
+}
+
+#[test]
+fn entire_balance_should_be_sent() {
+    let (mut app, user_addr, contract_addr) = setup(1_000);
+    // the amount drifts in earlier steps
+    app.send_tokens(user_addr.clone(), "other".into_addr(), &coins(337, DENOM))
+        .unwrap();
+
+    app.execute_contract_with_all_funds(
+        user_addr.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Deposit {},
+        DENOM,
+    )
+    .unwrap();
+
+    app.assert_emptied(&user_addr, DENOM);
+    app.assert_balance(&contract_addr, &coin(663, DENOM));
+}
+
+#[test]
+fn contract_should_send_its_entire_balance() {
+    let (mut app, user_addr, contract_addr) = setup(100);
+    app.execute_contract_with_all_funds(
+        user_addr.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Deposit {},
+        DENOM,
+    )
+    .unwrap();
+    let other_addr = app
+        .instantiate_contract(
+            app.contract_code_id(&contract_addr).unwrap(),
+            "owner".into_addr(),
+            &Empty {},
+            &[],
+            "other",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract_with_all_funds(
+        contract_addr.clone(),
+        other_addr.clone(),
+        &ExecuteMsg::Deposit {},
+        DENOM,
+    )
+    .unwrap();
+
+    app.assert_emptied(&contract_addr, DENOM);
+    app.assert_balance(&other_addr, &coin(100, DENOM));
+}
+
+#[test]
+#[should_panic(expected = "to be emptied of uatom, but 50uatom is left")]
+fn residual_should_be_detected() {
+    let (mut app, user_addr, contract_addr) = setup(100);
+
+    app.execute_contract_with_all_funds(
+        user_addr.clone(),
+        contract_addr,
+        &ExecuteMsg::DepositHalf {},
+        DENOM,
+    )
+    .unwrap();
+
+    app.assert_emptied(&user_addr, DENOM);
+}
+
+#[test]
+fn zero_balance_should_fail() {
+    let (mut app, user_addr, contract_addr) = setup(0);
+
+    let err = app
+        .execute_contract_with_all_funds(
+            user_addr.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            DENOM,
+        )
+        .unwrap_err();
+
+    assert_eq!(
+        format!("{} has no uatom to send", user_addr),
+        err.to_string()
+    );
+    app.assert_emptied(&contract_addr, DENOM);
+}
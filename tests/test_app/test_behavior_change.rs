@@ -0,0 +1,186 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::{next_block, App, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+/// Address of the contract queried when this contract is queried.
+const PEER: Item<String> = Item::new("peer");
+
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, peer: String) -> StdResult<Response> {
+    if !peer.is_empty() {
+        PEER.save(deps.storage, &peer)?;
+    }
+    Ok(Response::default())
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+/// Forwards the query to the peer contract, if set.
+fn query(deps: Deps, _env: Env, msg: Empty) -> Result<Binary, StdError> {
+    match PEER.may_load(deps.storage)? {
+        Some(peer) => to_json_binary(&deps.querier.query_wasm_smart::<Binary>(peer, &msg)?),
+        None => to_json_binary(&Binary::default()),
+    }
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+}
+
+#[test]
+fn scheduled_behavior_change_should_work() {
+    let mut app = App::default();
+
+    // instantiate two contracts, querying the first one queries the second one
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let second = app
+        .instantiate_contract(code_id, owner.clone(), &"", &[], "second", None)
+        .unwrap();
+    let first = app
+        .instantiate_contract(code_id, owner, &second, &[], "first", None)
+        .unwrap();
+
+    // schedule lowering the maximum query depth two blocks later
+    let upgrade_height = app.block_info().height + 2;
+    app.schedule_behavior_change(upgrade_height, "v2", |router, _, _| {
+        router.wasm_mut().set_max_query_depth(1);
+    });
+
+    // nested query works before the scheduled height
+    app.update_block(next_block);
+    app.wrap()
+        .query_wasm_smart::<Binary>(first.clone(), &Empty {})
+        .unwrap();
+    assert!(app.upgrade_events().is_empty());
+
+    // nested query fails after the scheduled height, the upgrade event is emitted
+    app.update_block(next_block);
+    let err = app
+        .wrap()
+        .query_wasm_smart::<Binary>(first, &Empty {})
+        .unwrap_err();
+    assert!(err.to_string().contains("max query depth 1 exceeded"));
+    assert_eq!(
+        vec![Event::new("upgrade")
+            .add_attribute("name", "v2")
+            .add_attribute("height", upgrade_height.to_string())],
+        app.upgrade_events()
+    );
+
+    // not nested query still works
+    app.wrap()
+        .query_wasm_smart::<Binary>(second, &Empty {})
+        .unwrap();
+}
+
+#[test]
+fn scheduled_behavior_changes_should_be_applied_in_order() {
+    let mut app = App::default();
+    let height = app.block_info().height;
+
+    app.schedule_behavior_change(height + 2, "third", |_, _, _| {});
+    app.schedule_behavior_change(height + 1, "first", |_, _, _| {});
+    app.schedule_behavior_change(height + 1, "second", |_, _, _| {});
+
+    // skipping blocks applies all changes up to the current height
+    app.update_block(|block| block.height += 5);
+    let names = app
+        .upgrade_events()
+        .iter()
+        .map(|event| event.attributes[0].value.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["first", "second", "third"], names);
+}
+
+#[test]
+#[cfg(feature = "staking")]
+fn scheduled_staking_params_change_should_work() {
+    use cosmwasm_std::{coin, Decimal, StakingMsg, Validator};
+    use cw_multi_test::{IntoBech32, StakingInfo};
+
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+
+    let mut app = App::new(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &delegator_addr, vec![coin(100, "stake")])
+            .unwrap();
+        router
+            .staking
+            .setup(
+                storage,
+                StakingInfo {
+                    bonded_denom: "stake".to_string(),
+                    unbonding_time: 60,
+                    apr: Decimal::percent(10),
+                },
+            )
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &cosmwasm_std::testing::mock_env().block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(90),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+
+    // shorten the unbonding time in the next block
+    app.schedule_behavior_change(app.block_info().height + 1, "v2", |router, _, storage| {
+        router
+            .staking
+            .setup(
+                storage,
+                StakingInfo {
+                    bonded_denom: "stake".to_string(),
+                    unbonding_time: 10,
+                    apr: Decimal::percent(10),
+                },
+            )
+            .unwrap();
+    });
+
+    // delegate and undelegate tokens before the upgrade
+    let delegate = StakingMsg::Delegate {
+        validator: validator_addr.to_string(),
+        amount: coin(50, "stake"),
+    };
+    let undelegate = StakingMsg::Undelegate {
+        validator: validator_addr.to_string(),
+        amount: coin(50, "stake"),
+    };
+    app.execute(delegator_addr.clone(), delegate.clone().into())
+        .unwrap();
+    app.execute(delegator_addr.clone(), undelegate.clone().into())
+        .unwrap();
+
+    // after the upgrade, the same operations use the new unbonding time
+    app.update_block(next_block);
+    app.execute(delegator_addr.clone(), delegate.into())
+        .unwrap();
+    app.execute(delegator_addr.clone(), undelegate.into())
+        .unwrap();
+
+    // 10 seconds later, only tokens undelegated after the upgrade are returned
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    let balance = app.wrap().query_balance(&delegator_addr, "stake").unwrap();
+    assert_eq!(50, balance.amount.u128());
+
+    // after another 60 seconds, all tokens are returned
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    let balance = app.wrap().query_balance(&delegator_addr, "stake").unwrap();
+    assert_eq!(100, balance.amount.u128());
+}
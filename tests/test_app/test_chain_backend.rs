@@ -0,0 +1,64 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{from_json, to_json_vec, Addr, Empty, WasmMsg};
+use cw_multi_test::{App, BasicApp, ChainBackend, IntoAddr};
+
+/// Deploys the counter contract using only the chain backend,
+/// increments the counter and returns the contract address.
+fn deploy(backend: &mut dyn ChainBackend, owner: &Addr) -> Addr {
+    let code_id = backend.upload(counter::contract());
+    let contract_addr = backend
+        .instantiate(
+            code_id,
+            owner,
+            &to_json_vec(&Empty {}).unwrap(),
+            &[],
+            "counter",
+            Some(owner),
+        )
+        .unwrap();
+    let msg = to_json_vec(&WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    })
+    .unwrap();
+    backend.execute(owner, &contract_addr, &msg, &[]).unwrap();
+    backend.advance_block();
+    contract_addr
+}
+
+#[test]
+fn deployment_through_chain_backend_should_work() {
+    let mut app: BasicApp = App::default();
+    let owner = "owner".into_addr();
+    let height = app.block_info().height;
+
+    let contract_addr = deploy(&mut app, &owner);
+
+    // the deployment is visible through both the chain backend and the application
+    let backend: &dyn ChainBackend = &app;
+    let query = to_json_vec(&counter::CounterQueryMsg::Counter {}).unwrap();
+    let response: counter::CounterResponseMsg =
+        from_json(backend.query(&contract_addr, &query).unwrap()).unwrap();
+    assert_eq!(2, response.value);
+    let contract_data = app.contract_data(&contract_addr).unwrap();
+    assert_eq!(Some(owner), contract_data.admin);
+    assert_eq!(height + 1, app.block_info().height);
+}
+
+#[test]
+fn chain_backend_should_report_errors() {
+    let mut app = App::default();
+    let owner = "owner".into_addr();
+    let backend: &mut dyn ChainBackend = &mut app;
+
+    // instantiating not uploaded code fails
+    backend
+        .instantiate(1, &owner, b"{}", &[], "counter", None)
+        .unwrap_err();
+
+    // querying not existing contract fails
+    let contract_addr = deploy(backend, &owner);
+    backend.query(&"unknown".into_addr(), b"{}").unwrap_err();
+
+    // invalid query message fails
+    backend.query(&contract_addr, b"{}").unwrap_err();
+}
@@ -0,0 +1,174 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{next_block, App, Contract, ContractWrapper, Executor, IntoAddr, Schedule};
+use cw_storage_plus::Item;
+
+/// Duration of the auction in seconds.
+const AUCTION_DURATION: u64 = 3600;
+
+/// Block time when the auction closes.
+const DEADLINE: Item<u64> = Item::new("deadline");
+/// Block height at which the auction was settled.
+const SETTLED_AT: Item<u64> = Item::new("settled_at");
+
+#[cw_serde]
+enum SudoMsg {
+    /// Settles the auction when its deadline passed.
+    Settle {},
+}
+
+fn instantiate(deps: DepsMut, env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    DEADLINE.save(deps.storage, &(env.block.time.seconds() + AUCTION_DURATION))?;
+    Ok(Response::default())
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> StdResult<Response> {
+    match msg {
+        SudoMsg::Settle {} => {
+            if SETTLED_AT.may_load(deps.storage)?.is_none()
+                && env.block.time.seconds() >= DEADLINE.load(deps.storage)?
+            {
+                SETTLED_AT.save(deps.storage, &env.block.height)?;
+            }
+            Ok(Response::default())
+        }
+    }
+}
+
+/// Returns the block height at which the auction was settled.
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&SETTLED_AT.may_load(deps.storage)?)
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query).with_sudo_empty(sudo))
+}
+
+fn settled_at(app: &App, contract_addr: &Addr) -> Option<u64> {
+    app.wrap()
+        .query_wasm_smart(contract_addr, &Empty {})
+        .unwrap()
+}
+
+#[test]
+fn halt_should_advance_time_and_a_single_block() {
+    let mut app = App::default();
+    let block = app.block_info();
+
+    app.simulate_halt(86400);
+
+    assert_eq!(block.height + 1, app.block_info().height);
+    assert_eq!(block.time.plus_seconds(86400), app.block_info().time);
+}
+
+#[test]
+fn auction_closing_during_halt_should_settle_in_restart_block() {
+    let mut app = App::default();
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            "owner".into_addr(),
+            &Empty {},
+            &[],
+            "auction",
+            None,
+        )
+        .unwrap();
+    // the auction is settled by a cron module checking it in every block
+    let schedule_id = app
+        .schedule_sudo(
+            contract_addr.clone(),
+            to_json_binary(&SudoMsg::Settle {}).unwrap(),
+            Schedule::EveryNBlocks(1),
+        )
+        .unwrap();
+
+    app.update_block(next_block);
+    assert!(!app.last_scheduled_results()[0].late);
+    assert_eq!(None, settled_at(&app, &contract_addr));
+
+    // the deadline passes while the chain is halted
+    app.simulate_halt(2 * AUCTION_DURATION);
+    let restart_height = app.block_info().height;
+
+    // the scheduled call is executed once, in the restart block
+    let results = app.last_scheduled_results();
+    assert_eq!(1, results.len());
+    assert_eq!(schedule_id, results[0].schedule_id);
+    assert_eq!(restart_height, results[0].height);
+    assert!(results[0].late);
+    assert!(results[0].result.is_ok());
+    assert_eq!(Some(restart_height), settled_at(&app, &contract_addr));
+}
+
+#[test]
+#[cfg(feature = "staking")]
+fn unbondings_maturing_during_halt_should_be_paid_out_on_restart() {
+    use cosmwasm_std::{coin, Decimal, StakingMsg, Validator};
+    use cw_multi_test::{AppBuilder, IntoBech32, StakingInfo};
+
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+    let mut app = AppBuilder::default().build(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &delegator_addr, vec![coin(100, "stake")])
+            .unwrap();
+        router
+            .staking
+            .setup(
+                storage,
+                StakingInfo {
+                    bonded_denom: "stake".to_string(),
+                    unbonding_time: 60,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &cosmwasm_std::testing::mock_env().block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(90),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+    for msg in [
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, "stake"),
+        },
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, "stake"),
+        },
+    ] {
+        app.execute(delegator_addr.clone(), msg.into()).unwrap();
+    }
+    let balance = |app: &App| {
+        app.wrap()
+            .query_balance(&delegator_addr, "stake")
+            .unwrap()
+            .amount
+            .u128()
+    };
+    assert_eq!(0, balance(&app));
+
+    // the unbonding matures while the chain is halted and is paid out on restart
+    app.simulate_halt(3600);
+    assert_eq!(100, balance(&app));
+}
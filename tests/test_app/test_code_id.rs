@@ -0,0 +1,56 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::Empty;
+use cw_multi_test::{App, CodeId, Executor, IntoAddr};
+
+#[test]
+fn contracts_should_be_looked_up_by_code() {
+    let mut app = App::default();
+    let owner = "owner".into_addr();
+    let vault_code_id = CodeId::from(app.store_code(counter::contract()));
+    let token_code_id = app.store_code(counter::contract());
+    let unused_code_id = app.store_code(counter::contract());
+
+    // both typed and raw code identifiers are accepted
+    let vault_1 = app
+        .instantiate_contract(
+            vault_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "vault-1",
+            None,
+        )
+        .unwrap();
+    let token = app
+        .instantiate_contract(token_code_id, owner.clone(), &Empty {}, &[], "token", None)
+        .unwrap();
+    let vault_2 = app
+        .instantiate_contract(vault_code_id, owner, &Empty {}, &[], "vault-2", None)
+        .unwrap();
+
+    assert_eq!(vault_code_id, app.contract_code_id(&vault_1).unwrap());
+    assert_eq!(token_code_id, app.contract_code_id(&token).unwrap());
+    let mut vaults = app.contracts_by_code(vault_code_id).unwrap();
+    vaults.sort();
+    let mut expected = vec![vault_1, vault_2];
+    expected.sort();
+    assert_eq!(expected, vaults);
+    assert_eq!(vec![token], app.contracts_by_code(token_code_id).unwrap());
+    assert!(app.contracts_by_code(unused_code_id).unwrap().is_empty());
+}
+
+#[test]
+fn code_id_of_unknown_contract_should_fail() {
+    let app = App::default();
+    assert!(app.contract_code_id(&"unknown".into_addr()).is_err());
+}
+
+#[test]
+fn code_id_should_convert_from_and_into_u64() {
+    let code_id = CodeId::new(5);
+    assert_eq!(5, *code_id);
+    assert_eq!(5u64, u64::from(code_id));
+    assert_eq!(code_id, CodeId::from(5));
+    assert_eq!("5", code_id.to_string());
+    assert_eq!("5", serde_json::to_string(&code_id).unwrap());
+}
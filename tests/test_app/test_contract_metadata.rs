@@ -0,0 +1,79 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{Empty, WasmMsg};
+use cw_multi_test::{App, Executor, IntoAddr};
+use std::collections::BTreeMap;
+
+fn metadata() -> BTreeMap<String, String> {
+    [
+        ("role".to_string(), "vault#3".to_string()),
+        ("script".to_string(), "deploy_vaults".to_string()),
+    ]
+    .into()
+}
+
+#[test]
+fn metadata_should_be_preserved_across_migrations_and_admin_changes() {
+    let mut app = App::default();
+    let owner = "owner".into_addr();
+    let code_id = app.store_code(noop::contract());
+    let new_code_id = app.store_code(noop::contract());
+    let contract_addr = app
+        .instantiate_contract_with_metadata(
+            code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "vault",
+            Some(owner.to_string()),
+            metadata(),
+        )
+        .unwrap();
+    assert_eq!(metadata(), app.contract_extensions(&contract_addr).unwrap());
+
+    app.migrate_contract(owner.clone(), contract_addr.clone(), &Empty {}, new_code_id)
+        .unwrap();
+    app.execute(
+        owner,
+        WasmMsg::UpdateAdmin {
+            contract_addr: contract_addr.to_string(),
+            admin: "new-owner".into_addr().to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let data = app.contract_data(&contract_addr).unwrap();
+    assert_eq!(new_code_id, data.code_id);
+    assert_eq!(Some("new-owner".into_addr()), data.admin);
+    assert_eq!(metadata(), app.contract_extensions(&contract_addr).unwrap());
+
+    app.update_contract_extensions(&contract_addr, |extensions| {
+        extensions.insert("schema".to_string(), "2".to_string());
+    })
+    .unwrap();
+    assert_eq!(
+        "2",
+        app.contract_extensions(&contract_addr).unwrap()["schema"]
+    );
+}
+
+#[test]
+fn contracts_should_have_no_metadata_by_default() {
+    let mut app = App::default();
+    let code_id = app.store_code(noop::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "vault", None)
+        .unwrap();
+    assert!(app.contract_extensions(&contract_addr).unwrap().is_empty());
+}
+
+#[test]
+fn metadata_of_missing_contract_should_fail() {
+    let mut app = App::default();
+    let contract_addr = "missing".into_addr();
+    app.contract_extensions(&contract_addr).unwrap_err();
+    app.update_contract_extensions(&contract_addr, |extensions| {
+        extensions.insert("role".to_string(), "vault#3".to_string());
+    })
+    .unwrap_err();
+}
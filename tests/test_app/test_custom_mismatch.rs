@@ -0,0 +1,93 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_vec, CosmosMsg, CustomQuery, Empty, Querier, QueryRequest, SystemError, SystemResult,
+};
+use cw_multi_test::error::Error;
+use cw_multi_test::{custom_app, no_init, App, Executor, IntoAddr};
+use std::any::type_name;
+
+#[cw_serde]
+enum PriceQuery {
+    Price {},
+}
+
+impl CustomQuery for PriceQuery {}
+
+#[cw_serde]
+enum CounterQuery {
+    Counter {},
+}
+
+impl CustomQuery for CounterQuery {}
+
+/// Sends a custom query to the querier and returns the reported error.
+fn custom_query_error<Q: CustomQuery>(querier: &dyn Querier, query: Q) -> String {
+    let request = to_json_vec(&QueryRequest::Custom(query)).unwrap();
+    match querier.raw_query(&request) {
+        SystemResult::Err(SystemError::InvalidRequest { error, .. }) => error,
+        other => panic!("unexpected query result: {:?}", other),
+    }
+}
+
+#[test]
+fn custom_query_not_matching_empty_should_be_explained() {
+    let app = App::default();
+
+    let err = custom_query_error(&app, PriceQuery::Price {});
+    assert_eq!(
+        Error::custom_query_mismatch(
+            type_name::<Empty>(),
+            r#"{"price":{}}"#,
+            format!("unexpected fields for {}", type_name::<Empty>())
+        )
+        .to_string(),
+        err
+    );
+    assert!(err.contains("`custom_app`"), "{}", err);
+    assert!(err.contains("`AppBuilder::with_custom`"), "{}", err);
+}
+
+#[test]
+fn custom_query_not_matching_custom_type_should_be_explained() {
+    let app = custom_app::<Empty, CounterQuery, _>(no_init);
+
+    let err = custom_query_error(&app, PriceQuery::Price {});
+    assert!(err.contains(type_name::<CounterQuery>()), "{}", err);
+    assert!(err.contains(r#"{"price":{}}"#), "{}", err);
+    assert!(err.contains("unknown variant `price`"), "{}", err);
+    assert!(err.contains("`custom_app`"), "{}", err);
+}
+
+#[test]
+fn matching_custom_query_should_reach_custom_module() {
+    let app = custom_app::<Empty, CounterQuery, _>(no_init);
+
+    let err = app
+        .wrap()
+        .query::<Empty>(&QueryRequest::Custom(CounterQuery::Counter {}))
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Unexpected custom query Counter"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn custom_message_sent_to_app_without_custom_module_should_be_explained() {
+    let mut app = App::default();
+    let sender_addr = "sender".into_addr();
+
+    let err = app
+        .execute(sender_addr.clone(), CosmosMsg::Custom(Empty {}))
+        .unwrap_err();
+    assert_eq!(
+        Error::custom_msg_mismatch(type_name::<Empty>(), "Empty").to_string(),
+        err.to_string()
+    );
+    // the original error is still available
+    assert_eq!(
+        format!(r#"Unexpected exec msg Empty from Addr("{}")"#, sender_addr),
+        err.root_cause().to_string()
+    );
+}
@@ -0,0 +1,121 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankQuery, Binary, Deps, Empty, Env, QueryRequest, StdResult,
+    WasmQuery,
+};
+use cw_multi_test::{
+    App, BankQueryKind, Contract, ContractWrapper, Executor, IntoAddr, QueryKind, WasmQueryKind,
+};
+
+const DENOM: &str = "uatom";
+
+#[cw_serde]
+enum QueryMsg {
+    /// Returns the balance of the contract, or `None` when the chain
+    /// does not support balance queries.
+    Balance {},
+}
+
+fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance {} => {
+            let balance = deps
+                .querier
+                .query_balance(env.contract.address, DENOM)
+                .ok()
+                .map(|coin| coin.amount.u128());
+            to_json_binary(&balance)
+        }
+    }
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        noop::execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+fn setup() -> (App, Addr) {
+    let mut app = App::default();
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "probe", None)
+        .unwrap();
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &contract_addr, coins(100, DENOM))
+            .unwrap();
+    });
+    (app, contract_addr)
+}
+
+fn query_probe(app: &App, contract_addr: &Addr) -> Option<u128> {
+    app.wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::Balance {})
+        .unwrap()
+}
+
+#[test]
+fn disabled_query_should_be_toggled_mid_test() {
+    let (mut app, contract_addr) = setup();
+    assert_eq!(Some(100), query_probe(&app, &contract_addr));
+
+    app.disable_query(QueryKind::Bank(BankQueryKind::Balance));
+    assert_eq!(None, query_probe(&app, &contract_addr));
+
+    app.enable_query(QueryKind::Bank(BankQueryKind::Balance));
+    assert_eq!(Some(100), query_probe(&app, &contract_addr));
+}
+
+#[test]
+fn disabled_query_should_fail_with_unsupported_request() {
+    let (mut app, contract_addr) = setup();
+    app.disable_query(QueryKind::Bank(BankQueryKind::Balance));
+
+    let err = app
+        .wrap()
+        .query::<Empty>(&QueryRequest::Bank(BankQuery::Balance {
+            address: contract_addr.to_string(),
+            denom: DENOM.to_string(),
+        }))
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unsupported query type: unknown BankQuery variant"));
+}
+
+#[test]
+fn other_queries_should_not_be_affected() {
+    let (mut app, contract_addr) = setup();
+    app.disable_query(QueryKind::Bank(BankQueryKind::AllBalances));
+    app.disable_query(QueryKind::Wasm(WasmQueryKind::Raw));
+
+    assert_eq!(Some(100), query_probe(&app, &contract_addr));
+    app.wrap().query_wasm_contract_info(&contract_addr).unwrap();
+    let err = app
+        .wrap()
+        .query::<Empty>(&QueryRequest::Wasm(WasmQuery::Raw {
+            contract_addr: contract_addr.to_string(),
+            key: Binary::default(),
+        }))
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unsupported query type: unknown WasmQuery variant"));
+}
+
+#[test]
+fn disabled_smart_query_should_fail_for_contracts() {
+    let (mut app, contract_addr) = setup();
+    app.disable_query(QueryKind::Wasm(WasmQueryKind::Smart));
+
+    let err = app
+        .wrap()
+        .query_wasm_smart::<Option<u128>>(&contract_addr, &QueryMsg::Balance {})
+        .unwrap_err();
+    assert!(err.to_string().contains("Unsupported query type"));
+}
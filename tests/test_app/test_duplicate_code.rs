@@ -0,0 +1,120 @@
+#![cfg(feature = "cosmwasm_1_2")]
+
+use crate::test_contracts::counter;
+use cosmwasm_std::{to_json_binary, Addr, Empty, WasmMsg};
+use cw_multi_test::{App, AppResponse, Executor};
+use cw_utils::parse_instantiate_response_data;
+
+const SALT: &[u8] = b"salt";
+
+/// Instantiates the contract with predictable address.
+fn instantiate2(app: &mut App, sender: &Addr, code_id: u64) -> anyhow::Result<AppResponse> {
+    let msg = WasmMsg::Instantiate2 {
+        admin: None,
+        code_id,
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+        label: "counter".into(),
+        salt: SALT.into(),
+    };
+    app.execute(sender.clone(), msg.into())
+}
+
+fn contract_address(res: AppResponse) -> String {
+    parse_instantiate_response_data(res.data.unwrap().as_slice())
+        .unwrap()
+        .contract_address
+}
+
+#[test]
+fn duplicated_code_should_have_the_same_checksum() {
+    let mut app = App::default();
+    let code_id = app.store_code(counter::contract());
+    let dup_code_id = app.duplicate_code(code_id).unwrap();
+
+    let code_info = app.wrap().query_wasm_code_info(code_id).unwrap();
+    let dup_code_info = app.wrap().query_wasm_code_info(dup_code_id).unwrap();
+    assert_eq!(code_info.checksum, dup_code_info.checksum);
+    assert_eq!(code_info.creator, dup_code_info.creator);
+}
+
+#[test]
+fn duplicated_code_with_new_checksum_should_have_different_checksum() {
+    let mut app = App::default();
+    let code_id = app.store_code(counter::contract());
+    let dup_code_id = app.duplicate_code_with_new_checksum(code_id).unwrap();
+    assert_ne!(code_id, dup_code_id);
+
+    let code_info = app.wrap().query_wasm_code_info(code_id).unwrap();
+    let dup_code_info = app.wrap().query_wasm_code_info(dup_code_id).unwrap();
+    assert_ne!(code_info.checksum, dup_code_info.checksum);
+    assert_eq!(code_info.creator, dup_code_info.creator);
+}
+
+#[test]
+fn duplicating_code_with_new_checksum_should_fail_for_unknown_code() {
+    let mut app = App::default();
+    assert_eq!(
+        "code id: invalid",
+        app.duplicate_code_with_new_checksum(0)
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        "code id 100: no such code",
+        app.duplicate_code_with_new_checksum(100)
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn instantiate2_of_duplicated_code_should_report_same_checksum() {
+    let mut app = App::default();
+    let sender = app.api().addr_make("sender");
+    let code_id = app.store_code(counter::contract());
+    let dup_code_id = app.duplicate_code(code_id).unwrap();
+
+    let contract_addr = contract_address(instantiate2(&mut app, &sender, code_id).unwrap());
+
+    let err = instantiate2(&mut app, &sender, dup_code_id).unwrap_err();
+    assert_eq!(
+        format!(
+            "Contract with this address already exists: {}, instantiated from code id {} \
+             with the same checksum, creator and salt; \
+             use `duplicate_code_with_new_checksum` to get a different address",
+            contract_addr, code_id
+        ),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn instantiate2_of_the_same_code_should_report_duplicated_address() {
+    let mut app = App::default();
+    let sender = app.api().addr_make("sender");
+    let code_id = app.store_code(counter::contract());
+
+    let contract_addr = contract_address(instantiate2(&mut app, &sender, code_id).unwrap());
+
+    let err = instantiate2(&mut app, &sender, code_id).unwrap_err();
+    assert_eq!(
+        format!(
+            "Contract with this address already exists: {}",
+            contract_addr
+        ),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn instantiate2_of_code_duplicated_with_new_checksum_should_work() {
+    let mut app = App::default();
+    let sender = app.api().addr_make("sender");
+    let code_id = app.store_code(counter::contract());
+    let dup_code_id = app.duplicate_code_with_new_checksum(code_id).unwrap();
+
+    let contract_addr = contract_address(instantiate2(&mut app, &sender, code_id).unwrap());
+    let dup_contract_addr = contract_address(instantiate2(&mut app, &sender, dup_code_id).unwrap());
+    assert_ne!(contract_addr, dup_contract_addr);
+}
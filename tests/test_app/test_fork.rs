@@ -0,0 +1,98 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{coin, coins, Empty, WasmMsg};
+use cw_multi_test::{App, Executor, IntoAddr};
+
+const DENOM: &str = "uatom";
+
+/// Prepares an application with funded user and instantiated counter contract.
+fn prepared_app() -> (App, u64, cosmwasm_std::Addr) {
+    let user = "user".into_addr();
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &user, coins(1000, DENOM))
+            .unwrap();
+    });
+    let code_id = app.store_code(counter::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, user, &Empty {}, &[], "counter", None)
+        .unwrap();
+    app.update_block(|block| block.height += 10);
+    (app, code_id, contract_addr)
+}
+
+fn counter_value(app: &App, contract_addr: &cosmwasm_std::Addr) -> u64 {
+    let response: counter::CounterResponseMsg = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &counter::CounterQueryMsg::Counter {})
+        .unwrap();
+    response.value
+}
+
+#[test]
+fn forked_app_should_have_identical_state() {
+    let (app, _, contract_addr) = prepared_app();
+    let fork = app.fork();
+
+    assert_eq!(app.block_info(), fork.block_info());
+    assert_eq!(
+        app.balances("user".into_addr()),
+        fork.balances("user".into_addr())
+    );
+    assert_eq!(
+        app.contract_data(&contract_addr).unwrap(),
+        fork.contract_data(&contract_addr).unwrap()
+    );
+    assert_eq!(
+        app.dump_wasm_raw(&contract_addr),
+        fork.dump_wasm_raw(&contract_addr)
+    );
+    assert_eq!(1, counter_value(&fork, &contract_addr));
+}
+
+#[test]
+fn forked_apps_should_diverge() {
+    let (mut app, code_id, contract_addr) = prepared_app();
+    let mut fork = app.fork();
+    let user = "user".into_addr();
+
+    // the original branch sends tokens and increments the counter
+    app.send_tokens(user.clone(), "alice".into_addr(), &coins(100, DENOM))
+        .unwrap();
+    app.execute_contract(
+        user.clone(),
+        contract_addr.clone(),
+        &WasmMsg::ClearAdmin {
+            contract_addr: contract_addr.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the forked branch sends tokens to someone else and moves to the next block
+    fork.send_tokens(user.clone(), "bob".into_addr(), &coins(300, DENOM))
+        .unwrap();
+    fork.update_block(|block| block.height += 1);
+
+    assert_eq!(coins(900, DENOM), app.balances(&user));
+    assert_eq!(coins(100, DENOM), app.balances("alice".into_addr()));
+    app.assert_balance("bob".into_addr(), &coin(0, DENOM));
+    assert_eq!(2, counter_value(&app, &contract_addr));
+
+    assert_eq!(coins(700, DENOM), fork.balances(&user));
+    assert_eq!(coins(300, DENOM), fork.balances("bob".into_addr()));
+    fork.assert_balance("alice".into_addr(), &coin(0, DENOM));
+    assert_eq!(1, counter_value(&fork, &contract_addr));
+
+    assert_eq!(app.block_info().height + 1, fork.block_info().height);
+
+    // contract code is shared, but instances and stored codes are independent
+    let forked_contract_addr = fork
+        .instantiate_contract(code_id, user.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    assert!(app.contract_data(&forked_contract_addr).is_err());
+    let forked_code_id = fork.store_code(counter::contract());
+    assert_eq!(code_id + 1, forked_code_id);
+    app.instantiate_contract(forked_code_id, user, &Empty {}, &[], "counter", None)
+        .unwrap_err();
+}
@@ -0,0 +1,93 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{
+    to_json_binary, Addr, Api, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::{
+    module_address, no_init, App, AppBuilder, Contract, ContractWrapper, Executor, IntoAddr,
+};
+use cw_storage_plus::Item;
+
+/// Address of the account allowed to execute the contract.
+const AUTHORITY: Item<Addr> = Item::new("authority");
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    authority: Addr,
+) -> StdResult<Response> {
+    AUTHORITY.save(deps.storage, &authority)?;
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, _env: Env, info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    if info.sender != AUTHORITY.load(deps.storage)? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    Ok(Response::new().add_attribute("action", "privileged"))
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&Empty {})
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Instantiates the contract gated on the governance authority of the application.
+fn instantiate_gated_contract(app: &mut App) -> Addr {
+    let code_id = app.store_code(contract());
+    let authority = app.gov_authority().unwrap();
+    let creator = app.api().addr_make("creator");
+    app.instantiate_contract(code_id, creator, &authority, &[], "gated", None)
+        .unwrap()
+}
+
+#[test]
+fn default_gov_authority_should_be_module_address() {
+    let app = App::default();
+    let authority = app.gov_authority().unwrap();
+    assert_eq!(module_address(app.api(), "gov").unwrap(), authority);
+    app.api().addr_validate(authority.as_str()).unwrap();
+}
+
+#[test]
+fn custom_gov_authority_should_work() {
+    let app = AppBuilder::default()
+        .with_gov_authority("authority".into_addr())
+        .build(no_init);
+    assert_eq!("authority".into_addr(), app.gov_authority().unwrap());
+}
+
+#[test]
+fn invalid_default_gov_authority_should_not_fail_building_app() {
+    // the api with an empty prefix can not humanize the module address
+    let mut app = AppBuilder::default()
+        .with_api(MockApi::default().with_prefix(""))
+        .build(no_init);
+    app.gov_authority().unwrap_err();
+    app.execute_as_gov("contract".into_addr(), &Empty {}, &[])
+        .unwrap_err();
+}
+
+#[test]
+fn execute_as_gov_should_work() {
+    let mut app = App::default();
+    let contract_addr = instantiate_gated_contract(&mut app);
+
+    app.execute_as_gov(contract_addr, &Empty {}, &[]).unwrap();
+}
+
+#[test]
+fn execute_by_user_should_fail() {
+    let mut app = App::default();
+    let contract_addr = instantiate_gated_contract(&mut app);
+    let user = app.api().addr_make("user");
+
+    let err = app
+        .execute_contract(user, contract_addr, &Empty {}, &[])
+        .unwrap_err();
+    assert_eq!("Generic error: unauthorized", err.root_cause().to_string());
+}
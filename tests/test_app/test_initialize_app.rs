@@ -19,3 +19,58 @@ fn initializing_app_should_work() {
     });
     assert_eq!(AMOUNT, amount);
 }
+
+#[test]
+#[cfg(feature = "staking")]
+fn adding_validator_after_build_should_work() {
+    use cosmwasm_std::{coin, Decimal, StakingMsg, Validator};
+    use cw_multi_test::{Executor, IntoBech32};
+
+    let mut app = App::default();
+
+    let delegator_addr = app.api().addr_make("delegator");
+    let validator_addr = "valoper".into_bech32();
+
+    // set initial balance and add a validator in the middle of the test
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &delegator_addr, vec![coin(100, "TOKEN")])
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(90),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+
+    // the validator is immediately available for delegations
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    let delegation = app
+        .wrap()
+        .query_delegation(delegator_addr, validator_addr.to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(coin(100, "TOKEN"), delegation.amount);
+
+    // the validator is listed in staking queries
+    assert_eq!(1, app.wrap().query_all_validators().unwrap().len());
+}
@@ -0,0 +1,102 @@
+use cosmwasm_std::{
+    Binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, Response, StdError, StdResult,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new()
+        .add_attribute("action", "init")
+        .add_event(Event::new("created").add_attribute("flavor", "vanilla")))
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> Result<Binary, StdError> {
+    Ok(Binary::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+}
+
+#[test]
+fn instantiate_contract_with_response_should_work() {
+    let mut app = App::default();
+
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+
+    let (contract_addr, res) = app
+        .instantiate_contract_with_response(code_id, owner, &Empty {}, &[], "label", None)
+        .unwrap();
+
+    // the instantiate event contains the contract address and code identifier
+    res.assert_event(
+        &Event::new("instantiate")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("code_id", code_id.to_string()),
+    );
+
+    // events emitted by the contract are available too
+    res.assert_event(
+        &Event::new("wasm")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("action", "init"),
+    );
+    res.assert_event(
+        &Event::new("wasm-created")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("flavor", "vanilla"),
+    );
+
+    // the response data is not modified
+    assert!(res.data.is_some());
+
+    // the address is the same as returned from the contract info query
+    let contract_data = app.contract_data(&contract_addr).unwrap();
+    assert_eq!(code_id, contract_data.code_id);
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_2")]
+fn instantiate2_contract_with_response_should_work() {
+    let mut app = App::default();
+
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+
+    let (contract_addr, res) = app
+        .instantiate2_contract_with_response(
+            code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            "label",
+            None,
+            [1, 2, 3],
+        )
+        .unwrap();
+
+    // the instantiate event contains the contract address and code identifier
+    res.assert_event(
+        &Event::new("instantiate")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("code_id", code_id.to_string()),
+    );
+
+    // events emitted by the contract are available too
+    res.assert_event(&Event::new("wasm").add_attribute("action", "init"));
+    res.assert_event(&Event::new("wasm-created").add_attribute("flavor", "vanilla"));
+
+    // the same address is returned by the helper not returning response
+    let mut other = App::default();
+    let code_id = other.store_code(contract());
+    assert_eq!(
+        contract_addr,
+        other
+            .instantiate2_contract(code_id, owner, &Empty {}, &[], "label", None, [1, 2, 3])
+            .unwrap()
+    );
+}
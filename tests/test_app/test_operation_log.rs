@@ -0,0 +1,431 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, to_json_vec, Addr, BankMsg, Binary, Deps, DepsMut, Empty,
+    Env, MessageInfo, Order, Record, Response, StdError, StdResult, Storage,
+};
+use cw_multi_test::error::Error;
+use cw_multi_test::{
+    next_block, no_init, replay, App, AppBuilder, AppResponse, Contract, ContractFactory,
+    ContractWrapper, Executor, IntoAddr, Operation, OperationLog, Outcome, Router, Schedule,
+};
+use cw_storage_plus::Item;
+use std::collections::BTreeMap;
+
+const TOTAL: Item<u64> = Item::new("total");
+
+#[cw_serde]
+struct AddMsg {
+    amount: u64,
+}
+
+#[cw_serde]
+struct ResetMsg {}
+
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    TOTAL.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+/// Adds the amount to the total, fails when there is nothing to add.
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: AddMsg) -> StdResult<Response> {
+    if msg.amount == 0 {
+        return Err(StdError::generic_err("nothing to add"));
+    }
+    TOTAL.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total + msg.amount)
+    })?;
+    Ok(Response::default())
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&TOTAL.load(deps.storage)?)
+}
+
+/// Resets the total.
+fn sudo(deps: DepsMut, _env: Env, _msg: ResetMsg) -> StdResult<Response> {
+    TOTAL.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query).with_sudo_empty(sudo))
+}
+
+/// Returns the registry of contract codes used in the scenario.
+fn code_registry() -> BTreeMap<String, ContractFactory> {
+    BTreeMap::from([("adder".to_string(), Box::new(contract) as ContractFactory)])
+}
+
+/// Returns all records held by the storage.
+fn dump(storage: &dyn Storage) -> Vec<Record> {
+    storage.range(None, None, Order::Ascending).collect()
+}
+
+/// Runs the scenario with the operation log enabled, returns the application
+/// and the address of the contract.
+fn scenario() -> (App, Addr) {
+    let owner = "owner".into_addr();
+    let mut app = AppBuilder::default()
+        .with_operation_log()
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &owner, coins(1000, "uatom"))
+                .unwrap();
+        });
+    let code_id = app.store_code_with_name("adder", contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "adder", None)
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        contract_addr.clone(),
+        &AddMsg { amount: 5 },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        contract_addr.clone(),
+        &AddMsg { amount: 0 },
+        &[],
+    )
+    .unwrap_err();
+    app.send_tokens(owner.clone(), contract_addr.clone(), &coins(100, "uatom"))
+        .unwrap();
+    app.update_block(next_block);
+    app.wasm_sudo(contract_addr.clone(), &ResetMsg {}).unwrap();
+    app.execute_contract(owner, contract_addr.clone(), &AddMsg { amount: 7 }, &[])
+        .unwrap();
+    (app, contract_addr)
+}
+
+#[test]
+fn operation_log_should_be_disabled_by_default() {
+    let app = App::default();
+    assert!(app.operation_log().is_none());
+}
+
+#[test]
+fn scenario_should_be_recorded() {
+    let (app, _) = scenario();
+    let log = app.operation_log().unwrap();
+
+    assert_eq!(8, log.operations.len());
+    assert!(matches!(
+        &log.operations[0].operation,
+        Operation::StoreCode { name: Some(name), code_id: 1, .. } if name == "adder"
+    ));
+    assert!(matches!(
+        log.operations[5].operation,
+        Operation::SetBlock { .. }
+    ));
+    assert!(matches!(
+        log.operations[6].operation,
+        Operation::Sudo { .. }
+    ));
+    for (step, recorded) in log.operations.iter().enumerate() {
+        assert_eq!(step != 3, recorded.outcome.is_success(), "step {}", step);
+    }
+}
+
+#[test]
+fn replayed_app_should_have_identical_state() {
+    let (app, contract_addr) = scenario();
+
+    let replayed = replay(
+        app.operation_log().unwrap(),
+        AppBuilder::default(),
+        no_init,
+        &code_registry(),
+    )
+    .unwrap();
+
+    assert_eq!(dump(app.storage()), dump(replayed.storage()));
+    assert_eq!(app.block_info(), replayed.block_info());
+    let total: u64 = replayed
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(7, total);
+}
+
+#[test]
+fn deserialized_log_should_be_replayed() {
+    let (app, _) = scenario();
+    let log = app.operation_log().unwrap();
+
+    let deserialized: OperationLog = from_json(to_json_vec(log).unwrap()).unwrap();
+    assert_eq!(log, &deserialized);
+
+    let replayed = replay(
+        &deserialized,
+        AppBuilder::default(),
+        no_init,
+        &code_registry(),
+    )
+    .unwrap();
+    assert_eq!(dump(app.storage()), dump(replayed.storage()));
+}
+
+#[test]
+fn tampered_outcome_should_report_divergence() {
+    let (app, _) = scenario();
+
+    // the failing execution is recorded as successful
+    let mut log = app.operation_log().unwrap().clone();
+    log.operations[3].outcome = Outcome::Success;
+    let err = replay(&log, AppBuilder::default(), no_init, &code_registry())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err.downcast_ref::<Error>(),
+        Some(Error::ReplayDivergence(3, _, _))
+    ));
+    assert!(
+        err.to_string()
+            .starts_with("replay diverged at operation 3: recorded success, replayed failure"),
+        "{}",
+        err
+    );
+
+    // the successful sudo call is recorded as failed
+    let mut log = app.operation_log().unwrap().clone();
+    log.operations[6].outcome = Outcome::Failure {
+        error: "tampered".to_string(),
+    };
+    let err = replay(&log, AppBuilder::default(), no_init, &code_registry())
+        .err()
+        .unwrap();
+    assert_eq!(
+        Error::replay_divergence(6, "failure (tampered)", "success").to_string(),
+        err.to_string()
+    );
+}
+
+#[test]
+fn log_should_be_replayed_with_provided_builder() {
+    let builder = || AppBuilder::default().with_api(MockApi::default().with_prefix("juno"));
+    let mut app = builder()
+        .with_operation_log()
+        .build(|router, api, storage| {
+            router
+                .bank
+                .init_balance(storage, &api.addr_make("owner"), coins(100, "ujuno"))
+                .unwrap()
+        });
+    let owner = app.api().addr_make("owner");
+    let recipient = app.api().addr_make("recipient");
+    app.send_tokens(owner.clone(), recipient.clone(), &coins(30, "ujuno"))
+        .unwrap();
+    let log = app.operation_log().unwrap();
+
+    let replayed = replay(log, builder(), no_init, &code_registry()).unwrap();
+    assert_eq!(dump(app.storage()), dump(replayed.storage()));
+    assert_eq!(coins(30, "ujuno"), replayed.balances(&recipient));
+    assert_eq!(owner, replayed.api().addr_make("owner"));
+}
+
+#[test]
+fn code_stored_without_name_should_not_be_replayed() {
+    let mut app = AppBuilder::default().with_operation_log().build(no_init);
+    app.store_code(contract());
+
+    let err = replay(
+        app.operation_log().unwrap(),
+        AppBuilder::default(),
+        no_init,
+        &code_registry(),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        "operation 0: code 1 was stored without a name, use `store_code_with_name` to replay it",
+        err.to_string()
+    );
+}
+
+#[test]
+fn code_stored_twice_with_the_same_name_should_be_replayed() {
+    let mut app = AppBuilder::default().with_operation_log().build(no_init);
+    app.store_code_with_name("adder", contract());
+    app.store_code_with_name("adder", contract());
+
+    let replayed = replay(
+        app.operation_log().unwrap(),
+        AppBuilder::default(),
+        no_init,
+        &code_registry(),
+    )
+    .unwrap();
+    assert_eq!(dump(app.storage()), dump(replayed.storage()));
+}
+
+#[test]
+fn code_missing_in_registry_should_not_be_replayed() {
+    let mut app = AppBuilder::default().with_operation_log().build(no_init);
+    app.store_code_with_name("unknown", contract());
+
+    let err = replay(
+        app.operation_log().unwrap(),
+        AppBuilder::default(),
+        no_init,
+        &code_registry(),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        "operation 0: no contract code registered under name unknown",
+        err.to_string()
+    );
+}
+
+/// Runs the scenario with a scheduled contract sudo call, returns the application
+/// and the address of the contract.
+fn scheduled_scenario() -> (App, Addr) {
+    let owner = "owner".into_addr();
+    let mut app = AppBuilder::default().with_operation_log().build(no_init);
+    let code_id = app.store_code_with_name("adder", contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "adder", None)
+        .unwrap();
+    app.execute_contract(owner, contract_addr.clone(), &AddMsg { amount: 5 }, &[])
+        .unwrap();
+    let reset_height = app.block_info().height + 2;
+    app.schedule_sudo(
+        contract_addr.clone(),
+        to_json_binary(&ResetMsg {}).unwrap(),
+        Schedule::AtHeight(reset_height),
+    )
+    .unwrap();
+    app.update_block(next_block);
+    app.update_block(next_block);
+    (app, contract_addr)
+}
+
+#[test]
+fn scheduled_sudo_should_be_replayed() {
+    let (app, contract_addr) = scheduled_scenario();
+    let log = app.operation_log().unwrap();
+
+    assert!(matches!(
+        log.operations[3].operation,
+        Operation::ScheduleSudo { .. }
+    ));
+    assert!(matches!(
+        log.operations[6].operation,
+        Operation::ScheduledSudo { schedule_id: 1, .. }
+    ));
+
+    let replayed = replay(log, AppBuilder::default(), no_init, &code_registry()).unwrap();
+    assert_eq!(dump(app.storage()), dump(replayed.storage()));
+    let total: u64 = replayed
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(0, total);
+}
+
+#[test]
+fn missing_scheduled_sudo_should_report_divergence() {
+    let (app, _) = scheduled_scenario();
+
+    // the scheduled call is not recorded
+    let mut log = app.operation_log().unwrap().clone();
+    log.operations.remove(6);
+    let err = replay(&log, AppBuilder::default(), no_init, &code_registry())
+        .err()
+        .unwrap();
+    assert_eq!(
+        Error::replay_divergence(6, "no scheduled call", "scheduled call 1 at height 12347")
+            .to_string(),
+        err.to_string()
+    );
+
+    // the schedule is not recorded
+    let mut log = app.operation_log().unwrap().clone();
+    log.operations.remove(3);
+    let err = replay(&log, AppBuilder::default(), no_init, &code_registry())
+        .err()
+        .unwrap();
+    assert_eq!(
+        Error::replay_divergence(5, "scheduled call 1 at height 12347", "no scheduled call")
+            .to_string(),
+        err.to_string()
+    );
+}
+
+#[test]
+fn different_error_should_report_divergence() {
+    let (app, _) = scenario();
+
+    let mut log = app.operation_log().unwrap().clone();
+    log.operations[3].outcome = Outcome::Failure {
+        error: "tampered".to_string(),
+    };
+    let err = replay(&log, AppBuilder::default(), no_init, &code_registry())
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string().starts_with(
+            "replay diverged at operation 3: recorded failure (tampered), replayed failure ("
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn named_sudo_should_be_replayed() {
+    let register = |router: &mut Router<_, _, _, _, _, _, _, _>, _: &_, _: &mut dyn Storage| {
+        router.register_sudo_handler("counter", |_, storage, _, _, msg| {
+            let amount: u64 = from_json(msg)?;
+            TOTAL.save(storage, &amount)?;
+            Ok(AppResponse::default())
+        });
+    };
+    let mut app = AppBuilder::default().with_operation_log().build(register);
+    app.sudo_named("counter", &7u64).unwrap();
+    let log = app.operation_log().unwrap();
+
+    assert!(matches!(
+        &log.operations[0].operation,
+        Operation::SudoNamed { name, .. } if name == "counter"
+    ));
+
+    let replayed = replay(log, AppBuilder::default(), register, &code_registry()).unwrap();
+    assert_eq!(dump(app.storage()), dump(replayed.storage()));
+
+    // the handler is not registered in the replayed application
+    let err = replay(log, AppBuilder::default(), no_init, &code_registry())
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("counter"), "{}", err);
+}
+
+#[test]
+fn scheduled_execution_should_be_rejected_while_recording() {
+    let mut app = AppBuilder::default().with_operation_log().build(no_init);
+    let msg = BankMsg::Send {
+        to_address: "recipient".into_addr().to_string(),
+        amount: coins(1, "uatom"),
+    };
+    let not_before = app.block_info().time;
+    let err = app
+        .schedule_execute("owner".into_addr(), msg.into(), not_before)
+        .unwrap_err();
+    assert_eq!(
+        "messages can not be scheduled for execution while the operation log is enabled",
+        err.to_string()
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "behavior changes can not be scheduled while the operation log is enabled"
+)]
+fn behavior_change_should_be_rejected_while_recording() {
+    let mut app = AppBuilder::default().with_operation_log().build(no_init);
+    app.schedule_behavior_change(app.block_info().height + 1, "v2", |_, _, _| {});
+}
@@ -0,0 +1,72 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{to_json_binary, Addr, Empty, WasmMsg};
+use cw_multi_test::{App, Executor, IntoAddr, Redaction};
+
+/// Instantiates and executes the counter contract on behalf of the specified sender
+/// and returns both responses redacted. Additional codes are stored before the counter
+/// contract, so the code identifier and the contract address differ between applications.
+fn redacted_responses(sender: &str, preceding_codes: usize) -> Vec<serde_json::Value> {
+    let mut app = App::default();
+    let sender_addr = sender.into_addr();
+
+    for _ in 0..preceding_codes {
+        app.store_code(counter::contract());
+    }
+    let code_id = app.store_code(counter::contract());
+
+    let instantiate_msg = WasmMsg::Instantiate {
+        admin: None,
+        code_id,
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+        label: "counter".to_string(),
+    };
+    let instantiate_response = app
+        .execute(sender_addr.clone(), instantiate_msg.into())
+        .unwrap();
+    let contract_addr = Addr::unchecked(&instantiate_response.events[0].attributes[0].value);
+
+    let execute_msg = WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    };
+    let execute_response = app
+        .execute_contract(sender_addr, contract_addr, &execute_msg, &[])
+        .unwrap();
+
+    let redactions = [
+        Redaction::Bech32Addresses("cosmwasm".to_string()),
+        Redaction::Attribute("code_id".to_string()),
+    ];
+    vec![
+        instantiate_response.redacted(&redactions),
+        execute_response.redacted(&redactions),
+    ]
+}
+
+#[test]
+fn redacted_responses_should_be_stable_across_apps() {
+    let first = redacted_responses("alice", 0);
+    let second = redacted_responses("bob", 3);
+    // only events are redacted, the instantiation data holds the encoded contract address
+    for (first, second) in first.iter().zip(&second) {
+        assert_eq!(first["events"], second["events"]);
+    }
+}
+
+#[test]
+fn redacted_responses_should_contain_placeholders() {
+    let responses = redacted_responses("alice", 2);
+
+    // instantiation event with redacted contract address and code identifier
+    let attributes = &responses[0]["events"][0]["attributes"];
+    assert_eq!("instantiate", responses[0]["events"][0]["type"]);
+    assert_eq!("_contract_address", attributes[0]["key"]);
+    assert_eq!("addr#1", attributes[0]["value"]);
+    assert_eq!("code_id", attributes[1]["key"]);
+    assert_eq!("[code_id]", attributes[1]["value"]);
+
+    // numbering of addresses starts over for every response
+    let attributes = &responses[1]["events"][0]["attributes"];
+    assert_eq!("execute", responses[1]["events"][0]["type"]);
+    assert_eq!("addr#1", attributes[0]["value"]);
+}
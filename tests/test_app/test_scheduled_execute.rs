@@ -0,0 +1,182 @@
+use cosmwasm_std::{coin, coins, Addr, BankMsg, CosmosMsg};
+use cw_multi_test::{App, AppBuilder, IntoAddr};
+
+const DENOM: &str = "uatom";
+
+/// Delay of the timelock in seconds.
+const DELAY: u64 = 2 * 86400;
+
+fn send(recipient: &Addr, amount: u128) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: coins(amount, DENOM),
+    }
+    .into()
+}
+
+fn timelock_app(auto_execute_due: bool) -> (App, Addr) {
+    let timelock_addr = "timelock".into_addr();
+    let app = AppBuilder::default()
+        .with_auto_execute_due(auto_execute_due)
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &timelock_addr, coins(100, DENOM))
+                .unwrap();
+        });
+    (app, timelock_addr)
+}
+
+#[test]
+fn early_execution_should_be_rejected() {
+    let (mut app, timelock_addr) = timelock_app(false);
+    let recipient_addr = "recipient".into_addr();
+    let not_before = app.block_info().time.plus_seconds(DELAY);
+    let id = app
+        .schedule_execute(timelock_addr.clone(), send(&recipient_addr, 60), not_before)
+        .unwrap();
+
+    app.update_block(|block| block.time = not_before.minus_seconds(1));
+    let err = app.try_execute_scheduled(id).unwrap_err();
+
+    assert!(err.to_string().contains("is not due before"));
+    app.assert_balance(&timelock_addr, &coin(100, DENOM));
+    app.assert_balance(&recipient_addr, &coin(0, DENOM));
+}
+
+#[test]
+fn due_execution_should_succeed_once() {
+    let (mut app, timelock_addr) = timelock_app(false);
+    let recipient_addr = "recipient".into_addr();
+    let not_before = app.block_info().time.plus_seconds(DELAY);
+    let id = app
+        .schedule_execute(timelock_addr.clone(), send(&recipient_addr, 60), not_before)
+        .unwrap();
+
+    app.update_block(|block| block.time = not_before);
+    app.try_execute_scheduled(id).unwrap();
+
+    app.assert_balance(&timelock_addr, &coin(40, DENOM));
+    app.assert_balance(&recipient_addr, &coin(60, DENOM));
+    // executed messages are removed
+    let err = app.try_execute_scheduled(id).unwrap_err();
+    assert_eq!(
+        format!("no scheduled execution with id {}", id),
+        err.to_string()
+    );
+}
+
+#[test]
+fn failed_execution_should_be_retried() {
+    let (mut app, timelock_addr) = timelock_app(false);
+    let recipient_addr = "recipient".into_addr();
+    let not_before = app.block_info().time;
+    let id = app
+        .schedule_execute(
+            timelock_addr.clone(),
+            send(&recipient_addr, 150),
+            not_before,
+        )
+        .unwrap();
+
+    // insufficient funds
+    app.try_execute_scheduled(id).unwrap_err();
+
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &timelock_addr, coins(150, DENOM))
+            .unwrap();
+    });
+    app.try_execute_scheduled(id).unwrap();
+    app.assert_balance(&recipient_addr, &coin(150, DENOM));
+}
+
+#[test]
+fn all_due_executions_should_be_executed_in_order() {
+    let (mut app, timelock_addr) = timelock_app(false);
+    let alice_addr = "alice".into_addr();
+    let bob_addr = "bob".into_addr();
+    let now = app.block_info().time;
+    let later = app
+        .schedule_execute(
+            timelock_addr.clone(),
+            send(&bob_addr, 100),
+            now.plus_seconds(20),
+        )
+        .unwrap();
+    let earlier = app
+        .schedule_execute(
+            timelock_addr.clone(),
+            send(&alice_addr, 100),
+            now.plus_seconds(10),
+        )
+        .unwrap();
+    let not_due = app
+        .schedule_execute(
+            timelock_addr.clone(),
+            send(&bob_addr, 1),
+            now.plus_seconds(30),
+        )
+        .unwrap();
+
+    app.update_block(|block| block.time = now.plus_seconds(20));
+    let results = app.execute_all_due();
+
+    // the earlier scheduled time is executed first and takes all tokens
+    assert_eq!(2, results.len());
+    assert_eq!(earlier, results[0].schedule_id);
+    assert!(results[0].result.is_ok());
+    assert_eq!(later, results[1].schedule_id);
+    assert!(results[1].result.is_err());
+    app.assert_balance(&alice_addr, &coin(100, DENOM));
+    app.assert_balance(&bob_addr, &coin(0, DENOM));
+    // the failed execution is kept for retry, the execution not due yet is kept too
+    assert!(app.cancel_scheduled_execution(later));
+    assert!(app.cancel_scheduled_execution(not_due));
+    assert!(!app.cancel_scheduled_execution(earlier));
+}
+
+#[test]
+fn cancelled_execution_should_not_be_executed() {
+    let (mut app, timelock_addr) = timelock_app(true);
+    let recipient_addr = "recipient".into_addr();
+    let not_before = app.block_info().time.plus_seconds(DELAY);
+    let id = app
+        .schedule_execute(timelock_addr.clone(), send(&recipient_addr, 60), not_before)
+        .unwrap();
+
+    assert!(app.cancel_scheduled_execution(id));
+    assert!(!app.cancel_scheduled_execution(id));
+    app.update_block(|block| block.time = not_before);
+
+    assert!(app.last_due_executions().is_empty());
+    app.try_execute_scheduled(id).unwrap_err();
+    app.assert_balance(&timelock_addr, &coin(100, DENOM));
+}
+
+#[test]
+fn due_executions_should_be_executed_when_block_changes() {
+    let (mut app, timelock_addr) = timelock_app(true);
+    let recipient_addr = "recipient".into_addr();
+    let not_before = app.block_info().time.plus_seconds(DELAY);
+    let id = app
+        .schedule_execute(timelock_addr.clone(), send(&recipient_addr, 60), not_before)
+        .unwrap();
+
+    app.update_block(|block| block.time = not_before.minus_seconds(1));
+    assert!(app.last_due_executions().is_empty());
+    app.assert_balance(&recipient_addr, &coin(0, DENOM));
+
+    app.update_block(|block| block.time = not_before);
+    let results = app.last_due_executions();
+    assert_eq!(1, results.len());
+    assert_eq!(id, results[0].schedule_id);
+    assert!(results[0].result.is_ok());
+    app.assert_balance(&recipient_addr, &coin(60, DENOM));
+
+    // executed only once
+    app.update_block(|block| block.time = not_before.plus_seconds(5));
+    assert!(app.last_due_executions().is_empty());
+    app.assert_balance(&recipient_addr, &coin(60, DENOM));
+}
@@ -0,0 +1,168 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, Response, StdError, StdResult,
+};
+use cw_multi_test::{next_block, App, Contract, ContractWrapper, Executor, Schedule};
+use cw_storage_plus::Item;
+
+const COUNTER: Item<u64> = Item::new("counter");
+
+#[cw_serde]
+enum ScheduleMsg {
+    Increment {},
+    Fail {},
+}
+
+fn sudo(deps: DepsMut, _env: Env, msg: ScheduleMsg) -> StdResult<Response> {
+    match msg {
+        ScheduleMsg::Increment {} => {
+            let counter = COUNTER.may_load(deps.storage)?.unwrap_or_default() + 1;
+            COUNTER.save(deps.storage, &counter)?;
+            Ok(Response::new().add_attribute("counter", counter.to_string()))
+        }
+        ScheduleMsg::Fail {} => {
+            // the change must be reverted
+            COUNTER.save(deps.storage, &1000)?;
+            Err(StdError::generic_err("scheduled call failed"))
+        }
+    }
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&COUNTER.may_load(deps.storage)?.unwrap_or_default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(noop::execute, noop::instantiate, query)
+            .with_sudo_empty(sudo),
+    )
+}
+
+fn setup() -> (App, Addr) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "cron", None)
+        .unwrap();
+    (app, contract_addr)
+}
+
+fn counter(app: &App, contract_addr: &Addr) -> u64 {
+    app.wrap()
+        .query_wasm_smart(contract_addr, &Empty {})
+        .unwrap()
+}
+
+fn increment() -> Binary {
+    to_json_binary(&ScheduleMsg::Increment {}).unwrap()
+}
+
+#[test]
+fn scheduled_sudo_should_be_called_every_n_blocks() {
+    let (mut app, contract_addr) = setup();
+    // start at the block height being a multiple of 3
+    app.update_block(|block| block.height = 12);
+    app.schedule_sudo(
+        contract_addr.clone(),
+        increment(),
+        Schedule::EveryNBlocks(3),
+    )
+    .unwrap();
+
+    // advancing block by block
+    for _ in 0..10 {
+        app.update_block(next_block);
+    }
+    assert_eq!(3, counter(&app, &contract_addr));
+
+    // advancing 10 blocks at once calls the contract for every crossed height
+    app.update_block(|block| block.height += 10);
+    assert_eq!(6, counter(&app, &contract_addr));
+    let heights: Vec<u64> = app
+        .last_scheduled_results()
+        .iter()
+        .map(|result| result.height)
+        .collect();
+    assert_eq!(vec![24, 27, 30], heights);
+}
+
+#[test]
+fn failing_scheduled_sudo_should_not_affect_others() {
+    let (mut app, contract_addr) = setup();
+    let height = app.block_info().height;
+
+    let failing_id = app
+        .schedule_sudo(
+            contract_addr.clone(),
+            to_json_binary(&ScheduleMsg::Fail {}).unwrap(),
+            Schedule::EveryNBlocks(1),
+        )
+        .unwrap();
+    let incrementing_id = app
+        .schedule_sudo(
+            contract_addr.clone(),
+            increment(),
+            Schedule::EveryNBlocks(1),
+        )
+        .unwrap();
+
+    app.update_block(next_block);
+    assert_eq!(1, counter(&app, &contract_addr));
+
+    let results = app.last_scheduled_results();
+    assert_eq!(2, results.len());
+    assert_eq!(failing_id, results[0].schedule_id);
+    assert_eq!(height + 1, results[0].height);
+    assert_eq!(
+        "Generic error: scheduled call failed",
+        results[0].result.as_ref().unwrap_err()
+    );
+    assert_eq!(incrementing_id, results[1].schedule_id);
+    assert_eq!(contract_addr, results[1].contract);
+    results[1].result.as_ref().unwrap();
+
+    // removed schedule is not called anymore
+    assert!(app.remove_scheduled_sudo(failing_id));
+    assert!(!app.remove_scheduled_sudo(failing_id));
+    app.update_block(next_block);
+    assert_eq!(2, counter(&app, &contract_addr));
+    assert_eq!(1, app.last_scheduled_results().len());
+}
+
+#[test]
+fn scheduled_sudo_should_be_called_once_at_height() {
+    let (mut app, contract_addr) = setup();
+    let height = app.block_info().height;
+    app.schedule_sudo(
+        contract_addr.clone(),
+        increment(),
+        Schedule::AtHeight(height + 2),
+    )
+    .unwrap();
+
+    app.update_block(next_block);
+    assert_eq!(0, counter(&app, &contract_addr));
+    assert!(app.last_scheduled_results().is_empty());
+
+    app.update_block(next_block);
+    assert_eq!(1, counter(&app, &contract_addr));
+
+    // going back and forth does not call the contract again
+    app.update_block(|block| block.height = height);
+    app.update_block(|block| block.height = height + 5);
+    assert_eq!(1, counter(&app, &contract_addr));
+}
+
+#[test]
+fn scheduling_every_zero_blocks_should_fail() {
+    let (mut app, contract_addr) = setup();
+    assert_eq!(
+        "number of blocks between scheduled calls must be greater than zero",
+        app.schedule_sudo(contract_addr, increment(), Schedule::EveryNBlocks(0))
+            .unwrap_err()
+            .to_string()
+    );
+}
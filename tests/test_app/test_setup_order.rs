@@ -0,0 +1,127 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult,
+};
+use cw_multi_test::{
+    assert_setup_order_independent, check_setup_order, no_init, App, AppBuilder, Contract,
+    ContractWrapper, Executor, IntoAddr,
+};
+use cw_storage_plus::{Item, Map};
+
+/// Member receiving the bonus, expected to be the member with the lowest address.
+const LEADER: Item<Addr> = Item::new("leader");
+const MEMBERS: Map<&Addr, Empty> = Map::new("members");
+
+#[cw_serde]
+enum ExecuteMsg {
+    Register {},
+}
+
+/// Order-dependence bug: the first registered member becomes the leader.
+fn execute_buggy(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    _msg: ExecuteMsg,
+) -> StdResult<Response> {
+    MEMBERS.save(deps.storage, &info.sender, &Empty {})?;
+    if LEADER.may_load(deps.storage)?.is_none() {
+        LEADER.save(deps.storage, &info.sender)?;
+    }
+    Ok(Response::default())
+}
+
+/// The member with the lowest address becomes the leader, whatever the registration order.
+fn execute_fixed(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    _msg: ExecuteMsg,
+) -> StdResult<Response> {
+    MEMBERS.save(deps.storage, &info.sender, &Empty {})?;
+    match LEADER.may_load(deps.storage)? {
+        Some(leader) if leader <= info.sender => {}
+        _ => LEADER.save(deps.storage, &info.sender)?,
+    }
+    Ok(Response::default())
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&LEADER.may_load(deps.storage)?)
+}
+
+/// Instantiates the contract and registers all members in the setup order.
+fn registration(contract: fn() -> Box<dyn Contract<Empty>>, reversed: bool) -> App {
+    let mut app = AppBuilder::default()
+        .with_reversed_setup(reversed)
+        .build(no_init);
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            "owner".into_addr(),
+            &Empty {},
+            &[],
+            "members",
+            None,
+        )
+        .unwrap();
+    let members = ["alice", "bob", "carol"].map(|name| name.into_addr());
+    for member in app.in_setup_order(members) {
+        app.execute_contract(member, contract_addr.clone(), &ExecuteMsg::Register {}, &[])
+            .unwrap();
+    }
+    app
+}
+
+fn buggy_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        execute_buggy,
+        noop::instantiate,
+        query,
+    ))
+}
+
+fn fixed_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        execute_fixed,
+        noop::instantiate,
+        query,
+    ))
+}
+
+#[test]
+fn setup_steps_should_be_reversed() {
+    let app = AppBuilder::default().build(no_init);
+    assert_eq!(vec![1, 2, 3], app.in_setup_order([1, 2, 3]));
+
+    let app = AppBuilder::default()
+        .with_reversed_setup(true)
+        .build(no_init);
+    assert_eq!(vec![3, 2, 1], app.in_setup_order([1, 2, 3]));
+}
+
+#[test]
+fn order_dependence_should_be_detected() {
+    let divergence = check_setup_order(|reversed| registration(buggy_contract, reversed)).unwrap();
+
+    // the leader is stored under the contract's namespace
+    assert!(divergence.key.ends_with(b"leader"));
+    let original: Addr = from_json(divergence.original.unwrap()).unwrap();
+    let reversed: Addr = from_json(divergence.reversed.unwrap()).unwrap();
+    assert_eq!("alice".into_addr(), original);
+    assert_eq!("carol".into_addr(), reversed);
+}
+
+#[test]
+#[should_panic(expected = "state depends on the setup order")]
+fn order_dependence_should_fail_assertion() {
+    assert_setup_order_independent(|reversed| registration(buggy_contract, reversed));
+}
+
+#[test]
+fn order_independent_contract_should_pass() {
+    assert_setup_order_independent(|reversed| registration(fixed_contract, reversed));
+}
@@ -0,0 +1,142 @@
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::{coins, to_json_vec, Storage};
+use cw_multi_test::error::Error;
+use cw_multi_test::prefixed_storage::{prefixed, prefixed_read};
+use cw_multi_test::{
+    migrate_state, no_init, App, AppBuilder, Executor, IntoAddr, SnapshotStorage, LAYOUT_VERSION,
+    LAYOUT_VERSION_KEY,
+};
+
+/// Namespaces of built-in keepers with versioned storage layouts.
+const NAMESPACES: &[&[u8]] = &[
+    b"bank",
+    #[cfg(feature = "staking")]
+    b"staking",
+    #[cfg(feature = "staking")]
+    b"distribution",
+    b"wasm",
+];
+
+/// Returns the raw layout version stored under specified namespace.
+fn layout_version(storage: &dyn Storage, namespace: &[u8]) -> Option<Vec<u8>> {
+    prefixed_read(storage, namespace).get(LAYOUT_VERSION_KEY.as_bytes())
+}
+
+/// Returns the storage of the application with some tokens held by the owner,
+/// with the layout versions removed, like the state written before they were introduced.
+fn unversioned_storage() -> MockStorage {
+    let owner = "owner".into_addr();
+    let app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, coins(100, "uatom"))
+            .unwrap();
+    });
+    let mut storage = app.storage().snapshot();
+    for namespace in NAMESPACES {
+        prefixed(&mut storage, namespace).remove(LAYOUT_VERSION_KEY.as_bytes());
+    }
+    storage
+}
+
+#[test]
+fn fresh_app_should_write_layout_versions() {
+    let app = App::default();
+
+    for namespace in NAMESPACES {
+        assert_eq!(
+            Some(to_json_vec(&LAYOUT_VERSION).unwrap()),
+            layout_version(app.storage(), namespace)
+        );
+    }
+}
+
+#[test]
+fn mismatched_layout_version_should_be_reported() {
+    let mut app = App::default();
+    let owner = "owner".into_addr();
+    prefixed(app.storage_mut(), b"bank").set(LAYOUT_VERSION_KEY.as_bytes(), b"2");
+
+    let expected = Error::storage_layout_mismatch("bank", 2, LAYOUT_VERSION).to_string();
+    assert_eq!(
+        "bank storage layout v2 found, this version expects v1; see MIGRATIONS.md",
+        expected
+    );
+
+    let err = app
+        .send_tokens(owner.clone(), "recipient".into_addr(), &coins(1, "uatom"))
+        .unwrap_err();
+    assert_eq!(expected, err.to_string());
+
+    let err = app.wrap().query_all_balances(&owner).unwrap_err();
+    assert!(err.to_string().contains(&expected), "{}", err);
+}
+
+#[test]
+fn unversioned_state_should_be_reported() {
+    let app = AppBuilder::default()
+        .with_storage(unversioned_storage())
+        .build(no_init);
+
+    let err = app
+        .wrap()
+        .query_all_balances("owner".into_addr())
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(&Error::storage_layout_mismatch("bank", 0, LAYOUT_VERSION).to_string()),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn migrating_unversioned_state_should_work() {
+    let mut storage = unversioned_storage();
+
+    migrate_state(&mut storage, 0).unwrap();
+
+    for namespace in NAMESPACES {
+        assert_eq!(
+            Some(to_json_vec(&LAYOUT_VERSION).unwrap()),
+            layout_version(&storage, namespace)
+        );
+    }
+    let app = AppBuilder::default().with_storage(storage).build(no_init);
+    assert_eq!(coins(100, "uatom"), app.balances("owner".into_addr()));
+}
+
+#[test]
+fn migrating_from_current_version_should_be_noop() {
+    let app = App::default();
+    let mut storage = app.storage().snapshot();
+
+    migrate_state(&mut storage, LAYOUT_VERSION).unwrap();
+
+    for namespace in NAMESPACES {
+        assert_eq!(
+            layout_version(app.storage(), namespace),
+            layout_version(&storage, namespace)
+        );
+    }
+}
+
+#[test]
+fn migrating_from_unexpected_version_should_fail() {
+    let app = App::default();
+    let mut storage = app.storage().snapshot();
+
+    // the state is already written in the current layout version
+    let err = migrate_state(&mut storage, 0).unwrap_err();
+    assert_eq!(
+        "bank storage layout v1 found, cannot migrate from v0",
+        err.to_string()
+    );
+
+    // there are no layout versions newer than the current one
+    let err = migrate_state(&mut storage, LAYOUT_VERSION + 1).unwrap_err();
+    assert_eq!(
+        "storage layout v2 is unknown, the latest one is v1",
+        err.to_string()
+    );
+}
@@ -0,0 +1,88 @@
+use cosmwasm_std::{coins, from_json, Api, Binary, Storage};
+use cw_multi_test::error::{bail, AnyResult, Error};
+use cw_multi_test::{App, AppBuilder, AppResponse, BankSudo, CosmosRouter, IntoAddr};
+use cw_storage_plus::Item;
+
+const PRICE: Item<u64> = Item::new("oracle_price");
+
+/// Stores the price, fails for zero price after storing it.
+fn oracle(_api: &dyn Api, storage: &mut dyn Storage, price: Binary) -> AnyResult<AppResponse> {
+    let price: u64 = from_json(price)?;
+    PRICE.save(storage, &price)?;
+    if price == 0 {
+        bail!("zero price");
+    }
+    Ok(AppResponse::default())
+}
+
+fn app() -> App {
+    AppBuilder::default().build(|router, _, _| {
+        router.register_sudo_handler("oracle", |api, storage, _, _, msg| {
+            oracle(api, storage, msg)
+        });
+        router.register_sudo_handler("treasury", |api, storage, router, block, msg| {
+            let recipient: String = from_json(msg)?;
+            router.sudo(
+                api,
+                storage,
+                block,
+                BankSudo::Mint {
+                    to_address: recipient,
+                    amount: coins(100, "uatom"),
+                }
+                .into(),
+            )
+        });
+    })
+}
+
+#[test]
+fn named_sudo_handlers_should_be_dispatched() {
+    let mut app = app();
+    let recipient_addr = "recipient".into_addr();
+
+    app.sudo_named("oracle", &42).unwrap();
+    assert_eq!(
+        42,
+        app.init_modules(|_, _, storage| PRICE.load(storage).unwrap())
+    );
+
+    let response = app.sudo_named("treasury", &recipient_addr).unwrap();
+    assert_eq!("coin_received", response.events[0].ty);
+    assert_eq!(coins(100, "uatom"), app.balances(&recipient_addr));
+}
+
+#[test]
+fn failing_named_sudo_handler_should_revert_changes() {
+    let mut app = app();
+
+    app.sudo_named("oracle", &42).unwrap();
+    let err = app.sudo_named("oracle", &0).unwrap_err();
+    assert_eq!("zero price", err.to_string());
+    assert_eq!(
+        42,
+        app.init_modules(|_, _, storage| PRICE.load(storage).unwrap())
+    );
+}
+
+#[test]
+fn unknown_named_sudo_handler_should_fail() {
+    let mut app = app();
+
+    let err = app.sudo_named("mint", &42).unwrap_err();
+    assert_eq!(
+        Error::unknown_sudo_handler("mint", "oracle, treasury").to_string(),
+        err.to_string()
+    );
+    assert_eq!(
+        r#"unknown sudo handler "mint", registered handlers: [oracle, treasury]"#,
+        err.to_string()
+    );
+
+    // no handlers registered
+    let err = App::default().sudo_named("oracle", &42).unwrap_err();
+    assert_eq!(
+        r#"unknown sudo handler "oracle", registered handlers: []"#,
+        err.to_string()
+    );
+}
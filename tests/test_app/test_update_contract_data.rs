@@ -0,0 +1,103 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::{App, Executor, IntoAddr};
+
+/// Stores the contract code and instantiates the contract without admin.
+fn setup(app: &mut App) -> (u64, Addr) {
+    let creator = app.api().addr_make("creator");
+    let code_id = app.store_code(noop::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, creator, &Empty {}, &[], "fixture", None)
+        .unwrap();
+    (code_id, contract_addr)
+}
+
+#[test]
+fn admin_set_directly_should_migrate_contract() {
+    let mut app = App::default();
+    let (_, contract_addr) = setup(&mut app);
+    let new_code_id = app.store_code(noop::contract());
+    let admin = app.gov_authority().unwrap();
+
+    app.set_contract_admin(&contract_addr, Some(admin.clone()))
+        .unwrap();
+
+    let contract_info = app.wrap().query_wasm_contract_info(&contract_addr).unwrap();
+    assert_eq!(Some(admin.clone()), contract_info.admin);
+
+    app.migrate_contract(admin, contract_addr.clone(), &Empty {}, new_code_id)
+        .unwrap();
+    assert_eq!(
+        new_code_id,
+        app.contract_data(&contract_addr).unwrap().code_id
+    );
+}
+
+#[test]
+fn clearing_admin_should_work() {
+    let mut app = App::default();
+    let (_, contract_addr) = setup(&mut app);
+    let admin = app.api().addr_make("admin");
+
+    app.set_contract_admin(&contract_addr, Some(admin)).unwrap();
+    app.set_contract_admin(&contract_addr, None).unwrap();
+
+    assert_eq!(None, app.contract_data(&contract_addr).unwrap().admin);
+}
+
+#[test]
+fn setting_creator_should_work() {
+    let mut app = App::default();
+    let (_, contract_addr) = setup(&mut app);
+    let creator = app.api().addr_make("mainnet-creator");
+
+    app.set_contract_creator(&contract_addr, creator.clone())
+        .unwrap();
+
+    let contract_info = app.wrap().query_wasm_contract_info(&contract_addr).unwrap();
+    assert_eq!(creator, contract_info.creator);
+}
+
+#[test]
+fn updating_contract_data_should_work() {
+    let mut app = App::default();
+    let (code_id, contract_addr) = setup(&mut app);
+
+    app.update_contract_data(&contract_addr, |data| {
+        data.created = 1;
+        data.label = "mainnet".to_string();
+    })
+    .unwrap();
+
+    let data = app.contract_data(&contract_addr).unwrap();
+    assert_eq!(code_id, data.code_id);
+    assert_eq!(1, data.created);
+    assert_eq!("mainnet", data.label);
+}
+
+#[test]
+fn invalid_addresses_should_be_rejected() {
+    let mut app = App::default();
+    let (_, contract_addr) = setup(&mut app);
+    let data = app.contract_data(&contract_addr).unwrap();
+
+    // addresses with another prefix are not valid for the default Api
+    app.set_contract_admin(&contract_addr, Some("admin".into_addr_with_prefix("juno")))
+        .unwrap_err();
+    app.set_contract_creator(&contract_addr, "creator".into_addr_with_prefix("juno"))
+        .unwrap_err();
+
+    assert_eq!(data, app.contract_data(&contract_addr).unwrap());
+}
+
+#[test]
+fn updating_non_existing_contract_should_fail() {
+    let mut app = App::default();
+    let contract_addr = app.api().addr_make("missing");
+
+    let err = app.set_contract_admin(&contract_addr, None).unwrap_err();
+    assert_eq!(
+        format!("contract {contract_addr} does not exist"),
+        err.to_string()
+    );
+}
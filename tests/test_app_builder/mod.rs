@@ -8,8 +8,11 @@ use std::marker::PhantomData;
 mod test_with_api;
 mod test_with_bank;
 mod test_with_block;
+mod test_with_block_jitter;
 #[cfg(feature = "staking")]
 mod test_with_distribution;
+mod test_with_event_provenance;
+mod test_with_genesis_balances;
 #[cfg(feature = "stargate")]
 mod test_with_gov;
 #[cfg(feature = "stargate")]
@@ -0,0 +1,147 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, BlockInfo, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult, WasmMsg,
+};
+use cw_multi_test::{no_init, App, AppBuilder, ContractWrapper, Executor, IntoAddr};
+use cw_storage_plus::Item;
+
+/// Block times (in seconds) observed by the contract, labeled by the caller.
+const TIMES: Item<Vec<(String, u64)>> = Item::new("times");
+
+#[cw_serde]
+enum ExecuteMsg {
+    /// Records the block time, then records it again in a submessage with the nested label.
+    Record {
+        label: String,
+        nested: Option<String>,
+    },
+}
+
+#[cw_serde]
+enum QueryMsg {
+    /// Returns the recorded block times.
+    Times {},
+    /// Returns the block time observed by the query.
+    Now {},
+}
+
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    TIMES.save(deps.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    let ExecuteMsg::Record { label, nested } = msg;
+    TIMES.update(deps.storage, |mut times| -> StdResult<_> {
+        times.push((label, env.block.time.seconds()));
+        Ok(times)
+    })?;
+    let mut response = Response::new();
+    if let Some(label) = nested {
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::Record {
+                label,
+                nested: None,
+            })?,
+            funds: vec![],
+        });
+    }
+    Ok(response)
+}
+
+fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Times {} => to_json_binary(&TIMES.load(deps.storage)?),
+        QueryMsg::Now {} => to_json_binary(&env.block.time.seconds()),
+    }
+}
+
+/// Instantiates the contract recording block times, this is the message with index 0.
+fn instantiate_contract(app: &mut App) -> Addr {
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    app.instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "times", None)
+        .unwrap()
+}
+
+fn record(contract_addr: &Addr, label: &str, nested: &str) -> cosmwasm_std::CosmosMsg {
+    WasmMsg::Execute {
+        contract_addr: contract_addr.to_string(),
+        msg: to_json_binary(&ExecuteMsg::Record {
+            label: label.to_string(),
+            nested: Some(nested.to_string()),
+        })
+        .unwrap(),
+        funds: vec![],
+    }
+    .into()
+}
+
+#[test]
+fn top_level_messages_should_observe_jittered_block() {
+    let mut app = AppBuilder::default()
+        .with_block_jitter(Box::new(|block: &BlockInfo, index: u64| {
+            let mut block = block.clone();
+            block.time = block.time.plus_seconds(10 * index);
+            block
+        }))
+        .build(no_init);
+    let canonical = app.block_info();
+    let contract_addr = instantiate_contract(&mut app);
+
+    app.execute_multi(
+        "sender".into_addr(),
+        vec![
+            record(&contract_addr, "first", "first-nested"),
+            record(&contract_addr, "second", "second-nested"),
+        ],
+    )
+    .unwrap();
+
+    // messages with index 1 and 2 are shifted, submessages observe the block of their parent
+    let now = canonical.time.seconds();
+    let times: Vec<(String, u64)> = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &QueryMsg::Times {})
+        .unwrap();
+    assert_eq!(
+        vec![
+            ("first".to_string(), now + 10),
+            ("first-nested".to_string(), now + 10),
+            ("second".to_string(), now + 20),
+            ("second-nested".to_string(), now + 20),
+        ],
+        times
+    );
+
+    // the canonical block is not changed and is observed by queries
+    assert_eq!(canonical, app.block_info());
+    let queried: u64 = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &QueryMsg::Now {})
+        .unwrap();
+    assert_eq!(now, queried);
+}
+
+#[test]
+fn messages_should_observe_canonical_block_by_default() {
+    let mut app = App::default();
+    let now = app.block_info().time.seconds();
+    let contract_addr = instantiate_contract(&mut app);
+
+    app.execute_multi(
+        "sender".into_addr(),
+        vec![
+            record(&contract_addr, "first", "first-nested"),
+            record(&contract_addr, "second", "second-nested"),
+        ],
+    )
+    .unwrap();
+
+    let times: Vec<(String, u64)> = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &QueryMsg::Times {})
+        .unwrap();
+    assert!(times.iter().all(|(_, time)| *time == now), "{:?}", times);
+}
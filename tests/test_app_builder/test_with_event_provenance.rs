@@ -0,0 +1,146 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, Addr, Api, BankMsg, Binary, BlockInfo, CosmosMsg, CustomMsg, CustomQuery, Empty, Event,
+    Querier, Storage,
+};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{
+    AppResponse, BasicAppBuilder, CosmosRouter, Executor, IntoAddr, Module, ModuleKind,
+};
+use serde::de::DeserializeOwned;
+
+const DENOM: &str = "denom";
+
+/// Transfers tokens through the custom module, like a custom wrapper of the bank module.
+#[cw_serde]
+struct WrappedTransfer {
+    recipient: String,
+    amount: u128,
+}
+
+impl CustomMsg for WrappedTransfer {}
+
+/// Custom module emitting its own `transfer` event next to the event of the bank transfer.
+struct TransferModule;
+
+impl Module for TransferModule {
+    type ExecT = WrappedTransfer;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: WrappedTransfer,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let send = BankMsg::Send {
+            to_address: msg.recipient.clone(),
+            amount: coins(msg.amount, DENOM),
+        };
+        let mut response = router.execute(api, storage, block, sender, send.into())?;
+        response.events.push(
+            Event::new("transfer")
+                .add_attribute("wrapped_recipient", msg.recipient)
+                .add_attribute("wrapped_amount", msg.amount.to_string()),
+        );
+        Ok(response)
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        _request: Empty,
+    ) -> AnyResult<Binary> {
+        bail!("queries are not supported")
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("sudo is not supported")
+    }
+}
+
+fn execute_wrapped_transfer(event_provenance: bool) -> AppResponse {
+    let sender = "sender".into_addr();
+    let mut app = BasicAppBuilder::<WrappedTransfer, Empty>::new_custom()
+        .with_custom(TransferModule)
+        .with_event_provenance(event_provenance)
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &sender, coins(100, DENOM))
+                .unwrap();
+        });
+    let msg = WrappedTransfer {
+        recipient: "recipient".into_addr().to_string(),
+        amount: 10,
+    };
+    app.execute(sender, CosmosMsg::Custom(msg)).unwrap()
+}
+
+#[test]
+fn events_should_be_tagged_with_emitting_module() {
+    let response = execute_wrapped_transfer(true);
+
+    let bank_events = response.events_from(ModuleKind::Bank);
+    assert_eq!(1, bank_events.len());
+    assert_eq!("transfer", bank_events[0].ty);
+    assert!(bank_events[0]
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "amount" && attr.value == "10denom"));
+
+    let custom_events = response.events_from(ModuleKind::Custom);
+    assert_eq!(1, custom_events.len());
+    assert_eq!("transfer", custom_events[0].ty);
+    assert!(custom_events[0]
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "wrapped_amount" && attr.value == "10"));
+
+    assert!(response.events_from(ModuleKind::Wasm).is_empty());
+    // each event is tagged exactly once
+    for event in &response.events {
+        assert_eq!(
+            1,
+            event
+                .attributes
+                .iter()
+                .filter(|attr| attr.key == "_module")
+                .count()
+        );
+    }
+}
+
+#[test]
+fn events_should_not_be_tagged_by_default() {
+    let response = execute_wrapped_transfer(false);
+
+    assert_eq!(2, response.events.len());
+    assert!(response.events_from(ModuleKind::Bank).is_empty());
+    assert!(response.events_from(ModuleKind::Custom).is_empty());
+    for event in &response.events {
+        assert!(event.attributes.iter().all(|attr| attr.key != "_module"));
+    }
+}
@@ -0,0 +1,64 @@
+use cosmwasm_std::coins;
+use cw_multi_test::{no_init, AppBuilder, IntoAddr};
+
+#[test]
+fn building_app_with_genesis_balances_should_work() {
+    let balances = (0..3u128)
+        .map(|i| (format!("account{}", i).into_addr(), coins(i + 1, "uatom")))
+        .collect::<Vec<_>>();
+
+    let app = AppBuilder::default()
+        .with_genesis_balances(balances)
+        .build(no_init);
+
+    // check all balances
+    for i in 0..3u128 {
+        let balance = app
+            .wrap()
+            .query_balance(format!("account{}", i).into_addr(), "uatom")
+            .unwrap();
+        assert_eq!(i + 1, balance.amount.u128());
+    }
+
+    // check the aggregated supply
+    #[cfg(feature = "cosmwasm_1_1")]
+    assert_eq!(6, app.wrap().query_supply("uatom").unwrap().amount.u128());
+}
+
+#[test]
+fn building_app_with_many_genesis_balances_should_work() {
+    let balances = (0..10_000u128)
+        .map(|i| (format!("account{}", i).into_addr(), coins(i + 1, "uatom")))
+        .collect::<Vec<_>>();
+
+    let app = AppBuilder::default()
+        .with_genesis_balances(balances)
+        .build(no_init);
+
+    // check a few balances
+    for i in [0, 4_999, 9_999] {
+        let balance = app
+            .wrap()
+            .query_balance(format!("account{}", i).into_addr(), "uatom")
+            .unwrap();
+        assert_eq!(i + 1, balance.amount.u128());
+    }
+
+    // check the aggregated supply
+    #[cfg(feature = "cosmwasm_1_1")]
+    assert_eq!(
+        10_000 * 10_001 / 2,
+        app.wrap().query_supply("uatom").unwrap().amount.u128()
+    );
+}
+
+#[test]
+#[should_panic(expected = "duplicated account in genesis balances")]
+fn building_app_with_duplicated_genesis_balances_should_panic() {
+    AppBuilder::default()
+        .with_genesis_balances(vec![
+            ("alice".into_addr(), coins(100, "uatom")),
+            ("alice".into_addr(), coins(200, "uatom")),
+        ])
+        .build(no_init);
+}
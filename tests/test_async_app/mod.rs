@@ -0,0 +1,2 @@
+mod test_sequential_processing;
+mod test_structured_messages;
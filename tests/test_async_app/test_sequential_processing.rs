@@ -0,0 +1,111 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{App, AsyncApp, Contract, ContractWrapper, IntoAddr};
+use cw_storage_plus::Item;
+use std::sync::Arc;
+
+/// Identifiers of executions in the order they were processed.
+const LOG: Item<Vec<u32>> = Item::new("log");
+
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    LOG.save(deps.storage, &vec![])?;
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, id: u32) -> StdResult<Response> {
+    LOG.update(deps.storage, |mut log| -> StdResult<_> {
+        log.push(id);
+        Ok(log)
+    })?;
+    Ok(Response::default())
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&LOG.load(deps.storage)?)
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+}
+
+/// Creates the wrapped application with instantiated logging contract.
+async fn setup() -> (AsyncApp, Addr) {
+    let app = AsyncApp::new(App::default);
+    let code_id = app.with_app(|app| app.store_code(contract())).await;
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            "owner".into_addr(),
+            Empty {},
+            vec![],
+            "logger".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+    (app, contract_addr)
+}
+
+#[tokio::test]
+async fn operations_should_be_processed_in_submission_order() {
+    let (app, contract_addr) = setup().await;
+    let sender = "sender".into_addr();
+
+    // submit all executions first, then await them in reverse order
+    let executions = (0..5)
+        .map(|id| app.execute_contract(sender.clone(), contract_addr.clone(), id, vec![]))
+        .collect::<Vec<_>>();
+    for execution in executions.into_iter().rev() {
+        execution.await.unwrap();
+    }
+
+    let log: Vec<u32> = app.query_wasm_smart(contract_addr, Empty {}).await.unwrap();
+    assert_eq!(vec![0, 1, 2, 3, 4], log);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_tasks_should_be_processed_sequentially() {
+    let (app, contract_addr) = setup().await;
+    let app = Arc::new(app);
+
+    // every task executes the contract and advances the block
+    let tasks = (0..10)
+        .map(|id| {
+            let app = Arc::clone(&app);
+            let contract_addr = contract_addr.clone();
+            tokio::spawn(async move {
+                app.execute_contract("sender".into_addr(), contract_addr, id, vec![])
+                    .await
+                    .unwrap();
+                app.update_block(|block| block.height += 1).await;
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    // each execution was processed exactly once
+    let mut log: Vec<u32> = app.query_wasm_smart(contract_addr, Empty {}).await.unwrap();
+    log.sort();
+    assert_eq!((0..10).collect::<Vec<_>>(), log);
+    let height = app.with_app(|app| app.block_info().height).await;
+    assert_eq!(12355, height);
+}
+
+#[tokio::test]
+async fn panic_should_be_propagated_to_awaiting_task() {
+    let (app, _) = setup().await;
+    let app = Arc::new(app);
+
+    let failing = Arc::clone(&app);
+    let err = tokio::spawn(async move { failing.with_app(|_| panic!("failure")).await })
+        .await
+        .unwrap_err();
+    assert!(err.is_panic());
+
+    // the application is still available after the panic
+    let height = app.with_app(|app| app.block_info().height).await;
+    assert_eq!(12345, height);
+}
@@ -0,0 +1,86 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{App, AsyncApp, Contract, ContractWrapper, IntoAddr};
+use cw_storage_plus::Item;
+
+const GREETING: Item<String> = Item::new("greeting");
+
+#[cw_serde]
+struct InstantiateMsg {
+    greeting: String,
+}
+
+#[cw_serde]
+enum ExecuteMsg {
+    SetGreeting { greeting: String },
+}
+
+#[cw_serde]
+enum QueryMsg {
+    Greeting {},
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    GREETING.save(deps.storage, &msg.greeting)?;
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::SetGreeting { greeting } => GREETING.save(deps.storage, &greeting)?,
+    }
+    Ok(Response::default())
+}
+
+fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Greeting {} => to_json_binary(&GREETING.load(deps.storage)?),
+    }
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query))
+}
+
+#[tokio::test]
+async fn structured_messages_should_be_processed() {
+    let app = AsyncApp::new(App::default);
+    let code_id = app.with_app(|app| app.store_code(contract())).await;
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            "owner".into_addr(),
+            InstantiateMsg {
+                greeting: "hello".to_string(),
+            },
+            vec![],
+            "greeter".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    app.execute_contract(
+        "sender".into_addr(),
+        contract_addr.clone(),
+        ExecuteMsg::SetGreeting {
+            greeting: "hi".to_string(),
+        },
+        vec![],
+    )
+    .await
+    .unwrap();
+
+    let greeting: String = app
+        .query_wasm_smart(contract_addr, QueryMsg::Greeting {})
+        .await
+        .unwrap();
+    assert_eq!("hi", greeting);
+}
@@ -1 +1,2 @@
+mod test_contract_events;
 mod test_empty_attribute;
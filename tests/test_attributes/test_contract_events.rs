@@ -0,0 +1,120 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, Response, StdResult,
+    WasmMsg,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+#[cw_serde]
+struct ExecMsg {
+    /// Identifier of the executed proposal.
+    id: String,
+    /// Address of the contract and the proposal identifier forwarded to it.
+    forward: Option<(String, String)>,
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    let mut response = Response::new()
+        .add_attribute("action", "execute")
+        .add_event(Event::new("proposal_executed").add_attribute("id", msg.id));
+    if let Some((contract_addr, id)) = msg.forward {
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr,
+            msg: to_json_binary(&ExecMsg { id, forward: None })?,
+            funds: vec![],
+        });
+    }
+    Ok(response)
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+#[test]
+fn contract_events_should_be_filtered_by_contract() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let first_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+    let second_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "second", None)
+        .unwrap();
+
+    // both contracts emit an event with the same type in one transaction
+    let response = app
+        .execute_contract(
+            owner_addr,
+            first_addr.clone(),
+            &ExecMsg {
+                id: "1".to_string(),
+                forward: Some((second_addr.to_string(), "2".to_string())),
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec![Event::new("proposal_executed").add_attribute("id", "1")],
+        response.contract_events(&first_addr)
+    );
+    assert_eq!(
+        vec![Event::new("proposal_executed").add_attribute("id", "2")],
+        response.contract_events(&second_addr)
+    );
+
+    assert_eq!(
+        Some("1".to_string()),
+        response.contract_attr(&first_addr, "proposal_executed", "id")
+    );
+    assert_eq!(
+        Some("2".to_string()),
+        response.contract_attr(&second_addr, "proposal_executed", "id")
+    );
+
+    // the `wasm` event is not a custom event
+    assert_eq!(None, response.contract_attr(&first_addr, "wasm", "action"));
+    assert_eq!(
+        None,
+        response.contract_attr(&first_addr, "proposal_executed", "missing")
+    );
+    assert_eq!(
+        None,
+        response.contract_attr(&first_addr, "proposal_rejected", "id")
+    );
+}
+
+#[test]
+fn contract_events_should_be_empty_for_other_contract() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+
+    let response = app
+        .execute_contract(
+            owner_addr.clone(),
+            contract_addr,
+            &ExecMsg {
+                id: "1".to_string(),
+                forward: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert!(response.contract_events(&owner_addr).is_empty());
+}
@@ -1 +1,9 @@
+mod test_balances;
+mod test_faucet;
+mod test_force_transfer;
 mod test_init_balance;
+mod test_mint_events;
+mod test_multi_send;
+mod test_send_tokens_multi;
+mod test_transfers;
+mod test_voucher_denoms;
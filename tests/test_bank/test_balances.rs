@@ -0,0 +1,98 @@
+use cosmwasm_std::{coin, coins, Coin};
+use cw_multi_test::{App, IntoAddr};
+
+fn app_with_balance(owner: &str, balance: Vec<Coin>) -> App {
+    App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner.into_addr(), balance)
+            .unwrap();
+    })
+}
+
+#[test]
+fn empty_account_should_have_no_balances() {
+    let app = App::default();
+    let addr = "unknown".into_addr();
+
+    // like on chain, all balances of an empty account is an empty list
+    assert_eq!(
+        Vec::<Coin>::new(),
+        app.wrap().query_all_balances(&addr).unwrap()
+    );
+    assert_eq!(Vec::<Coin>::new(), app.balances(&addr));
+
+    // balance in any denomination is a zero coin of this denomination
+    assert_eq!(
+        coin(0, "uatom"),
+        app.wrap().query_balance(&addr, "uatom").unwrap()
+    );
+}
+
+#[test]
+fn unknown_denom_should_have_zero_balance() {
+    let app = app_with_balance("owner", coins(100, "uatom"));
+    let addr = "owner".into_addr();
+
+    assert_eq!(
+        coin(0, "uosmo"),
+        app.wrap().query_balance(&addr, "uosmo").unwrap()
+    );
+
+    // balances in other denominations are not reported as zero entries
+    assert_eq!(coins(100, "uatom"), app.balances(&addr));
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_1")]
+fn unknown_denom_should_have_zero_supply() {
+    let app = app_with_balance("owner", coins(100, "uatom"));
+
+    assert_eq!(coin(0, "uosmo"), app.wrap().query_supply("uosmo").unwrap());
+    assert_eq!(
+        coin(100, "uatom"),
+        app.wrap().query_supply("uatom").unwrap()
+    );
+}
+
+#[test]
+fn balances_should_be_ordered_by_denom() {
+    let app = app_with_balance("owner", vec![coin(2, "uosmo"), coin(1, "uatom")]);
+
+    assert_eq!(
+        vec![coin(1, "uatom"), coin(2, "uosmo")],
+        app.balances("owner".into_addr())
+    );
+}
+
+#[test]
+fn assert_balance_should_pass_for_exact_amount() {
+    let app = app_with_balance("owner", coins(100, "uatom"));
+    let addr = "owner".into_addr();
+
+    app.assert_balance(&addr, &coin(100, "uatom"));
+    app.assert_balance(&addr, &coin(0, "uosmo"));
+    app.assert_balance_at_least(&addr, &coin(100, "uatom"));
+    app.assert_balance_at_least(&addr, &coin(99, "uatom"));
+    app.assert_balance_at_least(&addr, &coin(0, "uosmo"));
+}
+
+#[test]
+#[should_panic(expected = "to be 99uatom, but the balances are")]
+fn assert_balance_should_fail_for_different_amount() {
+    let app = app_with_balance("owner", coins(100, "uatom"));
+    app.assert_balance("owner".into_addr(), &coin(99, "uatom"));
+}
+
+#[test]
+#[should_panic(expected = "to be at least 101uatom")]
+fn assert_balance_at_least_should_fail_for_lower_amount() {
+    let app = app_with_balance("owner", coins(100, "uatom"));
+    app.assert_balance_at_least("owner".into_addr(), &coin(101, "uatom"));
+}
+
+#[test]
+#[should_panic(expected = "Failed to query balances of invalid")]
+fn balances_should_fail_for_invalid_address() {
+    App::default().balances("invalid");
+}
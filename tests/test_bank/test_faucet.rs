@@ -0,0 +1,110 @@
+use cosmwasm_std::coin;
+use cw_multi_test::ibc::denoms::voucher_denom;
+use cw_multi_test::{no_init, App, AppBuilder, IntoAddr};
+
+#[test]
+fn faucet_should_fund_exotic_denoms() {
+    let user_addr = "user".into_addr();
+    let voucher = voucher_denom("transfer", "channel-0", "uatom");
+    let factory = format!("factory/{}/ulp", "creator".into_addr());
+    let mut app = AppBuilder::default().with_faucet().build(no_init);
+
+    app.faucet()
+        .fund(
+            &user_addr,
+            &[
+                coin(1_000, "gamm/pool/1"),
+                coin(2_000, &voucher),
+                coin(3_000, &factory),
+            ],
+        )
+        .unwrap();
+
+    let mut expected = vec![
+        coin(3_000, &factory),
+        coin(1_000, "gamm/pool/1"),
+        coin(2_000, &voucher),
+    ];
+    expected.sort_by(|a, b| a.denom.cmp(&b.denom));
+    assert_eq!(expected, app.balances(&user_addr));
+}
+
+#[test]
+fn faucet_should_refuse_invalid_denoms() {
+    let user_addr = "user".into_addr();
+    let mut app = AppBuilder::default().with_faucet().build(no_init);
+
+    for denom in ["1abc", "ab", "bad denom", &"a".repeat(129)] {
+        let err = app
+            .faucet()
+            .fund(&user_addr, &[coin(100, "uatom"), coin(100, denom)])
+            .unwrap_err();
+        assert!(err.to_string().starts_with("invalid denom"));
+    }
+    // nothing is minted, not even the valid denomination
+    assert!(app.balances(&user_addr).is_empty());
+    assert!(app.faucet().total_minted().is_empty());
+
+    // zero amounts are refused
+    app.faucet()
+        .fund(&user_addr, &[coin(0, "uatom")])
+        .unwrap_err();
+    assert!(app.faucet().total_minted().is_empty());
+}
+
+#[test]
+fn faucet_should_track_total_minted() {
+    let alice_addr = "alice".into_addr();
+    let bob_addr = "bob".into_addr();
+    let mut app = AppBuilder::default().with_faucet().build(no_init);
+
+    app.faucet()
+        .fund(&alice_addr, &[coin(100, "uatom"), coin(5, "gamm/pool/1")])
+        .unwrap();
+    app.faucet().fund(&bob_addr, &[coin(50, "uatom")]).unwrap();
+
+    assert_eq!(
+        vec![coin(5, "gamm/pool/1"), coin(150, "uatom")],
+        app.faucet().total_minted()
+    );
+    // minted tokens are included in the supply
+    #[cfg(feature = "cosmwasm_1_1")]
+    assert_eq!(
+        coin(150, "uatom"),
+        app.wrap().query_supply("uatom").unwrap()
+    );
+}
+
+#[test]
+fn faucet_should_be_disabled_by_default() {
+    let user_addr = "user".into_addr();
+    let mut app = App::default();
+
+    let err = app
+        .faucet()
+        .fund(&user_addr, &[coin(100, "uatom")])
+        .unwrap_err();
+    assert_eq!(
+        "faucet is not enabled, see AppBuilder::with_faucet",
+        err.to_string()
+    );
+    assert!(app.balances(&user_addr).is_empty());
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_3")]
+fn faucet_should_register_minimal_metadata() {
+    let user_addr = "user".into_addr();
+    let mut app = AppBuilder::default().with_faucet().build(no_init);
+
+    app.faucet()
+        .fund(&user_addr, &[coin(100, "gamm/pool/1")])
+        .unwrap();
+
+    let metadata = app.wrap().query_denom_metadata("gamm/pool/1").unwrap();
+    assert_eq!("gamm/pool/1", metadata.base);
+    assert_eq!("gamm/pool/1", metadata.display);
+    assert_eq!(1, metadata.denom_units.len());
+    assert_eq!("gamm/pool/1", metadata.denom_units[0].denom);
+    assert_eq!(0, metadata.denom_units[0].exponent);
+}
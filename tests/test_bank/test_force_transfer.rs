@@ -0,0 +1,119 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{
+    coin, coins, Binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::{App, BankSudo, ContractWrapper, Executor, IntoAddr};
+
+const DENOM: &str = "denom";
+
+/// Refuses any execution, so tokens can be taken from the contract only by force.
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Err(StdError::generic_err("contract must not be executed"))
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+#[test]
+fn tokens_should_be_force_moved_from_contract() {
+    let owner_addr = "owner".into_addr();
+    let recipient_addr = "recipient".into_addr();
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(ContractWrapper::new(
+        execute,
+        noop::instantiate,
+        query,
+    )));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "locked", None)
+        .unwrap();
+    app.sudo(
+        BankSudo::Mint {
+            to_address: contract_addr.to_string(),
+            amount: coins(100, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let res = app
+        .force_send_tokens(
+            contract_addr.clone(),
+            recipient_addr.clone(),
+            &coins(60, DENOM),
+        )
+        .unwrap();
+
+    app.assert_balance(&contract_addr, &coin(40, DENOM));
+    app.assert_balance(&recipient_addr, &coin(60, DENOM));
+    assert_eq!(
+        vec![Event::new("force_transfer")
+            .add_attribute("recipient", recipient_addr.as_str())
+            .add_attribute("sender", contract_addr.as_str())
+            .add_attribute("amount", "60denom")],
+        res.events
+    );
+}
+
+#[test]
+fn insufficient_balance_should_fail() {
+    let from_addr = "from".into_addr();
+    let to_addr = "to".into_addr();
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &from_addr, coins(10, DENOM))
+            .unwrap();
+    });
+
+    app.force_send_tokens(from_addr.clone(), to_addr.clone(), &coins(11, DENOM))
+        .unwrap_err();
+
+    app.assert_balance(&from_addr, &coin(10, DENOM));
+    app.assert_balance(&to_addr, &coin(0, DENOM));
+}
+
+#[test]
+fn invalid_address_should_fail() {
+    let from_addr = "from".into_addr();
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &from_addr, coins(10, DENOM))
+            .unwrap();
+    });
+
+    app.sudo(
+        BankSudo::ForceTransfer {
+            from: from_addr.to_string(),
+            to: "invalid".to_string(),
+            amount: coins(5, DENOM),
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    app.assert_balance(&from_addr, &coin(10, DENOM));
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_1")]
+fn supply_should_not_change() {
+    let from_addr = "from".into_addr();
+    let to_addr = "to".into_addr();
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &from_addr, coins(100, DENOM))
+            .unwrap();
+    });
+    let supply_before = app.wrap().query_supply(DENOM).unwrap();
+
+    app.force_send_tokens(from_addr, to_addr, &coins(100, DENOM))
+        .unwrap();
+
+    assert_eq!(coin(100, DENOM), supply_before);
+    assert_eq!(supply_before, app.wrap().query_supply(DENOM).unwrap());
+}
@@ -0,0 +1,77 @@
+use cosmwasm_std::{coin, coins, Api, Event};
+use cw_multi_test::{module_address, no_init, App, AppBuilder, BankKeeper, BankSudo, IntoBech32};
+
+const DENOM: &str = "denom";
+
+#[test]
+fn mint_should_emit_coin_received_and_coinbase_events() {
+    let mut app = App::default();
+    let recipient = app.api().addr_make("recipient");
+    let minter = module_address(app.api(), "mint").unwrap();
+
+    let res = app
+        .sudo(
+            BankSudo::Mint {
+                to_address: recipient.to_string(),
+                amount: vec![coin(10, DENOM), coin(0, "zero"), coin(5, "other")],
+            }
+            .into(),
+        )
+        .unwrap();
+
+    // zero amounts are not reported
+    assert_eq!(
+        vec![
+            Event::new("coin_received")
+                .add_attribute("receiver", recipient.as_str())
+                .add_attribute("amount", "10denom,5other"),
+            Event::new("coinbase")
+                .add_attribute("minter", minter.as_str())
+                .add_attribute("amount", "10denom,5other"),
+        ],
+        res.events
+    );
+    assert_eq!(
+        coin(10, DENOM),
+        app.wrap().query_balance(&recipient, DENOM).unwrap()
+    );
+}
+
+#[test]
+fn default_minter_should_be_valid_module_address() {
+    let app = App::default();
+    let minter = BankKeeper::new().minter(app.api()).unwrap();
+    assert_eq!(module_address(app.api(), "mint").unwrap(), minter);
+    assert_eq!(minter, app.api().addr_validate(minter.as_str()).unwrap());
+}
+
+#[test]
+fn custom_minter_should_be_reported() {
+    let minter = "minter".into_bech32();
+    let mut app = AppBuilder::default()
+        .with_bank(BankKeeper::new().with_minter(minter.clone()))
+        .build(no_init);
+    let recipient = app.api().addr_make("recipient");
+
+    let res = app
+        .sudo(
+            BankSudo::Mint {
+                to_address: recipient.to_string(),
+                amount: coins(7, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            Event::new("coin_received")
+                .add_attribute("receiver", recipient.as_str())
+                .add_attribute("amount", "7denom"),
+            Event::new("coinbase")
+                .add_attribute("minter", minter.as_str())
+                .add_attribute("amount", "7denom"),
+        ],
+        res.events
+    );
+}
@@ -0,0 +1,156 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, coins, Addr, Event};
+use cw_multi_test::{App, AppBuilder, BankSudo};
+
+const DENOM: &str = "denom";
+
+/// Creates the application with the sender's balance initialized.
+fn app_with_balance(amount: u128) -> (App, Addr) {
+    let sender = MockApi::default().addr_make("sender");
+    let app = AppBuilder::new().build(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(amount, DENOM))
+            .unwrap();
+    });
+    (app, sender)
+}
+
+fn balance(app: &App, address: &Addr) -> u128 {
+    app.wrap()
+        .query_balance(address, DENOM)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn multi_send_should_work() {
+    let (mut app, sender) = app_with_balance(100);
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+
+    let res = app
+        .multi_send_tokens(
+            sender.clone(),
+            &[
+                (alice.clone(), coins(30, DENOM)),
+                (bob.clone(), coins(50, DENOM)),
+            ],
+        )
+        .unwrap();
+
+    assert_eq!(20, balance(&app, &sender));
+    assert_eq!(30, balance(&app, &alice));
+    assert_eq!(50, balance(&app, &bob));
+    assert_eq!(
+        vec![
+            Event::new("transfer")
+                .add_attribute("recipient", alice.as_str())
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("amount", "30denom"),
+            Event::new("transfer")
+                .add_attribute("recipient", bob.as_str())
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("amount", "50denom"),
+        ],
+        res.events
+    );
+}
+
+#[test]
+fn multi_send_sudo_should_work() {
+    let (mut app, sender) = app_with_balance(100);
+    let alice = app.api().addr_make("alice");
+
+    app.sudo(
+        BankSudo::MultiSend {
+            from_address: sender.to_string(),
+            outputs: vec![(alice.to_string(), vec![coin(40, DENOM)])],
+        }
+        .into(),
+    )
+    .unwrap();
+
+    assert_eq!(60, balance(&app, &sender));
+    assert_eq!(40, balance(&app, &alice));
+}
+
+#[test]
+fn multi_send_with_insufficient_funds_should_fail() {
+    let (mut app, sender) = app_with_balance(100);
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+
+    // each output is covered by the balance, but the total is not
+    app.multi_send_tokens(
+        sender.clone(),
+        &[
+            (alice.clone(), coins(60, DENOM)),
+            (bob.clone(), coins(60, DENOM)),
+        ],
+    )
+    .unwrap_err();
+
+    assert_eq!(100, balance(&app, &sender));
+    assert_eq!(0, balance(&app, &alice));
+    assert_eq!(0, balance(&app, &bob));
+}
+
+#[test]
+fn multi_send_with_invalid_address_should_fail() {
+    let (mut app, sender) = app_with_balance(100);
+    let alice = app.api().addr_make("alice");
+
+    app.sudo(
+        BankSudo::MultiSend {
+            from_address: sender.to_string(),
+            outputs: vec![
+                (alice.to_string(), coins(10, DENOM)),
+                ("invalid".to_string(), coins(10, DENOM)),
+            ],
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    assert_eq!(100, balance(&app, &sender));
+    assert_eq!(0, balance(&app, &alice));
+}
+
+#[test]
+fn multi_send_with_empty_output_should_fail() {
+    let (mut app, sender) = app_with_balance(100);
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+
+    app.multi_send_tokens(
+        sender.clone(),
+        &[(alice.clone(), coins(10, DENOM)), (bob, vec![])],
+    )
+    .unwrap_err();
+
+    assert_eq!(100, balance(&app, &sender));
+    assert_eq!(0, balance(&app, &alice));
+}
+
+#[test]
+fn multi_send_to_many_addresses_should_work() {
+    let (mut app, sender) = app_with_balance(10_000);
+    let outputs = (0..1_000)
+        .map(|i| {
+            (
+                app.api().addr_make(&format!("recipient{i}")),
+                coins(10, DENOM),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let res = app.multi_send_tokens(sender.clone(), &outputs).unwrap();
+
+    assert_eq!(1_000, res.events.len());
+    assert_eq!(0, balance(&app, &sender));
+    for (recipient, _) in &outputs {
+        assert_eq!(10, balance(&app, recipient));
+    }
+}
@@ -0,0 +1,113 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, coins, Addr, Coin};
+use cw_multi_test::{App, AppBuilder};
+
+const DENOM: &str = "denom";
+
+/// Creates the application with the balances of the faucet initialized.
+fn app_with_faucet(balances: Vec<Coin>) -> (App, Addr) {
+    let faucet = MockApi::default().addr_make("faucet");
+    let app = AppBuilder::new().build(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &faucet, balances)
+            .unwrap();
+    });
+    (app, faucet)
+}
+
+#[test]
+fn send_tokens_multi_should_work() {
+    let (mut app, faucet) = app_with_faucet(coins(100, DENOM));
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+
+    let responses = app
+        .send_tokens_multi(
+            faucet.clone(),
+            &[
+                (alice.clone(), coins(30, DENOM)),
+                (bob.clone(), coins(50, DENOM)),
+            ],
+        )
+        .unwrap();
+
+    // each transfer is a separate bank message reporting its own events
+    assert_eq!(2, responses.len());
+    assert!(responses
+        .iter()
+        .all(|res| res.events.iter().any(|event| event.ty == "transfer")));
+    assert_eq!(coins(20, DENOM), app.balances(&faucet));
+    assert_eq!(coins(30, DENOM), app.balances(&alice));
+    assert_eq!(coins(50, DENOM), app.balances(&bob));
+}
+
+#[test]
+fn send_tokens_multi_should_skip_zero_amounts() {
+    let (mut app, faucet) = app_with_faucet(coins(100, DENOM));
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+
+    let responses = app
+        .send_tokens_multi(
+            faucet.clone(),
+            &[
+                (alice.clone(), vec![coin(0, "other"), coin(10, DENOM)]),
+                (bob.clone(), coins(0, DENOM)),
+            ],
+        )
+        .unwrap();
+
+    assert_eq!(1, responses.len());
+    assert_eq!(coins(90, DENOM), app.balances(&faucet));
+    assert_eq!(coins(10, DENOM), app.balances(&alice));
+    assert!(app.balances(&bob).is_empty());
+}
+
+#[test]
+fn failing_recipient_should_roll_back_all_sends() {
+    let (mut app, faucet) = app_with_faucet(coins(100, DENOM));
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+
+    app.send_tokens_multi(
+        faucet.clone(),
+        &[
+            (alice.clone(), coins(30, DENOM)),
+            (bob.clone(), coins(80, DENOM)),
+        ],
+    )
+    .unwrap_err();
+
+    assert_eq!(coins(100, DENOM), app.balances(&faucet));
+    assert!(app.balances(&alice).is_empty());
+    assert!(app.balances(&bob).is_empty());
+}
+
+#[test]
+fn drain_tokens_should_move_all_balances() {
+    let (mut app, faucet) = app_with_faucet(vec![coin(100, DENOM), coin(5, "other")]);
+    let alice = app.api().addr_make("alice");
+
+    let moved = app.drain_tokens(faucet.clone(), alice.clone()).unwrap();
+
+    assert_eq!(vec![coin(100, DENOM), coin(5, "other")], moved);
+    assert!(app.balances(&faucet).is_empty());
+    assert_eq!(
+        vec![coin(100, DENOM), coin(5, "other")],
+        app.balances(&alice)
+    );
+}
+
+#[test]
+fn draining_empty_balance_should_be_noop() {
+    let (mut app, _) = app_with_faucet(vec![]);
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+
+    let moved = app.drain_tokens(alice.clone(), bob.clone()).unwrap();
+
+    assert!(moved.is_empty());
+    assert!(app.balances(&alice).is_empty());
+    assert!(app.balances(&bob).is_empty());
+}
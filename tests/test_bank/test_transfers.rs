@@ -0,0 +1,153 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{
+    coin, coins, BankMsg, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{
+    App, BankTransfers, Contract, ContractWrapper, Executor, IntoAddr, TransferKind, TransferRecord,
+};
+
+const DENOM: &str = "uatom";
+
+/// Forwards half of the received funds to the recipient and burns the rest.
+fn execute(_deps: DepsMut, _env: Env, info: MessageInfo, recipient: String) -> StdResult<Response> {
+    let amount = info.funds[0].amount.u128();
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient,
+            amount: coins(amount / 2, DENOM),
+        })
+        .add_message(BankMsg::Burn {
+            amount: coins(amount - amount / 2, DENOM),
+        }))
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+fn app_with_balance() -> App {
+    App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &"owner".into_addr(), coins(100, DENOM))
+            .unwrap();
+    })
+}
+
+#[test]
+fn direct_send_should_be_reported() {
+    let mut app = app_with_balance();
+    let owner_addr = "owner".into_addr();
+    let recipient_addr = "recipient".into_addr();
+
+    let response = app
+        .send_tokens(
+            owner_addr.clone(),
+            recipient_addr.clone(),
+            &coins(40, DENOM),
+        )
+        .unwrap();
+
+    let transfers = BankTransfers::from(&response);
+    transfers.assert_exact(&[TransferRecord::send(
+        &owner_addr,
+        &recipient_addr,
+        &coins(40, DENOM),
+    )]);
+    assert_eq!(TransferKind::Send, transfers.records()[0].kind);
+    transfers.assert_none_involving(&"other".into_addr());
+}
+
+#[test]
+fn direct_burn_should_be_reported() {
+    let mut app = app_with_balance();
+    let owner_addr = "owner".into_addr();
+
+    let response = app
+        .execute(
+            owner_addr.clone(),
+            BankMsg::Burn {
+                amount: coins(30, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    BankTransfers::from(&response)
+        .assert_exact(&[TransferRecord::burn(&owner_addr, &coins(30, DENOM))]);
+    app.assert_balance(&owner_addr, &coin(70, DENOM));
+}
+
+#[test]
+fn transfers_in_wasm_messages_should_be_reported_in_order() {
+    let mut app = app_with_balance();
+    let owner_addr = "owner".into_addr();
+    let recipient_addr = "recipient".into_addr();
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "forward", None)
+        .unwrap();
+
+    let response = app
+        .execute_contract(
+            owner_addr.clone(),
+            contract_addr.clone(),
+            &recipient_addr,
+            &coins(60, DENOM),
+        )
+        .unwrap();
+
+    let transfers = BankTransfers::from(&response);
+    transfers.assert_exact(&[
+        TransferRecord::send(&owner_addr, &contract_addr, &coins(60, DENOM)),
+        TransferRecord::send(&contract_addr, &recipient_addr, &coins(30, DENOM)),
+        TransferRecord::burn(&contract_addr, &coins(30, DENOM)),
+    ]);
+    transfers.assert_contains(&TransferRecord::burn(&contract_addr, &coins(30, DENOM)));
+    transfers.assert_none_involving(&"other".into_addr());
+}
+
+#[test]
+fn force_transfer_should_be_reported() {
+    let mut app = app_with_balance();
+    let owner_addr = "owner".into_addr();
+    let recipient_addr = "recipient".into_addr();
+
+    let response = app
+        .force_send_tokens(
+            owner_addr.clone(),
+            recipient_addr.clone(),
+            &coins(25, DENOM),
+        )
+        .unwrap();
+
+    let transfers = BankTransfers::from(&response);
+    transfers.assert_exact(&[TransferRecord::force_transfer(
+        &owner_addr,
+        &recipient_addr,
+        &coins(25, DENOM),
+    )]);
+    assert_eq!(TransferKind::ForceTransfer, transfers.records()[0].kind);
+    assert!(transfers.records()[0].involves(&owner_addr));
+}
+
+#[test]
+#[should_panic(expected = "Expected no bank transfers involving")]
+fn assert_none_involving_should_panic_for_involved_account() {
+    let mut app = app_with_balance();
+    let owner_addr = "owner".into_addr();
+
+    let response = app
+        .send_tokens(owner_addr, "recipient".into_addr(), &coins(40, DENOM))
+        .unwrap();
+
+    BankTransfers::from(&response).assert_none_involving(&"recipient".into_addr());
+}
@@ -0,0 +1,61 @@
+use cosmwasm_std::{coin, coins};
+use cw_multi_test::ibc::denoms::voucher_denom;
+use cw_multi_test::{App, BankSudo, Executor, IntoAddr, SudoMsg};
+
+#[test]
+fn voucher_denom_should_be_ics20_hash() {
+    assert_eq!(
+        "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+        voucher_denom("transfer", "channel-0", "uatom")
+    );
+    // every part of the trace path changes the hash
+    assert_ne!(
+        voucher_denom("transfer", "channel-0", "uatom"),
+        voucher_denom("transfer", "channel-1", "uatom")
+    );
+    assert_ne!(
+        voucher_denom("transfer", "channel-0", "uatom"),
+        voucher_denom("wasm.contract", "channel-0", "uatom")
+    );
+    assert_eq!(68, voucher_denom("transfer", "channel-0", "uosmo").len());
+}
+
+#[test]
+fn namespaced_denoms_should_work_with_bank() {
+    let owner_addr = "owner".into_addr();
+    let recipient_addr = "recipient".into_addr();
+    let voucher = voucher_denom("transfer", "channel-0", "uatom");
+    let factory = format!("factory/{}/utoken", owner_addr);
+
+    // pre-fund the account with a voucher, without relaying any packets
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner_addr, coins(100, &voucher))
+            .unwrap();
+    });
+
+    // mint a token factory denomination
+    app.sudo(SudoMsg::Bank(BankSudo::Mint {
+        to_address: owner_addr.to_string(),
+        amount: coins(50, &factory),
+    }))
+    .unwrap();
+
+    app.send_tokens(
+        owner_addr.clone(),
+        recipient_addr.clone(),
+        &[coin(40, &voucher), coin(20, &factory)],
+    )
+    .unwrap();
+
+    // balances are ordered by denomination
+    assert_eq!(
+        vec![coin(30, &factory), coin(60, &voucher)],
+        app.balances(&owner_addr)
+    );
+    assert_eq!(
+        vec![coin(20, &factory), coin(40, &voucher)],
+        app.balances(&recipient_addr)
+    );
+}
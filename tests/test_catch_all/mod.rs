@@ -0,0 +1,20 @@
+use cosmwasm_std::{Addr, Coin};
+use cw_multi_test::catch_all::{mock_dependency_contract, CatchAllInstantiateMsg};
+use cw_multi_test::{App, Executor};
+
+mod test_matching;
+mod test_recording;
+
+/// Stores and instantiates the catch-all contract with the specified configuration.
+fn instantiate(app: &mut App, owner: &Addr, msg: &CatchAllInstantiateMsg, funds: &[Coin]) -> Addr {
+    let code_id = app.store_code(mock_dependency_contract());
+    app.instantiate_contract(
+        code_id,
+        owner.clone(),
+        msg,
+        funds,
+        "dependency",
+        Some(owner.to_string()),
+    )
+    .unwrap()
+}
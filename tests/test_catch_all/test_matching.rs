@@ -0,0 +1,184 @@
+use super::*;
+use cosmwasm_std::{coins, Binary, Empty, Event, Response};
+use cw_multi_test::catch_all::{CannedQuery, CannedResponse, MsgMatcher};
+use cw_multi_test::EntryPoint;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DependencyMsg {
+    Transfer { recipient: String, amount: u64 },
+    Burn { amount: u64 },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DependencyQuery {
+    Price { denom: String },
+    Owner {},
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct PriceResponse {
+    price: u64,
+}
+
+fn transfer(recipient: &str, amount: u64) -> DependencyMsg {
+    DependencyMsg::Transfer {
+        recipient: recipient.to_string(),
+        amount,
+    }
+}
+
+fn price(denom: &str) -> DependencyQuery {
+    DependencyQuery::Price {
+        denom: denom.to_string(),
+    }
+}
+
+#[test]
+fn first_matching_response_should_be_returned() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let msg = CatchAllInstantiateMsg::new()
+        .with_response(CannedResponse::new(
+            MsgMatcher::path_eq("transfer.recipient", &"alice").unwrap(),
+            Response::new()
+                .add_event(Event::new("to-alice"))
+                .set_data(Binary::from(b"alice")),
+        ))
+        .with_response(CannedResponse::new(
+            MsgMatcher::path("transfer"),
+            Response::new().add_event(Event::new("transfer")),
+        ));
+    let contract_addr = instantiate(&mut app, &owner, &msg, &[]);
+
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &transfer("alice", 1),
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-to-alice")));
+    assert!(!res.has_event(&Event::new("wasm-transfer")));
+    assert_eq!(Some(Binary::from(b"alice")), res.data);
+
+    let res = app
+        .execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &transfer("bob", 1),
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("wasm-transfer")));
+    assert_eq!(None, res.data);
+
+    // no matching response, empty response is returned
+    let res = app
+        .execute_contract(
+            owner,
+            contract_addr,
+            &DependencyMsg::Burn { amount: 1 },
+            &[],
+        )
+        .unwrap();
+    assert!(!res.has_event(&Event::new("wasm-transfer")));
+    assert_eq!(None, res.data);
+}
+
+#[test]
+fn response_should_be_restricted_to_entry_point() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let msg = CatchAllInstantiateMsg::new().with_response(
+        CannedResponse::new(
+            MsgMatcher::any(),
+            Response::new().add_event(Event::new("sudo")),
+        )
+        .on(EntryPoint::Sudo),
+    );
+    let contract_addr = instantiate(&mut app, &owner, &msg, &[]);
+
+    let res = app
+        .execute_contract(owner, contract_addr.clone(), &Empty {}, &[])
+        .unwrap();
+    assert!(!res.has_event(&Event::new("wasm-sudo")));
+
+    let res = app.wasm_sudo(contract_addr, &Empty {}).unwrap();
+    assert!(res.has_event(&Event::new("wasm-sudo")));
+}
+
+#[test]
+fn canned_submessages_should_be_executed() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let recipient = app.api().addr_make("recipient");
+    app.init_modules(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &api.addr_make("owner"), coins(100, "denom"))
+            .unwrap();
+    });
+    let msg = CatchAllInstantiateMsg::new().with_response(CannedResponse::new(
+        MsgMatcher::path("transfer"),
+        Response::new().add_message(cosmwasm_std::BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(30, "denom"),
+        }),
+    ));
+    let contract_addr = instantiate(&mut app, &owner, &msg, &coins(50, "denom"));
+
+    app.execute_contract(owner, contract_addr.clone(), &transfer("any", 1), &[])
+        .unwrap();
+
+    let balance = |addr| {
+        app.wrap()
+            .query_balance(addr, "denom")
+            .unwrap()
+            .amount
+            .u128()
+    };
+    assert_eq!(30, balance(&recipient));
+    assert_eq!(20, balance(&contract_addr));
+}
+
+#[test]
+fn canned_query_answers_should_be_returned() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let msg = CatchAllInstantiateMsg::new()
+        .with_query(
+            CannedQuery::new(
+                MsgMatcher::path_eq("price.denom", &"atom").unwrap(),
+                &PriceResponse { price: 10 },
+            )
+            .unwrap(),
+        )
+        .with_query(
+            CannedQuery::new(MsgMatcher::path("price"), &PriceResponse { price: 1 }).unwrap(),
+        );
+    let contract_addr = instantiate(&mut app, &owner, &msg, &[]);
+
+    let res: PriceResponse = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &price("atom"))
+        .unwrap();
+    assert_eq!(PriceResponse { price: 10 }, res);
+
+    let res: PriceResponse = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &price("osmo"))
+        .unwrap();
+    assert_eq!(PriceResponse { price: 1 }, res);
+
+    let err = app
+        .wrap()
+        .query_wasm_smart::<PriceResponse>(&contract_addr, &DependencyQuery::Owner {})
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(r#"no canned answer for query: {"owner":{}}"#));
+}
@@ -0,0 +1,144 @@
+use super::*;
+use cosmwasm_std::{coins, to_json_binary, BankMsg, Binary, Empty, Response, WasmMsg};
+use cw_multi_test::catch_all::{CannedResponse, MsgMatcher, RecordedCall, RecordedCalls};
+use cw_multi_test::EntryPoint;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DependencyMsg {
+    Transfer { recipient: String, amount: u64 },
+}
+
+#[test]
+fn instantiation_should_be_recorded() {
+    let mut app = App::new(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &api.addr_make("owner"), coins(100, "denom"))
+            .unwrap();
+    });
+    let owner = app.api().addr_make("owner");
+    let msg = CatchAllInstantiateMsg::new();
+    let height = app.block_info().height;
+
+    let contract_addr = instantiate(&mut app, &owner, &msg, &coins(10, "denom"));
+
+    assert_eq!(
+        RecordedCalls {
+            calls: vec![RecordedCall {
+                entry_point: EntryPoint::Instantiate,
+                sender: Some(owner),
+                funds: coins(10, "denom"),
+                msg: to_json_binary(&msg).unwrap(),
+                height,
+            }]
+        },
+        RecordedCalls::load(&app, &contract_addr).unwrap()
+    );
+}
+
+#[test]
+fn any_instantiation_message_should_be_accepted() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(mock_dependency_contract());
+    let msg = DependencyMsg::Transfer {
+        recipient: "alice".to_string(),
+        amount: 1,
+    };
+
+    let contract_addr = app
+        .instantiate_contract(code_id, owner, &msg, &[], "dependency", None)
+        .unwrap();
+
+    let calls = RecordedCalls::load(&app, &contract_addr).unwrap().calls;
+    assert_eq!(1, calls.len());
+    assert_eq!(to_json_binary(&msg).unwrap(), calls[0].msg);
+}
+
+#[test]
+fn all_calls_should_be_recorded_in_order() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let sender = app.api().addr_make("sender");
+    let contract_addr = instantiate(&mut app, &owner, &CatchAllInstantiateMsg::new(), &[]);
+    let transfer = DependencyMsg::Transfer {
+        recipient: "alice".to_string(),
+        amount: 5,
+    };
+
+    app.update_block(|block| block.height += 1);
+    app.execute_contract(sender.clone(), contract_addr.clone(), &transfer, &[])
+        .unwrap();
+    app.update_block(|block| block.height += 1);
+    app.wasm_sudo(contract_addr.clone(), &Empty {}).unwrap();
+    let code_id = app.store_code(mock_dependency_contract());
+    app.execute(
+        owner.clone(),
+        WasmMsg::Migrate {
+            contract_addr: contract_addr.to_string(),
+            new_code_id: code_id,
+            msg: Binary::from(br#"{"upgrade":{}}"#),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let height = app.block_info().height;
+    let calls = RecordedCalls::load(&app, &contract_addr).unwrap().calls;
+    assert_eq!(
+        vec![
+            EntryPoint::Instantiate,
+            EntryPoint::Execute,
+            EntryPoint::Sudo,
+            EntryPoint::Migrate
+        ],
+        calls
+            .iter()
+            .map(|call| call.entry_point)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        RecordedCall {
+            entry_point: EntryPoint::Execute,
+            sender: Some(sender),
+            funds: vec![],
+            msg: to_json_binary(&transfer).unwrap(),
+            height: height - 1,
+        },
+        calls[1]
+    );
+    assert_eq!(
+        RecordedCall {
+            entry_point: EntryPoint::Sudo,
+            sender: None,
+            funds: vec![],
+            msg: to_json_binary(&Empty {}).unwrap(),
+            height,
+        },
+        calls[2]
+    );
+    assert_eq!(Binary::from(br#"{"upgrade":{}}"#), calls[3].msg);
+}
+
+#[test]
+fn failed_calls_should_not_be_recorded() {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    // the contract has no funds, so the canned submessage fails and the call is rolled back
+    let msg = CatchAllInstantiateMsg::new().with_response(CannedResponse::new(
+        MsgMatcher::any(),
+        Response::new().add_message(BankMsg::Send {
+            to_address: owner.to_string(),
+            amount: coins(1, "denom"),
+        }),
+    ));
+    let contract_addr = instantiate(&mut app, &owner, &msg, &[]);
+
+    app.execute_contract(owner, contract_addr.clone(), &Empty {}, &[])
+        .unwrap_err();
+
+    let calls = RecordedCalls::load(&app, &contract_addr).unwrap().calls;
+    assert_eq!(1, calls.len());
+}
@@ -0,0 +1,4 @@
+mod test_chaotic_bank;
+#[cfg(feature = "staking")]
+mod test_chaotic_staking;
+mod test_chaotic_wasm;
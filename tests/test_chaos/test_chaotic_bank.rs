@@ -0,0 +1,184 @@
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo,
+    Reply, Response, StdResult, SubMsg,
+};
+use cw_multi_test::{
+    App, AppBuilder, BankKeeper, ChaosConfig, ChaoticBank, Contract, ContractWrapper, Executor,
+    IntoAddr,
+};
+use cw_storage_plus::Item;
+use serde::{Deserialize, Serialize};
+
+type ChaoticApp = App<ChaoticBank>;
+
+const DENOM: &str = "denom";
+
+/// Builds the application with chaotic bank and the owner's balance initialized.
+fn chaotic_app(config: ChaosConfig) -> (ChaoticApp, Addr) {
+    let owner = "owner".into_addr();
+    let app = AppBuilder::default()
+        .with_bank(ChaoticBank::new(BankKeeper::new(), config))
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &owner, coins(1_000, DENOM))
+                .unwrap();
+        });
+    (app, owner)
+}
+
+fn balance(app: &ChaoticApp, addr: &Addr) -> u128 {
+    app.wrap().query_balance(addr, DENOM).unwrap().amount.u128()
+}
+
+/// Sends one token to the recipient in each of `count` transactions,
+/// returns the outcomes of the transactions.
+fn send_many(app: &mut ChaoticApp, owner: &Addr, recipient: &Addr, count: usize) -> Vec<bool> {
+    (0..count)
+        .map(|_| {
+            app.send_tokens(owner.clone(), recipient.clone(), &coins(1, DENOM))
+                .is_ok()
+        })
+        .collect()
+}
+
+#[test]
+fn same_seed_should_fail_same_calls() {
+    let config = ChaosConfig::new(2024, Decimal::percent(40));
+    let (mut app_1, owner) = chaotic_app(config.clone());
+    let (mut app_2, _) = chaotic_app(config);
+    let recipient = app_1.api().addr_make("recipient");
+
+    let outcomes_1 = send_many(&mut app_1, &owner, &recipient, 50);
+    let outcomes_2 = send_many(&mut app_2, &owner, &recipient, 50);
+
+    assert_eq!(outcomes_1, outcomes_2);
+    assert!(outcomes_1.contains(&true));
+    assert!(outcomes_1.contains(&false));
+    assert_eq!(50, app_1.router().bank.calls());
+}
+
+#[test]
+fn failed_calls_should_not_change_state() {
+    let (mut app, owner) = chaotic_app(ChaosConfig::new(7, Decimal::percent(50)));
+    let recipient = app.api().addr_make("recipient");
+
+    let outcomes = send_many(&mut app, &owner, &recipient, 40);
+
+    let succeeded = outcomes.iter().filter(|ok| **ok).count() as u128;
+    assert_eq!(succeeded, balance(&app, &recipient));
+    assert_eq!(1_000 - succeeded, balance(&app, &owner));
+}
+
+#[test]
+fn deterministic_failures_should_report_error_message() {
+    let (mut app, owner) = chaotic_app(
+        ChaosConfig::deterministic_failures(vec![1, 2]).with_error_message("injected failure"),
+    );
+    let recipient = app.api().addr_make("recipient");
+
+    assert_eq!(vec![true], send_many(&mut app, &owner, &recipient, 1));
+    let err = app
+        .send_tokens(owner.clone(), recipient.clone(), &coins(1, DENOM))
+        .unwrap_err();
+    assert_eq!("injected failure", err.root_cause().to_string());
+    assert_eq!(
+        vec![false, true, true],
+        send_many(&mut app, &owner, &recipient, 3)
+    );
+
+    // queries never fail unless enabled
+    assert_eq!(3, balance(&app, &recipient));
+}
+
+#[test]
+fn failing_queries_should_work() {
+    let (app, owner) =
+        chaotic_app(ChaosConfig::deterministic_failures(vec![0]).with_failing_queries());
+
+    let err = app.wrap().query_balance(&owner, DENOM).unwrap_err();
+    assert!(err.to_string().contains("out of gas"));
+    assert_eq!(1_000, balance(&app, &owner));
+}
+
+mod retrying {
+    use super::*;
+
+    /// Message sending tokens to the recipient, retried once when sending fails.
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct SendMsg {
+        pub recipient: String,
+        pub amount: u128,
+    }
+
+    const PENDING: Item<BankMsg> = Item::new("pending");
+
+    const SEND_REPLY_ID: u64 = 1;
+
+    fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(deps: DepsMut, _: Env, _: MessageInfo, msg: SendMsg) -> StdResult<Response> {
+        let send = BankMsg::Send {
+            to_address: msg.recipient,
+            amount: coins(msg.amount, DENOM),
+        };
+        PENDING.save(deps.storage, &send)?;
+        Ok(Response::new().add_submessage(SubMsg::reply_on_error(send, SEND_REPLY_ID)))
+    }
+
+    fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+        to_json_binary(&Empty {})
+    }
+
+    fn reply(deps: DepsMut, _: Env, msg: Reply) -> StdResult<Response> {
+        assert_eq!(SEND_REPLY_ID, msg.id);
+        let send = PENDING.load(deps.storage)?;
+        Ok(Response::new()
+            .add_attribute("retried", msg.result.unwrap_err())
+            .add_message(send))
+    }
+
+    pub fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+    }
+}
+
+#[test]
+fn contract_should_recover_from_injected_bank_failure() {
+    let (mut app, owner) = chaotic_app(ChaosConfig::deterministic_failures(vec![0]));
+    let recipient = app.api().addr_make("recipient");
+    let code_id = app.store_code(retrying::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "retrying", None)
+        .unwrap();
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &contract_addr, coins(100, DENOM))
+            .unwrap()
+    });
+
+    let res = app
+        .execute_contract(
+            owner,
+            contract_addr.clone(),
+            &retrying::SendMsg {
+                recipient: recipient.to_string(),
+                amount: 10,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // the first send failed, the retried send succeeded
+    assert!(res
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .any(|attr| attr.key == "retried" && attr.value.contains("out of gas")));
+    assert_eq!(10, balance(&app, &recipient));
+    assert_eq!(90, balance(&app, &contract_addr));
+    assert_eq!(2, app.router().bank.calls());
+}
@@ -0,0 +1,72 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coin, Decimal, StakingMsg, Validator};
+use cw_multi_test::{
+    no_init, AppBuilder, ChaosConfig, ChaoticStaking, Executor, IntoBech32, StakeKeeper,
+};
+
+#[test]
+fn staking_queries_should_fail_as_scripted() {
+    let staking = ChaoticStaking::new(
+        StakeKeeper::new(),
+        ChaosConfig::deterministic_failures(vec![0, 2]).with_failing_queries(),
+    );
+    let app = AppBuilder::default().with_staking(staking).build(no_init);
+
+    let outcomes: Vec<bool> = (0..4)
+        .map(|_| app.wrap().query_bonded_denom().is_ok())
+        .collect();
+    assert_eq!(vec![false, true, false, true], outcomes);
+}
+
+#[test]
+fn same_seed_should_fail_same_staking_messages() {
+    let run = || {
+        let delegator = "delegator".into_bech32();
+        let validator = "validator".into_bech32();
+        let staking = ChaoticStaking::new(
+            StakeKeeper::new(),
+            ChaosConfig::new(99, Decimal::percent(50)),
+        );
+        let block = mock_env().block;
+        let mut app = AppBuilder::default()
+            .with_staking(staking)
+            .build(|router, api, storage| {
+                router
+                    .bank
+                    .init_balance(storage, &delegator, vec![coin(100, "TOKEN")])
+                    .unwrap();
+                router
+                    .staking
+                    .add_validator(
+                        api,
+                        storage,
+                        &block,
+                        Validator::new(
+                            validator.to_string(),
+                            Decimal::percent(10),
+                            Decimal::percent(90),
+                            Decimal::percent(1),
+                        ),
+                    )
+                    .unwrap();
+            });
+        (0..20)
+            .map(|_| {
+                app.execute(
+                    delegator.clone(),
+                    StakingMsg::Delegate {
+                        validator: validator.to_string(),
+                        amount: coin(1, "TOKEN"),
+                    }
+                    .into(),
+                )
+                .is_ok()
+            })
+            .collect::<Vec<bool>>()
+    };
+
+    let outcomes = run();
+    assert_eq!(outcomes, run());
+    assert!(outcomes.contains(&true));
+    assert!(outcomes.contains(&false));
+}
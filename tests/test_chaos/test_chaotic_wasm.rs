@@ -0,0 +1,65 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{Empty, WasmMsg};
+use cw_multi_test::{no_init, AppBuilder, ChaosConfig, ChaoticWasm, Executor, WasmKeeper};
+
+#[test]
+fn wasm_messages_should_fail_as_scripted() {
+    let wasm = ChaoticWasm::new(
+        WasmKeeper::new(),
+        ChaosConfig::deterministic_failures(vec![1]).with_error_message("wasm failure"),
+    );
+    let mut app = AppBuilder::default().with_wasm(wasm).build(no_init);
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(counter::contract());
+
+    // instantiation is the first call
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    let msg = WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    };
+
+    // the second call fails and does not reach the contract
+    let err = app
+        .execute_contract(owner.clone(), contract_addr.clone(), &msg, &[])
+        .unwrap_err();
+    assert_eq!("wasm failure", err.root_cause().to_string());
+    let res: counter::CounterResponseMsg = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &counter::CounterQueryMsg::Counter {})
+        .unwrap();
+    assert_eq!(1, res.value);
+
+    // the third call succeeds
+    app.execute_contract(owner, contract_addr.clone(), &msg, &[])
+        .unwrap();
+    let res: counter::CounterResponseMsg = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &counter::CounterQueryMsg::Counter {})
+        .unwrap();
+    assert_eq!(2, res.value);
+    assert_eq!(3, app.router().wasm().calls());
+}
+
+#[test]
+fn wasm_queries_should_fail_when_enabled() {
+    let wasm = ChaoticWasm::new(
+        WasmKeeper::new(),
+        ChaosConfig::deterministic_failures(vec![1]).with_failing_queries(),
+    );
+    let mut app = AppBuilder::default().with_wasm(wasm).build(no_init);
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(counter::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner, &Empty {}, &[], "counter", None)
+        .unwrap();
+
+    let query = counter::CounterQueryMsg::Counter {};
+    app.wrap()
+        .query_wasm_smart::<counter::CounterResponseMsg>(&contract_addr, &query)
+        .unwrap_err();
+    let res: counter::CounterResponseMsg =
+        app.wrap().query_wasm_smart(&contract_addr, &query).unwrap();
+    assert_eq!(1, res.value);
+}
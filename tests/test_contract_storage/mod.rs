@@ -1,3 +1,5 @@
+mod test_storage_stats;
+
 use crate::test_contracts::counter;
 use crate::test_contracts::counter::{CounterQueryMsg, CounterResponseMsg};
 use cosmwasm_std::{to_json_binary, Empty, WasmMsg};
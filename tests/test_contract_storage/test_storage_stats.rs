@@ -0,0 +1,126 @@
+use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+use cw_multi_test::{App, ContractWrapper, Executor, IntoAddr, PrefixStats, StorageBudget};
+use cw_storage_plus::{Deque, Item, Map};
+
+const CONFIG: Item<u64> = Item::new("config");
+const BALANCES: Map<&str, u64> = Map::new("balances");
+const QUEUE: Deque<u64> = Deque::new("queue");
+
+fn instantiate(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    CONFIG.save(deps.storage, &42)?;
+    BALANCES.save(deps.storage, "alice", &100)?;
+    BALANCES.save(deps.storage, "bob", &100)?;
+    for value in 1..=3 {
+        QUEUE.push_back(deps.storage, &value)?;
+    }
+    Ok(Response::new())
+}
+
+fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+/// Instantiates the contract writing an item, a map with two entries and a deque with three values.
+fn instantiate_contract(app: &mut App) -> cosmwasm_std::Addr {
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    app.instantiate_contract(
+        code_id,
+        "owner".into_addr(),
+        &Empty {},
+        &[],
+        "storage",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn storage_stats_should_be_grouped_by_namespace() {
+    let mut app = App::default();
+    let contract_addr = instantiate_contract(&mut app);
+
+    let stats = app.contract_storage_stats(&contract_addr);
+
+    // item key is the namespace itself: `config` (6 bytes), value `42` (2 bytes)
+    assert_eq!(
+        Some(&PrefixStats {
+            keys: 1,
+            total_key_bytes: 6,
+            total_value_bytes: 2,
+        }),
+        stats.prefix("config")
+    );
+    // map keys are length-prefixed namespace followed by the key: 2 + 8 + 5 and 2 + 8 + 3 bytes,
+    // values `100` (3 bytes each)
+    assert_eq!(
+        Some(&PrefixStats {
+            keys: 2,
+            total_key_bytes: 28,
+            total_value_bytes: 6,
+        }),
+        stats.prefix("balances")
+    );
+    // deque keeps three values under 4-byte positions (2 + 5 + 4 bytes each, values 1 byte each)
+    // and the tail position under `t` key (2 + 5 + 1 bytes, value 4 bytes)
+    assert_eq!(
+        Some(&PrefixStats {
+            keys: 4,
+            total_key_bytes: 41,
+            total_value_bytes: 7,
+        }),
+        stats.prefix("queue")
+    );
+    assert_eq!(3, stats.by_prefix.len());
+
+    assert_eq!(7, stats.keys);
+    assert_eq!(75, stats.total_key_bytes);
+    assert_eq!(15, stats.total_value_bytes);
+    assert_eq!(90, stats.total_bytes());
+}
+
+#[test]
+fn storage_stats_of_unknown_contract_should_be_empty() {
+    let app = App::default();
+
+    let stats = app.contract_storage_stats(&"unknown".into_addr());
+
+    assert_eq!(0, stats.keys);
+    assert_eq!(0, stats.total_bytes());
+    assert!(stats.by_prefix.is_empty());
+}
+
+#[test]
+fn storage_budget_should_be_kept() {
+    let mut app = App::default();
+    let contract_addr = instantiate_contract(&mut app);
+
+    app.assert_storage_budget(
+        &contract_addr,
+        StorageBudget {
+            max_keys: 7,
+            max_bytes: 90,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "7 keys (limit 6), 90 bytes (limit 100), per prefix:
+  \"balances\": 2 keys, 34 bytes (28 in keys, 6 in values)
+  \"config\": 1 keys, 8 bytes (6 in keys, 2 in values)
+  \"queue\": 4 keys, 48 bytes (41 in keys, 7 in values)")]
+fn exceeded_storage_budget_should_report_prefixes() {
+    let mut app = App::default();
+    let contract_addr = instantiate_contract(&mut app);
+
+    app.assert_storage_budget(
+        &contract_addr,
+        StorageBudget {
+            max_keys: 6,
+            max_bytes: 100,
+        },
+    );
+}
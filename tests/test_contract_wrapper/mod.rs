@@ -0,0 +1,2 @@
+mod test_closures;
+mod test_macro;
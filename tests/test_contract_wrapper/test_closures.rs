@@ -0,0 +1,74 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Returns a mock price oracle, that replies with the configured price
+/// and counts how many times it was called.
+fn price_oracle(price: Arc<AtomicU64>, calls: Arc<AtomicU64>) -> Box<dyn Contract<Empty>> {
+    let execute_calls = calls.clone();
+    let query_calls = calls.clone();
+    let sudo_calls = calls;
+    let contract = ContractWrapper::new_with_closures(
+        move |_deps: DepsMut,
+              _env: Env,
+              _info: MessageInfo,
+              _msg: Empty|
+              -> Result<Response, StdError> {
+            execute_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Response::new())
+        },
+        |_deps: DepsMut,
+         _env: Env,
+         _info: MessageInfo,
+         _msg: Empty|
+         -> Result<Response, StdError> { Ok(Response::new()) },
+        move |_deps: Deps, _env: Env, _msg: Empty| -> Result<Binary, StdError> {
+            query_calls.fetch_add(1, Ordering::SeqCst);
+            to_json_binary(&price.load(Ordering::SeqCst))
+        },
+    )
+    .with_sudo_closure(
+        move |_deps: DepsMut, _env: Env, _msg: Empty| -> Result<Response, StdError> {
+            sudo_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Response::new())
+        },
+    );
+    Box::new(contract)
+}
+
+#[test]
+fn closures_capturing_state_should_work() {
+    let price = Arc::new(AtomicU64::new(100));
+    let calls = Arc::new(AtomicU64::new(0));
+
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(price_oracle(price.clone(), calls.clone()));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "oracle", None)
+        .unwrap();
+
+    // query returns the price configured by the test
+    let value: u64 = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(100, value);
+
+    // changing the captured state changes the contract's behavior
+    price.store(250, Ordering::SeqCst);
+    let value: u64 = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(250, value);
+
+    // invocations of execute and sudo entry-points are counted too
+    app.execute_contract(owner_addr, contract_addr.clone(), &Empty {}, &[])
+        .unwrap();
+    app.wasm_sudo(contract_addr, &Empty {}).unwrap();
+    assert_eq!(4, calls.load(Ordering::SeqCst));
+}
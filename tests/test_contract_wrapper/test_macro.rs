@@ -0,0 +1,155 @@
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::{contract_wrapper, App, Contract, Executor, IntoAddr};
+
+mod contracts {
+    pub mod counter {
+        use cosmwasm_schema::cw_serde;
+        use cosmwasm_std::{
+            to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response,
+            StdResult, SubMsg, WasmMsg,
+        };
+        use cw_storage_plus::Item;
+
+        const COUNT: Item<u64> = Item::new("count");
+
+        #[cw_serde]
+        pub enum ExecuteMsg {
+            /// Increments the counter by one.
+            Increment {},
+            /// Increments the counter in a sub-message, and by ten more in `reply`.
+            IncrementWithReply {},
+        }
+
+        #[cw_serde]
+        pub struct MigrateMsg {
+            pub count: u64,
+        }
+
+        pub fn instantiate(
+            deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            _msg: Empty,
+        ) -> StdResult<Response> {
+            COUNT.save(deps.storage, &0)?;
+            Ok(Response::new())
+        }
+
+        pub fn execute(
+            deps: DepsMut,
+            env: Env,
+            _info: MessageInfo,
+            msg: ExecuteMsg,
+        ) -> StdResult<Response> {
+            match msg {
+                ExecuteMsg::Increment {} => {
+                    COUNT.update(deps.storage, |count| StdResult::Ok(count + 1))?;
+                    Ok(Response::new())
+                }
+                ExecuteMsg::IncrementWithReply {} => {
+                    let msg = WasmMsg::Execute {
+                        contract_addr: env.contract.address.to_string(),
+                        msg: to_json_binary(&ExecuteMsg::Increment {})?,
+                        funds: vec![],
+                    };
+                    Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, 1)))
+                }
+            }
+        }
+
+        pub fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+            to_json_binary(&COUNT.load(deps.storage)?)
+        }
+
+        pub fn reply(deps: DepsMut, _env: Env, _msg: Reply) -> StdResult<Response> {
+            COUNT.update(deps.storage, |count| StdResult::Ok(count + 10))?;
+            Ok(Response::new())
+        }
+
+        pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+            COUNT.save(deps.storage, &msg.count)?;
+            Ok(Response::new())
+        }
+    }
+}
+
+use contracts::counter::{ExecuteMsg, MigrateMsg};
+
+fn count(app: &App, contract_addr: &Addr) -> u64 {
+    app.wrap()
+        .query_wasm_smart(contract_addr, &Empty {})
+        .unwrap()
+}
+
+fn instantiate(contract: Box<dyn Contract<Empty>>) -> (App, Addr) {
+    let mut app = App::default();
+    let code_id = app.store_code(contract);
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            "owner".into_addr(),
+            &Empty {},
+            &[],
+            "counter",
+            Some("owner".into_addr().to_string()),
+        )
+        .unwrap();
+    (app, contract_addr)
+}
+
+#[test]
+fn required_entry_points_should_be_wired() {
+    let (mut app, contract_addr) = instantiate(contract_wrapper!(contracts::counter));
+
+    app.execute_contract(
+        "user".into_addr(),
+        contract_addr.clone(),
+        &ExecuteMsg::Increment {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(1, count(&app, &contract_addr));
+
+    // optional entry-points are not wired unless listed
+    app.execute_contract(
+        "user".into_addr(),
+        contract_addr.clone(),
+        &ExecuteMsg::IncrementWithReply {},
+        &[],
+    )
+    .unwrap_err();
+    let code_id = app.contract_code_id(&contract_addr).unwrap();
+    app.migrate_contract(
+        "owner".into_addr(),
+        contract_addr.clone(),
+        &MigrateMsg { count: 5 },
+        code_id,
+    )
+    .unwrap_err();
+    assert_eq!(1, count(&app, &contract_addr));
+}
+
+#[test]
+fn listed_entry_points_should_be_wired() {
+    let (mut app, contract_addr) =
+        instantiate(contract_wrapper!(contracts::counter, migrate, reply));
+
+    app.execute_contract(
+        "user".into_addr(),
+        contract_addr.clone(),
+        &ExecuteMsg::IncrementWithReply {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(11, count(&app, &contract_addr));
+
+    let code_id = app.contract_code_id(&contract_addr).unwrap();
+    app.migrate_contract(
+        "owner".into_addr(),
+        contract_addr.clone(),
+        &MigrateMsg { count: 5 },
+        code_id,
+    )
+    .unwrap();
+    assert_eq!(5, count(&app, &contract_addr));
+}
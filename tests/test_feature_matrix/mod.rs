@@ -0,0 +1,57 @@
+//! Tests verifying that all `WasmMsg` and `WasmQuery` variants available
+//! with enabled feature flags are supported by `WasmKeeper`.
+//!
+//! When a new variant is added to **cosmwasm-std**, tests comparing
+//! the variant names fail, so the variant has to be handled explicitly.
+
+mod test_wasm_msg;
+mod test_wasm_query;
+
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{Contract, ContractWrapper};
+use schemars::schema::{RootSchema, Schema};
+use std::collections::BTreeSet;
+
+/// Key of the raw value stored by the test contract.
+const VERSION_KEY: &[u8] = b"version";
+
+/// Returns the (serialized) names of all variants of the enum described by the schema.
+fn variant_names(schema: RootSchema) -> BTreeSet<String> {
+    schema
+        .schema
+        .subschemas
+        .and_then(|subschemas| subschemas.one_of)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|variant| match variant {
+            Schema::Object(object) => object.object,
+            Schema::Bool(_) => None,
+        })
+        .flat_map(|object| object.required)
+        .collect()
+}
+
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    deps.storage.set(VERSION_KEY, b"1");
+    Ok(Response::default())
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("action", "execute"))
+}
+
+fn query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&deps.storage.get(VERSION_KEY))
+}
+
+fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    deps.storage.set(VERSION_KEY, b"2");
+    Ok(Response::default())
+}
+
+/// Returns a contract implementing all entry-points used by `WasmMsg` and `WasmQuery` variants.
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(execute, instantiate, query).with_migrate(migrate))
+}
@@ -0,0 +1,143 @@
+use super::{contract, variant_names, VERSION_KEY};
+use cosmwasm_std::{to_json_binary, Addr, Empty, Event, WasmMsg};
+use cw_multi_test::{App, AppResponse, Executor};
+use cw_utils::parse_instantiate_response_data;
+use schemars::schema_for;
+use std::collections::BTreeSet;
+
+/// Stores the test contract and instantiates it with the creator set as admin.
+fn setup(app: &mut App) -> (Addr, u64, Addr) {
+    let creator = app.api().addr_make("creator");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "feature-matrix",
+            Some(creator.to_string()),
+        )
+        .unwrap();
+    (creator, code_id, contract_addr)
+}
+
+/// Asserts that the response reports a new contract instantiated from specified code.
+fn assert_instantiated(app: &App, res: &AppResponse, code_id: u64) {
+    let data = parse_instantiate_response_data(res.data.as_ref().unwrap()).unwrap();
+    let contract_addr = Addr::unchecked(data.contract_address);
+    assert_eq!(code_id, app.contract_data(&contract_addr).unwrap().code_id);
+}
+
+#[test]
+fn all_wasm_messages_should_be_covered() {
+    #[allow(unused_mut)]
+    let mut expected: BTreeSet<String> = [
+        "execute",
+        "instantiate",
+        "migrate",
+        "update_admin",
+        "clear_admin",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
+    #[cfg(feature = "cosmwasm_1_2")]
+    expected.insert("instantiate2".to_string());
+    assert_eq!(expected, variant_names(schema_for!(WasmMsg)));
+}
+
+#[test]
+fn execute_should_work() {
+    let mut app = App::default();
+    let (creator, _, contract_addr) = setup(&mut app);
+    let msg = WasmMsg::Execute {
+        contract_addr: contract_addr.to_string(),
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+    };
+    let res = app.execute(creator, msg.into()).unwrap();
+    assert!(res.has_event(&Event::new("wasm").add_attribute("action", "execute")));
+}
+
+#[test]
+fn instantiate_should_work() {
+    let mut app = App::default();
+    let (creator, code_id, _) = setup(&mut app);
+    let msg = WasmMsg::Instantiate {
+        admin: None,
+        code_id,
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+        label: "instantiate".to_string(),
+    };
+    let res = app.execute(creator, msg.into()).unwrap();
+    assert_instantiated(&app, &res, code_id);
+}
+
+#[cfg(feature = "cosmwasm_1_2")]
+#[test]
+fn instantiate2_should_work() {
+    let mut app = App::default();
+    let (creator, code_id, _) = setup(&mut app);
+    let msg = WasmMsg::Instantiate2 {
+        admin: None,
+        code_id,
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+        label: "instantiate2".to_string(),
+        salt: b"salt".into(),
+    };
+    let res = app.execute(creator, msg.into()).unwrap();
+    assert_instantiated(&app, &res, code_id);
+}
+
+#[test]
+fn migrate_should_work() {
+    let mut app = App::default();
+    let (creator, _, contract_addr) = setup(&mut app);
+    let new_code_id = app.store_code(contract());
+    let msg = WasmMsg::Migrate {
+        contract_addr: contract_addr.to_string(),
+        new_code_id,
+        msg: to_json_binary(&Empty {}).unwrap(),
+    };
+    app.execute(creator, msg.into()).unwrap();
+    assert_eq!(
+        new_code_id,
+        app.contract_data(&contract_addr).unwrap().code_id
+    );
+    assert_eq!(
+        Some(b"2".to_vec()),
+        app.wrap()
+            .query_wasm_raw(&contract_addr, VERSION_KEY)
+            .unwrap()
+    );
+}
+
+#[test]
+fn update_admin_should_work() {
+    let mut app = App::default();
+    let (creator, _, contract_addr) = setup(&mut app);
+    let new_admin = app.api().addr_make("admin");
+    let msg = WasmMsg::UpdateAdmin {
+        contract_addr: contract_addr.to_string(),
+        admin: new_admin.to_string(),
+    };
+    app.execute(creator, msg.into()).unwrap();
+    assert_eq!(
+        Some(new_admin),
+        app.contract_data(&contract_addr).unwrap().admin
+    );
+}
+
+#[test]
+fn clear_admin_should_work() {
+    let mut app = App::default();
+    let (creator, _, contract_addr) = setup(&mut app);
+    let msg = WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    };
+    app.execute(creator, msg.into()).unwrap();
+    assert_eq!(None, app.contract_data(&contract_addr).unwrap().admin);
+}
@@ -0,0 +1,76 @@
+use super::{contract, variant_names, VERSION_KEY};
+use cosmwasm_std::{Addr, Empty, WasmQuery};
+use cw_multi_test::{App, Executor};
+use schemars::schema_for;
+use std::collections::BTreeSet;
+
+/// Stores and instantiates the test contract, returns the creator, code id and contract address.
+fn setup(app: &mut App) -> (Addr, u64, Addr) {
+    let creator = app.api().addr_make("creator");
+    let code_id = app.store_code_with_creator(creator.clone(), contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "feature-matrix",
+            None,
+        )
+        .unwrap();
+    (creator, code_id, contract_addr)
+}
+
+#[test]
+fn all_wasm_queries_should_be_covered() {
+    #[allow(unused_mut)]
+    let mut expected: BTreeSet<String> = ["smart", "raw", "contract_info"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    #[cfg(feature = "cosmwasm_1_2")]
+    expected.insert("code_info".to_string());
+    assert_eq!(expected, variant_names(schema_for!(WasmQuery)));
+}
+
+#[test]
+fn smart_query_should_work() {
+    let mut app = App::default();
+    let (_, _, contract_addr) = setup(&mut app);
+    let version: Option<Vec<u8>> = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(Some(b"1".to_vec()), version);
+}
+
+#[test]
+fn raw_query_should_work() {
+    let mut app = App::default();
+    let (_, _, contract_addr) = setup(&mut app);
+    let version = app
+        .wrap()
+        .query_wasm_raw(&contract_addr, VERSION_KEY)
+        .unwrap();
+    assert_eq!(Some(b"1".to_vec()), version);
+}
+
+#[test]
+fn contract_info_query_should_work() {
+    let mut app = App::default();
+    let (creator, code_id, contract_addr) = setup(&mut app);
+    let res = app.wrap().query_wasm_contract_info(&contract_addr).unwrap();
+    assert_eq!(code_id, res.code_id);
+    assert_eq!(creator, res.creator);
+    assert_eq!(None, res.admin);
+}
+
+#[cfg(feature = "cosmwasm_1_2")]
+#[test]
+fn code_info_query_should_work() {
+    let mut app = App::default();
+    let (creator, code_id, _) = setup(&mut app);
+    let res = app.wrap().query_wasm_code_info(code_id).unwrap();
+    assert_eq!(code_id, res.code_id);
+    assert_eq!(creator, res.creator);
+}
@@ -1,2 +1,3 @@
 mod test_accepting_module;
 mod test_failing_module;
+mod test_transactional_module;
@@ -0,0 +1,184 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::{
+    coins, Addr, Api, BankMsg, Binary, BlockInfo, CosmosMsg, CustomMsg, CustomQuery, Empty, Event,
+    Querier, Storage,
+};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{
+    no_init, transactional, AppResponse, BasicAppBuilder, CosmosRouter, Executor, IntoAddr, Module,
+};
+use serde::de::DeserializeOwned;
+
+const DENOM: &str = "denom";
+
+/// Settles a batch of bank transfers sent from the sender's account.
+#[cw_serde]
+struct SettleBatch {
+    /// Transfers in the batch, pairs of recipient address and amount.
+    transfers: Vec<(String, u128)>,
+    /// Flag indicating if the batch should be settled atomically.
+    atomic: bool,
+}
+
+impl CustomMsg for SettleBatch {}
+
+/// Custom module settling batches of transfers, reporting failed batches
+/// in events instead of failing the whole message.
+struct BatchModule;
+
+impl BatchModule {
+    fn settle<ExecC, QueryC>(
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: &Addr,
+        transfers: &[(String, u128)],
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let mut response = AppResponse::default();
+        for (recipient, amount) in transfers {
+            let send = BankMsg::Send {
+                to_address: recipient.clone(),
+                amount: coins(*amount, DENOM),
+            };
+            let res = router.execute(api, storage, block, sender.clone(), send.into())?;
+            response.events.extend(res.events);
+        }
+        Ok(response)
+    }
+}
+
+impl Module for BatchModule {
+    type ExecT = SettleBatch;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: SettleBatch,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let result = if msg.atomic {
+            transactional(storage, |cache, _| {
+                Self::settle(api, cache, router, block, &sender, &msg.transfers)
+            })
+        } else {
+            Self::settle(api, storage, router, block, &sender, &msg.transfers)
+        };
+        let status = if result.is_ok() { "settled" } else { "failed" };
+        let mut response = result.unwrap_or_default();
+        response
+            .events
+            .push(Event::new("batch").add_attribute("status", status));
+        Ok(response)
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        _request: Empty,
+    ) -> AnyResult<Binary> {
+        bail!("queries not implemented for BatchModule")
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("sudo not implemented for BatchModule")
+    }
+}
+
+/// Settles a batch of two transfers from the account holding 100 tokens,
+/// returns the status of the batch and the balances of both recipients.
+fn settle_two_transfers(atomic: bool, second_amount: u128) -> (String, u128, u128) {
+    let sender = "sender".into_addr();
+    let alice = "alice".into_addr();
+    let bob = "bob".into_addr();
+    let mut app = BasicAppBuilder::<SettleBatch, Empty>::new_custom()
+        .with_custom(BatchModule)
+        .with_genesis_balances(vec![(sender.clone(), coins(100, DENOM))])
+        .build(no_init);
+    let res = app
+        .execute(
+            sender,
+            CosmosMsg::Custom(SettleBatch {
+                transfers: vec![(alice.to_string(), 60), (bob.to_string(), second_amount)],
+                atomic,
+            }),
+        )
+        .unwrap();
+    let status = res
+        .events
+        .iter()
+        .find(|event| event.ty == "batch")
+        .map(|event| event.attributes[0].value.clone())
+        .unwrap();
+    let balance = |addr: &Addr| app.wrap().query_balance(addr, DENOM).unwrap().amount.u128();
+    (status, balance(&alice), balance(&bob))
+}
+
+#[test]
+fn atomic_batch_should_be_settled() {
+    assert_eq!(
+        ("settled".to_string(), 60, 40),
+        settle_two_transfers(true, 40)
+    );
+}
+
+#[test]
+fn atomic_batch_should_be_rolled_back_when_second_transfer_fails() {
+    // the sender has only 40 tokens left for the second transfer, so it fails,
+    // and the first transfer is rolled back, although the message itself succeeds
+    assert_eq!(("failed".to_string(), 0, 0), settle_two_transfers(true, 50));
+}
+
+#[test]
+fn non_atomic_batch_should_leave_partial_state_when_second_transfer_fails() {
+    // without the nested transaction the first transfer remains
+    assert_eq!(
+        ("failed".to_string(), 60, 0),
+        settle_two_transfers(false, 50)
+    );
+}
+
+#[test]
+fn nested_transactions_should_commit_only_with_outer_transaction() {
+    let mut storage = MockStorage::new();
+    let result: AnyResult<()> = transactional(&mut storage, |outer, _| {
+        transactional(outer, |inner, _| {
+            inner.set(b"key", b"inner");
+            Ok(())
+        })?;
+        // changes committed by the inner transaction are visible in the outer one
+        assert_eq!(Some(b"inner".to_vec()), outer.get(b"key"));
+        bail!("outer failure")
+    });
+    assert!(result.is_err());
+    // the outer transaction failed, so the changes of the inner one are discarded too
+    assert_eq!(None, storage.get(b"key"));
+}
@@ -1,3 +1,4 @@
+mod test_reply_traces;
 mod test_submessage_payload;
 
 mod test_contracts {
@@ -14,9 +15,13 @@ mod test_contracts {
             SendMulti(String, u128, String, u128, String),
             Burn(u128, String),
             BurnNoPayload(u128, String),
+            BurnRawPayload(u128, String),
             Nop,
         }
 
+        /// Payload that is not valid JSON.
+        pub const RAW_PAYLOAD: &[u8] = &[0x00, 0xff, 0x7b];
+
         #[cw_serde]
         pub struct Payload {
             pub id: u64,
@@ -116,6 +121,18 @@ mod test_contracts {
                         reply_on: ReplyOn::Always,
                     });
                 }
+                ExecuteMessage::BurnRawPayload(amount, denom) => {
+                    let msg_send = BankMsg::Burn {
+                        amount: vec![Coin::new(Uint128::new(amount), denom.clone())],
+                    };
+                    response = response.add_submessage(SubMsg {
+                        id: 6,
+                        payload: Binary::from(RAW_PAYLOAD),
+                        msg: msg_send.into(),
+                        gas_limit: None,
+                        reply_on: ReplyOn::Always,
+                    });
+                }
                 ExecuteMessage::Nop => {}
             }
             Ok(response)
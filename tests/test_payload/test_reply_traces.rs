@@ -0,0 +1,286 @@
+use super::test_contracts::payloader::{ExecuteMessage, Payload, RAW_PAYLOAD};
+use super::test_submessage_payload::payloader_contract;
+use cosmwasm_std::{coins, Addr, Binary, Empty};
+use cw_multi_test::{
+    no_init, App, AppBuilder, ChaosConfig, ChaoticWasm, Executor, IntoAddr, ReplyTrace, WasmKeeper,
+};
+use serde_json::json;
+
+const DENOM: &str = "pao";
+
+/// Instantiates the payloader contract holding some tokens to be burnt.
+fn instantiate_payloader(app: &mut App) -> Addr {
+    let owner = "owner".into_addr();
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, coins(100, DENOM))
+            .unwrap();
+    });
+    let code_id = app.store_code(payloader_contract());
+    app.instantiate_contract(
+        code_id,
+        owner,
+        &Empty {},
+        &coins(100, DENOM),
+        "payloader",
+        None,
+    )
+    .unwrap()
+}
+
+fn execute(app: &mut App, contract_addr: &Addr, msg: ExecuteMessage) {
+    app.execute_contract("owner".into_addr(), contract_addr.clone(), &msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn reply_traces_should_be_empty_when_disabled() {
+    let mut app = App::default();
+    let contract_addr = instantiate_payloader(&mut app);
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::Burn(1, DENOM.to_string()),
+    );
+
+    assert_eq!(Vec::<ReplyTrace>::new(), app.reply_traces());
+}
+
+#[test]
+fn json_payload_should_be_traced() {
+    let mut app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    let contract_addr = instantiate_payloader(&mut app);
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::Burn(1, DENOM.to_string()),
+    );
+
+    let traces = app.reply_traces();
+    assert_eq!(1, traces.len());
+    assert_eq!(contract_addr, traces[0].contract);
+    assert_eq!(4, traces[0].id);
+    assert_eq!(
+        Some(json!({"id": 0, "action": "BURN"})),
+        traces[0].payload_json
+    );
+    app.assert_reply_payload(
+        &contract_addr,
+        4,
+        &Payload {
+            id: 0,
+            action: "BURN".to_string(),
+        },
+    );
+}
+
+#[test]
+fn payloads_of_all_replies_should_be_traced() {
+    let mut app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    let contract_addr = instantiate_payloader(&mut app);
+    let bob_addr = "bob".into_addr();
+    let cecil_addr = "cecil".into_addr();
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::Send(bob_addr.to_string(), 10, DENOM.to_string()),
+    );
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::SendMulti(
+            bob_addr.to_string(),
+            1,
+            cecil_addr.to_string(),
+            2,
+            DENOM.to_string(),
+        ),
+    );
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::Burn(1, DENOM.to_string()),
+    );
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::BurnNoPayload(1, DENOM.to_string()),
+    );
+
+    let send_payload = Payload {
+        id: 0,
+        action: "SEND".to_string(),
+    };
+    app.assert_reply_payload(&contract_addr, 1, &send_payload);
+    app.assert_reply_payload(&contract_addr, 2, &send_payload);
+    app.assert_reply_payload(&contract_addr, 3, &send_payload);
+    app.assert_reply_payload(
+        &contract_addr,
+        4,
+        &Payload {
+            id: 0,
+            action: "BURN".to_string(),
+        },
+    );
+    assert!(app.reply_traces().last().unwrap().payload.is_empty());
+    assert_eq!(
+        vec![1, 2, 3, 4, 5],
+        app.reply_traces()
+            .iter()
+            .map(|trace| trace.id)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn empty_payload_should_be_traced() {
+    let mut app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    let contract_addr = instantiate_payloader(&mut app);
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::BurnNoPayload(1, DENOM.to_string()),
+    );
+
+    assert_eq!(
+        vec![ReplyTrace {
+            contract: contract_addr,
+            id: 5,
+            payload: Binary::default(),
+            payload_json: None,
+        }],
+        app.reply_traces()
+    );
+}
+
+#[test]
+fn binary_payload_should_be_traced() {
+    let mut app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    let contract_addr = instantiate_payloader(&mut app);
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::BurnRawPayload(1, DENOM.to_string()),
+    );
+
+    assert_eq!(
+        vec![ReplyTrace {
+            contract: contract_addr,
+            id: 6,
+            payload: Binary::from(RAW_PAYLOAD),
+            payload_json: None,
+        }],
+        app.reply_traces()
+    );
+}
+
+#[test]
+#[should_panic(expected = "Failed to decode reply payload")]
+fn asserting_binary_payload_should_fail() {
+    let mut app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    let contract_addr = instantiate_payloader(&mut app);
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::BurnRawPayload(1, DENOM.to_string()),
+    );
+
+    app.assert_reply_payload(
+        &contract_addr,
+        6,
+        &Payload {
+            id: 0,
+            action: "BURN".to_string(),
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected reply payload")]
+fn asserting_different_payload_should_fail() {
+    let mut app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    let contract_addr = instantiate_payloader(&mut app);
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::Burn(1, DENOM.to_string()),
+    );
+
+    app.assert_reply_payload(
+        &contract_addr,
+        4,
+        &Payload {
+            id: 0,
+            action: "SEND".to_string(),
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "Expected a reply with id 7")]
+fn asserting_missing_reply_should_fail() {
+    let mut app = AppBuilder::default().with_reply_traces(true).build(no_init);
+    let contract_addr = instantiate_payloader(&mut app);
+
+    execute(
+        &mut app,
+        &contract_addr,
+        ExecuteMessage::Burn(1, DENOM.to_string()),
+    );
+
+    app.assert_reply_payload(&contract_addr, 7, &Empty {});
+}
+
+#[test]
+fn replies_should_be_traced_by_chaotic_wasm_keeper() {
+    let wasm = ChaoticWasm::new(
+        WasmKeeper::new().with_reply_traces(true),
+        ChaosConfig::deterministic_failures(vec![]),
+    );
+    let owner = "owner".into_addr();
+    let mut app = AppBuilder::default()
+        .with_wasm(wasm)
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &owner, coins(100, DENOM))
+                .unwrap();
+        });
+    let code_id = app.store_code(payloader_contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner.clone(),
+            &Empty {},
+            &coins(100, DENOM),
+            "payloader",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(
+        owner,
+        contract_addr.clone(),
+        &ExecuteMessage::Burn(1, DENOM.to_string()),
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(1, app.reply_traces().len());
+    app.assert_reply_payload(
+        &contract_addr,
+        4,
+        &Payload {
+            id: 0,
+            action: "BURN".to_string(),
+        },
+    );
+}
@@ -1,2 +1,3 @@
 mod test_prefixed_multilevel_storage;
 mod test_prefixed_storage_bank;
+mod test_sub_prefix;
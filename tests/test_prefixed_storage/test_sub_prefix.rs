@@ -0,0 +1,139 @@
+use cosmwasm_std::testing::MockStorage;
+use cosmwasm_std::{Order, Storage};
+use cw_multi_test::prefixed_storage::{
+    to_length_prefixed, to_length_prefixed_nested, PrefixedStorage, ReadonlyPrefixedStorage,
+};
+
+/// Returns all keys from the range.
+fn keys(
+    storage: &dyn Storage,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+) -> Vec<Vec<u8>> {
+    storage
+        .range(start, end, order)
+        .map(|(key, _)| key)
+        .collect()
+}
+
+#[test]
+fn three_level_nesting_should_work() {
+    let mut storage = MockStorage::new();
+
+    // write using nested sub-prefixes
+    let mut first = PrefixedStorage::new(&mut storage, b"first");
+    first
+        .sub_prefix(b"second")
+        .sub_prefix(b"third")
+        .set(b"key", b"value");
+
+    // the raw key has the same layout as in multilevel prefixed storage
+    let mut raw_key = to_length_prefixed_nested(&[b"first", b"second", b"third"]);
+    raw_key.extend_from_slice(b"key");
+    assert_eq!(
+        b"\x00\x05first\x00\x06second\x00\x05thirdkey".to_vec(),
+        raw_key
+    );
+    assert_eq!(Some(b"value".to_vec()), storage.get(&raw_key));
+
+    // read using multilevel prefixed storage and nested sub-prefixes
+    let third = ReadonlyPrefixedStorage::multilevel(&storage, &[b"first", b"second", b"third"]);
+    assert_eq!(Some(b"value".to_vec()), third.get(b"key"));
+    let first = ReadonlyPrefixedStorage::new(&storage, b"first");
+    assert_eq!(
+        Some(b"value".to_vec()),
+        first.sub_prefix(b"second").sub_prefix(b"third").get(b"key")
+    );
+
+    // keys returned from range have the namespace stripped
+    let second = first.sub_prefix(b"second");
+    assert_eq!(
+        vec![b"key".to_vec()],
+        keys(&second.sub_prefix(b"third"), None, None, Order::Ascending)
+    );
+
+    // keys from nested namespaces contain their length-prefixed namespace
+    let mut nested_key = to_length_prefixed(b"third");
+    nested_key.extend_from_slice(b"key");
+    assert_eq!(
+        vec![nested_key],
+        keys(&second, None, None, Order::Ascending)
+    );
+}
+
+#[test]
+fn boundary_keys_should_work() {
+    let mut storage = MockStorage::new();
+    let namespace: &[u8] = b"\xFF\xFF";
+
+    // records directly outside the namespace
+    let prefix = to_length_prefixed(namespace);
+    storage.set(b"\x00\x01\xFF", b"before");
+    storage.set(b"\x00\x03\x00\x00", b"after");
+
+    let mut prefixed = PrefixedStorage::new(&mut storage, namespace);
+    prefixed.set(b"", b"empty");
+    prefixed.set(b"\x00", b"zero");
+    prefixed.set(b"\xFF", b"max");
+    prefixed.set(b"\xFF\xFF\xFF", b"max3");
+    assert_eq!(Some(b"empty".to_vec()), storage.get(&prefix));
+
+    let prefixed = ReadonlyPrefixedStorage::new(&storage, namespace);
+    assert_eq!(Some(b"empty".to_vec()), prefixed.get(b""));
+    let all_keys = vec![
+        b"".to_vec(),
+        b"\x00".to_vec(),
+        b"\xFF".to_vec(),
+        b"\xFF\xFF\xFF".to_vec(),
+    ];
+    assert_eq!(all_keys, keys(&prefixed, None, None, Order::Ascending));
+    assert_eq!(all_keys, keys(&prefixed, Some(b""), None, Order::Ascending));
+
+    // start is inclusive, end is exclusive
+    assert_eq!(
+        vec![b"\x00".to_vec(), b"\xFF".to_vec()],
+        keys(
+            &prefixed,
+            Some(b"\x00"),
+            Some(b"\xFF\xFF"),
+            Order::Ascending
+        )
+    );
+    assert_eq!(
+        vec![b"\xFF\xFF\xFF".to_vec()],
+        keys(&prefixed, Some(b"\xFF\xFF"), None, Order::Ascending)
+    );
+}
+
+#[test]
+fn descending_iteration_should_work() {
+    let mut storage = MockStorage::new();
+    let mut prefixed = PrefixedStorage::multilevel(&mut storage, &[b"a", b"b"]);
+    let mut third = prefixed.sub_prefix(b"c");
+    for key in [
+        b"".as_slice(),
+        b"1".as_slice(),
+        b"2".as_slice(),
+        b"\xFF".as_slice(),
+    ] {
+        third.set(key, b"value");
+    }
+    // record in the sibling namespace is not returned
+    prefixed.sub_prefix(b"d").set(b"0", b"sibling");
+
+    let third = ReadonlyPrefixedStorage::multilevel(&storage, &[b"a", b"b", b"c"]);
+    assert_eq!(
+        vec![b"\xFF".to_vec(), b"2".to_vec(), b"1".to_vec(), b"".to_vec()],
+        keys(&third, None, None, Order::Descending)
+    );
+    assert_eq!(
+        vec![b"2".to_vec(), b"1".to_vec()],
+        keys(&third, Some(b"1"), Some(b"\xFF"), Order::Descending)
+    );
+    let values = third
+        .range(None, None, Order::Descending)
+        .map(|(_, value)| value)
+        .collect::<Vec<_>>();
+    assert_eq!(vec![b"value".to_vec(); 4], values);
+}
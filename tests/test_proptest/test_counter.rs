@@ -0,0 +1,70 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{Empty, WasmMsg};
+use cw_multi_test::proptest_support::{arb_addr, arb_funds, arb_label, proptest_app};
+use cw_multi_test::Executor;
+
+const DENOMS: &[&str] = &[
+    "uatom",
+    "uosmo",
+    "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+];
+
+#[test]
+fn counter_should_count_executions() {
+    let api = MockApi::default();
+    proptest_app(
+        (
+            arb_addr(&api),
+            arb_funds(DENOMS, 1_000_000),
+            arb_label(),
+            0..10_u64,
+        ),
+        |app, (owner, funds, label, executions)| {
+            app.init_modules(|router, _, storage| {
+                router
+                    .bank
+                    .init_balance(storage, &owner, funds.clone())
+                    .unwrap();
+            });
+            let code_id = app.store_code(counter::contract());
+            let contract_addr = app
+                .instantiate_contract(code_id, owner.clone(), &Empty {}, &funds, label, None)
+                .unwrap();
+            let msg = WasmMsg::ClearAdmin {
+                contract_addr: contract_addr.to_string(),
+            };
+            for _ in 0..executions {
+                app.execute_contract(owner.clone(), contract_addr.clone(), &msg, &[])
+                    .unwrap();
+            }
+
+            let res: counter::CounterResponseMsg = app
+                .wrap()
+                .query_wasm_smart(&contract_addr, &counter::CounterQueryMsg::Counter {})
+                .unwrap();
+            assert_eq!(executions + 1, res.value);
+            for coin in &funds {
+                app.assert_balance(&contract_addr, coin);
+            }
+        },
+    );
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_2")]
+fn predictable_addresses_should_be_accepted() {
+    use cw_multi_test::proptest_support::arb_salt;
+
+    let api = MockApi::default();
+    proptest_app(
+        (arb_addr(&api), arb_label(), arb_salt()),
+        |app, (creator, label, salt)| {
+            let code_id = app.store_code(counter::contract());
+            let contract_addr = app
+                .instantiate2_contract(code_id, creator, &Empty {}, &[], label, None, salt)
+                .unwrap();
+            assert!(app.contract_data(&contract_addr).is_ok());
+        },
+    );
+}
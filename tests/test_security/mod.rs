@@ -0,0 +1,2 @@
+mod test_malformed_messages;
+mod test_sender_spoofing;
@@ -0,0 +1,104 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{from_json, to_json_binary, Addr, Binary, Empty, WasmMsg};
+use cw_multi_test::error::AnyError;
+use cw_multi_test::{App, Executor, IntoAddr};
+
+/// Messages rejected by the counter contract: invalid JSON, valid JSON with unknown variant
+/// and valid JSON nested deeper than the recursion limit of the JSON parser.
+fn malformed_messages() -> Vec<Binary> {
+    vec![
+        Binary::from(b"{\"counter\":".as_slice()),
+        Binary::from(b"{\"unknown\":{}}".as_slice()),
+        deeply_nested(),
+    ]
+}
+
+fn deeply_nested() -> Binary {
+    Binary::from(format!("{}{}", "[".repeat(1000), "]".repeat(1000)).into_bytes())
+}
+
+/// Asserts the message was rejected while parsing it in the contract.
+fn assert_parse_error(err: AnyError) {
+    let message = err.root_cause().to_string();
+    assert!(
+        message.contains("Error parsing into type"),
+        "unexpected error: {}",
+        message
+    );
+}
+
+fn instantiate_counter(app: &mut App) -> Addr {
+    let code_id = app.store_code(counter::contract());
+    app.instantiate_contract_raw(
+        code_id,
+        "owner".into_addr(),
+        to_json_binary(&Empty {}).unwrap(),
+        &[],
+        "counter",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn malformed_instantiate_messages_should_fail_cleanly() {
+    let mut app = App::default();
+    let code_id = app.store_code(counter::contract());
+    // the instantiate message of the counter contract is empty, so it has no variants
+    let messages = vec![
+        Binary::from(b"{".as_slice()),
+        Binary::from(b"\"unknown\"".as_slice()),
+        deeply_nested(),
+    ];
+    for msg in messages {
+        let err = app
+            .instantiate_contract_raw(code_id, "owner".into_addr(), msg, &[], "counter", None)
+            .unwrap_err();
+        assert_parse_error(err);
+    }
+}
+
+#[test]
+fn malformed_execute_messages_should_fail_cleanly() {
+    let mut app = App::default();
+    let contract_addr = instantiate_counter(&mut app);
+    for msg in malformed_messages() {
+        let err = app
+            .execute_contract_raw("owner".into_addr(), contract_addr.clone(), msg, &[])
+            .unwrap_err();
+        assert_parse_error(err);
+    }
+
+    // well-formed raw message is processed like a typed one
+    let msg = WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    };
+    app.execute_contract_raw(
+        "owner".into_addr(),
+        contract_addr.clone(),
+        to_json_binary(&msg).unwrap(),
+        &[],
+    )
+    .unwrap();
+    let response: counter::CounterResponseMsg = from_json(
+        app.query_wasm_smart_binary(
+            &contract_addr,
+            to_json_binary(&counter::CounterQueryMsg::Counter {}).unwrap(),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(2, response.value);
+}
+
+#[test]
+fn malformed_queries_should_fail_cleanly() {
+    let mut app = App::default();
+    let contract_addr = instantiate_counter(&mut app);
+    for msg in malformed_messages() {
+        let err = app
+            .query_wasm_smart_binary(&contract_addr, msg)
+            .unwrap_err();
+        assert_parse_error(err);
+    }
+}
@@ -0,0 +1,450 @@
+//! Documents that contracts can not act on behalf of other addresses.
+//!
+//! The malicious contract reflects any message it receives, trying to make other contracts
+//! and modules believe the message was sent by the user calling the malicious contract.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, CustomMsg,
+    CustomQuery, Deps, DepsMut, Empty, Env, Event, MessageInfo, OverflowError, OverflowOperation,
+    Querier, Reply, Response, StdError, StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{
+    no_init, App, AppResponse, BankKeeper, BasicAppBuilder, Contract, ContractWrapper,
+    CosmosRouter, Executor, Module, WasmKeeper,
+};
+use cw_storage_plus::Item;
+use serde::de::DeserializeOwned;
+
+const DENOM: &str = "uatom";
+
+/// Custom message claiming to be sent on behalf of another address.
+#[cw_serde]
+enum ClaimMsg {
+    ActAs { sender: String },
+}
+
+impl CustomMsg for ClaimMsg {}
+
+/// Custom module reporting the actual sender of a custom message together with the claimed one.
+struct ClaimModule;
+
+impl Module for ClaimModule {
+    type ExecT = ClaimMsg;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        sender: Addr,
+        msg: ClaimMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let ClaimMsg::ActAs { sender: claimed } = msg;
+        Ok(AppResponse {
+            events: vec![Event::new("act_as")
+                .add_attribute("sender", sender)
+                .add_attribute("claimed", claimed)],
+            data: None,
+        })
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        _request: Empty,
+    ) -> AnyResult<Binary> {
+        bail!("query not implemented for ClaimModule")
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("sudo not implemented for ClaimModule")
+    }
+}
+
+/// Immediate caller of the victim contract, as seen by the victim.
+const CALLER: Item<Addr> = Item::new("caller");
+
+/// Funds received by the victim contract in the last call.
+const FUNDS: Item<Vec<Coin>> = Item::new("funds");
+
+fn victim_call(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<Response<ClaimMsg>> {
+    CALLER.save(deps.storage, &info.sender)?;
+    FUNDS.save(deps.storage, &info.funds)?;
+    Ok(Response::new())
+}
+
+fn victim_instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<ClaimMsg>> {
+    victim_call(deps, env, info)
+}
+
+fn victim_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<ClaimMsg>> {
+    victim_call(deps, env, info)
+}
+
+fn victim_query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&CALLER.load(deps.storage)?)
+}
+
+fn victim_migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response<ClaimMsg>> {
+    Ok(Response::new())
+}
+
+fn victim() -> Box<dyn Contract<ClaimMsg>> {
+    Box::new(
+        ContractWrapper::new(victim_execute, victim_instantiate, victim_query)
+            .with_migrate(victim_migrate),
+    )
+}
+
+#[cw_serde]
+enum MaliciousMsg {
+    /// Sends the messages.
+    Reflect { msgs: Vec<CosmosMsg<ClaimMsg>> },
+    /// Sends the messages from the reply entry-point.
+    ReflectInReply { msgs: Vec<CosmosMsg<ClaimMsg>> },
+}
+
+/// Messages sent by the malicious contract from the reply entry-point.
+const REPLY_MSGS: Item<Vec<CosmosMsg<ClaimMsg>>> = Item::new("reply_msgs");
+
+fn malicious_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<ClaimMsg>> {
+    Ok(Response::new())
+}
+
+fn malicious_execute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: MaliciousMsg,
+) -> StdResult<Response<ClaimMsg>> {
+    match msg {
+        MaliciousMsg::Reflect { msgs } => Ok(Response::new().add_messages(msgs)),
+        MaliciousMsg::ReflectInReply { msgs } => {
+            REPLY_MSGS.save(deps.storage, &msgs)?;
+            let trigger = CosmosMsg::Custom(ClaimMsg::ActAs {
+                sender: env.contract.address.to_string(),
+            });
+            Ok(Response::new().add_submessage(SubMsg::reply_on_success(trigger, 1)))
+        }
+    }
+}
+
+fn malicious_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+fn malicious_reply(deps: DepsMut, _env: Env, _msg: Reply) -> StdResult<Response<ClaimMsg>> {
+    Ok(Response::new().add_messages(REPLY_MSGS.load(deps.storage)?))
+}
+
+fn malicious() -> Box<dyn Contract<ClaimMsg>> {
+    Box::new(
+        ContractWrapper::new(malicious_execute, malicious_instantiate, malicious_query)
+            .with_reply(malicious_reply),
+    )
+}
+
+type SecurityApp = App<BankKeeper, MockApi, MockStorage, ClaimModule, WasmKeeper<ClaimMsg, Empty>>;
+
+/// Application with the user having some funds, the malicious contract
+/// and the victim contract administered by the user.
+struct Suite {
+    app: SecurityApp,
+    user: Addr,
+    malicious_code_id: u64,
+    victim_code_id: u64,
+    malicious: Addr,
+    victim: Addr,
+}
+
+impl Suite {
+    fn new() -> Self {
+        let mut app = BasicAppBuilder::<ClaimMsg, Empty>::new_custom()
+            .with_custom(ClaimModule)
+            .with_sender_checks(true)
+            .build(no_init);
+        let user = app.api().addr_make("user");
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &user, coins(100, DENOM))
+                .unwrap();
+        });
+        let malicious_code_id = app.store_code(malicious());
+        let victim_code_id = app.store_code(victim());
+        let malicious = app
+            .instantiate_contract(
+                malicious_code_id,
+                user.clone(),
+                &Empty {},
+                &[],
+                "malicious",
+                None,
+            )
+            .unwrap();
+        let victim = app
+            .instantiate_contract(
+                victim_code_id,
+                user.clone(),
+                &Empty {},
+                &[],
+                "victim",
+                Some(user.to_string()),
+            )
+            .unwrap();
+        Self {
+            app,
+            user,
+            malicious_code_id,
+            victim_code_id,
+            malicious,
+            victim,
+        }
+    }
+
+    /// Executes the malicious contract by the user, reflecting specified messages.
+    fn reflect(&mut self, msgs: Vec<CosmosMsg<ClaimMsg>>) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            self.user.clone(),
+            self.malicious.clone(),
+            &MaliciousMsg::Reflect { msgs },
+            &[],
+        )
+    }
+
+    /// Returns the immediate caller of the victim contract, as seen by the victim.
+    fn victim_caller(&self, victim: &Addr) -> Addr {
+        self.app.wrap().query_wasm_smart(victim, &Empty {}).unwrap()
+    }
+
+    fn balance(&self, addr: &Addr) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+}
+
+/// Returns the message executing the victim contract with specified funds.
+fn execute_victim(victim: &Addr, funds: Vec<Coin>) -> CosmosMsg<ClaimMsg> {
+    WasmMsg::Execute {
+        contract_addr: victim.to_string(),
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds,
+    }
+    .into()
+}
+
+#[test]
+fn nested_call_should_see_the_calling_contract_as_sender() {
+    let mut suite = Suite::new();
+    assert_eq!(suite.user, suite.victim_caller(&suite.victim));
+
+    suite
+        .reflect(vec![execute_victim(&suite.victim, vec![])])
+        .unwrap();
+
+    assert_eq!(suite.malicious, suite.victim_caller(&suite.victim));
+}
+
+#[test]
+fn call_chain_should_see_the_immediate_caller_as_sender() {
+    let mut suite = Suite::new();
+    let proxy = suite
+        .app
+        .instantiate_contract(
+            suite.malicious_code_id,
+            suite.user.clone(),
+            &Empty {},
+            &[],
+            "proxy",
+            None,
+        )
+        .unwrap();
+
+    // user -> malicious -> proxy -> victim
+    let via_proxy = WasmMsg::Execute {
+        contract_addr: proxy.to_string(),
+        msg: to_json_binary(&MaliciousMsg::Reflect {
+            msgs: vec![execute_victim(&suite.victim, vec![])],
+        })
+        .unwrap(),
+        funds: vec![],
+    };
+    suite.reflect(vec![via_proxy.into()]).unwrap();
+
+    assert_eq!(proxy, suite.victim_caller(&suite.victim));
+}
+
+#[test]
+fn messages_sent_from_reply_should_have_the_contract_as_sender() {
+    let mut suite = Suite::new();
+
+    suite
+        .app
+        .execute_contract(
+            suite.user.clone(),
+            suite.malicious.clone(),
+            &MaliciousMsg::ReflectInReply {
+                msgs: vec![execute_victim(&suite.victim, vec![])],
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(suite.malicious, suite.victim_caller(&suite.victim));
+}
+
+#[test]
+fn funds_of_the_caller_should_not_be_forwarded() {
+    let mut suite = Suite::new();
+
+    // funds are always taken from the emitting contract, which has none
+    let err = suite
+        .reflect(vec![execute_victim(&suite.victim, coins(100, DENOM))])
+        .unwrap_err();
+    assert_eq!(
+        StdError::overflow(OverflowError::new(OverflowOperation::Sub)),
+        err.downcast().unwrap()
+    );
+
+    assert_eq!(100, suite.balance(&suite.user));
+    assert_eq!(0, suite.balance(&suite.victim));
+    assert_eq!(suite.user, suite.victim_caller(&suite.victim));
+}
+
+#[test]
+fn bank_transfer_from_the_caller_should_be_impossible() {
+    let mut suite = Suite::new();
+    let attacker = suite.app.api().addr_make("attacker");
+
+    // bank messages have no sender field, tokens are always sent by the emitting contract
+    let send = BankMsg::Send {
+        to_address: attacker.to_string(),
+        amount: coins(100, DENOM),
+    };
+    let err = suite.reflect(vec![send.into()]).unwrap_err();
+    assert_eq!(
+        StdError::overflow(OverflowError::new(OverflowOperation::Sub)),
+        err.downcast().unwrap()
+    );
+
+    assert_eq!(100, suite.balance(&suite.user));
+    assert_eq!(0, suite.balance(&attacker));
+}
+
+#[test]
+fn instantiated_contract_should_see_the_emitting_contract_as_creator() {
+    let mut suite = Suite::new();
+
+    let instantiate = WasmMsg::Instantiate {
+        admin: Some(suite.user.to_string()),
+        code_id: suite.victim_code_id,
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+        label: "spoofed".to_string(),
+    };
+    let res = suite.reflect(vec![instantiate.into()]).unwrap();
+
+    let instantiated = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "instantiate")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == "_contract_address")
+        .map(|attr| Addr::unchecked(&attr.value))
+        .unwrap();
+    assert_eq!(
+        suite.malicious,
+        suite.app.contract_data(&instantiated).unwrap().creator
+    );
+    assert_eq!(suite.malicious, suite.victim_caller(&instantiated));
+}
+
+#[test]
+fn admin_actions_on_foreign_contract_should_be_rejected() {
+    let mut suite = Suite::new();
+
+    let msgs: Vec<WasmMsg> = vec![
+        WasmMsg::UpdateAdmin {
+            contract_addr: suite.victim.to_string(),
+            admin: suite.malicious.to_string(),
+        },
+        WasmMsg::ClearAdmin {
+            contract_addr: suite.victim.to_string(),
+        },
+        WasmMsg::Migrate {
+            contract_addr: suite.victim.to_string(),
+            new_code_id: suite.victim_code_id,
+            msg: to_json_binary(&Empty {}).unwrap(),
+        },
+    ];
+    for msg in msgs {
+        let err = suite.reflect(vec![msg.into()]).unwrap_err();
+        let err = err.root_cause().to_string();
+        assert!(err.starts_with("Only admin"), "{}", err);
+    }
+
+    assert_eq!(
+        Some(suite.user.clone()),
+        suite.app.contract_data(&suite.victim).unwrap().admin
+    );
+}
+
+#[test]
+fn custom_message_should_not_change_the_sender() {
+    let mut suite = Suite::new();
+
+    let claim = CosmosMsg::Custom(ClaimMsg::ActAs {
+        sender: suite.user.to_string(),
+    });
+    let res = suite.reflect(vec![claim]).unwrap();
+
+    // the module receives the emitting contract as sender, whatever the message claims
+    res.assert_event(
+        &Event::new("act_as")
+            .add_attribute("sender", suite.malicious.as_str())
+            .add_attribute("claimed", suite.user.as_str()),
+    );
+}
@@ -1 +1,11 @@
+#[cfg(feature = "cosmwasm_1_3")]
+mod test_denom_metadata;
+mod test_fund_validator_rewards;
+mod test_jailing;
+mod test_module_address;
+mod test_reward_mint_events;
+mod test_rewards_clock;
+mod test_rewards_model;
+mod test_sdk_rounding;
 mod test_stake_unstake;
+mod test_staking_denom;
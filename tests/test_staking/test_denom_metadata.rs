@@ -0,0 +1,218 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Binary, Decimal, DenomMetadata, DenomUnit, Deps, Empty, Env,
+    StakingMsg, StdError, StdResult, Uint128, Validator,
+};
+use cw_multi_test::{
+    no_init, App, AppBuilder, Contract, ContractWrapper, Executor, IntoBech32, StakeKeeper,
+    StakingInfo,
+};
+
+const BONDED_DENOM: &str = "uatom"; // denominator of the staking token
+
+/// Metadata of the staking token, displayed in `ATOM` with 6 decimal places.
+fn atom_metadata() -> DenomMetadata {
+    DenomMetadata {
+        base: BONDED_DENOM.to_string(),
+        display: "atom".to_string(),
+        symbol: "ATOM".to_string(),
+        denom_units: vec![
+            DenomUnit {
+                denom: BONDED_DENOM.to_string(),
+                exponent: 0,
+                aliases: vec!["microatom".to_string()],
+            },
+            DenomUnit {
+                denom: "atom".to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+#[cw_serde]
+struct DelegationSummaryQuery {
+    delegator: String,
+    validator: String,
+}
+
+/// Returns the delegated amount formatted in the display unit of the bonded denominator.
+fn query(deps: Deps, _env: Env, msg: DelegationSummaryQuery) -> StdResult<Binary> {
+    let delegation = deps
+        .querier
+        .query_delegation(msg.delegator, msg.validator)?
+        .ok_or_else(|| StdError::generic_err("delegation not found"))?;
+    let metadata = deps
+        .querier
+        .query_denom_metadata(&delegation.amount.denom)?;
+    let unit = metadata
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == metadata.display)
+        .ok_or_else(|| StdError::generic_err("display unit not found"))?;
+    let summary = format!(
+        "{} {}",
+        format_amount(delegation.amount.amount, unit.exponent),
+        metadata.symbol
+    );
+    to_json_binary(&summary)
+}
+
+/// Formats the amount of base units as a decimal number of units with specified exponent.
+fn format_amount(amount: Uint128, exponent: u32) -> String {
+    let scale = 10u128.pow(exponent);
+    let whole = amount.u128() / scale;
+    let fraction = amount.u128() % scale;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0width$}", fraction, width = exponent as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        noop::execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+#[test]
+fn staking_denom_metadata_should_be_registered_in_bank() {
+    let app = AppBuilder::default()
+        .with_staking_denom_metadata(atom_metadata())
+        .build(no_init);
+    assert_eq!(BONDED_DENOM, app.staking_denom().unwrap());
+    assert_eq!(
+        atom_metadata(),
+        app.wrap().query_denom_metadata(BONDED_DENOM).unwrap()
+    );
+}
+
+#[test]
+fn staking_denom_metadata_should_be_registered_by_setup() {
+    let stake_keeper = StakeKeeper::new().with_denom_metadata(atom_metadata());
+    let app = AppBuilder::default()
+        .with_staking(stake_keeper)
+        .build(|router, _, storage| {
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: BONDED_DENOM.to_string(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        });
+    assert_eq!(BONDED_DENOM, app.staking_denom().unwrap());
+    assert_eq!(
+        atom_metadata(),
+        app.wrap().query_denom_metadata(BONDED_DENOM).unwrap()
+    );
+}
+
+#[test]
+fn staking_denom_metadata_of_other_denom_should_fail() {
+    let stake_keeper = StakeKeeper::new().with_denom_metadata(atom_metadata());
+    AppBuilder::default()
+        .with_staking(stake_keeper)
+        .build(|router, _, storage| {
+            let err = router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: "uosmo".to_string(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap_err();
+            assert_eq!(
+                "denom metadata of uatom does not match staking denominator uosmo",
+                err.to_string()
+            );
+        });
+}
+
+#[test]
+fn contract_should_format_delegation_using_denom_metadata() {
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+
+    let mut app = AppBuilder::default()
+        .with_staking_denom_metadata(atom_metadata())
+        .with_genesis_balances(vec![(
+            delegator_addr.clone(),
+            coins(2_000_000, BONDED_DENOM),
+        )])
+        .build(no_init);
+
+    // add a validator
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(90),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap()
+    });
+
+    // delegate 1.25 ATOM
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(1_250_000, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            delegator_addr.clone(),
+            &Empty {},
+            &[],
+            "summary",
+            None,
+        )
+        .unwrap();
+
+    let summary: String = app
+        .wrap()
+        .query_wasm_smart(
+            contract_addr,
+            &DelegationSummaryQuery {
+                delegator: delegator_addr.to_string(),
+                validator: validator_addr.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!("1.25 ATOM", summary);
+}
+
+#[test]
+fn default_staking_denom_should_have_no_metadata() {
+    let app = App::default();
+    assert_eq!(
+        DenomMetadata::default(),
+        app.wrap().query_denom_metadata("TOKEN").unwrap()
+    );
+}
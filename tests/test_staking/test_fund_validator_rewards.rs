@@ -0,0 +1,179 @@
+use cosmwasm_std::{coin, coins, Addr, Coin, Decimal, DistributionMsg, StakingMsg, Validator};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{no_init, App, AppBuilder, AppResponse, Executor, IntoBech32};
+
+const BONDED_DENOM: &str = "uatom"; // denominator of the staking token
+const YEAR: u64 = 60 * 60 * 24 * 365; // one year in seconds
+
+/// Prepares the blockchain with a single validator, two delegators staking 100 and 200 tokens
+/// and a funder of external rewards owning 300 tokens.
+fn setup() -> (App, Addr, Addr, Addr, Addr) {
+    let delegator_addr_1 = "delegator1".into_bech32();
+    let delegator_addr_2 = "delegator2".into_bech32();
+    let funder_addr = "funder".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+
+    let mut app = AppBuilder::default()
+        .with_staking_denom(BONDED_DENOM)
+        .with_genesis_balances(vec![
+            (delegator_addr_1.clone(), coins(1000, BONDED_DENOM)),
+            (delegator_addr_2.clone(), coins(1000, BONDED_DENOM)),
+            (funder_addr.clone(), coins(300, BONDED_DENOM)),
+        ])
+        .build(no_init);
+
+    // add a validator with 10% commission
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(90),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap()
+    });
+
+    // delegate with 1:2 stake
+    for (delegator_addr, amount) in [(&delegator_addr_1, 100), (&delegator_addr_2, 200)] {
+        app.execute(
+            delegator_addr.clone(),
+            StakingMsg::Delegate {
+                validator: validator_addr.to_string(),
+                amount: coin(amount, BONDED_DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+    }
+
+    (
+        app,
+        delegator_addr_1,
+        delegator_addr_2,
+        funder_addr,
+        validator_addr,
+    )
+}
+
+fn try_fund_rewards(
+    app: &mut App,
+    funder: &Addr,
+    validator: &Addr,
+    amount: Vec<Coin>,
+) -> AnyResult<AppResponse> {
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router.distribution.fund_validator_rewards(
+            api,
+            storage,
+            router,
+            &block,
+            funder,
+            validator.as_str(),
+            amount,
+        )
+    })
+}
+
+fn fund_rewards(app: &mut App, funder: &Addr, validator: &Addr, amount: Vec<Coin>) {
+    try_fund_rewards(app, funder, validator, amount).unwrap();
+}
+
+fn outstanding_rewards(app: &App, validator: &Addr) -> Vec<Coin> {
+    app.read_module(|router, _, storage| {
+        router
+            .distribution
+            .outstanding_rewards(storage, validator.as_str())
+            .unwrap()
+    })
+}
+
+fn withdraw_rewards(app: &mut App, delegator: &Addr, validator: &Addr) {
+    app.execute(
+        delegator.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn funded_rewards_should_be_split_by_stake_share() {
+    let (mut app, delegator_addr_1, delegator_addr_2, funder_addr, validator_addr) = setup();
+    assert!(outstanding_rewards(&app, &validator_addr).is_empty());
+
+    fund_rewards(
+        &mut app,
+        &funder_addr,
+        &validator_addr,
+        coins(300, BONDED_DENOM),
+    );
+    assert!(app.balances(&funder_addr).is_empty());
+    assert_eq!(
+        coins(300, BONDED_DENOM),
+        outstanding_rewards(&app, &validator_addr)
+    );
+
+    // after a year, the APR rewards accrue on top of the funded ones:
+    // 300 * 10% APR * (1 - 10% commission) = 27, split 9/18
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(YEAR);
+    });
+    let delegation = app
+        .wrap()
+        .query_delegation(delegator_addr_1.clone(), validator_addr.to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        vec![coin(109, BONDED_DENOM)],
+        delegation.accumulated_rewards
+    );
+
+    withdraw_rewards(&mut app, &delegator_addr_1, &validator_addr);
+    withdraw_rewards(&mut app, &delegator_addr_2, &validator_addr);
+    assert_eq!(
+        coins(900 + 100 + 9, BONDED_DENOM),
+        app.balances(&delegator_addr_1)
+    );
+    assert_eq!(
+        coins(800 + 200 + 18, BONDED_DENOM),
+        app.balances(&delegator_addr_2)
+    );
+    assert!(outstanding_rewards(&app, &validator_addr).is_empty());
+}
+
+#[test]
+fn funding_rewards_with_other_denom_should_fail() {
+    let (mut app, _, _, funder_addr, validator_addr) = setup();
+    let err =
+        try_fund_rewards(&mut app, &funder_addr, &validator_addr, coins(300, "uosmo")).unwrap_err();
+    assert_eq!(
+        "cannot fund rewards with coins of denominator uosmo, only of uatom",
+        err.to_string()
+    );
+}
+
+#[test]
+fn funding_rewards_without_funds_should_fail() {
+    let (mut app, _, _, funder_addr, validator_addr) = setup();
+    try_fund_rewards(
+        &mut app,
+        &funder_addr,
+        &validator_addr,
+        coins(301, BONDED_DENOM),
+    )
+    .unwrap_err();
+    assert_eq!(coins(300, BONDED_DENOM), app.balances(&funder_addr));
+    assert!(outstanding_rewards(&app, &validator_addr).is_empty());
+}
@@ -0,0 +1,179 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coin, Addr, Decimal, StakingMsg, Validator};
+use cw_multi_test::{App, AppBuilder, Executor, IntoBech32, StakingInfo, StakingSudo, SudoMsg};
+
+const BONDED_DENOM: &str = "stake"; // denominator of the staking token
+const DELEGATION_AMOUNT: u128 = 100; // amount of tokens to be delegated
+const YEAR: u64 = 60 * 60 * 24 * 365; // one year in seconds
+
+/// Prepares the blockchain with single validator and a delegator owning tokens.
+fn setup() -> (App, Addr, Addr) {
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+    let valoper = Validator::new(
+        validator_addr.to_string(),
+        Decimal::percent(10),
+        Decimal::percent(90),
+        Decimal::percent(1),
+    );
+    let block = mock_env().block;
+    let app = AppBuilder::default().build(|router, api, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &delegator_addr,
+                vec![coin(2 * DELEGATION_AMOUNT, BONDED_DENOM)],
+            )
+            .unwrap();
+        router
+            .staking
+            .setup(
+                storage,
+                StakingInfo {
+                    bonded_denom: BONDED_DENOM.to_string(),
+                    unbonding_time: 60,
+                    apr: Decimal::percent(10),
+                },
+            )
+            .unwrap();
+        router
+            .staking
+            .add_validator(api, storage, &block, valoper)
+            .unwrap();
+    });
+    (app, delegator_addr, validator_addr)
+}
+
+fn delegate(app: &mut App, delegator: &Addr, validator: &Addr) -> anyhow::Result<()> {
+    app.execute(
+        delegator.clone(),
+        StakingMsg::Delegate {
+            validator: validator.to_string(),
+            amount: coin(DELEGATION_AMOUNT, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .map(|_| ())
+}
+
+fn jail(app: &mut App, validator: &Addr) {
+    app.sudo(SudoMsg::Staking(StakingSudo::Jail {
+        validator: validator.to_string(),
+    }))
+    .unwrap();
+}
+
+fn unjail(app: &mut App, validator: &Addr) {
+    app.sudo(SudoMsg::Staking(StakingSudo::Unjail {
+        validator: validator.to_string(),
+    }))
+    .unwrap();
+}
+
+fn rewards(app: &App, delegator: &Addr, validator: &Addr) -> u128 {
+    app.wrap()
+        .query_delegation(delegator, validator)
+        .unwrap()
+        .unwrap()
+        .accumulated_rewards
+        .first()
+        .map(|reward| reward.amount.u128())
+        .unwrap_or_default()
+}
+
+fn advance_year(app: &mut App) {
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(YEAR);
+    });
+}
+
+#[test]
+fn jailed_validator_should_not_accept_delegations() {
+    let (mut app, delegator_addr, validator_addr) = setup();
+
+    jail(&mut app, &validator_addr);
+    assert!(app
+        .router()
+        .staking
+        .is_jailed(app.storage(), validator_addr.as_str())
+        .unwrap());
+    assert!(app.wrap().query_all_validators().unwrap().is_empty());
+
+    // delegating to jailed validator fails
+    let err = delegate(&mut app, &delegator_addr, &validator_addr).unwrap_err();
+    assert_eq!(
+        "validator for this address is currently jailed",
+        err.root_cause().to_string()
+    );
+
+    // jailing twice fails
+    let err = app
+        .sudo(SudoMsg::Staking(StakingSudo::Jail {
+            validator: validator_addr.to_string(),
+        }))
+        .unwrap_err();
+    assert_eq!("validator already jailed", err.to_string());
+
+    // delegating after unjailing succeeds
+    unjail(&mut app, &validator_addr);
+    assert!(!app
+        .router()
+        .staking
+        .is_jailed(app.storage(), validator_addr.as_str())
+        .unwrap());
+    assert_eq!(1, app.wrap().query_all_validators().unwrap().len());
+    delegate(&mut app, &delegator_addr, &validator_addr).unwrap();
+
+    // unjailing not jailed validator fails
+    let err = app
+        .sudo(SudoMsg::Staking(StakingSudo::Unjail {
+            validator: validator_addr.to_string(),
+        }))
+        .unwrap_err();
+    assert_eq!("validator not jailed; cannot be unjailed", err.to_string());
+}
+
+#[test]
+fn undelegating_from_jailed_validator_should_work() {
+    let (mut app, delegator_addr, validator_addr) = setup();
+    delegate(&mut app, &delegator_addr, &validator_addr).unwrap();
+    jail(&mut app, &validator_addr);
+
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(DELEGATION_AMOUNT, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert!(app
+        .wrap()
+        .query_delegation(&delegator_addr, &validator_addr)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn rewards_should_not_accrue_while_jailed() {
+    let (mut app, delegator_addr, validator_addr) = setup();
+    delegate(&mut app, &delegator_addr, &validator_addr).unwrap();
+
+    // a year accrues 100 * 10% - 10% commission = 9 tokens
+    advance_year(&mut app);
+    assert_eq!(9, rewards(&app, &delegator_addr, &validator_addr));
+
+    // no rewards accrue while jailed
+    jail(&mut app, &validator_addr);
+    advance_year(&mut app);
+    assert_eq!(9, rewards(&app, &delegator_addr, &validator_addr));
+
+    // rewards accrue again after unjailing
+    unjail(&mut app, &validator_addr);
+    assert_eq!(9, rewards(&app, &delegator_addr, &validator_addr));
+    advance_year(&mut app);
+    assert_eq!(18, rewards(&app, &delegator_addr, &validator_addr));
+}
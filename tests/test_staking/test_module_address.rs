@@ -0,0 +1,129 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coin, Addr, Api, Decimal, StakingMsg, Validator};
+use cw_multi_test::{
+    module_address, no_init, App, AppBuilder, Executor, IntoBech32, StakeKeeper, StakingInfo,
+};
+
+const BONDED_DENOM: &str = "stake";
+const UNBONDING_TIME: u64 = 60;
+const AMOUNT: u128 = 100;
+
+/// Creates the application with a validator and delegator's balance initialized.
+fn setup(staking: StakeKeeper) -> (App, Addr, Addr) {
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+    let valoper = Validator::new(
+        validator_addr.to_string(),
+        Decimal::percent(10),
+        Decimal::percent(90),
+        Decimal::percent(1),
+    );
+    let block = mock_env().block;
+    let app = AppBuilder::default()
+        .with_staking(staking)
+        .build(|router, api, storage| {
+            router
+                .bank
+                .init_balance(storage, &delegator_addr, vec![coin(AMOUNT, BONDED_DENOM)])
+                .unwrap();
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: BONDED_DENOM.to_string(),
+                        unbonding_time: UNBONDING_TIME,
+                        apr: Decimal::percent(10),
+                    },
+                )
+                .unwrap();
+            router
+                .staking
+                .add_validator(api, storage, &block, valoper)
+                .unwrap();
+        });
+    (app, delegator_addr, validator_addr)
+}
+
+fn balance(app: &App, address: &Addr) -> u128 {
+    app.wrap()
+        .query_balance(address, BONDED_DENOM)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn default_module_address_should_be_valid() {
+    let app = AppBuilder::default().build(no_init);
+    let module_addr = StakeKeeper::new().module_address(app.api()).unwrap();
+
+    // the address is a valid bech32 address with the chain prefix
+    assert!(module_addr.as_str().starts_with("cosmwasm1"));
+    app.api().addr_validate(module_addr.as_str()).unwrap();
+    app.api().addr_canonicalize(module_addr.as_str()).unwrap();
+
+    // the address is deterministic
+    assert_eq!(
+        module_addr,
+        StakeKeeper::new().module_address(app.api()).unwrap()
+    );
+    assert_eq!(
+        module_addr,
+        module_address(app.api(), "bonded_tokens_pool").unwrap()
+    );
+}
+
+#[test]
+fn staked_tokens_should_be_held_by_module_address() {
+    let (mut app, delegator_addr, validator_addr) = setup(StakeKeeper::new());
+    let module_addr = StakeKeeper::new().module_address(app.api()).unwrap();
+
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(AMOUNT, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(0, balance(&app, &delegator_addr));
+    assert_eq!(AMOUNT, balance(&app, &module_addr));
+
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(AMOUNT, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(UNBONDING_TIME);
+        block.height += 1;
+    });
+
+    // unbonded tokens are paid out from the module address
+    assert_eq!(AMOUNT, balance(&app, &delegator_addr));
+    assert_eq!(0, balance(&app, &module_addr));
+}
+
+#[test]
+fn custom_module_address_should_work() {
+    let custom_addr = "custom-staking-module".into_bech32();
+    let staking = StakeKeeper::new().with_module_address(custom_addr.clone());
+    let (mut app, delegator_addr, validator_addr) = setup(staking);
+
+    app.execute(
+        delegator_addr,
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(AMOUNT, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(AMOUNT, balance(&app, &custom_addr));
+}
@@ -0,0 +1,130 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coin, Addr, Decimal, DistributionMsg, Event, StakingMsg, Validator};
+use cw_multi_test::{
+    module_address, App, AppBuilder, BankKeeper, BankTransfers, Executor, IntoBech32, StakingInfo,
+    TransferRecord,
+};
+
+const BONDED_DENOM: &str = "stake";
+
+/// Creates the application with a delegation to a single validator.
+fn setup(bank: BankKeeper) -> (App, Addr, Addr) {
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+    let valoper = Validator::new(
+        validator_addr.to_string(),
+        Decimal::percent(10),
+        Decimal::percent(90),
+        Decimal::percent(1),
+    );
+    let block = mock_env().block;
+    let mut app = AppBuilder::default()
+        .with_bank(bank)
+        .build(|router, api, storage| {
+            router
+                .bank
+                .init_balance(storage, &delegator_addr, vec![coin(100, BONDED_DENOM)])
+                .unwrap();
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: BONDED_DENOM.to_string(),
+                        unbonding_time: 60,
+                        apr: Decimal::percent(10),
+                    },
+                )
+                .unwrap();
+            router
+                .staking
+                .add_validator(api, storage, &block, valoper)
+                .unwrap();
+        });
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    // one year, 10% apr, 10% commission, 100 tokens staked
+    app.update_block(|block| block.time = block.time.plus_seconds(60 * 60 * 24 * 365));
+    (app, delegator_addr, validator_addr)
+}
+
+#[test]
+fn withdrawing_rewards_should_emit_mint_events() {
+    let (mut app, delegator, validator) = setup(BankKeeper::new());
+    let minter = module_address(app.api(), "mint").unwrap();
+
+    let res = app
+        .execute(
+            delegator.clone(),
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: validator.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            Event::new("coin_received")
+                .add_attribute("receiver", &delegator)
+                .add_attribute("amount", "9stake"),
+            Event::new("coinbase")
+                .add_attribute("minter", minter.as_str())
+                .add_attribute("amount", "9stake"),
+            Event::new("withdraw_delegator_reward")
+                .add_attribute("validator", &validator)
+                .add_attribute("sender", &delegator)
+                .add_attribute("amount", "9stake"),
+        ],
+        res.events
+    );
+}
+
+#[test]
+fn withdrawing_rewards_should_report_custom_minter() {
+    let minter = "custom-minter".into_bech32();
+    let (mut app, delegator, validator) = setup(BankKeeper::new().with_minter(minter.clone()));
+
+    let res = app
+        .execute(
+            delegator,
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: validator.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    let coinbase = res.events.iter().find(|e| e.ty == "coinbase").unwrap();
+    assert_eq!(
+        &Event::new("coinbase")
+            .add_attribute("minter", minter.as_str())
+            .add_attribute("amount", "9stake"),
+        coinbase
+    );
+}
+
+#[test]
+fn withdrawn_rewards_should_be_reported_as_mint() {
+    let (mut app, delegator, validator) = setup(BankKeeper::new());
+
+    let res = app
+        .execute(
+            delegator.clone(),
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: validator.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+
+    BankTransfers::from(&res)
+        .assert_exact(&[TransferRecord::mint(&delegator, &[coin(9, BONDED_DENOM)])]);
+}
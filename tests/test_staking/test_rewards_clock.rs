@@ -0,0 +1,105 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coin, Decimal, StakingMsg, Validator};
+use cw_multi_test::{
+    no_init, AppBuilder, Executor, IntoBech32, RewardsClock, StakeKeeper, StakingInfo, StakingSudo,
+    SudoMsg,
+};
+
+const BONDED_DENOM: &str = "stake"; // denominator of the staking token
+const DELEGATION_AMOUNT: u128 = 100; // amount of tokens to be delegated
+const YEAR: u64 = 60 * 60 * 24 * 365; // one year in seconds
+
+#[test]
+fn manual_rewards_clock_should_work() {
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+
+    let valoper = Validator::new(
+        validator_addr.to_string(),
+        Decimal::percent(10),
+        Decimal::percent(90),
+        Decimal::percent(1),
+    );
+
+    // prepare the blockchain with manual rewards clock
+    let block = mock_env().block;
+    let mut app = AppBuilder::default()
+        .with_staking(StakeKeeper::new().with_rewards_clock(RewardsClock::Manual))
+        .build(|router, api, storage| {
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &delegator_addr,
+                    vec![coin(DELEGATION_AMOUNT, BONDED_DENOM)],
+                )
+                .unwrap();
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: BONDED_DENOM.to_string(),
+                        unbonding_time: 60,
+                        apr: Decimal::percent(10),
+                    },
+                )
+                .unwrap();
+            router
+                .staking
+                .add_validator(api, storage, &block, valoper)
+                .unwrap();
+        });
+
+    // delegate tokens to validator
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(DELEGATION_AMOUNT, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // advancing 1000 blocks (and a year of block time) accrues no rewards
+    app.update_block(|block| {
+        block.height += 1000;
+        block.time = block.time.plus_seconds(YEAR);
+    });
+    let delegation = app
+        .wrap()
+        .query_delegation(delegator_addr.clone(), validator_addr.clone())
+        .unwrap()
+        .unwrap();
+    assert!(delegation.accumulated_rewards.is_empty());
+
+    // advancing the rewards clock by a year accrues 100 * 10% - 10% commission = 9 tokens,
+    // block is not changed
+    let height = app.block_info().height;
+    app.sudo(SudoMsg::Staking(StakingSudo::AdvanceRewardsClock {
+        seconds: YEAR,
+    }))
+    .unwrap();
+    assert_eq!(height, app.block_info().height);
+    let delegation = app
+        .wrap()
+        .query_delegation(delegator_addr, validator_addr)
+        .unwrap()
+        .unwrap();
+    assert_eq!(vec![coin(9, BONDED_DENOM)], delegation.accumulated_rewards);
+}
+
+#[test]
+fn advancing_rewards_clock_should_fail_for_block_time_clock() {
+    let mut app = AppBuilder::default().build(no_init);
+
+    assert_eq!(
+        "rewards clock is not manual",
+        app.sudo(SudoMsg::Staking(StakingSudo::AdvanceRewardsClock {
+            seconds: YEAR,
+        }))
+        .unwrap_err()
+        .to_string()
+    );
+}
@@ -0,0 +1,147 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coin, Addr, Decimal, DistributionMsg, StakingMsg, Uint128, Validator};
+use cw_multi_test::{
+    App, AppBuilder, Executor, IntoBech32, RewardsModel, StakeKeeper, StakingInfo,
+};
+
+const BONDED_DENOM: &str = "stake"; // denominator of the staking token
+const YEAR: u64 = 60 * 60 * 24 * 365; // one year in seconds
+
+/// Prepares the blockchain with a single validator (10% commission)
+/// and two delegators, delegating 100 and 300 tokens.
+fn setup(rewards_model: RewardsModel) -> (App, Addr, Addr, Addr) {
+    let delegator_addr_1 = "delegator1".into_bech32();
+    let delegator_addr_2 = "delegator2".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+
+    let valoper = Validator::new(
+        validator_addr.to_string(),
+        Decimal::percent(10),
+        Decimal::percent(90),
+        Decimal::percent(1),
+    );
+
+    let block = mock_env().block;
+    let stake_keeper = StakeKeeper::new().with_rewards_model(rewards_model);
+    let mut app = AppBuilder::default()
+        .with_staking(stake_keeper)
+        .build(|router, api, storage| {
+            router
+                .bank
+                .init_balance(storage, &delegator_addr_1, vec![coin(100, BONDED_DENOM)])
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &delegator_addr_2, vec![coin(300, BONDED_DENOM)])
+                .unwrap();
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: BONDED_DENOM.to_string(),
+                        unbonding_time: 60,
+                        apr: Decimal::percent(10),
+                    },
+                )
+                .unwrap();
+            router
+                .staking
+                .add_validator(api, storage, &block, valoper)
+                .unwrap();
+        });
+
+    for (delegator_addr, amount) in [(&delegator_addr_1, 100), (&delegator_addr_2, 300)] {
+        app.execute(
+            delegator_addr.clone(),
+            StakingMsg::Delegate {
+                validator: validator_addr.to_string(),
+                amount: coin(amount, BONDED_DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+    }
+
+    (app, validator_addr, delegator_addr_1, delegator_addr_2)
+}
+
+/// Advances the chain by 100 blocks and a year of block time,
+/// withdraws rewards of both delegators and returns their balances.
+fn run_scenario(rewards_model: RewardsModel) -> (u128, u128) {
+    let (mut app, validator_addr, delegator_addr_1, delegator_addr_2) = setup(rewards_model);
+
+    app.update_block(|block| {
+        block.height += 100;
+        block.time = block.time.plus_seconds(YEAR);
+    });
+
+    let mut balances = vec![];
+    for delegator_addr in [delegator_addr_1, delegator_addr_2] {
+        app.execute(
+            delegator_addr.clone(),
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: validator_addr.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+        balances.push(
+            app.wrap()
+                .query_balance(delegator_addr, BONDED_DENOM)
+                .unwrap()
+                .amount
+                .u128(),
+        );
+    }
+    (balances[0], balances[1])
+}
+
+#[test]
+fn apr_rewards_model_should_work() {
+    // 400 * 10% - 10% commission = 36 tokens, split 1:3
+    assert_eq!(
+        (9, 27),
+        run_scenario(RewardsModel::Apr(Decimal::percent(10)))
+    );
+}
+
+#[test]
+fn per_block_rewards_model_should_work() {
+    // 100 blocks * 2 tokens - 10% commission = 180 tokens, split 1:3
+    assert_eq!(
+        (45, 135),
+        run_scenario(RewardsModel::PerBlock {
+            amount_per_block: Uint128::new(2)
+        })
+    );
+}
+
+#[test]
+fn per_block_rewards_should_not_depend_on_block_time() {
+    let (mut app, validator_addr, delegator_addr, _) = setup(RewardsModel::PerBlock {
+        amount_per_block: Uint128::new(10),
+    });
+
+    // time passes, but no blocks are produced
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(YEAR);
+    });
+    let delegation = app
+        .wrap()
+        .query_delegation(delegator_addr.clone(), validator_addr.clone())
+        .unwrap()
+        .unwrap();
+    assert!(delegation.accumulated_rewards.is_empty());
+
+    // 10 blocks * 10 tokens - 10% commission = 90 tokens, a quarter for this delegator
+    app.update_block(|block| {
+        block.height += 10;
+    });
+    let delegation = app
+        .wrap()
+        .query_delegation(delegator_addr, validator_addr)
+        .unwrap()
+        .unwrap();
+    assert_eq!(vec![coin(22, BONDED_DENOM)], delegation.accumulated_rewards);
+}
@@ -0,0 +1,164 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coin, Addr, Decimal, DistributionMsg, StakingMsg, Uint128, Validator};
+use cw_multi_test::{
+    App, AppBuilder, Executor, IntoBech32, RewardsModel, StakeKeeper, StakingInfo,
+};
+
+const BONDED_DENOM: &str = "stake"; // denominator of the staking token
+
+// The expected values below are derived by applying the formulas of the F1 fee distribution
+// (truncated reward ratio per period, truncated delegator rewards, truncated withdrawal)
+// to each scenario, they were not captured from a running chain.
+
+/// Prepares the blockchain with a single validator without commission, accruing 10 tokens
+/// per block, and delegators funded with the given amounts of staking tokens.
+fn setup(sdk_rounding: bool, delegators: &[(&Addr, u128)]) -> (App, Addr) {
+    let validator_addr = "valoper".into_bech32();
+    let valoper = Validator::new(
+        validator_addr.to_string(),
+        Decimal::zero(),
+        Decimal::percent(90),
+        Decimal::percent(1),
+    );
+    let block = mock_env().block;
+    let stake_keeper = StakeKeeper::new()
+        .with_rewards_model(RewardsModel::PerBlock {
+            amount_per_block: Uint128::new(10),
+        })
+        .with_sdk_rounding(sdk_rounding);
+    let app = AppBuilder::default()
+        .with_staking(stake_keeper)
+        .build(|router, api, storage| {
+            for (delegator_addr, amount) in delegators {
+                router
+                    .bank
+                    .init_balance(storage, delegator_addr, vec![coin(*amount, BONDED_DENOM)])
+                    .unwrap();
+            }
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: BONDED_DENOM.to_string(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            router
+                .staking
+                .add_validator(api, storage, &block, valoper)
+                .unwrap();
+        });
+    (app, validator_addr)
+}
+
+fn delegate(app: &mut App, delegator_addr: &Addr, validator_addr: &Addr, amount: u128) {
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(amount, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+/// Withdraws the rewards of the delegator and returns its balance.
+fn withdraw(app: &mut App, delegator_addr: &Addr, validator_addr: &Addr) -> u128 {
+    app.execute(
+        delegator_addr.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.wrap()
+        .query_balance(delegator_addr, BONDED_DENOM)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+/// Single delegator staking 3 tokens, a single block accruing 10 tokens.
+fn single_delegator(sdk_rounding: bool) -> (u128, u128) {
+    let delegator_addr = "delegator".into_bech32();
+    let (mut app, validator_addr) = setup(sdk_rounding, &[(&delegator_addr, 3)]);
+    delegate(&mut app, &delegator_addr, &validator_addr, 3);
+    app.update_block(|block| block.height += 1);
+
+    let queried = app
+        .wrap()
+        .query_delegation(delegator_addr.clone(), validator_addr.clone())
+        .unwrap()
+        .unwrap()
+        .accumulated_rewards[0]
+        .amount
+        .u128();
+    (
+        queried,
+        withdraw(&mut app, &delegator_addr, &validator_addr),
+    )
+}
+
+/// Two delegators staking 3 tokens each, the second one delegates a block later,
+/// then another block accrues 10 tokens.
+fn two_delegators(sdk_rounding: bool) -> (u128, u128) {
+    let delegator_addr_1 = "delegator1".into_bech32();
+    let delegator_addr_2 = "delegator2".into_bech32();
+    let (mut app, validator_addr) = setup(
+        sdk_rounding,
+        &[(&delegator_addr_1, 3), (&delegator_addr_2, 3)],
+    );
+    delegate(&mut app, &delegator_addr_1, &validator_addr, 3);
+    app.update_block(|block| block.height += 1);
+    delegate(&mut app, &delegator_addr_2, &validator_addr, 3);
+    app.update_block(|block| block.height += 1);
+
+    (
+        withdraw(&mut app, &delegator_addr_1, &validator_addr),
+        withdraw(&mut app, &delegator_addr_2, &validator_addr),
+    )
+}
+
+#[test]
+fn single_delegator_rewards_should_be_truncated_like_in_sdk() {
+    // ratio: 10 / 3 = 3.333333333333333333
+    // rewards: 3 * 3.333333333333333333 = 9.999999999999999999 => 9
+    assert_eq!((9, 9), single_delegator(true));
+    // default model: 10 * 3 / 3 = 10
+    assert_eq!((10, 10), single_delegator(false));
+}
+
+#[test]
+fn two_delegators_rewards_should_be_truncated_like_in_sdk() {
+    // ratio after the first block: 10 / 3 = 3.333333333333333333
+    // ratio after the second block: 3.333333333333333333 + 10 / 6 = 4.999999999999999999
+    // first delegator: 3 * 4.999999999999999999 = 14.999999999999999997 => 14
+    // second delegator: 3 * (4.999999999999999999 - 3.333333333333333333) => 4
+    assert_eq!((14, 4), two_delegators(true));
+    // default model: 10 + 5 and 5
+    assert_eq!((15, 5), two_delegators(false));
+}
+
+#[test]
+fn withdrawn_rewards_should_start_from_the_current_ratio() {
+    let delegator_addr = "delegator".into_bech32();
+    let (mut app, validator_addr) = setup(true, &[(&delegator_addr, 3)]);
+    delegate(&mut app, &delegator_addr, &validator_addr, 3);
+
+    // each withdrawal truncates the rewards accrued in a single block
+    for expected in [9, 18, 27] {
+        app.update_block(|block| block.height += 1);
+        assert_eq!(
+            expected,
+            withdraw(&mut app, &delegator_addr, &validator_addr)
+        );
+    }
+
+    // rewards of the next 3 blocks are settled at once: 3 * (30 / 3) = 30
+    app.update_block(|block| block.height += 3);
+    assert_eq!(57, withdraw(&mut app, &delegator_addr, &validator_addr));
+}
@@ -0,0 +1,141 @@
+use cosmwasm_std::{coin, coins, Decimal, DistributionMsg, StakingMsg, Validator};
+use cw_multi_test::{no_init, App, AppBuilder, Executor, IntoBech32};
+
+const BONDED_DENOM: &str = "uatom"; // denominator of the staking token
+const UNBONDING_TIME: u64 = 60; // default unbonding time (in seconds)
+const YEAR: u64 = 60 * 60 * 24 * 365; // one year in seconds
+
+fn balance(app: &App, addr: &cosmwasm_std::Addr) -> u128 {
+    app.wrap()
+        .query_balance(addr, BONDED_DENOM)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+#[test]
+fn default_staking_denom_should_work() {
+    let app = App::default();
+    assert_eq!("TOKEN", app.staking_denom().unwrap());
+}
+
+#[test]
+fn staking_denom_should_be_set_by_builder() {
+    let app = AppBuilder::default()
+        .with_staking_denom(BONDED_DENOM)
+        .build(no_init);
+    assert_eq!(BONDED_DENOM, app.staking_denom().unwrap());
+    assert_eq!(BONDED_DENOM, app.wrap().query_bonded_denom().unwrap());
+}
+
+#[test]
+fn full_staking_cycle_should_work_with_builder_denom() {
+    let delegator_addr = "delegator".into_bech32();
+    let validator_addr = "valoper".into_bech32();
+
+    let mut app = AppBuilder::default()
+        .with_staking_denom(BONDED_DENOM)
+        .with_genesis_balances(vec![(delegator_addr.clone(), coins(1000, BONDED_DENOM))])
+        .build(no_init);
+
+    // add a validator with 10% commission
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(90),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap()
+    });
+
+    // delegating in the denominator set by the builder works
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(900, balance(&app, &delegator_addr));
+
+    // after a year, rewards are accrued in the same denominator: 100 * 10% APR * (1 - 10% commission)
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(YEAR);
+    });
+    let delegation = app
+        .wrap()
+        .query_delegation(delegator_addr.clone(), validator_addr.to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(vec![coin(9, BONDED_DENOM)], delegation.accumulated_rewards);
+
+    // withdrawing rewards pays them out in the same denominator
+    app.execute(
+        delegator_addr.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(909, balance(&app, &delegator_addr));
+
+    // undelegated tokens are paid back in the same denominator after the unbonding time
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, BONDED_DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(UNBONDING_TIME);
+    });
+    assert_eq!(1009, balance(&app, &delegator_addr));
+    assert_eq!(
+        0,
+        app.wrap()
+            .query_balance(&delegator_addr, "TOKEN")
+            .unwrap()
+            .amount
+            .u128()
+    );
+}
+
+#[test]
+fn delegating_other_denom_should_fail() {
+    let delegator_addr = "delegator".into_bech32();
+    let mut app = AppBuilder::default()
+        .with_staking_denom(BONDED_DENOM)
+        .with_genesis_balances(vec![(delegator_addr.clone(), coins(1000, "TOKEN"))])
+        .build(no_init);
+    let err = app
+        .execute(
+            delegator_addr,
+            StakingMsg::Delegate {
+                validator: "valoper".into_bech32().to_string(),
+                amount: coin(100, "TOKEN"),
+            }
+            .into(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        "cannot delegate coins of denominator TOKEN, only of uatom",
+        err.root_cause().to_string()
+    );
+}
@@ -1,3 +1,22 @@
+mod test_call_depth;
+mod test_call_stats;
+mod test_contract_history;
+mod test_deterministic_addr_gen;
+mod test_error_ordering;
+mod test_instantiate2_addr_gen;
+mod test_label_validation;
+mod test_migrate_events;
+mod test_pending_state;
+mod test_permissions;
+mod test_query_depth;
+mod test_query_errors;
+mod test_query_storage_writes;
+mod test_reentrancy_guard;
+mod test_reply_data;
+mod test_response_limits;
+mod test_self_contract_info;
+mod test_self_migration;
+mod test_storage_access_stats;
 mod test_with_addr_gen;
 #[cfg(feature = "cosmwasm_1_2")]
 mod test_with_checksum_gen;
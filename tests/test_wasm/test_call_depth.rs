@@ -0,0 +1,155 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, WasmMsg,
+};
+use cw_multi_test::error::{AnyResult, Error};
+use cw_multi_test::{
+    no_init, App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor, WasmKeeper,
+};
+use std::thread;
+
+/// Default maximum depth of nested contract calls.
+const MAX_CALL_DEPTH: u32 = 500;
+
+/// Calls itself recursively, until the received depth is zero.
+fn execute(_deps: DepsMut, env: Env, _info: MessageInfo, depth: u32) -> StdResult<Response> {
+    let mut response = Response::new();
+    if depth > 0 {
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&(depth - 1))?,
+            funds: vec![],
+        });
+    }
+    Ok(response)
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+/// Executes the recursive contract, resulting in `depth + 1` nested contract calls.
+fn execute_recursively(app: &mut App, depth: u32) -> AnyResult<AppResponse> {
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "recursive",
+            None,
+        )
+        .unwrap();
+    app.execute_contract(owner_addr, contract_addr, &depth, &[])
+}
+
+/// Runs the test in a thread with a large stack, deep recursion does not fit into the default one.
+fn with_large_stack(test: impl FnOnce() + Send + 'static) {
+    thread::Builder::new()
+        .stack_size(512 * 1024 * 1024)
+        .spawn(test)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn default_max_call_depth_should_be_enforced() {
+    with_large_stack(|| {
+        let mut app = App::default();
+        execute_recursively(&mut app, MAX_CALL_DEPTH - 1).unwrap();
+
+        let mut app = App::default();
+        let err = execute_recursively(&mut app, MAX_CALL_DEPTH).unwrap_err();
+        let message = err.root_cause().to_string();
+        assert!(message.starts_with(
+            "max call depth exceeded: depth 501 reached with the limit of 500, call chain: "
+        ));
+    });
+}
+
+#[test]
+fn max_call_depth_should_report_call_chain() {
+    let mut app = AppBuilder::default().with_max_call_depth(2).build(no_init);
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "recursive",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(owner_addr.clone(), contract_addr.clone(), &1, &[])
+        .unwrap();
+
+    let err = app
+        .execute_contract(owner_addr, contract_addr.clone(), &2, &[])
+        .unwrap_err();
+    assert_eq!(
+        Error::max_call_depth_exceeded(
+            3,
+            2,
+            format!("{contract_addr} -> {contract_addr} -> {contract_addr}")
+        )
+        .to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn increasing_max_call_depth_should_allow_deeper_recursion() {
+    with_large_stack(|| {
+        let mut app = AppBuilder::default()
+            .with_max_call_depth(600)
+            .build(no_init);
+        execute_recursively(&mut app, MAX_CALL_DEPTH).unwrap();
+    });
+}
+
+#[test]
+fn max_submessages_should_be_enforced() {
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_max_submessages(Some(5)))
+        .build(no_init);
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "recursive",
+            None,
+        )
+        .unwrap();
+
+    // the counter of submessages is reset for every top-level call
+    for _ in 0..2 {
+        app.execute_contract(owner_addr.clone(), contract_addr.clone(), &5, &[])
+            .unwrap();
+    }
+
+    let err = app
+        .execute_contract(owner_addr, contract_addr, &6, &[])
+        .unwrap_err();
+    assert_eq!(
+        Error::too_many_submessages(5).to_string(),
+        err.root_cause().to_string()
+    );
+}
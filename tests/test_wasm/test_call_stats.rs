@@ -0,0 +1,108 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{
+    to_json_binary, Addr, DepsMut, Empty, Env, MessageInfo, Response, StdResult, SubMsg, WasmMsg,
+};
+use cw_multi_test::{no_init, App, AppBuilder, Contract, ContractWrapper, Executor, IntoAddr};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecMsg {
+    /// Queries the peer contract and executes it in a submessage.
+    Forward { peer: String },
+    /// Does nothing.
+    Noop {},
+}
+
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    match msg {
+        ExecMsg::Forward { peer } => {
+            deps.querier
+                .query_wasm_smart::<Empty>(peer.clone(), &Empty {})?;
+            let msg = WasmMsg::Execute {
+                contract_addr: peer,
+                msg: to_json_binary(&ExecMsg::Noop {})?,
+                funds: vec![],
+            };
+            Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, 1)))
+        }
+        ExecMsg::Noop {} => Ok(Response::default()),
+    }
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(execute, noop::instantiate, noop::query)
+            .with_reply(noop::reply)
+            .with_sudo(noop::sudo),
+    )
+}
+
+/// Instantiates two contracts, returns their addresses.
+fn instantiate_contracts(app: &mut App) -> (Addr, Addr) {
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let first = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+    let second = app
+        .instantiate_contract(code_id, owner, &Empty {}, &[], "second", None)
+        .unwrap();
+    (first, second)
+}
+
+#[test]
+fn call_stats_should_count_nested_calls_and_queries() {
+    let mut app = AppBuilder::default().with_call_stats(true).build(no_init);
+    let (first, second) = instantiate_contracts(&mut app);
+
+    // first contract queries and executes the second one, receives the reply
+    let sender = "sender".into_addr();
+    let msg = ExecMsg::Forward {
+        peer: second.to_string(),
+    };
+    app.execute_contract(sender, first.clone(), &msg, &[])
+        .unwrap();
+    app.wrap()
+        .query_wasm_smart::<Empty>(first.clone(), &Empty {})
+        .unwrap();
+    app.wasm_sudo(second.clone(), &Empty {}).unwrap();
+
+    let stats = app.call_stats();
+    assert_eq!(1, stats.instantiations(&first));
+    assert_eq!(1, stats.instantiations(&second));
+    assert_eq!(1, stats.executes(&first));
+    assert_eq!(1, stats.executes(&second));
+    assert_eq!(1, stats.queries(&first));
+    assert_eq!(1, stats.queries(&second));
+    assert_eq!(1, stats.replies(&first));
+    assert_eq!(0, stats.replies(&second));
+    assert_eq!(0, stats.sudos(&first));
+    assert_eq!(1, stats.sudos(&second));
+    assert_eq!(0, stats.migrations(&first));
+    assert_eq!(2, stats.total_instantiations());
+    assert_eq!(2, stats.total_executes());
+    assert_eq!(2, stats.total_queries());
+    assert_eq!(1, stats.total_replies());
+    assert_eq!(1, stats.total_sudos());
+    assert_eq!(0, stats.total_migrations());
+
+    // statistics are cleared after reset
+    app.reset_call_stats();
+    assert!(app.call_stats().is_empty());
+    app.wrap()
+        .query_wasm_smart::<Empty>(second.clone(), &Empty {})
+        .unwrap();
+    assert_eq!(1, app.call_stats().total_queries());
+    assert_eq!(1, app.call_stats().queries(&second));
+}
+
+#[test]
+fn call_stats_should_be_empty_when_disabled() {
+    let mut app = App::default();
+    let (first, _) = instantiate_contracts(&mut app);
+    app.wrap()
+        .query_wasm_smart::<Empty>(first, &Empty {})
+        .unwrap();
+    assert!(app.call_stats().is_empty());
+}
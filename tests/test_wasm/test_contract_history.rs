@@ -0,0 +1,176 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw_multi_test::{
+    no_init, App, AppBuilder, Contract, ContractHistoryEntry, ContractHistoryOperation,
+    ContractWrapper, Executor, WasmKeeper,
+};
+
+#[cw_serde]
+struct InitMsg {
+    version: u64,
+}
+
+#[cw_serde]
+struct MigrateMsg {
+    version: u64,
+}
+
+fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InitMsg,
+) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&Empty {})
+}
+
+fn migrate(_deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    if msg.version == 0 {
+        return Err(StdError::generic_err("invalid version"));
+    }
+    Ok(Response::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_migrate(migrate))
+}
+
+fn app_with_contract_history() -> App {
+    AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_contract_history(true))
+        .build(no_init)
+}
+
+fn entry(
+    operation: ContractHistoryOperation,
+    code_id: u64,
+    msg: Binary,
+    height: u64,
+) -> ContractHistoryEntry {
+    ContractHistoryEntry {
+        operation,
+        code_id,
+        msg,
+        height,
+    }
+}
+
+#[test]
+fn contract_history_should_be_recorded() {
+    let mut app = app_with_contract_history();
+    let creator = app.api().addr_make("creator");
+    let code_id_1 = app.store_code(contract());
+    let code_id_2 = app.store_code(contract());
+    let code_id_3 = app.store_code(contract());
+    let init_msg = InitMsg { version: 1 };
+    let migrate_msg_2 = MigrateMsg { version: 2 };
+    let migrate_msg_3 = MigrateMsg { version: 3 };
+
+    let init_height = app.block_info().height;
+    let contract_addr = app
+        .instantiate_contract(
+            code_id_1,
+            creator.clone(),
+            &init_msg,
+            &[],
+            "history",
+            Some(creator.to_string()),
+        )
+        .unwrap();
+
+    app.update_block(|block| block.height += 1);
+    app.migrate_contract(
+        creator.clone(),
+        contract_addr.clone(),
+        &migrate_msg_2,
+        code_id_2,
+    )
+    .unwrap();
+
+    app.update_block(|block| block.height += 1);
+    app.migrate_contract(creator, contract_addr.clone(), &migrate_msg_3, code_id_3)
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            entry(
+                ContractHistoryOperation::Init,
+                code_id_1,
+                to_json_binary(&init_msg).unwrap(),
+                init_height
+            ),
+            entry(
+                ContractHistoryOperation::Migrate,
+                code_id_2,
+                to_json_binary(&migrate_msg_2).unwrap(),
+                init_height + 1
+            ),
+            entry(
+                ContractHistoryOperation::Migrate,
+                code_id_3,
+                to_json_binary(&migrate_msg_3).unwrap(),
+                init_height + 2
+            ),
+        ],
+        app.contract_history(&contract_addr).unwrap()
+    );
+}
+
+#[test]
+fn failed_migration_should_not_be_recorded() {
+    let mut app = app_with_contract_history();
+    let creator = app.api().addr_make("creator");
+    let code_id_1 = app.store_code(contract());
+    let code_id_2 = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id_1,
+            creator.clone(),
+            &InitMsg { version: 1 },
+            &[],
+            "history",
+            Some(creator.to_string()),
+        )
+        .unwrap();
+
+    app.migrate_contract(
+        creator,
+        contract_addr.clone(),
+        &MigrateMsg { version: 0 },
+        code_id_2,
+    )
+    .unwrap_err();
+
+    let history = app.contract_history(&contract_addr).unwrap();
+    assert_eq!(1, history.len());
+    assert_eq!(ContractHistoryOperation::Init, history[0].operation);
+}
+
+#[test]
+fn contract_history_should_be_empty_when_disabled() {
+    let mut app = App::default();
+    let creator = app.api().addr_make("creator");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            creator,
+            &InitMsg { version: 1 },
+            &[],
+            "history",
+            None,
+        )
+        .unwrap();
+
+    assert!(app.contract_history(&contract_addr).unwrap().is_empty());
+}
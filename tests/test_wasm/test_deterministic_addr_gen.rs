@@ -0,0 +1,122 @@
+use cosmwasm_std::{Addr, Api, Empty};
+use cw_multi_test::{
+    instantiate_address, no_init, AddressGenerator, App, AppBuilder, DeterministicAddressGenerator,
+    Executor, SimpleAddressGenerator, WasmKeeper,
+};
+
+use crate::test_contracts;
+
+/// Creates the application using the specified address generator, with two stored contract codes.
+fn setup(address_generator: impl AddressGenerator + 'static) -> (App, u64, u64) {
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_address_generator(address_generator))
+        .build(no_init);
+    let code_id_1 = app.store_code(test_contracts::counter::contract());
+    let code_id_2 = app.store_code(test_contracts::counter::contract());
+    (app, code_id_1, code_id_2)
+}
+
+/// Instantiates contracts from the two codes in specified order,
+/// returns the contract addresses in the order of code identifiers.
+fn instantiate_in_order(
+    address_generator: impl AddressGenerator + 'static,
+    reversed: bool,
+) -> (Addr, Addr) {
+    let (mut app, code_id_1, code_id_2) = setup(address_generator);
+    let creator = app.api().addr_make("creator");
+    let mut instantiate = |code_id| {
+        app.instantiate_contract(code_id, creator.clone(), &Empty {}, &[], "counter", None)
+            .unwrap()
+    };
+    if reversed {
+        let addr_2 = instantiate(code_id_2);
+        let addr_1 = instantiate(code_id_1);
+        (addr_1, addr_2)
+    } else {
+        let addr_1 = instantiate(code_id_1);
+        let addr_2 = instantiate(code_id_2);
+        (addr_1, addr_2)
+    }
+}
+
+#[test]
+fn deterministic_addresses_should_not_depend_on_instantiation_order() {
+    assert_eq!(
+        instantiate_in_order(DeterministicAddressGenerator, false),
+        instantiate_in_order(DeterministicAddressGenerator, true)
+    );
+}
+
+#[test]
+fn default_addresses_should_depend_on_instantiation_order() {
+    assert_ne!(
+        instantiate_in_order(SimpleAddressGenerator, false),
+        instantiate_in_order(SimpleAddressGenerator, true)
+    );
+}
+
+#[test]
+fn deterministic_addresses_should_depend_on_label_and_creator() {
+    let (mut app, code_id, _) = setup(DeterministicAddressGenerator);
+    let creator_1 = app.api().addr_make("creator1");
+    let creator_2 = app.api().addr_make("creator2");
+
+    let addr_1 = app
+        .instantiate_contract(code_id, creator_1.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+    let addr_2 = app
+        .instantiate_contract(code_id, creator_1, &Empty {}, &[], "second", None)
+        .unwrap();
+    let addr_3 = app
+        .instantiate_contract(code_id, creator_2, &Empty {}, &[], "first", None)
+        .unwrap();
+
+    assert_ne!(addr_1, addr_2);
+    assert_ne!(addr_1, addr_3);
+    assert_ne!(addr_2, addr_3);
+}
+
+#[test]
+fn duplicated_instantiation_should_fail() {
+    let (mut app, code_id, _) = setup(DeterministicAddressGenerator);
+    let creator = app.api().addr_make("creator");
+
+    let contract_addr = app
+        .instantiate_contract(code_id, creator.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    let err = app
+        .instantiate_contract(code_id, creator.clone(), &Empty {}, &[], "counter", None)
+        .unwrap_err();
+
+    assert_eq!(
+        format!(
+            "contract with code id {code_id}, label 'counter' and creator {creator} already instantiated at {contract_addr}"
+        ),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn default_addresses_should_be_predictable() {
+    let (mut app, code_id_1, code_id_2) = setup(SimpleAddressGenerator);
+    let creator = app.api().addr_make("creator");
+
+    let addr_1 = app
+        .instantiate_contract(code_id_1, creator.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    let addr_2 = app
+        .instantiate_contract(code_id_2, creator, &Empty {}, &[], "counter", None)
+        .unwrap();
+
+    let api = app.api();
+    assert_eq!(
+        api.addr_humanize(&instantiate_address(code_id_1, 0))
+            .unwrap(),
+        addr_1
+    );
+    assert_eq!(
+        api.addr_humanize(&instantiate_address(code_id_2, 1))
+            .unwrap(),
+        addr_2
+    );
+}
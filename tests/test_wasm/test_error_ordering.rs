@@ -0,0 +1,154 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{coins, Addr, Coin, Empty, OverflowError, OverflowOperation, StdError, WasmMsg};
+use cw_multi_test::error::{AnyError, Error};
+use cw_multi_test::{App, Executor, IntoAddr};
+
+/// Funds the creator does not have.
+fn unaffordable_funds() -> Vec<Coin> {
+    coins(100, "uatom")
+}
+
+/// Instantiates the counter contract, expecting it to fail.
+fn instantiate_err(
+    app: &mut App,
+    code_id: u64,
+    funds: &[Coin],
+    label: &str,
+    admin: Option<String>,
+) -> AnyError {
+    app.instantiate_contract(
+        code_id,
+        "creator".into_addr(),
+        &Empty {},
+        funds,
+        label,
+        admin,
+    )
+    .unwrap_err()
+}
+
+#[test]
+fn zero_code_id_should_be_reported_before_invalid_label() {
+    let mut app = App::default();
+    app.store_code(counter::contract());
+
+    let err = instantiate_err(&mut app, 0, &[], "", None);
+    assert_eq!(
+        Error::invalid_code_id().to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn invalid_label_should_be_reported_before_unregistered_code_id() {
+    let mut app = App::default();
+    let code_id = app.store_code(counter::contract());
+
+    let err = instantiate_err(&mut app, code_id + 1, &[], " label", None);
+    assert_eq!(
+        Error::invalid_label(" label", "label must not start or end with whitespace").to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn unregistered_code_id_should_be_reported_before_insufficient_funds() {
+    let mut app = App::default();
+    let code_id = app.store_code(counter::contract());
+
+    let err = instantiate_err(
+        &mut app,
+        code_id + 1,
+        &unaffordable_funds(),
+        "counter",
+        None,
+    );
+    assert_eq!(
+        "Cannot init contract with unregistered code id",
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn insufficient_funds_should_be_reported_before_contract_call() {
+    let mut app = App::default();
+    let code_id = app.store_code(counter::contract());
+
+    let err = instantiate_err(&mut app, code_id, &unaffordable_funds(), "counter", None);
+    assert_eq!(
+        StdError::overflow(OverflowError::new(OverflowOperation::Sub)),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_2")]
+fn duplicated_address_should_be_reported_before_insufficient_funds() {
+    let mut app = App::default();
+    let code_id = app.store_code(counter::contract());
+    let creator = "creator".into_addr();
+
+    let contract_addr = app
+        .instantiate2_contract(
+            code_id,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "counter",
+            None,
+            [1, 2, 3],
+        )
+        .unwrap();
+
+    let err = app
+        .instantiate2_contract(
+            code_id,
+            creator,
+            &Empty {},
+            &unaffordable_funds(),
+            "counter",
+            None,
+            [1, 2, 3],
+        )
+        .unwrap_err();
+    assert_eq!(
+        Error::duplicated_contract_address(contract_addr).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn invalid_contract_address_should_be_reported_before_insufficient_funds() {
+    let mut app = App::default();
+
+    let err = app
+        .execute_contract(
+            "sender".into_addr(),
+            Addr::unchecked("contract"),
+            &WasmMsg::ClearAdmin {
+                contract_addr: "contract".to_string(),
+            },
+            &unaffordable_funds(),
+        )
+        .unwrap_err();
+    let err = err.root_cause().to_string();
+    assert!(err.contains("Error decoding bech32"), "{}", err);
+}
+
+#[test]
+fn missing_contract_should_be_reported_before_insufficient_funds() {
+    let mut app = App::default();
+
+    let err = app
+        .execute_contract(
+            "sender".into_addr(),
+            "contract".into_addr(),
+            &WasmMsg::ClearAdmin {
+                contract_addr: "contract".to_string(),
+            },
+            &unaffordable_funds(),
+        )
+        .unwrap_err();
+    let err = err.root_cause().to_string();
+    assert!(err.contains("not found"), "{}", err);
+}
@@ -0,0 +1,100 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{instantiate2_address, Addr, Api, CanonicalAddr, Checksum};
+use cw_multi_test::{
+    predict_instantiate2_address, CreatorCanonicalization, Instantiate2AddressGenerator,
+};
+
+const SALT: &[u8] = b"salt";
+
+/// Returns the address of the creator with canonical address of specified length.
+fn creator(api: &MockApi, len: usize) -> (CanonicalAddr, Addr) {
+    let canonical = CanonicalAddr::from(vec![0xAB; len]);
+    let addr = api.addr_humanize(&canonical).unwrap();
+    (canonical, addr)
+}
+
+/// Returns the address derived with `instantiate2_address` from `cosmwasm-std`.
+fn expected_address(api: &MockApi, checksum: &Checksum, creator: &[u8]) -> Addr {
+    let canonical = instantiate2_address(checksum.as_slice(), &creator.into(), SALT).unwrap();
+    api.addr_humanize(&canonical).unwrap()
+}
+
+#[test]
+fn predicted_address_should_match_cosmwasm_std_for_20_and_32_byte_creators() {
+    let api = MockApi::default();
+    let checksum = Checksum::generate(&[1]);
+    for len in [20, 32] {
+        let (canonical, addr) = creator(&api, len);
+        assert_eq!(
+            expected_address(&api, &checksum, canonical.as_slice()),
+            predict_instantiate2_address(&api, checksum.as_slice(), &addr, SALT).unwrap()
+        );
+    }
+}
+
+#[test]
+fn truncated_creator_should_use_first_20_bytes() {
+    let api = MockApi::default();
+    let checksum = Checksum::generate(&[1]);
+    let generator = Instantiate2AddressGenerator::new()
+        .with_creator_canonicalization(CreatorCanonicalization::Truncated20);
+    for len in [20, 32] {
+        let (canonical, addr) = creator(&api, len);
+        assert_eq!(
+            expected_address(&api, &checksum, &canonical.as_slice()[..20]),
+            generator
+                .predict(&api, checksum.as_slice(), &addr, SALT)
+                .unwrap()
+        );
+    }
+    // 32-byte creators get different addresses than with full canonical address
+    let (_, addr) = creator(&api, 32);
+    assert_ne!(
+        predict_instantiate2_address(&api, checksum.as_slice(), &addr, SALT).unwrap(),
+        generator
+            .predict(&api, checksum.as_slice(), &addr, SALT)
+            .unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "cosmwasm_1_2")]
+fn instantiated_contract_should_get_predicted_address() {
+    use crate::test_contracts::counter;
+    use cosmwasm_std::{to_json_binary, Empty, WasmMsg};
+    use cw_multi_test::{no_init, AppBuilder, Executor, IntoAddr, WasmKeeper};
+    use cw_utils::parse_instantiate_response_data;
+
+    let generator = Instantiate2AddressGenerator::new()
+        .with_creator_canonicalization(CreatorCanonicalization::Truncated20);
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_address_generator(generator))
+        .build(no_init);
+    let code_id = app.store_code(counter::contract());
+    let checksum = app.wrap().query_wasm_code_info(code_id).unwrap().checksum;
+    let sender = "sender".into_addr();
+
+    let res = app
+        .execute(
+            sender.clone(),
+            WasmMsg::Instantiate2 {
+                admin: None,
+                code_id,
+                msg: to_json_binary(&Empty {}).unwrap(),
+                funds: vec![],
+                label: "counter".into(),
+                salt: SALT.into(),
+            }
+            .into(),
+        )
+        .unwrap();
+    let parsed = parse_instantiate_response_data(res.data.unwrap().as_slice()).unwrap();
+
+    assert_eq!(
+        generator
+            .predict(app.api(), checksum.as_slice(), &sender, SALT)
+            .unwrap()
+            .as_str(),
+        parsed.contract_address
+    );
+}
@@ -0,0 +1,111 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::Empty;
+use cw_multi_test::error::{AnyResult, Error};
+use cw_multi_test::{no_init, App, AppBuilder, Executor, IntoAddr, LabelValidation};
+
+/// Instantiates the counter contract with the specified label.
+fn instantiate_with_label(app: &mut App, label: &str) -> AnyResult<cosmwasm_std::Addr> {
+    let code_id = app.store_code(counter::contract());
+    app.instantiate_contract(code_id, "creator".into_addr(), &Empty {}, &[], label, None)
+}
+
+/// Asserts that the label is rejected with the specified reason.
+#[track_caller]
+fn assert_rejected(app: &mut App, label: &str, reason: &str) {
+    let err = instantiate_with_label(app, label).unwrap_err();
+    assert_eq!(
+        Error::invalid_label(label, reason).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn wasmd_rules_should_be_applied_by_default() {
+    let mut app = App::default();
+
+    instantiate_with_label(&mut app, "my contract").unwrap();
+    instantiate_with_label(&mut app, "żółw 🐢").unwrap();
+
+    assert_rejected(&mut app, "", "label is required");
+    assert_rejected(
+        &mut app,
+        " leading",
+        "label must not start or end with whitespace",
+    );
+    assert_rejected(
+        &mut app,
+        "trailing\t",
+        "label must not start or end with whitespace",
+    );
+    assert_rejected(
+        &mut app,
+        "new\nline",
+        "label must not contain control characters",
+    );
+}
+
+#[test]
+fn wasmd_rules_should_limit_label_length_in_bytes() {
+    let mut app = App::default();
+
+    instantiate_with_label(&mut app, &"a".repeat(128)).unwrap();
+    assert_rejected(
+        &mut app,
+        &"a".repeat(129),
+        "label cannot be longer than 128 bytes",
+    );
+
+    // non-ASCII characters count as several bytes, like in wasmd
+    instantiate_with_label(&mut app, &"é".repeat(64)).unwrap();
+    assert_rejected(
+        &mut app,
+        &"é".repeat(65),
+        "label cannot be longer than 128 bytes",
+    );
+}
+
+#[test]
+fn permissive_validation_should_reject_only_empty_labels() {
+    let mut app = AppBuilder::default()
+        .with_label_validation(LabelValidation::Permissive)
+        .build(no_init);
+
+    instantiate_with_label(&mut app, " leading and trailing ").unwrap();
+    instantiate_with_label(&mut app, "new\nline").unwrap();
+    instantiate_with_label(&mut app, &"a".repeat(1000)).unwrap();
+
+    assert_rejected(&mut app, "", "label is required");
+}
+
+#[test]
+fn custom_validation_should_be_applied() {
+    let mut app = AppBuilder::default()
+        .with_label_validation(LabelValidation::custom(|label| {
+            if label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                Ok(())
+            } else {
+                Err("only alphanumeric characters and dashes are allowed".to_string())
+            }
+        }))
+        .build(no_init);
+
+    instantiate_with_label(&mut app, "dao-core-1").unwrap();
+    // custom rules replace the built-in ones
+    instantiate_with_label(&mut app, "").unwrap();
+
+    assert_rejected(
+        &mut app,
+        "dao core",
+        "only alphanumeric characters and dashes are allowed",
+    );
+}
+
+#[test]
+fn error_should_include_label() {
+    let mut app = App::default();
+    let err = instantiate_with_label(&mut app, " counter").unwrap_err();
+    assert_eq!(
+        r#"invalid label " counter": label must not start or end with whitespace"#,
+        err.root_cause().to_string()
+    );
+}
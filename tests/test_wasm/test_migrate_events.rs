@@ -0,0 +1,85 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{Addr, DepsMut, Empty, Env, Event, Response, StdResult};
+use cw_multi_test::{
+    no_init, App, AppBuilder, AppResponse, ContractWrapper, Executor, IntoAddr, WasmKeeper,
+};
+
+fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_event(Event::new("upgraded").add_attribute("version", "2")))
+}
+
+/// Stores two versions of the contract and migrates the instance of the first one to the second.
+fn migrate_v1_to_v2(app: &mut App) -> (Addr, u64, u64, AppResponse) {
+    let owner_addr = "owner".into_addr();
+    let code_id_v1 = app.store_code(Box::new(
+        ContractWrapper::new(noop::execute, noop::instantiate, noop::query).with_migrate(migrate),
+    ));
+    let code_id_v2 = app.store_code(Box::new(
+        ContractWrapper::new(noop::execute, noop::instantiate, noop::query).with_migrate(migrate),
+    ));
+    let contract_addr = app
+        .instantiate_contract(
+            code_id_v1,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "upgradable",
+            Some(owner_addr.to_string()),
+        )
+        .unwrap();
+    let res = app
+        .migrate_contract(owner_addr, contract_addr.clone(), &Empty {}, code_id_v2)
+        .unwrap();
+    (contract_addr, code_id_v1, code_id_v2, res)
+}
+
+fn event<'a>(res: &'a AppResponse, ty: &str) -> &'a Event {
+    res.events
+        .iter()
+        .find(|event| event.ty == ty)
+        .unwrap_or_else(|| panic!("no {} event in {:?}", ty, res.events))
+}
+
+#[test]
+fn migration_should_return_all_events() {
+    let mut app = App::default();
+    let (contract_addr, _, code_id_v2, res) = migrate_v1_to_v2(&mut app);
+
+    assert_eq!(
+        &Event::new("migrate")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("code_id", code_id_v2.to_string()),
+        event(&res, "migrate")
+    );
+    // events emitted by the contract are returned too
+    assert_eq!(
+        &Event::new("wasm")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("action", "migrate"),
+        event(&res, "wasm")
+    );
+    assert_eq!(
+        &Event::new("wasm-upgraded")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("version", "2"),
+        event(&res, "wasm-upgraded")
+    );
+}
+
+#[test]
+fn migrate_event_should_report_old_code_id_when_enabled() {
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_old_code_id_in_migrate_event(true))
+        .build(no_init);
+    let (contract_addr, code_id_v1, code_id_v2, res) = migrate_v1_to_v2(&mut app);
+
+    assert_eq!(
+        &Event::new("migrate")
+            .add_attribute("_contract_address", contract_addr.as_str())
+            .add_attribute("code_id", code_id_v2.to_string())
+            .add_attribute("old_code_id", code_id_v1.to_string()),
+        event(&res, "migrate")
+    );
+}
@@ -0,0 +1,264 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, CosmosMsg, CustomMsg,
+    CustomQuery, Deps, DepsMut, Empty, Env, MessageInfo, Querier, QueryRequest, Reply, Response,
+    StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{
+    no_init, App, AppResponse, BankKeeper, BasicAppBuilder, Contract, ContractWrapper,
+    CosmosRouter, Executor, Module, WasmKeeper,
+};
+use cw_storage_plus::Item;
+use serde::de::DeserializeOwned;
+
+const DENOM: &str = "denom";
+
+/// Key of the counter kept in storage by the custom module.
+const MODULE_COUNTER: &[u8] = b"module-counter";
+
+/// Value written by the contract and returned by its smart query.
+const VALUE: Item<u64> = Item::new("value");
+
+#[cw_serde]
+enum CounterMsg {
+    Increment {},
+}
+
+impl CustomMsg for CounterMsg {}
+
+#[cw_serde]
+enum CounterQuery {
+    Counter {},
+}
+
+impl CustomQuery for CounterQuery {}
+
+/// Custom module keeping a counter in storage.
+struct CounterModule;
+
+impl Module for CounterModule {
+    type ExecT = CounterMsg;
+    type QueryT = CounterQuery;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        msg: CounterMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg {
+            CounterMsg::Increment {} => {
+                let counter = module_counter(storage)?;
+                storage.set(MODULE_COUNTER, &to_json_binary(&(counter + 1))?);
+                Ok(AppResponse::default())
+            }
+        }
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: CounterQuery,
+    ) -> AnyResult<Binary> {
+        match request {
+            CounterQuery::Counter {} => Ok(to_json_binary(&module_counter(storage)?)?),
+        }
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("sudo not implemented for CounterModule")
+    }
+}
+
+fn module_counter(storage: &dyn Storage) -> AnyResult<u64> {
+    Ok(match storage.get(MODULE_COUNTER) {
+        Some(value) => from_json(value)?,
+        None => 0,
+    })
+}
+
+#[cw_serde]
+#[allow(clippy::enum_variant_names)]
+enum ExecuteMsg {
+    /// Sends tokens to the recipient and queries own balance in reply.
+    SendAndQuery { recipient: String },
+    /// Increments the counter of the custom module and queries it `depth` messages later.
+    IncrementAndQuery { depth: u32 },
+    /// Writes the value to storage and queries it back with a smart query to itself.
+    WriteAndQuery { value: u64 },
+}
+
+fn instantiate(
+    _deps: DepsMut<CounterQuery>,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response<CounterMsg>> {
+    Ok(Response::default())
+}
+
+fn execute(
+    deps: DepsMut<CounterQuery>,
+    env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response<CounterMsg>> {
+    match msg {
+        ExecuteMsg::SendAndQuery { recipient } => {
+            let send = BankMsg::Send {
+                to_address: recipient,
+                amount: coins(30, DENOM),
+            };
+            Ok(Response::new().add_submessage(SubMsg::reply_on_success(send, 1)))
+        }
+        ExecuteMsg::IncrementAndQuery { depth: 0 } => {
+            let counter: u64 = deps
+                .querier
+                .query(&QueryRequest::Custom(CounterQuery::Counter {}))?;
+            Ok(Response::new().add_attribute("counter", counter.to_string()))
+        }
+        ExecuteMsg::IncrementAndQuery { depth } => Ok(Response::new()
+            .add_message(CosmosMsg::Custom(CounterMsg::Increment {}))
+            .add_message(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::IncrementAndQuery { depth: depth - 1 })?,
+                funds: vec![],
+            })),
+        ExecuteMsg::WriteAndQuery { value } => {
+            VALUE.save(deps.storage, &value)?;
+            let queried: u64 = deps
+                .querier
+                .query_wasm_smart(env.contract.address, &Empty {})?;
+            Ok(Response::new().add_attribute("value", queried.to_string()))
+        }
+    }
+}
+
+fn query(deps: Deps<CounterQuery>, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&VALUE.may_load(deps.storage)?.unwrap_or_default())
+}
+
+fn reply(deps: DepsMut<CounterQuery>, env: Env, _msg: Reply) -> StdResult<Response<CounterMsg>> {
+    let balance = deps.querier.query_balance(env.contract.address, DENOM)?;
+    Ok(Response::new().add_attribute("balance", balance.amount.to_string()))
+}
+
+fn contract() -> Box<dyn Contract<CounterMsg, CounterQuery>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+type CounterApp =
+    App<BankKeeper, MockApi, MockStorage, CounterModule, WasmKeeper<CounterMsg, CounterQuery>>;
+
+/// Creates the application with the custom module and instantiated contract having some funds.
+fn setup() -> (CounterApp, Addr) {
+    let mut app = BasicAppBuilder::<CounterMsg, CounterQuery>::new_custom()
+        .with_custom(CounterModule)
+        .build(no_init);
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner, &Empty {}, &[], "pending", None)
+        .unwrap();
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &contract_addr, coins(100, DENOM))
+            .unwrap();
+    });
+    (app, contract_addr)
+}
+
+/// Returns the value of the attribute of `wasm` events, added by the contract.
+fn wasm_attribute(res: &AppResponse, key: &str) -> String {
+    res.events
+        .iter()
+        .filter(|event| event.ty == "wasm")
+        .flat_map(|event| event.attributes.iter())
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.clone())
+        .unwrap()
+}
+
+#[test]
+fn balance_query_should_reflect_funds_sent_in_the_same_execute() {
+    let (mut app, contract_addr) = setup();
+    let sender = app.api().addr_make("sender");
+    let recipient = app.api().addr_make("recipient");
+
+    let res = app
+        .execute_contract(
+            sender,
+            contract_addr,
+            &ExecuteMsg::SendAndQuery {
+                recipient: recipient.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!("70", wasm_attribute(&res, "balance"));
+}
+
+/// Executes the contract incrementing the custom module counter and querying it
+/// `depth` messages later, returns the queried value.
+fn increment_and_query(app: &mut CounterApp, contract_addr: &Addr, depth: u32) -> String {
+    let sender = app.api().addr_make("sender");
+    let res = app
+        .execute_contract(
+            sender,
+            contract_addr.clone(),
+            &ExecuteMsg::IncrementAndQuery { depth },
+            &[],
+        )
+        .unwrap();
+    wasm_attribute(&res, "counter")
+}
+
+#[test]
+fn custom_query_should_reflect_custom_execute_in_the_same_execute() {
+    let (mut app, contract_addr) = setup();
+
+    // the counter is incremented once per nesting level
+    assert_eq!("1", increment_and_query(&mut app, &contract_addr, 1));
+    assert_eq!("3", increment_and_query(&mut app, &contract_addr, 2));
+}
+
+#[test]
+fn smart_query_should_reflect_writes_made_in_the_same_call() {
+    let (mut app, contract_addr) = setup();
+    let sender = app.api().addr_make("sender");
+
+    let res = app
+        .execute_contract(
+            sender,
+            contract_addr.clone(),
+            &ExecuteMsg::WriteAndQuery { value: 42 },
+            &[],
+        )
+        .unwrap();
+    assert_eq!("42", wasm_attribute(&res, "value"));
+}
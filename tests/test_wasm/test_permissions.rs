@@ -0,0 +1,195 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::error::{AnyResult, Error};
+use cw_multi_test::{
+    no_init, App, AppBuilder, Executor, InstantiatePermission, IntoAddr, WasmPermissions,
+    WasmPermissionsSudo,
+};
+
+/// Builds the application where only the `uploader` can store code.
+fn permissioned_app() -> App {
+    AppBuilder::default()
+        .with_wasm_permissions(WasmPermissions::PermissionedUpload {
+            allowed_uploaders: vec!["uploader".into_addr()],
+        })
+        .build(no_init)
+}
+
+/// Instantiates the counter contract from the code on behalf of the sender.
+fn instantiate(app: &mut App, code_id: u64, sender: &str) -> AnyResult<Addr> {
+    app.instantiate_contract(code_id, sender.into_addr(), &Empty {}, &[], "counter", None)
+}
+
+#[test]
+fn any_address_should_store_code_by_default() {
+    let mut app = App::default();
+    app.store_code_with_id("anybody".into_addr(), 1, counter::contract())
+        .unwrap();
+}
+
+#[test]
+fn unauthorized_uploader_should_be_rejected() {
+    let mut app = permissioned_app();
+
+    let err = app
+        .store_code_with_id("intruder".into_addr(), 1, counter::contract())
+        .unwrap_err();
+    assert_eq!(
+        Error::upload_not_allowed("intruder".into_addr()).to_string(),
+        err.to_string()
+    );
+
+    // the allowed uploader can store the code
+    app.store_code_with_id("uploader".into_addr(), 1, counter::contract())
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "is not allowed to store code")]
+fn storing_code_by_unauthorized_creator_should_panic() {
+    let mut app = permissioned_app();
+    app.store_code_with_creator("intruder".into_addr(), counter::contract());
+}
+
+#[test]
+fn storing_code_by_unauthorized_creator_should_fail() {
+    let mut app = permissioned_app();
+
+    let err = app
+        .try_store_code_with_creator("intruder".into_addr(), counter::contract())
+        .unwrap_err();
+    assert_eq!(
+        Error::upload_not_allowed("intruder".into_addr()).to_string(),
+        err.to_string()
+    );
+
+    // the allowed uploader can store the code
+    let code_id = app
+        .try_store_code_with_creator("uploader".into_addr(), counter::contract())
+        .unwrap();
+    assert_eq!(1, code_id);
+}
+
+#[test]
+fn gov_sudo_should_add_uploader() {
+    let mut app = permissioned_app();
+
+    app.sudo(
+        WasmPermissionsSudo::UpdatePermissions {
+            permissions: WasmPermissions::PermissionedUpload {
+                allowed_uploaders: vec!["uploader".into_addr(), "newcomer".into_addr()],
+            },
+        }
+        .into(),
+    )
+    .unwrap();
+
+    app.store_code_with_id("newcomer".into_addr(), 1, counter::contract())
+        .unwrap();
+}
+
+#[test]
+fn duplicating_code_should_respect_upload_permissions() {
+    let mut app = App::default();
+    let code_id = app.store_code_with_creator("creator".into_addr(), counter::contract());
+
+    app.sudo(
+        WasmPermissionsSudo::UpdatePermissions {
+            permissions: WasmPermissions::PermissionedUpload {
+                allowed_uploaders: vec!["uploader".into_addr()],
+            },
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let err = app.duplicate_code(code_id).unwrap_err();
+    assert_eq!(
+        Error::upload_not_allowed("creator".into_addr()).to_string(),
+        err.to_string()
+    );
+}
+
+#[test]
+fn default_instantiate_permission_should_apply_to_codes_without_own_permission() {
+    let mut app = AppBuilder::default()
+        .with_default_instantiate_permission(InstantiatePermission::AnyOfAddresses(vec![
+            "deployer".into_addr(),
+        ]))
+        .build(no_init);
+    let code_id = app.store_code(counter::contract());
+
+    let err = instantiate(&mut app, code_id, "factory").unwrap_err();
+    assert_eq!(
+        Error::instantiate_not_allowed(code_id, "factory".into_addr()).to_string(),
+        err.root_cause().to_string()
+    );
+
+    instantiate(&mut app, code_id, "deployer").unwrap();
+}
+
+#[test]
+fn code_instantiate_permission_should_override_default_one() {
+    let mut app = AppBuilder::default()
+        .with_default_instantiate_permission(InstantiatePermission::Nobody)
+        .build(no_init);
+    let restricted_code_id = app.store_code(counter::contract());
+    let open_code_id = app.store_code(counter::contract());
+
+    app.sudo(
+        WasmPermissionsSudo::UpdateCodeInstantiatePermission {
+            code_id: open_code_id,
+            permission: Some(InstantiatePermission::Everybody),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // the code with own permission can be instantiated by anybody
+    instantiate(&mut app, open_code_id, "factory").unwrap();
+    // other codes still use the default permission
+    let err = instantiate(&mut app, restricted_code_id, "factory").unwrap_err();
+    assert_eq!(
+        Error::instantiate_not_allowed(restricted_code_id, "factory".into_addr()).to_string(),
+        err.root_cause().to_string()
+    );
+
+    // changing the default permission does not affect the code with own permission
+    app.sudo(
+        WasmPermissionsSudo::UpdateDefaultInstantiatePermission {
+            permission: InstantiatePermission::AnyOfAddresses(vec!["deployer".into_addr()]),
+        }
+        .into(),
+    )
+    .unwrap();
+    instantiate(&mut app, open_code_id, "factory").unwrap();
+    instantiate(&mut app, restricted_code_id, "deployer").unwrap();
+
+    // clearing the permission of the code falls back to the default permission
+    app.sudo(
+        WasmPermissionsSudo::UpdateCodeInstantiatePermission {
+            code_id: open_code_id,
+            permission: None,
+        }
+        .into(),
+    )
+    .unwrap();
+    let err = instantiate(&mut app, open_code_id, "factory").unwrap_err();
+    assert_eq!(
+        Error::instantiate_not_allowed(open_code_id, "factory".into_addr()).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn unregistered_code_should_be_reported_before_instantiate_permission() {
+    let mut app = AppBuilder::default()
+        .with_default_instantiate_permission(InstantiatePermission::Nobody)
+        .build(no_init);
+
+    let err = instantiate(&mut app, 1, "factory").unwrap_err();
+    assert_eq!(
+        "Cannot init contract with unregistered code id",
+        err.root_cause().to_string()
+    );
+}
@@ -0,0 +1,104 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult};
+use cw_multi_test::{no_init, App, AppBuilder, Contract, ContractWrapper, Executor, WasmKeeper};
+use cw_storage_plus::Item;
+
+/// Address of the contract queried when this contract is queried.
+const PEER: Item<String> = Item::new("peer");
+
+/// Sets the address of the peer contract.
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, peer: String) -> StdResult<Response> {
+    PEER.save(deps.storage, &peer)?;
+    Ok(Response::default())
+}
+
+/// Forwards the query to the peer contract, if set.
+fn query(deps: Deps, _env: Env, msg: Empty) -> Result<Binary, StdError> {
+    match PEER.may_load(deps.storage)? {
+        Some(peer) => deps.querier.query_wasm_smart(peer, &msg),
+        None => Ok(Binary::default()),
+    }
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+/// Instantiates two contracts querying each other, returns their addresses.
+fn instantiate_looping_contracts(app: &mut App) -> (String, String) {
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let first = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+    let second = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "second", None)
+        .unwrap();
+    app.execute_contract(owner.clone(), first.clone(), &second, &[])
+        .unwrap();
+    app.execute_contract(owner, second.clone(), &first, &[])
+        .unwrap();
+    (first.to_string(), second.to_string())
+}
+
+#[test]
+fn query_loop_should_fail_with_max_query_depth_error() {
+    let mut app = App::default();
+    let (first, second) = instantiate_looping_contracts(&mut app);
+
+    // querying the loop fails with descriptive error instead of overflowing the stack
+    let err = app
+        .wrap()
+        .query_wasm_smart::<Binary>(first.clone(), &Empty {})
+        .unwrap_err()
+        .to_string();
+    let query_chain = [&first, &second]
+        .repeat(5)
+        .into_iter()
+        .chain([&first])
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    assert!(
+        err.contains(&format!(
+            "max query depth 10 exceeded, query chain: {}",
+            query_chain
+        )),
+        "unexpected error: {}",
+        err
+    );
+
+    // the depth counter is reset after the failed query, the next query fails the same way
+    let next_err = app
+        .wrap()
+        .query_wasm_smart::<Binary>(first, &Empty {})
+        .unwrap_err()
+        .to_string();
+    assert_eq!(err, next_err);
+}
+
+#[test]
+fn max_query_depth_should_be_configurable() {
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_max_query_depth(2))
+        .build(no_init);
+    let (first, second) = instantiate_looping_contracts(&mut app);
+
+    let err = app
+        .wrap()
+        .query_wasm_smart::<Binary>(second.clone(), &Empty {})
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(&format!(
+            "max query depth 2 exceeded, query chain: {} -> {} -> {}",
+            second, first, second
+        )),
+        "unexpected error: {}",
+        err
+    );
+}
@@ -0,0 +1,89 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{from_json, to_json_binary, Binary, Deps, Empty, Env};
+use cw_multi_test::error::Error;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor, IntoAddr};
+
+/// Error returned by the queried contract.
+#[derive(Debug, PartialEq, thiserror::Error)]
+enum PriceError {
+    #[error("unknown price of {0}")]
+    UnknownPrice(String),
+}
+
+/// Returns the price of the denomination, only the price of `atom` is known.
+fn query(_deps: Deps, _env: Env, denom: String) -> Result<Binary, PriceError> {
+    match denom.as_str() {
+        "atom" => Ok(to_json_binary(&10u128).unwrap()),
+        _ => Err(PriceError::UnknownPrice(denom)),
+    }
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        noop::execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+fn setup() -> (App, String) {
+    let mut app = App::default();
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "prices", None)
+        .unwrap();
+    (app, contract_addr.to_string())
+}
+
+#[test]
+fn query_error_should_be_reported_like_wasmd() {
+    let (app, contract_addr) = setup();
+
+    let err = app
+        .wrap()
+        .query_wasm_smart::<u128>(&contract_addr, &"osmo")
+        .unwrap_err();
+    assert_eq!(
+        format!(
+            "Generic error: Querier contract error: contract {}: unknown price of osmo: query wasm contract failed",
+            contract_addr
+        ),
+        err.to_string()
+    );
+}
+
+#[test]
+fn raw_query_should_preserve_contract_error() {
+    let (app, contract_addr) = setup();
+
+    let err = app
+        .query_wasm_smart_raw(&contract_addr, &"osmo")
+        .unwrap_err();
+    assert_eq!(
+        Some(&PriceError::UnknownPrice("osmo".to_string())),
+        err.downcast_ref::<PriceError>()
+    );
+    assert_eq!(
+        Error::query_contract_failed(&contract_addr, "unknown price of osmo").to_string(),
+        err.to_string()
+    );
+}
+
+#[test]
+fn raw_query_should_return_contract_response() {
+    let (app, contract_addr) = setup();
+
+    let response = app.query_wasm_smart_raw(&contract_addr, &"atom").unwrap();
+    assert_eq!(10, from_json::<u128>(response).unwrap());
+}
+
+#[test]
+fn raw_query_of_missing_contract_should_fail() {
+    let (app, _) = setup();
+
+    let err = app
+        .query_wasm_smart_raw("missing".into_addr(), &"atom")
+        .unwrap_err();
+    assert!(err.downcast_ref::<PriceError>().is_none());
+    assert!(err.to_string().contains("not found"), "{}", err);
+}
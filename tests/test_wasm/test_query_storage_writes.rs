@@ -0,0 +1,85 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, Response, StdResult,
+    Storage,
+};
+use cw_multi_test::error::Error;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor, IntoAddr};
+
+const KEY: &[u8] = b"counter";
+
+#[cw_serde]
+enum QueryMsg {
+    /// Reads the counter, like a well-behaving query.
+    Read {},
+    /// Writes the counter, like a query calling a helper that modifies the storage.
+    Write {},
+    /// Removes the counter.
+    Remove {},
+}
+
+fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    deps.storage.set(KEY, b"1");
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    deps.storage.set(KEY, b"2");
+    Ok(Response::default())
+}
+
+/// Deliberately misbehaving query, casting away the immutability of the storage.
+#[allow(invalid_reference_casting)]
+fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let storage = unsafe { &mut *(deps.storage as *const dyn Storage as *mut dyn Storage) };
+    match msg {
+        QueryMsg::Read {} => {}
+        QueryMsg::Write {} => storage.set(KEY, b"100"),
+        QueryMsg::Remove {} => storage.remove(KEY),
+    }
+    to_json_binary(&String::from_utf8(deps.storage.get(KEY).unwrap_or_default()).unwrap())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+#[test]
+fn storage_write_during_query_should_be_reported() {
+    let mut app = App::default();
+    let code_id = app.store_code(contract());
+    let owner = "owner".into_addr();
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+
+    let expected = Error::query_storage_write(contract_addr.clone(), HexBinary::from(KEY).to_hex())
+        .to_string();
+    assert_eq!(
+        "contract ".to_string()
+            + contract_addr.as_str()
+            + " attempted to write key 636f756e746572 during query",
+        expected
+    );
+    for msg in [QueryMsg::Write {}, QueryMsg::Remove {}] {
+        let err = app
+            .wrap()
+            .query_wasm_smart::<String>(&contract_addr, &msg)
+            .unwrap_err();
+        assert!(err.to_string().contains(&expected), "{}", err);
+    }
+
+    // the storage is not modified and the App remains usable
+    let value: String = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &QueryMsg::Read {})
+        .unwrap();
+    assert_eq!("1", value);
+    app.execute_contract(owner, contract_addr.clone(), &Empty {}, &[])
+        .unwrap();
+    let value: String = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &QueryMsg::Read {})
+        .unwrap();
+    assert_eq!("2", value);
+}
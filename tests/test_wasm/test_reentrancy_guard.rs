@@ -0,0 +1,118 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{
+    to_json_binary, Addr, DepsMut, Empty, Env, MessageInfo, Response, StdResult, SubMsg, WasmMsg,
+};
+use cw_multi_test::{
+    no_init, App, AppBuilder, Contract, ContractWrapper, Executor, GuardMode, IntoAddr, WasmKeeper,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+struct ForwardMsg {
+    /// Addresses of contracts to be called one after another.
+    path: Vec<String>,
+    /// Flag indicating if the forwarded message should be replied.
+    reply: bool,
+}
+
+/// Forwards the message to the first contract on the path.
+fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, msg: ForwardMsg) -> StdResult<Response> {
+    let Some((next, path)) = msg.path.split_first() else {
+        return Ok(Response::default());
+    };
+    let forward = WasmMsg::Execute {
+        contract_addr: next.clone(),
+        msg: to_json_binary(&ForwardMsg {
+            path: path.to_vec(),
+            reply: msg.reply,
+        })?,
+        funds: vec![],
+    };
+    let sub_msg = if msg.reply {
+        SubMsg::reply_on_success(forward, 1)
+    } else {
+        SubMsg::new(forward)
+    };
+    Ok(Response::new().add_submessage(sub_msg))
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(execute, noop::instantiate, noop::query)
+            .with_reply(noop::reply),
+    )
+}
+
+/// Prepares the chain with re-entrancy guard and two contracts, returns their addresses.
+fn setup(mode: GuardMode) -> (App, Addr, Addr) {
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_reentrancy_guard(mode))
+        .build(no_init);
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let first = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+    let second = app
+        .instantiate_contract(code_id, owner, &Empty {}, &[], "second", None)
+        .unwrap();
+    (app, first, second)
+}
+
+fn forward(app: &mut App, contract: &Addr, path: &[&Addr], reply: bool) -> anyhow::Result<()> {
+    let msg = ForwardMsg {
+        path: path.iter().map(|addr| addr.to_string()).collect(),
+        reply,
+    };
+    app.execute_contract("sender".into_addr(), contract.clone(), &msg, &[])
+        .map(|_| ())
+}
+
+#[test]
+fn reentrant_call_should_fail_in_deny_mode() {
+    let (mut app, first, second) = setup(GuardMode::Deny);
+
+    // first -> second -> first
+    let err = forward(&mut app, &first, &[&second, &first], false).unwrap_err();
+    assert_eq!(
+        format!(
+            "re-entrant call into {} (call stack: {} -> {} -> {})",
+            first, first, second, first
+        ),
+        err.root_cause().to_string()
+    );
+
+    // the call stack is cleared after the failure, calls without re-entrancy work
+    forward(&mut app, &first, &[&second], false).unwrap();
+    forward(&mut app, &second, &[&first], false).unwrap();
+    assert!(app.reentrancy_warnings().is_empty());
+}
+
+#[test]
+fn reentrant_call_should_be_reported_in_warn_mode() {
+    let (mut app, first, second) = setup(GuardMode::Warn);
+
+    // first -> second -> first succeeds, but the warning is recorded
+    forward(&mut app, &first, &[&second, &first], false).unwrap();
+    assert_eq!(
+        vec![format!(
+            "re-entrant call into {} (call stack: {} -> {} -> {})",
+            first, first, second, first
+        )],
+        app.reentrancy_warnings()
+    );
+
+    // no more warnings for calls without re-entrancy
+    forward(&mut app, &first, &[&second], false).unwrap();
+    assert_eq!(1, app.reentrancy_warnings().len());
+}
+
+#[test]
+fn reply_should_not_be_reentrant_call() {
+    let (mut app, first, second) = setup(GuardMode::Deny);
+
+    // first -> second, then second replies to first
+    forward(&mut app, &first, &[&second], true).unwrap();
+    assert!(app.reentrancy_warnings().is_empty());
+}
@@ -0,0 +1,285 @@
+use crate::test_contracts::noop;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, DepsMut, Empty, Env, MessageInfo, Reply, ReplyOn,
+    Response, StdError, StdResult, SubMsg, SubMsgResult, WasmMsg,
+};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use cw_utils::parse_execute_response_data;
+use serde::{Deserialize, Serialize};
+
+/// Data set by the `reply` entry-point.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReplyData {
+    /// No data is set.
+    None,
+    /// Fixed value is set.
+    Fixed,
+    /// Data returned by the submessage is set (if any).
+    Echo,
+}
+
+const REPLY_DATA: &str = "reply";
+
+/// Message executed by the scripted contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScriptMsg {
+    /// Flag indicating if the execution fails.
+    fail: bool,
+    /// Data set by the `execute` entry-point.
+    data: Option<String>,
+    /// Submessages sent by the contract, each executed by the same contract.
+    calls: Vec<ScriptCall>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScriptCall {
+    msg: ScriptMsg,
+    reply_on: ReplyOn,
+    reply_data: ReplyData,
+}
+
+impl ScriptMsg {
+    fn leaf(fail: bool, data: Option<&str>) -> Self {
+        Self {
+            fail,
+            data: data.map(ToString::to_string),
+            calls: vec![],
+        }
+    }
+
+    /// Returns the data expected to be returned, following the semantics of `wasmd`:
+    /// - data returned by a submessage is never used directly,
+    /// - data set by a reply overrides the data set by the contract,
+    /// - later replies setting data override earlier ones,
+    /// - replies not setting data do not change the data.
+    fn expected(&self) -> Result<Option<String>, ()> {
+        if self.fail {
+            return Err(());
+        }
+        let mut dispatched = None;
+        for call in &self.calls {
+            let result = call.msg.expected();
+            let replied = match (&result, &call.reply_on) {
+                (Ok(_), ReplyOn::Success | ReplyOn::Always) => true,
+                (Ok(_), ReplyOn::Error | ReplyOn::Never) => false,
+                (Err(_), ReplyOn::Error | ReplyOn::Always) => true,
+                (Err(_), ReplyOn::Success | ReplyOn::Never) => return Err(()),
+            };
+            if replied {
+                let reply_data = match call.reply_data {
+                    ReplyData::None => None,
+                    ReplyData::Fixed => Some(REPLY_DATA.to_string()),
+                    ReplyData::Echo => result.ok().flatten(),
+                };
+                if reply_data.is_some() {
+                    dispatched = reply_data;
+                }
+            }
+        }
+        Ok(dispatched.or_else(|| self.data.clone()))
+    }
+}
+
+fn execute(_deps: DepsMut, env: Env, _info: MessageInfo, msg: ScriptMsg) -> StdResult<Response> {
+    if msg.fail {
+        return Err(StdError::generic_err("scripted failure"));
+    }
+    let mut response = Response::new();
+    if let Some(data) = msg.data {
+        response = response.set_data(data.into_bytes());
+    }
+    for call in msg.calls {
+        let mut sub_msg = SubMsg::new(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&call.msg)?,
+            funds: vec![],
+        })
+        .with_payload(to_json_binary(&call.reply_data)?);
+        sub_msg.reply_on = call.reply_on;
+        response = response.add_submessage(sub_msg);
+    }
+    Ok(response)
+}
+
+fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    let reply_data: ReplyData = from_json(&msg.payload)?;
+    let data = match reply_data {
+        ReplyData::None => None,
+        ReplyData::Fixed => Some(Binary::from(REPLY_DATA.as_bytes())),
+        ReplyData::Echo => match msg.result {
+            #[allow(deprecated)]
+            SubMsgResult::Ok(response) => response
+                .data
+                .and_then(|data| parse_execute_response_data(&data).unwrap().data),
+            SubMsgResult::Err(_) => None,
+        },
+    };
+    Ok(match data {
+        Some(data) => Response::new().set_data(data),
+        None => Response::new(),
+    })
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, noop::instantiate, noop::query).with_reply(reply))
+}
+
+fn setup() -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let owner = app.api().addr_make("owner");
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "script", None)
+        .unwrap();
+    (app, owner, contract_addr)
+}
+
+const REPLY_ON: [ReplyOn; 4] = [
+    ReplyOn::Never,
+    ReplyOn::Success,
+    ReplyOn::Error,
+    ReplyOn::Always,
+];
+
+const REPLY_DATA_VARIANTS: [ReplyData; 3] = [ReplyData::None, ReplyData::Fixed, ReplyData::Echo];
+
+/// Returns all submessages executed by the outer contract: leaves (succeeding with or without
+/// data, or failing) and succeeding messages with a single nested submessage.
+fn children() -> Vec<ScriptMsg> {
+    let leaves = vec![
+        ScriptMsg::leaf(false, Some("child")),
+        ScriptMsg::leaf(false, None),
+        ScriptMsg::leaf(true, None),
+    ];
+    let mut children = leaves.clone();
+    for grandchild in [
+        ScriptMsg::leaf(false, Some("grandchild")),
+        ScriptMsg::leaf(true, None),
+    ] {
+        for reply_on in REPLY_ON {
+            for reply_data in REPLY_DATA_VARIANTS {
+                children.push(ScriptMsg {
+                    fail: false,
+                    data: Some("child".to_string()),
+                    calls: vec![ScriptCall {
+                        msg: grandchild.clone(),
+                        reply_on: reply_on.clone(),
+                        reply_data,
+                    }],
+                });
+            }
+        }
+    }
+    children
+}
+
+#[track_caller]
+fn assert_data(expected: Result<Option<String>, ()>, actual: anyhow::Result<AppResponse>) {
+    match (expected, actual) {
+        (Ok(expected), Ok(res)) => {
+            assert_eq!(expected.map(|d| Binary::from(d.into_bytes())), res.data)
+        }
+        (Err(()), Err(_)) => {}
+        (expected, actual) => panic!("expected {:?}, got {:?}", expected, actual),
+    }
+}
+
+#[test]
+fn reply_data_should_follow_wasmd_semantics() {
+    let (mut app, owner, contract_addr) = setup();
+    let mut cases = 0;
+    for outer_data in [None, Some("outer")] {
+        for child in children() {
+            for reply_on in REPLY_ON {
+                for reply_data in REPLY_DATA_VARIANTS {
+                    let msg = ScriptMsg {
+                        fail: false,
+                        data: outer_data.map(ToString::to_string),
+                        calls: vec![ScriptCall {
+                            msg: child.clone(),
+                            reply_on: reply_on.clone(),
+                            reply_data,
+                        }],
+                    };
+                    let actual =
+                        app.execute_contract(owner.clone(), contract_addr.clone(), &msg, &[]);
+                    assert_data(msg.expected(), actual);
+                    cases += 1;
+                }
+            }
+        }
+    }
+    assert_eq!(2 * 27 * 4 * 3, cases);
+}
+
+#[test]
+fn later_reply_data_should_override_earlier() {
+    let (mut app, owner, contract_addr) = setup();
+    let call = |fail, data, reply_data| ScriptCall {
+        msg: ScriptMsg::leaf(fail, data),
+        reply_on: ReplyOn::Always,
+        reply_data,
+    };
+    let msg = ScriptMsg {
+        fail: false,
+        data: Some("outer".to_string()),
+        calls: vec![
+            call(false, Some("first"), ReplyData::Echo),
+            call(true, None, ReplyData::Fixed),
+            call(false, Some("third"), ReplyData::Echo),
+            call(true, None, ReplyData::Echo),
+            call(false, None, ReplyData::None),
+        ],
+    };
+    assert_eq!(Ok(Some("third".to_string())), msg.expected());
+    let actual = app.execute_contract(owner, contract_addr, &msg, &[]);
+    assert_data(msg.expected(), actual);
+}
+
+#[test]
+fn unwrap_data_should_decode_data() {
+    let (mut app, owner, contract_addr) = setup();
+    let msg = ScriptMsg::leaf(false, Some(r#"{"value":7}"#));
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Value {
+        value: u64,
+    }
+
+    // data unwrapped by execute_contract
+    let res = app
+        .execute_contract(owner.clone(), contract_addr.clone(), &msg, &[])
+        .unwrap();
+    assert_eq!(Value { value: 7 }, res.unwrap_data());
+
+    // data wrapped in MsgExecuteContractResponse
+    let res = app
+        .execute(
+            owner,
+            WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&msg).unwrap(),
+                funds: vec![],
+            }
+            .into(),
+        )
+        .unwrap();
+    assert_eq!(Value { value: 7 }, res.unwrap_data());
+}
+
+#[test]
+#[should_panic(expected = "Expected response data of type u64, but the response has no data")]
+fn unwrap_data_should_panic_without_data() {
+    AppResponse::default().unwrap_data::<u64>();
+}
+
+#[test]
+#[should_panic(expected = "Failed to decode response data")]
+fn unwrap_data_should_panic_on_invalid_data() {
+    AppResponse {
+        events: vec![],
+        data: Some(Binary::from(b"not a json")),
+    }
+    .unwrap_data::<u64>();
+}
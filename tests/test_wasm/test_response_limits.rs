@@ -0,0 +1,239 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, Response,
+    StdResult,
+};
+use cw_multi_test::error::Error;
+use cw_multi_test::{
+    no_init, App, AppBuilder, Contract, ContractWrapper, Executor, IntoAddr, ResponseLimits,
+    WasmKeeper,
+};
+
+/// Shape of the response returned from `execute` entry-point.
+#[cw_serde]
+struct ResponseShape {
+    /// Number of attributes in the response and in each event.
+    attributes: usize,
+    /// Number of custom events.
+    events: usize,
+    /// Size of response data in bytes.
+    data: usize,
+    /// Key and value of an attribute added to the `debug` event.
+    #[serde(default)]
+    debug_attribute: Option<(String, String)>,
+}
+
+fn execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ResponseShape,
+) -> StdResult<Response> {
+    let attributes = (0..msg.attributes).map(|i| (format!("key{i}"), "value"));
+    let events = (0..msg.events).map(|i| {
+        Event::new(format!("event{i}"))
+            .add_attributes((0..msg.attributes).map(|j| (format!("key{j}"), "value")))
+    });
+    let mut response = Response::new()
+        .add_attributes(attributes)
+        .add_events(events);
+    if msg.data > 0 {
+        response = response.set_data(vec![1; msg.data]);
+    }
+    if let Some((key, value)) = msg.debug_attribute {
+        response = response.add_event(Event::new("debug").add_attribute(key, value));
+    }
+    Ok(response)
+}
+
+/// Returns a JSON encoded string, the response has exactly the requested size in bytes.
+fn query(_deps: Deps, _env: Env, size: usize) -> StdResult<Binary> {
+    to_json_binary(&"a".repeat(size - 2))
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        execute,
+        noop::instantiate,
+        query,
+    ))
+}
+
+fn limits() -> ResponseLimits {
+    ResponseLimits {
+        max_query_response_bytes: Some(100),
+        max_data_bytes: Some(50),
+        max_events: Some(2),
+        max_attributes_per_event: Some(5),
+        max_attribute_key_bytes: Some(10),
+        max_attribute_value_bytes: Some(20),
+    }
+}
+
+fn setup(limits: ResponseLimits) -> (App, Addr) {
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_response_limits(limits))
+        .build(no_init);
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, "owner".into_addr(), &Empty {}, &[], "limits", None)
+        .unwrap();
+    (app, contract_addr)
+}
+
+fn execute_shape(
+    app: &mut App,
+    contract_addr: &Addr,
+    attributes: usize,
+    events: usize,
+    data: usize,
+) -> cw_multi_test::error::AnyResult<cw_multi_test::AppResponse> {
+    app.execute_contract(
+        "owner".into_addr(),
+        contract_addr.clone(),
+        &ResponseShape {
+            attributes,
+            events,
+            data,
+            debug_attribute: None,
+        },
+        &[],
+    )
+}
+
+fn execute_debug_attribute(
+    app: &mut App,
+    contract_addr: &Addr,
+    key: &str,
+    value: &str,
+) -> cw_multi_test::error::AnyResult<cw_multi_test::AppResponse> {
+    app.execute_contract(
+        "owner".into_addr(),
+        contract_addr.clone(),
+        &ResponseShape {
+            attributes: 0,
+            events: 0,
+            data: 0,
+            debug_attribute: Some((key.to_string(), value.to_string())),
+        },
+        &[],
+    )
+}
+
+#[test]
+fn query_response_size_should_be_limited() {
+    let (app, contract_addr) = setup(limits());
+
+    let response: String = app.wrap().query_wasm_smart(&contract_addr, &100).unwrap();
+    assert_eq!(98, response.len());
+
+    let err = app
+        .wrap()
+        .query_wasm_smart::<String>(&contract_addr, &101)
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&Error::query_response_too_large(101, 100).to_string()));
+}
+
+#[test]
+fn attributes_per_event_should_be_limited() {
+    let (mut app, contract_addr) = setup(limits());
+
+    execute_shape(&mut app, &contract_addr, 5, 2, 0).unwrap();
+
+    let err = execute_shape(&mut app, &contract_addr, 6, 0, 0).unwrap_err();
+    assert_eq!(
+        Error::too_many_attributes("wasm", 6, 5).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn events_should_be_limited() {
+    let (mut app, contract_addr) = setup(limits());
+
+    execute_shape(&mut app, &contract_addr, 1, 2, 0).unwrap();
+
+    let err = execute_shape(&mut app, &contract_addr, 1, 3, 0).unwrap_err();
+    assert_eq!(
+        Error::too_many_events(3, 2).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn data_size_should_be_limited() {
+    let (mut app, contract_addr) = setup(limits());
+
+    execute_shape(&mut app, &contract_addr, 0, 0, 50).unwrap();
+
+    let err = execute_shape(&mut app, &contract_addr, 0, 0, 51).unwrap_err();
+    assert_eq!(
+        Error::response_data_too_large(51, 50).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn attribute_key_size_should_be_limited() {
+    let (mut app, contract_addr) = setup(limits());
+
+    execute_debug_attribute(&mut app, &contract_addr, &"k".repeat(10), "value").unwrap();
+
+    let key = "k".repeat(11);
+    let err = execute_debug_attribute(&mut app, &contract_addr, &key, "value").unwrap_err();
+    assert_eq!(
+        Error::attribute_key_too_long("debug", key, 11, 10).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn attribute_value_size_should_be_limited() {
+    let (mut app, contract_addr) = setup(limits());
+
+    execute_debug_attribute(&mut app, &contract_addr, "state", &"v".repeat(20)).unwrap();
+
+    let err =
+        execute_debug_attribute(&mut app, &contract_addr, "state", &"v".repeat(21)).unwrap_err();
+    assert_eq!(
+        Error::attribute_value_too_long("debug", "state", 21, 20).to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn attribute_size_error_should_locate_the_attribute() {
+    let (mut app, contract_addr) = setup(limits());
+
+    let err = execute_debug_attribute(&mut app, &contract_addr, "state", &"v".repeat(1000))
+        .unwrap_err()
+        .root_cause()
+        .to_string();
+    // event type, attribute key, measured size and the limit are reported
+    for context in ["debug", "\"state\"", "1000 bytes", "20 bytes"] {
+        assert!(err.contains(context), "{context} not found in: {err}");
+    }
+}
+
+#[test]
+fn unlimited_should_not_check_limits() {
+    let (mut app, contract_addr) = setup(ResponseLimits::unlimited());
+
+    let response: String = app.wrap().query_wasm_smart(&contract_addr, &1000).unwrap();
+    assert_eq!(998, response.len());
+    execute_shape(&mut app, &contract_addr, 100, 100, 1000).unwrap();
+}
+
+#[test]
+fn default_limits_should_mirror_vm() {
+    let limits = ResponseLimits::default();
+    assert_eq!(Some(64 * 1024 * 1024), limits.max_query_response_bytes);
+    assert_eq!(Some(64 * 1024 * 1024), limits.max_data_bytes);
+    assert_eq!(None, limits.max_events);
+    assert_eq!(None, limits.max_attributes_per_event);
+    assert_eq!(None, limits.max_attribute_key_bytes);
+    assert_eq!(None, limits.max_attribute_value_bytes);
+}
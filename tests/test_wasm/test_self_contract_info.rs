@@ -0,0 +1,215 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+    WasmMsg,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+/// Code identifier and creator of the contract, as returned by querying its own contract info.
+const SELF_INFO: Item<(u64, Addr)> = Item::new("self_info");
+
+/// Stores the code identifier and the creator read from the contract's own contract info.
+fn save_self_info(deps: DepsMut, env: &Env) -> StdResult<Response> {
+    let info = deps
+        .querier
+        .query_wasm_contract_info(env.contract.address.clone())?;
+    SELF_INFO.save(deps.storage, &(info.code_id, info.creator))?;
+    Ok(Response::default())
+}
+
+fn inspector_instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    save_self_info(deps, &env)
+}
+
+fn inspector_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn inspector_query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&SELF_INFO.load(deps.storage)?)
+}
+
+fn inspector_migrate(deps: DepsMut, env: Env, _msg: Empty) -> StdResult<Response> {
+    save_self_info(deps, &env)
+}
+
+/// Contract querying its own contract info in `instantiate` and `migrate` entry-points.
+fn inspector() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(inspector_execute, inspector_instantiate, inspector_query)
+            .with_migrate(inspector_migrate),
+    )
+}
+
+fn proxy_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+/// Forwards the received message as a submessage.
+fn proxy_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: WasmMsg,
+) -> StdResult<Response> {
+    Ok(Response::new().add_message(msg))
+}
+
+fn proxy_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary(&Empty {})
+}
+
+/// Contract sending the messages it receives as submessages.
+fn proxy() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        proxy_execute,
+        proxy_instantiate,
+        proxy_query,
+    ))
+}
+
+fn query_self_info(app: &App, contract_addr: &Addr) -> (u64, Addr) {
+    app.wrap()
+        .query_wasm_smart(contract_addr, &Empty {})
+        .unwrap()
+}
+
+#[test]
+fn contract_should_query_own_info_in_instantiate() {
+    let mut app = App::default();
+    let creator = app.api().addr_make("creator");
+    let code_id = app.store_code(inspector());
+
+    let contract_addr = app
+        .instantiate_contract(code_id, creator.clone(), &Empty {}, &[], "inspector", None)
+        .unwrap();
+
+    assert_eq!((code_id, creator), query_self_info(&app, &contract_addr));
+}
+
+#[test]
+fn contract_should_query_own_info_in_instantiate_submessage() {
+    let mut app = App::default();
+    let creator = app.api().addr_make("creator");
+    let proxy_code_id = app.store_code(proxy());
+    let inspector_code_id = app.store_code(inspector());
+    let proxy_addr = app
+        .instantiate_contract(
+            proxy_code_id,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "proxy",
+            None,
+        )
+        .unwrap();
+
+    let msg = WasmMsg::Instantiate {
+        admin: None,
+        code_id: inspector_code_id,
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+        label: "inspector".to_string(),
+    };
+    let res = app
+        .execute_contract(creator, proxy_addr.clone(), &msg, &[])
+        .unwrap();
+
+    // the inspector was instantiated by the proxy contract
+    let instantiate_event = res
+        .events
+        .iter()
+        .find(|event| event.ty == "instantiate")
+        .unwrap();
+    let inspector_addr = Addr::unchecked(&instantiate_event.attributes[0].value);
+    assert_eq!(
+        (inspector_code_id, proxy_addr),
+        query_self_info(&app, &inspector_addr)
+    );
+}
+
+#[test]
+fn contract_should_query_own_info_in_migrate() {
+    let mut app = App::default();
+    let creator = app.api().addr_make("creator");
+    let code_id_v1 = app.store_code(inspector());
+    let code_id_v2 = app.store_code(inspector());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id_v1,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "inspector",
+            Some(creator.to_string()),
+        )
+        .unwrap();
+    assert_eq!(
+        (code_id_v1, creator.clone()),
+        query_self_info(&app, &contract_addr)
+    );
+
+    // during migration the contract sees the new code identifier
+    app.migrate_contract(
+        creator.clone(),
+        contract_addr.clone(),
+        &Empty {},
+        code_id_v2,
+    )
+    .unwrap();
+    assert_eq!((code_id_v2, creator), query_self_info(&app, &contract_addr));
+}
+
+#[test]
+fn contract_should_query_own_info_in_migrate_submessage() {
+    let mut app = App::default();
+    let creator = app.api().addr_make("creator");
+    let proxy_code_id = app.store_code(proxy());
+    let code_id_v1 = app.store_code(inspector());
+    let code_id_v2 = app.store_code(inspector());
+    let proxy_addr = app
+        .instantiate_contract(
+            proxy_code_id,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "proxy",
+            None,
+        )
+        .unwrap();
+    let contract_addr = app
+        .instantiate_contract(
+            code_id_v1,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "inspector",
+            Some(proxy_addr.to_string()),
+        )
+        .unwrap();
+
+    // the proxy contract is the admin, so it migrates the inspector in a submessage
+    let msg = WasmMsg::Migrate {
+        contract_addr: contract_addr.to_string(),
+        new_code_id: code_id_v2,
+        msg: to_json_binary(&Empty {}).unwrap(),
+    };
+    app.execute_contract(creator.clone(), proxy_addr, &msg, &[])
+        .unwrap();
+    assert_eq!((code_id_v2, creator), query_self_info(&app, &contract_addr));
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "cosmwasm_1_2")]
+
+use crate::test_contracts::noop;
+use cosmwasm_std::{
+    instantiate2_address, to_json_binary, Addr, Api, Binary, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdResult, WasmMsg,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+const SALT: &[u8] = b"self-admin";
+
+/// Migrates the contract itself to the code identifier passed in the message.
+fn execute(_deps: DepsMut, env: Env, _info: MessageInfo, new_code_id: u64) -> StdResult<Response> {
+    Ok(Response::new().add_message(WasmMsg::Migrate {
+        contract_addr: env.contract.address.to_string(),
+        new_code_id,
+        msg: to_json_binary(&Empty {})?,
+    }))
+}
+
+fn query_v1(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary("v1")
+}
+
+fn query_v2(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    to_json_binary("v2")
+}
+
+fn contract_v1() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(execute, noop::instantiate, query_v1)
+            .with_migrate(noop::migrate),
+    )
+}
+
+fn contract_v2() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(execute, noop::instantiate, query_v2)
+            .with_migrate(noop::migrate),
+    )
+}
+
+/// Instantiates the contract with its own predictable address set as admin.
+fn instantiate_self_administered(app: &mut App, code_id: u64) -> Addr {
+    let creator = app.api().addr_make("creator");
+    let checksum = app.wrap().query_wasm_code_info(code_id).unwrap().checksum;
+    let canonical_creator = app.api().addr_canonicalize(creator.as_str()).unwrap();
+    let canonical_addr =
+        instantiate2_address(checksum.as_slice(), &canonical_creator, SALT).unwrap();
+    let self_addr = app.api().addr_humanize(&canonical_addr).unwrap();
+    let contract_addr = app
+        .instantiate2_contract(
+            code_id,
+            creator,
+            &Empty {},
+            &[],
+            "self-administered",
+            Some(self_addr.to_string()),
+            SALT,
+        )
+        .unwrap();
+    assert_eq!(self_addr, contract_addr);
+    contract_addr
+}
+
+#[test]
+fn contract_administered_by_itself_should_migrate_itself() {
+    let mut app = App::default();
+    let code_id_v1 = app.store_code(contract_v1());
+    let code_id_v2 = app.store_code(contract_v2());
+    let contract_addr = instantiate_self_administered(&mut app, code_id_v1);
+
+    // the contract is its own admin
+    let contract_data = app.contract_data(&contract_addr).unwrap();
+    assert_eq!(Some(contract_addr.clone()), contract_data.admin);
+
+    // any sender may trigger the migration, because the contract sends the migrate message
+    let sender = app.api().addr_make("sender");
+    app.execute_contract(sender, contract_addr.clone(), &code_id_v2, &[])
+        .unwrap();
+    let contract_data = app.contract_data(&contract_addr).unwrap();
+    assert_eq!(code_id_v2, contract_data.code_id);
+    let version: String = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!("v2", version);
+}
+
+#[test]
+fn non_admin_should_not_migrate_self_administered_contract() {
+    let mut app = App::default();
+    let code_id_v1 = app.store_code(contract_v1());
+    let code_id_v2 = app.store_code(contract_v2());
+    let contract_addr = instantiate_self_administered(&mut app, code_id_v1);
+
+    // the creator is not the admin, so the migration fails
+    let creator = app.api().addr_make("creator");
+    let err = app
+        .migrate_contract(creator, contract_addr.clone(), &Empty {}, code_id_v2)
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("Only admin can migrate contract"));
+    let contract_data = app.contract_data(&contract_addr).unwrap();
+    assert_eq!(code_id_v1, contract_data.code_id);
+}
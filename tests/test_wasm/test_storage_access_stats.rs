@@ -0,0 +1,130 @@
+use crate::test_contracts::noop;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply, Response,
+    StdResult, SubMsg, WasmMsg,
+};
+use cw_multi_test::{
+    no_init, App, AppBuilder, Contract, ContractWrapper, Executor, IntoAddr, StorageAccessCounts,
+};
+
+#[cw_serde]
+enum ExecuteMsg {
+    /// Performs a known number of storage operations, queries the other contract
+    /// and executes it in a submessage, writing once more in the reply.
+    Run { other: String },
+    /// Does not access the storage.
+    Noop {},
+}
+
+#[cw_serde]
+struct QueryMsg {}
+
+fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Run { other } => {
+            deps.storage.set(b"a", b"1");
+            deps.storage.set(b"b", b"2");
+            deps.storage.set(b"c", b"3");
+            assert!(deps.storage.get(b"a").is_some());
+            deps.storage.remove(b"b");
+            assert_eq!(2, deps.storage.range(None, None, Order::Ascending).count());
+            let _: Empty = deps.querier.query_wasm_smart(&other, &QueryMsg {})?;
+            Ok(Response::new().add_submessage(SubMsg::reply_on_success(
+                WasmMsg::Execute {
+                    contract_addr: other,
+                    msg: to_json_binary(&ExecuteMsg::Noop {})?,
+                    funds: vec![],
+                },
+                1,
+            )))
+        }
+        ExecuteMsg::Noop {} => Ok(Response::default()),
+    }
+}
+
+fn query(deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
+    let _ = deps.storage.get(b"a");
+    to_json_binary(&Empty {})
+}
+
+fn reply(deps: DepsMut, _env: Env, _msg: Reply) -> StdResult<Response> {
+    deps.storage.set(b"reply", b"1");
+    Ok(Response::default())
+}
+
+fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, noop::instantiate, query).with_reply(reply))
+}
+
+#[test]
+fn storage_operations_should_be_counted_per_contract() {
+    let mut app = AppBuilder::default()
+        .with_storage_access_stats(true)
+        .build(no_init);
+    let owner = "owner".into_addr();
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+    let other_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "other", None)
+        .unwrap();
+
+    app.execute_contract(
+        owner.clone(),
+        contract_addr.clone(),
+        &ExecuteMsg::Run {
+            other: other_addr.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let stats = app.last_execution_stats();
+    assert_eq!(
+        StorageAccessCounts {
+            reads: 1,
+            writes: 4,
+            removals: 1,
+            range_iterations: 1,
+            items_yielded: 2,
+        },
+        stats.contract(&contract_addr)
+    );
+    // the query sent to the other contract is counted against the queried contract
+    assert_eq!(
+        StorageAccessCounts {
+            reads: 1,
+            ..Default::default()
+        },
+        stats.contract(&other_addr)
+    );
+    assert_eq!(2, stats.total().reads);
+
+    // statistics are cleared by the next execution
+    app.execute_contract(owner, other_addr, &ExecuteMsg::Noop {}, &[])
+        .unwrap();
+    assert!(app.last_execution_stats().is_empty());
+}
+
+#[test]
+fn storage_operations_should_not_be_counted_by_default() {
+    let mut app = App::default();
+    let owner = "owner".into_addr();
+    let code_id = app.store_code(contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "first", None)
+        .unwrap();
+
+    app.execute_contract(
+        owner,
+        contract_addr.clone(),
+        &ExecuteMsg::Run {
+            other: contract_addr.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert!(app.last_execution_stats().is_empty());
+}
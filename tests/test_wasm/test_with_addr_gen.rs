@@ -1,7 +1,10 @@
 use cosmwasm_std::testing::MockApi;
-use cosmwasm_std::{Addr, Api, Empty, Storage};
+use cosmwasm_std::{Addr, Api, CanonicalAddr, Empty, Storage};
 use cw_multi_test::error::AnyResult;
-use cw_multi_test::{no_init, AddressGenerator, AppBuilder, Executor, WasmKeeper};
+use cw_multi_test::{
+    no_init, AddressGenerator, AppBuilder, ContractInstantiationInfo, Executor, WasmKeeper,
+};
+use sha2::{Digest, Sha256};
 
 use crate::test_contracts;
 
@@ -87,6 +90,50 @@ fn custom_address_generator_should_work() {
     );
 }
 
+#[test]
+fn label_based_address_generator_should_work() {
+    // prepare address generator deriving addresses from contract labels
+    struct LabelAddressGenerator;
+
+    impl AddressGenerator for LabelAddressGenerator {
+        fn contract_address_with_info(
+            &self,
+            api: &dyn Api,
+            _storage: &mut dyn Storage,
+            info: &ContractInstantiationInfo,
+        ) -> AnyResult<Addr> {
+            let seed = format!("{}/{}", info.creator, info.label);
+            Ok(api.addr_humanize(&CanonicalAddr::from(Sha256::digest(seed).as_slice()))?)
+        }
+    }
+
+    // instantiates contracts labeled with specified labels, in specified order
+    let instantiate_all = |labels: &[&str]| -> Vec<(String, Addr)> {
+        let wasm_keeper = WasmKeeper::new().with_address_generator(LabelAddressGenerator);
+        let mut app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+        let owner_addr = app.api().addr_make("owner");
+        let code_id = app.store_code(test_contracts::counter::contract());
+        let mut addresses = labels
+            .iter()
+            .map(|label| {
+                let owner_addr = owner_addr.clone();
+                let addr = app
+                    .instantiate_contract(code_id, owner_addr, &Empty {}, &[], *label, None)
+                    .unwrap();
+                (label.to_string(), addr)
+            })
+            .collect::<Vec<_>>();
+        addresses.sort();
+        addresses
+    };
+
+    // addresses do not depend on the order of instantiations
+    assert_eq!(
+        instantiate_all(&["vault", "oracle", "router"]),
+        instantiate_all(&["router", "vault", "oracle"])
+    );
+}
+
 #[test]
 #[cfg(feature = "cosmwasm_1_2")]
 fn predictable_contract_address_should_work() {